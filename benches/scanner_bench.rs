@@ -11,11 +11,7 @@ fn create_benchmark_dir(file_count: usize, dir_count: usize) -> TempDir {
     let dir = TempDir::new().unwrap();
     let root = dir.path();
 
-    let files_per_dir = if dir_count > 0 {
-        file_count / dir_count
-    } else {
-        file_count
-    };
+    let files_per_dir = file_count.checked_div(dir_count).unwrap_or(file_count);
 
     for d in 0..dir_count {
         let subdir = root.join(format!("dir{}", d));
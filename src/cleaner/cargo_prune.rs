@@ -0,0 +1,594 @@
+//! Selective pruning of Cargo target directories.
+//!
+//! `cargo clean` removes the whole target directory, so the next build starts
+//! from scratch. [`CargoPrune`] instead finds the stale parts, much like
+//! cargo-sweep: target triples and profiles unused for a while, compilation
+//! units built by an old toolchain or unused for a while, and superseded
+//! incremental sessions.
+//!
+//! A target directory holds profile directories (`debug/`, `release/`, custom
+//! profiles), directly or under a target triple (`wasm32-unknown-unknown/`).
+//! Every unit of a profile has a `.fingerprint/<name>-<hash>/` directory,
+//! whose JSON records the `rustc` that built it; its outputs are the
+//! `deps/`, `examples/` and `build/` entries carrying the same hash. A unit's
+//! last use is the newest access or modification time of its fingerprint
+//! files, which Cargo reads on every build.
+
+use crate::cleaner::measure::{apparent_size, last_used};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Directories of a profile holding unit outputs named `<name>-<hash>`.
+const OUTPUT_DIRS: [&str; 3] = ["deps", "examples", "build"];
+
+/// Which parts of a Cargo target directory to prune.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CargoPrune {
+    /// Remove target triples, profiles and units unused for this many days.
+    pub unused_days: Option<u32>,
+    /// Remove units built by a different `rustc` than a profile's latest
+    /// build.
+    pub old_toolchains: bool,
+    /// Remove all but the newest incremental session of each crate.
+    pub old_incremental: bool,
+}
+
+/// Why part of a target directory is pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PruneReason {
+    /// A target triple none of whose profiles was used recently.
+    UnusedTriple,
+    /// A profile none of whose units was used recently.
+    UnusedProfile,
+    /// A unit built by an older toolchain.
+    OldToolchain,
+    /// A unit, or a crate's incremental cache, not used recently.
+    UnusedUnit,
+    /// An incremental session superseded by a newer one.
+    OldIncremental,
+}
+
+/// One thing to remove from a target directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneItem {
+    /// Profile or triple the item belongs to, relative to the target
+    /// directory (e.g. `debug` or `wasm32-unknown-unknown/release`).
+    pub scope: String,
+    pub reason: PruneReason,
+    /// Files and directories to remove.
+    pub paths: Vec<PathBuf>,
+    /// Apparent size of `paths` in bytes.
+    pub size: u64,
+}
+
+/// What pruning a target directory would remove.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunePlan {
+    /// The target directory.
+    pub target: PathBuf,
+    pub items: Vec<PruneItem>,
+    /// The `unused_days` the plan was made with, for descriptions.
+    unused_days: Option<u32>,
+}
+
+/// Items of a plan with the same scope and reason, for reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneSummary {
+    pub scope: String,
+    /// E.g. "12 units unused for 30+ days".
+    pub description: String,
+    pub size: u64,
+}
+
+/// A compilation unit of a profile, from its fingerprint directory.
+struct Unit {
+    fingerprint: PathBuf,
+    hash: String,
+    last_used: SystemTime,
+    /// The `rustc` field of the fingerprint, a hash of the compiler version.
+    rustc: Option<u64>,
+}
+
+impl CargoPrune {
+    /// Whether this prunes anything at all.
+    pub fn is_empty(&self) -> bool {
+        self.unused_days.is_none() && !self.old_toolchains && !self.old_incremental
+    }
+
+    /// Find what to prune in the target directory `target`.
+    pub fn plan(&self, target: &Path) -> PrunePlan {
+        self.plan_at(target, SystemTime::now())
+    }
+
+    fn plan_at(&self, target: &Path, now: SystemTime) -> PrunePlan {
+        let cutoff = self
+            .unused_days
+            .map(|days| now - Duration::from_secs(u64::from(days) * 86400));
+        let mut items = Vec::new();
+
+        for dir in subdirs(target) {
+            let name = file_name(&dir);
+            if is_profile(&dir) {
+                self.plan_profile(&dir, name, cutoff, &mut items);
+                continue;
+            }
+
+            // Anything else with profiles in it is a target triple
+            let profiles: Vec<PathBuf> = subdirs(&dir)
+                .into_iter()
+                .filter(|p| is_profile(p))
+                .collect();
+            if profiles.is_empty() {
+                continue;
+            }
+            let triple_used = profiles
+                .iter()
+                .map(|p| profile_last_used(&units(p)))
+                .max()
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            if cutoff.is_some_and(|cutoff| triple_used < cutoff) {
+                items.push(item(&name, PruneReason::UnusedTriple, vec![dir]));
+                continue;
+            }
+            for profile in profiles {
+                let scope = format!("{}/{}", name, file_name(&profile));
+                self.plan_profile(&profile, scope, cutoff, &mut items);
+            }
+        }
+
+        PrunePlan {
+            target: target.to_path_buf(),
+            items,
+            unused_days: self.unused_days,
+        }
+    }
+
+    fn plan_profile(
+        &self,
+        profile: &Path,
+        scope: String,
+        cutoff: Option<SystemTime>,
+        items: &mut Vec<PruneItem>,
+    ) {
+        let units = units(profile);
+        if cutoff.is_some_and(|cutoff| profile_last_used(&units) < cutoff) {
+            items.push(item(
+                &scope,
+                PruneReason::UnusedProfile,
+                vec![profile.to_path_buf()],
+            ));
+            return;
+        }
+
+        // The toolchain of the latest build is the current one
+        let current_rustc = units
+            .iter()
+            .filter(|u| u.rustc.is_some())
+            .max_by_key(|u| u.last_used)
+            .and_then(|u| u.rustc);
+        let outputs: Vec<PathBuf> = OUTPUT_DIRS
+            .iter()
+            .flat_map(|dir| entries(&profile.join(dir)))
+            .collect();
+
+        for unit in &units {
+            let reason =
+                if self.old_toolchains && unit.rustc.is_some() && unit.rustc != current_rustc {
+                    PruneReason::OldToolchain
+                } else if cutoff.is_some_and(|cutoff| unit.last_used < cutoff) {
+                    PruneReason::UnusedUnit
+                } else {
+                    continue;
+                };
+            let mut paths = vec![unit.fingerprint.clone()];
+            paths.extend(
+                outputs
+                    .iter()
+                    .filter(|output| has_hash(&file_name(output), &unit.hash))
+                    .cloned(),
+            );
+            items.push(item(&scope, reason, paths));
+        }
+
+        self.plan_incremental(&profile.join("incremental"), &scope, cutoff, items);
+    }
+
+    /// Incremental caches live in `incremental/<crate>-<hash>/`, one
+    /// `s-<session>` directory (and `.lock` file) per compilation session.
+    fn plan_incremental(
+        &self,
+        incremental: &Path,
+        scope: &str,
+        cutoff: Option<SystemTime>,
+        items: &mut Vec<PruneItem>,
+    ) {
+        for krate in subdirs(incremental) {
+            if cutoff.is_some_and(|cutoff| last_used(&krate) < cutoff) {
+                items.push(item(scope, PruneReason::UnusedUnit, vec![krate]));
+                continue;
+            }
+            if !self.old_incremental {
+                continue;
+            }
+
+            let mut sessions: Vec<(SystemTime, PathBuf)> = subdirs(&krate)
+                .into_iter()
+                .map(|session| (modified(&session), session))
+                .collect();
+            sessions.sort();
+            // Keep the newest session
+            sessions.pop();
+            for (_, session) in sessions {
+                let lock = session.with_extension("lock");
+                let mut paths = vec![session];
+                if lock.exists() {
+                    paths.push(lock);
+                }
+                items.push(item(scope, PruneReason::OldIncremental, paths));
+            }
+        }
+    }
+}
+
+impl PrunePlan {
+    /// Total apparent size of everything to prune.
+    pub fn size(&self) -> u64 {
+        self.items.iter().map(|i| i.size).sum()
+    }
+
+    /// Whether there is nothing to prune.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The items grouped by scope and reason, in scope order.
+    pub fn summary(&self) -> Vec<PruneSummary> {
+        let mut groups: Vec<(&str, PruneReason, usize, u64)> = Vec::new();
+        for item in &self.items {
+            match groups
+                .iter_mut()
+                .find(|(scope, reason, _, _)| *scope == item.scope && *reason == item.reason)
+            {
+                Some(group) => {
+                    group.2 += 1;
+                    group.3 += item.size;
+                }
+                None => groups.push((&item.scope, item.reason, 1, item.size)),
+            }
+        }
+        groups.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+
+        groups
+            .into_iter()
+            .map(|(scope, reason, count, size)| PruneSummary {
+                scope: scope.to_string(),
+                description: self.describe(reason, count),
+                size,
+            })
+            .collect()
+    }
+
+    fn describe(&self, reason: PruneReason, count: usize) -> String {
+        let plural = |noun: &str| {
+            if count == 1 {
+                format!("1 {}", noun)
+            } else {
+                format!("{} {}s", count, noun)
+            }
+        };
+        let unused = match self.unused_days {
+            Some(days) => format!("unused for {}+ days", days),
+            None => "unused".to_string(),
+        };
+        match reason {
+            PruneReason::UnusedTriple => format!("target triple {}", unused),
+            PruneReason::UnusedProfile => format!("profile {}", unused),
+            PruneReason::OldToolchain => format!("{} from an old toolchain", plural("unit")),
+            PruneReason::UnusedUnit => format!("{} {}", plural("unit"), unused),
+            PruneReason::OldIncremental => plural("old incremental session"),
+        }
+    }
+}
+
+impl fmt::Display for PruneSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.scope, self.description)
+    }
+}
+
+fn item(scope: &str, reason: PruneReason, paths: Vec<PathBuf>) -> PruneItem {
+    PruneItem {
+        scope: scope.to_string(),
+        reason,
+        size: apparent_size(&paths),
+        paths,
+    }
+}
+
+/// A profile directory has a `.fingerprint` directory.
+fn is_profile(dir: &Path) -> bool {
+    dir.join(".fingerprint").is_dir()
+}
+
+/// The units of a profile, from `.fingerprint/<name>-<hash>/`.
+fn units(profile: &Path) -> Vec<Unit> {
+    subdirs(&profile.join(".fingerprint"))
+        .into_iter()
+        .filter_map(|fingerprint| {
+            let hash = unit_hash(&file_name(&fingerprint))?.to_string();
+            Some(Unit {
+                last_used: last_used(&fingerprint),
+                rustc: fingerprint_rustc(&fingerprint),
+                hash,
+                fingerprint,
+            })
+        })
+        .collect()
+}
+
+fn profile_last_used(units: &[Unit]) -> SystemTime {
+    units
+        .iter()
+        .map(|u| u.last_used)
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// The `rustc` hash recorded in a unit's fingerprint JSON files.
+fn fingerprint_rustc(fingerprint: &Path) -> Option<u64> {
+    entries(fingerprint)
+        .iter()
+        .filter(|file| file.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|file| read_untouched(file))
+        .filter_map(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .find_map(|value| value["rustc"].as_u64())
+}
+
+/// Read a file, then restore its access time, so that planning (a dry run
+/// included) doesn't make a unit look recently used.
+fn read_untouched(path: &Path) -> Option<String> {
+    let accessed = fs::metadata(path).and_then(|m| m.accessed()).ok();
+    let content = fs::read_to_string(path).ok()?;
+    if let Some(accessed) = accessed {
+        let _ = fs::File::open(path)
+            .and_then(|f| f.set_times(fs::FileTimes::new().set_accessed(accessed)));
+    }
+    Some(content)
+}
+
+/// The 16 hex digit hash ending a unit directory name like `serde-1a2b…`.
+fn unit_hash(name: &str) -> Option<&str> {
+    let (_, hash) = name.rsplit_once('-')?;
+    (hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+/// Whether an output name like `libserde-<hash>.rlib` belongs to the unit
+/// with `hash`.
+fn has_hash(name: &str, hash: &str) -> bool {
+    name.match_indices(hash).any(|(i, _)| {
+        name[..i].ends_with('-')
+            && name[i + hash.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| c == '.')
+    })
+}
+
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    paths
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    entries(dir)
+        .into_iter()
+        .filter(|p| fs::symlink_metadata(p).is_ok_and(|m| m.is_dir()))
+        .collect()
+}
+
+fn modified(path: &Path) -> SystemTime {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{File, FileTimes};
+    use tempfile::TempDir;
+
+    const SERDE: &str = "0123456789abcdef";
+    const ANYHOW: &str = "00000000000000aa";
+    const APP: &str = "fedcba9876543210";
+
+    /// Write `path` with a timestamp `days_ago`.
+    fn write(target: &Path, rel: &str, content: &str, days_ago: u64) {
+        let path = target.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        age(&path, days_ago);
+    }
+
+    fn age(path: &Path, days_ago: u64) {
+        let time = SystemTime::now() - Duration::from_secs(days_ago * 86400);
+        File::open(path)
+            .unwrap()
+            .set_times(FileTimes::new().set_accessed(time).set_modified(time))
+            .unwrap();
+    }
+
+    fn unit(target: &Path, profile: &str, name: &str, hash: &str, rustc: u64, days_ago: u64) {
+        write(
+            target,
+            &format!("{profile}/.fingerprint/{name}-{hash}/lib-{name}.json"),
+            &format!("{{\"rustc\":{rustc},\"features\":\"[]\"}}"),
+            days_ago,
+        );
+        write(
+            target,
+            &format!("{profile}/deps/lib{name}-{hash}.rlib"),
+            &"x".repeat(100),
+            days_ago,
+        );
+        write(
+            target,
+            &format!("{profile}/deps/{name}-{hash}.d"),
+            "",
+            days_ago,
+        );
+    }
+
+    fn setup_target() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let target = tmp.path();
+        write(
+            target,
+            "CACHEDIR.TAG",
+            "Signature: 8a477f597d28d172789f06886806bc55",
+            0,
+        );
+        unit(target, "debug", "serde", SERDE, 1, 60);
+        write(
+            target,
+            &format!("debug/build/serde-{SERDE}/out/generated.rs"),
+            "x",
+            60,
+        );
+        unit(target, "debug", "anyhow", ANYHOW, 2, 40);
+        unit(target, "debug", "app", APP, 2, 0);
+        write(
+            target,
+            "debug/incremental/app-1xyz/s-old-1/query-cache.bin",
+            "x",
+            5,
+        );
+        write(target, "debug/incremental/app-1xyz/s-old-1.lock", "", 5);
+        write(
+            target,
+            "debug/incremental/app-1xyz/s-new-2/query-cache.bin",
+            "x",
+            0,
+        );
+        age(&target.join("debug/incremental/app-1xyz/s-old-1"), 5);
+        unit(target, "release", "app", "1111111111111111", 1, 90);
+        unit(
+            target,
+            "wasm32-unknown-unknown/debug",
+            "app",
+            "2222222222222222",
+            2,
+            90,
+        );
+        write(target, "doc/index.html", "", 90);
+        tmp
+    }
+
+    fn reasons(plan: &PrunePlan) -> Vec<(&str, PruneReason)> {
+        plan.items
+            .iter()
+            .map(|i| (i.scope.as_str(), i.reason))
+            .collect()
+    }
+
+    #[test]
+    fn test_prune_old_toolchain_units() {
+        let tmp = setup_target();
+        let prune = CargoPrune {
+            old_toolchains: true,
+            ..Default::default()
+        };
+
+        let plan = prune.plan(tmp.path());
+
+        // release was last built by toolchain 1, so that's current there
+        assert_eq!(reasons(&plan), vec![("debug", PruneReason::OldToolchain)]);
+        let debug = tmp.path().join("debug");
+        assert_eq!(
+            plan.items[0].paths,
+            vec![
+                debug.join(format!(".fingerprint/serde-{SERDE}")),
+                debug.join(format!("deps/libserde-{SERDE}.rlib")),
+                debug.join(format!("deps/serde-{SERDE}.d")),
+                debug.join(format!("build/serde-{SERDE}")),
+            ]
+        );
+        // Fingerprint JSON, rlib and build script output
+        assert_eq!(plan.size(), 27 + 100 + 1);
+    }
+
+    #[test]
+    fn test_prune_unused_triples_profiles_and_units() {
+        let tmp = setup_target();
+        let prune = CargoPrune {
+            unused_days: Some(30),
+            ..Default::default()
+        };
+
+        let plan = prune.plan(tmp.path());
+
+        assert_eq!(
+            reasons(&plan),
+            vec![
+                ("debug", PruneReason::UnusedUnit),
+                ("debug", PruneReason::UnusedUnit),
+                ("release", PruneReason::UnusedProfile),
+                ("wasm32-unknown-unknown", PruneReason::UnusedTriple),
+            ]
+        );
+        assert_eq!(plan.items[2].paths, vec![tmp.path().join("release")]);
+
+        let summary: Vec<String> = plan.summary().iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "debug: 2 units unused for 30+ days",
+                "release: profile unused for 30+ days",
+                "wasm32-unknown-unknown: target triple unused for 30+ days",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_old_incremental_sessions() {
+        let tmp = setup_target();
+        let prune = CargoPrune {
+            old_incremental: true,
+            ..Default::default()
+        };
+
+        let plan = prune.plan(tmp.path());
+
+        let sessions = tmp.path().join("debug/incremental/app-1xyz");
+        assert_eq!(reasons(&plan), vec![("debug", PruneReason::OldIncremental)]);
+        assert_eq!(
+            plan.items[0].paths,
+            vec![sessions.join("s-old-1"), sessions.join("s-old-1.lock")]
+        );
+        assert_eq!(plan.summary()[0].description, "1 old incremental session");
+        assert!(CargoPrune::default().plan(tmp.path()).is_empty());
+    }
+
+    #[test]
+    fn test_unit_hashes() {
+        assert_eq!(unit_hash(&format!("serde_json-{SERDE}")), Some(SERDE));
+        assert_eq!(unit_hash("serde-json"), None);
+
+        assert!(has_hash(&format!("libserde-{SERDE}.rlib"), SERDE));
+        assert!(has_hash(&format!("app-{SERDE}"), SERDE));
+        assert!(!has_hash(&format!("app-{SERDE}0"), SERDE));
+        assert!(!has_hash(&format!("app{SERDE}.d"), SERDE));
+    }
+}
@@ -0,0 +1,1001 @@
+//! Desktop cache cleaners.
+//!
+//! On workstations the space goes to the XDG cache (`~/.cache`), thumbnails,
+//! the freedesktop.org Trash, Flatpak runtimes no app needs any more and the
+//! disabled revisions snapd keeps after every refresh. The XDG cache is
+//! broken down per application and the Trash per trashed path, so single
+//! entries can be listed and removed. Thumbnails are pruned by age, 30 days
+//! unless a `min_age_days` policy rule says otherwise; for the XDG cache and
+//! the Trash such a rule limits cleaning to older entries, judging the Trash
+//! by the deletion date in each `.trashinfo`.
+
+use crate::cleaner::docker::{command_result, command_stdout, parse_docker_size};
+use crate::cleaner::linux_system::{missing_privilege, privilege_note};
+use crate::cleaner::measure::{apparent_size, last_used, reclaimable_usage, SpaceProbe};
+use crate::cleaner::policy::CleanPolicy;
+use crate::cleaner::system_cleaner::{
+    dry_run_result, run_quietly, DetectedSystemResource, SystemCleanResult, SystemCleaner,
+    SystemResourceItem,
+};
+use crate::quarantine::trash::{Trash, TrashedEntry};
+use crate::quarantine::Quarantine;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Directories in the XDG cache that other cleaners report.
+const CLAIMED_CACHE_DIRS: &[&str] = &["thumbnails", "pip", "yarn", "go-build"];
+
+/// Age past which thumbnails are pruned without a `min_age_days` rule.
+const THUMBNAIL_MAX_AGE_DAYS: u32 = 30;
+
+/// Where snapd keeps the squashfs image of every installed revision.
+const SNAPS_DIR: &str = "/var/lib/snapd/snaps";
+
+/// Ids of all desktop cleaners.
+pub fn desktop_ids() -> Vec<&'static str> {
+    vec!["xdg-cache", "thumbnails", "trash", "flatpak", "snap"]
+}
+
+/// One cleaner per desktop cache, located from the process environment and
+/// limited by each id's `min_age_days` in `policy`.
+pub fn desktop_cleaners(policy: &CleanPolicy) -> Vec<Box<dyn SystemCleaner>> {
+    let home = dirs::home_dir();
+    let xdg = |key: &str, default: &str| {
+        std::env::var_os(key)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(default)))
+    };
+    let cache_home = xdg("XDG_CACHE_HOME", ".cache");
+    let data_home = xdg("XDG_DATA_HOME", ".local/share");
+
+    let mut thumbnail_dirs: Vec<PathBuf> =
+        cache_home.iter().map(|c| c.join("thumbnails")).collect();
+    // Used before the thumbnail spec moved to the XDG cache
+    thumbnail_dirs.extend(home.as_ref().map(|h| h.join(".thumbnails")));
+
+    vec![
+        Box::new(XdgCacheCleaner {
+            root: cache_home,
+            min_age_days: policy.explicit_min_age_days("xdg-cache"),
+        }),
+        Box::new(ThumbnailCleaner {
+            dirs: thumbnail_dirs,
+            max_age_days: policy
+                .explicit_min_age_days("thumbnails")
+                .unwrap_or(THUMBNAIL_MAX_AGE_DAYS),
+        }),
+        Box::new(TrashCleaner::new(
+            data_home.map(|d| Trash::new(d.join("Trash"))),
+            Quarantine::open().ok(),
+            policy.explicit_min_age_days("trash"),
+        )),
+        Box::new(FlatpakCleaner),
+        Box::new(SnapCleaner {
+            snaps_dir: PathBuf::from(SNAPS_DIR),
+        }),
+    ]
+}
+
+/// Cleaner for the per-application caches in `$XDG_CACHE_HOME`.
+///
+/// Each top-level entry is one application's cache and one item.
+pub struct XdgCacheCleaner {
+    root: Option<PathBuf>,
+    min_age_days: Option<u32>,
+}
+
+impl XdgCacheCleaner {
+    fn existing_root(&self) -> Option<&Path> {
+        self.root.as_deref().filter(|r| r.is_dir())
+    }
+
+    /// Application caches, except the ones other cleaners report.
+    fn app_caches(&self) -> Vec<PathBuf> {
+        let Some(entries) = self.existing_root().and_then(|r| fs::read_dir(r).ok()) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|e| {
+                e.file_name()
+                    .to_str()
+                    .is_none_or(|name| !CLAIMED_CACHE_DIRS.contains(&name))
+            })
+            .map(|e| e.path())
+            .collect()
+    }
+
+    /// Application caches unused for longer than the age limit, if any.
+    fn targets(&self, now: SystemTime) -> Vec<PathBuf> {
+        let cutoff = cutoff(now, self.min_age_days);
+        self.app_caches()
+            .into_iter()
+            .filter(|path| cutoff.is_none_or(|cutoff| last_used(path) < cutoff))
+            .collect()
+    }
+
+    fn detect_at(&self, now: SystemTime) -> Vec<DetectedSystemResource> {
+        if self.existing_root().is_none() {
+            return Vec::new();
+        }
+
+        let targets = self.targets(now);
+        vec![DetectedSystemResource {
+            resource_id: "xdg-cache".to_string(),
+            display_name: "Application Caches".to_string(),
+            category: "xdg-cache".to_string(),
+            size: apparent_size(&targets),
+            description: format!("Per-application caches{}", unused_for(self.min_age_days)),
+            item_count: Some(targets.len() as u64),
+        }]
+    }
+}
+
+impl SystemCleaner for XdgCacheCleaner {
+    fn id(&self) -> &'static str {
+        "xdg-cache"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Application Caches"
+    }
+
+    fn is_available(&self) -> bool {
+        self.existing_root().is_some()
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        self.detect_at(SystemTime::now())
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        remove_paths(resource, &self.targets(SystemTime::now()))
+    }
+
+    fn list_items(&self, _resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        let mut items: Vec<_> = self
+            .app_caches()
+            .into_iter()
+            .map(|path| {
+                let name = file_name(&path);
+                SystemResourceItem {
+                    id: name.clone(),
+                    name,
+                    size: apparent_size(std::slice::from_ref(&path)),
+                    last_used: Some(last_used(&path)),
+                }
+            })
+            .collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items
+    }
+
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        let path = self
+            .app_caches()
+            .into_iter()
+            .find(|path| file_name(path) == item.id);
+        match path {
+            Some(path) => remove_paths(resource, &[path]),
+            None => no_such_item(resource, item),
+        }
+    }
+}
+
+/// Cleaner for cached thumbnails, pruned by when they were last read.
+pub struct ThumbnailCleaner {
+    dirs: Vec<PathBuf>,
+    max_age_days: u32,
+}
+
+impl ThumbnailCleaner {
+    fn existing_dirs(&self) -> Vec<PathBuf> {
+        self.dirs.iter().filter(|d| d.is_dir()).cloned().collect()
+    }
+
+    /// Thumbnails older than the age limit.
+    fn stale(&self, now: SystemTime) -> Vec<PathBuf> {
+        let cutoff = cutoff(now, Some(self.max_age_days));
+        self.existing_dirs()
+            .iter()
+            .flat_map(|dir| WalkDir::new(dir).into_iter().flatten())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|path| cutoff.is_none_or(|cutoff| last_used(path) < cutoff))
+            .collect()
+    }
+
+    fn detect_at(&self, now: SystemTime) -> Vec<DetectedSystemResource> {
+        if self.existing_dirs().is_empty() {
+            return Vec::new();
+        }
+
+        let stale = self.stale(now);
+        vec![DetectedSystemResource {
+            resource_id: "thumbnails".to_string(),
+            display_name: "Thumbnail Cache".to_string(),
+            category: "thumbnails".to_string(),
+            size: apparent_size(&stale),
+            description: format!("Thumbnails unused for {}+ days", self.max_age_days),
+            item_count: Some(stale.len() as u64),
+        }]
+    }
+}
+
+impl SystemCleaner for ThumbnailCleaner {
+    fn id(&self) -> &'static str {
+        "thumbnails"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Thumbnail Cache"
+    }
+
+    fn is_available(&self) -> bool {
+        !self.existing_dirs().is_empty()
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        self.detect_at(SystemTime::now())
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        remove_paths(resource, &self.stale(SystemTime::now()))
+    }
+}
+
+/// Cleaner for the home Trash.
+///
+/// Each path in `files/` is one item, named after where it was trashed from.
+/// Removing it also removes its `.trashinfo`. Entries `clean --quarantine`
+/// moved into the Trash stay out of reach until the quarantine purges them,
+/// and `clean` removes only the entries the last `detect` reported.
+pub struct TrashCleaner {
+    trash: Option<Trash>,
+    /// Index of quarantined entries, some of which may live in the Trash
+    quarantine: Option<Quarantine>,
+    min_age_days: Option<u32>,
+    /// Stored paths of the entries reported by the last `detect`
+    detected: Mutex<Option<HashSet<PathBuf>>>,
+}
+
+impl TrashCleaner {
+    pub fn new(
+        trash: Option<Trash>,
+        quarantine: Option<Quarantine>,
+        min_age_days: Option<u32>,
+    ) -> Self {
+        Self {
+            trash,
+            quarantine,
+            min_age_days,
+            detected: Mutex::new(None),
+        }
+    }
+
+    fn existing_trash(&self) -> Option<&Trash> {
+        self.trash.as_ref().filter(|t| t.root().is_dir())
+    }
+
+    /// Trashed entries that the quarantine index doesn't claim.
+    fn entries(&self) -> Vec<TrashedEntry> {
+        let Some(trash) = self.existing_trash() else {
+            return Vec::new();
+        };
+        let quarantined: HashSet<PathBuf> = match self.quarantine.as_ref().map(Quarantine::list) {
+            Some(Ok(entries)) => entries.into_iter().map(|e| e.stored_path).collect(),
+            Some(Err(e)) => {
+                tracing::warn!(error = %e, "Cannot read the quarantine index");
+                HashSet::new()
+            }
+            None => HashSet::new(),
+        };
+        trash
+            .entries()
+            .into_iter()
+            .filter(|entry| !quarantined.contains(&entry.stored))
+            .collect()
+    }
+
+    /// Trashed entries deleted longer ago than the age limit, if any. Entries
+    /// without a deletion date are judged by their modification time.
+    fn targets(&self, now: SystemTime) -> Vec<TrashedEntry> {
+        let cutoff = cutoff(now, self.min_age_days);
+        self.entries()
+            .into_iter()
+            .filter(|entry| cutoff.is_none_or(|cutoff| deleted_at(entry) < cutoff))
+            .collect()
+    }
+
+    fn detect_at(&self, now: SystemTime) -> Vec<DetectedSystemResource> {
+        if self.existing_trash().is_none() {
+            return Vec::new();
+        }
+
+        let targets = self.targets(now);
+        let paths: Vec<PathBuf> = targets.iter().map(|e| e.stored.clone()).collect();
+        *self.detected.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some(paths.iter().cloned().collect());
+        let age = match self.min_age_days {
+            Some(days) => format!(" deleted {}+ days ago", days),
+            None => String::new(),
+        };
+        vec![DetectedSystemResource {
+            resource_id: "trash".to_string(),
+            display_name: "Trash".to_string(),
+            category: "trash".to_string(),
+            size: apparent_size(&paths),
+            description: format!("Trashed files{}", age),
+            item_count: Some(targets.len() as u64),
+        }]
+    }
+
+    fn remove_entries(
+        &self,
+        resource: &DetectedSystemResource,
+        entries: &[TrashedEntry],
+    ) -> SystemCleanResult {
+        let paths: Vec<PathBuf> = entries.iter().map(|e| e.stored.clone()).collect();
+        let result = remove_paths(resource, &paths);
+        if let Some(trash) = self.existing_trash() {
+            for path in paths.iter().filter(|p| p.symlink_metadata().is_err()) {
+                trash.remove_info(path);
+            }
+        }
+        result
+    }
+}
+
+impl SystemCleaner for TrashCleaner {
+    fn id(&self) -> &'static str {
+        "trash"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Trash"
+    }
+
+    fn is_available(&self) -> bool {
+        self.existing_trash().is_some()
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        self.detect_at(SystemTime::now())
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        let mut targets = self.targets(SystemTime::now());
+        // Entries trashed since detection weren't reported, so they stay
+        if let Some(detected) = &*self.detected.lock().unwrap_or_else(|e| e.into_inner()) {
+            targets.retain(|entry| detected.contains(&entry.stored));
+        }
+        self.remove_entries(resource, &targets)
+    }
+
+    fn list_items(&self, _resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        let mut items: Vec<_> = self
+            .entries()
+            .into_iter()
+            .map(|entry| SystemResourceItem {
+                id: file_name(&entry.stored),
+                name: match &entry.original {
+                    Some(original) => original.display().to_string(),
+                    None => file_name(&entry.stored),
+                },
+                size: apparent_size(std::slice::from_ref(&entry.stored)),
+                last_used: Some(deleted_at(&entry)),
+            })
+            .collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items
+    }
+
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        let entry = self
+            .entries()
+            .into_iter()
+            .find(|e| file_name(&e.stored) == item.id);
+        match entry {
+            Some(entry) => self.remove_entries(resource, &[entry]),
+            None => no_such_item(resource, item),
+        }
+    }
+}
+
+/// Cleaner for Flatpak runtimes that no installed app uses.
+///
+/// Detection approximates what `flatpak uninstall --unused` removes: runtimes
+/// that are neither an app's runtime nor an extension of one or of an app.
+/// Cleaning runs that command, so Flatpak has the last word.
+pub struct FlatpakCleaner;
+
+impl SystemCleaner for FlatpakCleaner {
+    fn id(&self) -> &'static str {
+        "flatpak"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Flatpak"
+    }
+
+    fn is_available(&self) -> bool {
+        run_quietly(&["flatpak", "--version"])
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        let unused = list_unused_runtimes();
+        vec![DetectedSystemResource {
+            resource_id: "flatpak-unused".to_string(),
+            display_name: "Flatpak Unused Runtimes".to_string(),
+            category: "flatpak".to_string(),
+            size: unused.iter().map(|r| r.size).sum(),
+            description: "Runtimes and extensions no installed app uses".to_string(),
+            item_count: Some(unused.len() as u64),
+        }]
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+
+        // Runtimes share objects in the OSTree repo, so only the free space
+        // on its filesystem tells what an uninstall freed
+        let probe = dirs::data_local_dir()
+            .map(|d| d.join("flatpak"))
+            .filter(|d| d.is_dir())
+            .map(|d| SpaceProbe::start(&[], &d));
+        let result = flatpak(&["uninstall", "--unused", "-y", "--noninteractive"]);
+        command_result(resource, result, resource.size, |_| {
+            probe.and_then(|p| p.freed())
+        })
+    }
+
+    fn list_items(&self, _resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        let mut items: Vec<_> = list_unused_runtimes()
+            .into_iter()
+            .map(|runtime| SystemResourceItem {
+                id: runtime.reference.clone(),
+                name: runtime.reference,
+                size: runtime.size,
+                last_used: None,
+            })
+            .collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items
+    }
+
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        let result = flatpak(&["uninstall", "-y", "--noninteractive", &item.id]);
+        command_result(resource, result, item.size, |_| None)
+    }
+}
+
+/// An installed Flatpak runtime.
+#[derive(Debug, Clone, PartialEq)]
+struct FlatpakRuntime {
+    /// `name/arch/branch`
+    reference: String,
+    size: u64,
+}
+
+fn flatpak(args: &[&str]) -> io::Result<Output> {
+    Command::new("flatpak")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+}
+
+/// Unused runtimes from `flatpak list`.
+fn list_unused_runtimes() -> Vec<FlatpakRuntime> {
+    let runtimes = command_stdout("flatpak", &["list", "--runtime", "--columns=ref,size"]);
+    let apps = command_stdout(
+        "flatpak",
+        &["list", "--app", "--columns=application,runtime"],
+    );
+    match (runtimes, apps) {
+        (Some(runtimes), Some(apps)) => parse_unused_runtimes(&runtimes, &apps),
+        _ => Vec::new(),
+    }
+}
+
+/// Runtimes in `runtimes` (`ref<TAB>size` lines) that no app in `apps`
+/// (`application<TAB>runtime` lines) uses. Extensions such as
+/// `org.freedesktop.Platform.GL.default` or `org.mozilla.firefox.Locale`
+/// count as used when the runtime or app they extend is.
+fn parse_unused_runtimes(runtimes: &str, apps: &str) -> Vec<FlatpakRuntime> {
+    let name = |reference: &str| reference.split('/').next().unwrap_or("").to_string();
+    let mut used: HashSet<String> = HashSet::new();
+    let mut extended: HashSet<String> = HashSet::new();
+    for line in apps.lines() {
+        let mut columns = line.split('\t').map(str::trim);
+        let (Some(app), Some(runtime)) = (columns.next(), columns.next()) else {
+            continue;
+        };
+        extended.insert(app.to_string());
+        extended.insert(name(runtime));
+        used.insert(runtime.trim_start_matches("runtime/").to_string());
+    }
+
+    runtimes
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t').map(str::trim);
+            let reference = columns.next()?.trim_start_matches("runtime/");
+            if !reference.contains('/') {
+                return None;
+            }
+            // Some locales print a decimal comma
+            let size = parse_docker_size(&columns.next().unwrap_or("").replace(',', "."));
+            Some(FlatpakRuntime {
+                reference: reference.to_string(),
+                size,
+            })
+        })
+        .filter(|runtime| {
+            let runtime_name = name(&runtime.reference);
+            !used.contains(&runtime.reference)
+                && !extended
+                    .iter()
+                    .any(|base| runtime_name.starts_with(&format!("{}.", base)))
+        })
+        .collect()
+}
+
+/// Cleaner for the disabled revisions snapd keeps after a refresh.
+///
+/// Each revision is removed with `snap remove <name> --revision=<rev>`, which
+/// needs root.
+pub struct SnapCleaner {
+    snaps_dir: PathBuf,
+}
+
+impl SnapCleaner {
+    fn disabled_revisions(&self) -> Vec<SnapRevision> {
+        command_stdout("snap", &["list", "--all"])
+            .map(|out| parse_disabled_revisions(&out))
+            .unwrap_or_default()
+    }
+
+    /// The squashfs image of a revision.
+    fn image(&self, revision: &SnapRevision) -> PathBuf {
+        self.snaps_dir
+            .join(format!("{}_{}.snap", revision.name, revision.revision))
+    }
+
+    fn remove_revisions(
+        &self,
+        resource: &DetectedSystemResource,
+        revisions: &[SnapRevision],
+    ) -> SystemCleanResult {
+        if let Some(reason) = missing_privilege(std::slice::from_ref(&self.snaps_dir)) {
+            return SystemCleanResult::Skipped {
+                resource: resource.clone(),
+                reason,
+            };
+        }
+
+        let images: Vec<PathBuf> = revisions.iter().map(|r| self.image(r)).collect();
+        let usage = reclaimable_usage(&images);
+        for revision in revisions {
+            let result = Command::new("snap")
+                .args(["remove", &revision.name])
+                .arg(format!("--revision={}", revision.revision))
+                .stdin(Stdio::null())
+                .output();
+            let failed = command_result(resource, result, 0, |_| None);
+            if let SystemCleanResult::Failed { .. } = failed {
+                return failed;
+            }
+        }
+
+        SystemCleanResult::Success {
+            resource: resource.clone(),
+            freed_bytes: usage.saturating_sub(reclaimable_usage(&images)),
+            estimated_bytes: apparent_size(&images),
+        }
+    }
+}
+
+impl SystemCleaner for SnapCleaner {
+    fn id(&self) -> &'static str {
+        "snap"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Snap"
+    }
+
+    fn is_available(&self) -> bool {
+        self.snaps_dir.is_dir() && run_quietly(&["snap", "version"])
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        let revisions = self.disabled_revisions();
+        let images: Vec<PathBuf> = revisions.iter().map(|r| self.image(r)).collect();
+        vec![DetectedSystemResource {
+            resource_id: "snap-disabled".to_string(),
+            display_name: "Snap Disabled Revisions".to_string(),
+            category: "snap".to_string(),
+            size: apparent_size(&images),
+            description: format!(
+                "Old revisions kept after refreshes{}",
+                privilege_note(std::slice::from_ref(&self.snaps_dir))
+            ),
+            item_count: Some(revisions.len() as u64),
+        }]
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        self.remove_revisions(resource, &self.disabled_revisions())
+    }
+
+    fn list_items(&self, _resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        let mut items: Vec<_> = self
+            .disabled_revisions()
+            .into_iter()
+            .map(|revision| {
+                let image = self.image(&revision);
+                SystemResourceItem {
+                    id: format!("{}_{}", revision.name, revision.revision),
+                    name: format!("{} (revision {})", revision.name, revision.revision),
+                    size: apparent_size(std::slice::from_ref(&image)),
+                    last_used: fs::metadata(&image).and_then(|m| m.modified()).ok(),
+                }
+            })
+            .collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items
+    }
+
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        let revision = self
+            .disabled_revisions()
+            .into_iter()
+            .find(|r| format!("{}_{}", r.name, r.revision) == item.id);
+        match revision {
+            Some(revision) => self.remove_revisions(resource, &[revision]),
+            None => no_such_item(resource, item),
+        }
+    }
+}
+
+/// An installed snap revision.
+#[derive(Debug, Clone, PartialEq)]
+struct SnapRevision {
+    name: String,
+    revision: String,
+}
+
+/// Disabled revisions in `snap list --all` output, whose last column holds
+/// comma-separated notes such as `base,disabled`.
+fn parse_disabled_revisions(output: &str) -> Vec<SnapRevision> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let notes = columns.last()?;
+            if columns.len() < 4 || !notes.split(',').any(|n| n == "disabled") {
+                return None;
+            }
+            Some(SnapRevision {
+                name: columns[0].to_string(),
+                revision: columns[2].to_string(),
+            })
+        })
+        .collect()
+}
+
+fn no_such_item(resource: &DetectedSystemResource, item: &SystemResourceItem) -> SystemCleanResult {
+    SystemCleanResult::Failed {
+        resource: resource.clone(),
+        error: format!("No item '{}' in {}", item.id, resource.resource_id),
+    }
+}
+
+/// The time `days` before `now`.
+fn cutoff(now: SystemTime, days: Option<u32>) -> Option<SystemTime> {
+    days.map(|days| now - Duration::from_secs(u64::from(days) * 86400))
+}
+
+/// Description suffix for an age limit.
+fn unused_for(min_age_days: Option<u32>) -> String {
+    match min_age_days {
+        Some(days) => format!(" unused for {}+ days", days),
+        None => String::new(),
+    }
+}
+
+fn deleted_at(entry: &TrashedEntry) -> SystemTime {
+    entry.deleted_at.unwrap_or_else(|| {
+        fs::symlink_metadata(&entry.stored)
+            .and_then(|m| m.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Delete files and directories, measuring the space freed. Paths that are
+/// already gone are ignored.
+fn remove_paths(resource: &DetectedSystemResource, paths: &[PathBuf]) -> SystemCleanResult {
+    let usage = reclaimable_usage(paths);
+    for path in paths {
+        let removed = match path.symlink_metadata() {
+            Ok(meta) if meta.is_dir() => fs::remove_dir_all(path),
+            Ok(_) => fs::remove_file(path),
+            Err(e) => Err(e),
+        };
+        match removed {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return SystemCleanResult::Failed {
+                    resource: resource.clone(),
+                    error: format!("Failed to delete {}: {}", path.display(), e),
+                }
+            }
+        }
+    }
+
+    SystemCleanResult::Success {
+        resource: resource.clone(),
+        freed_bytes: usage.saturating_sub(reclaimable_usage(paths)),
+        estimated_bytes: resource.size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::test_util::write_file;
+    use tempfile::TempDir;
+
+    fn setup_cache() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        write_file(&tmp.path().join("mozilla/firefox/cache2/entry"), 3000, 1);
+        write_file(&tmp.path().join("old-app/data.bin"), 1000, 90);
+        write_file(&tmp.path().join("fontconfig-stamp"), 10, 90);
+        write_file(&tmp.path().join("thumbnails/normal/a.png"), 500, 90);
+        write_file(&tmp.path().join("pip/http/b"), 500, 90);
+        tmp
+    }
+
+    #[test]
+    fn test_xdg_cache_breaks_down_per_application() {
+        let tmp = setup_cache();
+        let cleaner = XdgCacheCleaner {
+            root: Some(tmp.path().to_path_buf()),
+            min_age_days: None,
+        };
+
+        let resource = cleaner.detect_at(SystemTime::now()).remove(0);
+        assert_eq!(resource.size, 4010);
+        assert_eq!(resource.item_count, Some(3));
+
+        let items = cleaner.list_items(&resource);
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["mozilla", "old-app", "fontconfig-stamp"]);
+        assert_eq!(items[0].size, 3000);
+
+        let result = cleaner.remove_item(&resource, &items[1]);
+        assert!(matches!(result, SystemCleanResult::Success { .. }));
+        assert!(!tmp.path().join("old-app").exists());
+        assert!(tmp.path().join("mozilla").exists());
+
+        let escape = SystemResourceItem {
+            id: "../outside".to_string(),
+            ..items[0].clone()
+        };
+        let result = cleaner.remove_item(&resource, &escape);
+        assert!(matches!(result, SystemCleanResult::Failed { .. }));
+    }
+
+    #[test]
+    fn test_xdg_cache_clean_by_age_keeps_claimed_dirs() {
+        let tmp = setup_cache();
+        let cleaner = XdgCacheCleaner {
+            root: Some(tmp.path().to_path_buf()),
+            min_age_days: Some(30),
+        };
+        let resource = cleaner.detect_at(SystemTime::now()).remove(0);
+        assert_eq!(resource.item_count, Some(2));
+
+        let result = cleaner.clean(&resource, false);
+        assert!(matches!(result, SystemCleanResult::Success { .. }));
+        assert!(!tmp.path().join("old-app").exists());
+        assert!(!tmp.path().join("fontconfig-stamp").exists());
+        assert!(tmp.path().join("mozilla/firefox/cache2/entry").exists());
+        assert!(tmp.path().join("thumbnails/normal/a.png").exists());
+        assert!(tmp.path().join("pip/http/b").exists());
+    }
+
+    #[test]
+    fn test_thumbnails_are_pruned_by_age() {
+        let tmp = TempDir::new().unwrap();
+        write_file(&tmp.path().join("normal/old.png"), 800, 45);
+        write_file(&tmp.path().join("large/recent.png"), 1200, 3);
+        let cleaner = ThumbnailCleaner {
+            dirs: vec![tmp.path().to_path_buf(), tmp.path().join("missing")],
+            max_age_days: THUMBNAIL_MAX_AGE_DAYS,
+        };
+
+        let resource = cleaner.detect_at(SystemTime::now()).remove(0);
+        assert_eq!(resource.size, 800);
+        assert_eq!(resource.description, "Thumbnails unused for 30+ days");
+
+        cleaner.clean(&resource, false);
+        assert!(!tmp.path().join("normal/old.png").exists());
+        assert!(tmp.path().join("large/recent.png").exists());
+    }
+
+    #[test]
+    fn test_trash_honors_deletion_dates() {
+        let tmp = TempDir::new().unwrap();
+        let trash = Trash::new(tmp.path().join("Trash"));
+        let days_ago = |days: u64| SystemTime::now() - Duration::from_secs(days * 86400);
+        for (name, days) in [("old.iso", 60), ("recent.txt", 2)] {
+            let path = tmp.path().join(name);
+            fs::write(&path, "x".repeat(100)).unwrap();
+            trash.put(&path, days_ago(days)).unwrap();
+        }
+        let cleaner = TrashCleaner::new(Some(trash), None, Some(30));
+
+        let resource = cleaner.detect_at(SystemTime::now()).remove(0);
+        assert_eq!(resource.item_count, Some(1));
+        assert_eq!(resource.description, "Trashed files deleted 30+ days ago");
+
+        let items = cleaner.list_items(&resource);
+        assert_eq!(items.len(), 2);
+        assert!(items
+            .iter()
+            .any(|i| i.name == tmp.path().join("old.iso").display().to_string()));
+
+        cleaner.clean(&resource, false);
+        let trash_dir = tmp.path().join("Trash");
+        assert!(!trash_dir.join("files/old.iso").exists());
+        assert!(!trash_dir.join("info/old.iso.trashinfo").exists());
+        assert!(trash_dir.join("files/recent.txt").exists());
+
+        let item = cleaner
+            .list_items(&resource)
+            .into_iter()
+            .find(|i| i.id == "recent.txt")
+            .unwrap();
+        let result = cleaner.remove_item(&resource, &item);
+        assert!(matches!(result, SystemCleanResult::Success { .. }));
+        assert!(!trash_dir.join("info/recent.txt.trashinfo").exists());
+    }
+
+    #[test]
+    fn test_trash_keeps_quarantined_and_undetected_entries() {
+        let tmp = TempDir::new().unwrap();
+        let trash_dir = tmp.path().join("Trash");
+        let trash = Trash::new(trash_dir.clone());
+        let quarantine = Quarantine::with_dirs(tmp.path().join("quarantine"), trash_dir.clone())
+            .without_holding();
+        for name in ["old.iso", "target"] {
+            fs::write(tmp.path().join(name), "x".repeat(100)).unwrap();
+        }
+        trash
+            .put(&tmp.path().join("old.iso"), SystemTime::now())
+            .unwrap();
+        quarantine.quarantine(&tmp.path().join("target")).unwrap();
+        let cleaner = TrashCleaner::new(Some(trash), Some(quarantine), None);
+
+        let resource = cleaner.detect_at(SystemTime::now()).remove(0);
+        assert_eq!(resource.item_count, Some(1));
+        assert_eq!(resource.size, 100);
+        let items = cleaner.list_items(&resource);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "old.iso");
+
+        let late = tmp.path().join("late.txt");
+        fs::write(&late, "x").unwrap();
+        Trash::new(trash_dir.clone())
+            .put(&late, SystemTime::now())
+            .unwrap();
+
+        cleaner.clean(&resource, false);
+        assert!(!trash_dir.join("files/old.iso").exists());
+        assert!(trash_dir.join("files/target").exists());
+        assert!(trash_dir.join("info/target.trashinfo").exists());
+        assert!(trash_dir.join("files/late.txt").exists());
+    }
+
+    #[test]
+    fn test_parse_unused_runtimes() {
+        let runtimes = "org.freedesktop.Platform/x86_64/23.08\t1.2 GB\n\
+                        org.freedesktop.Platform.GL.default/x86_64/23.08\t300.5 MB\n\
+                        org.gnome.Platform/x86_64/44\t1,1 GB\n\
+                        org.gnome.Platform.Locale/x86_64/44\t12.0 kB\n\
+                        org.mozilla.firefox.Locale/x86_64/stable\t20.0 MB\n\
+                        org.kde.Platform/x86_64/5.15-23.08\t900.0 MB\n";
+        let apps = "org.mozilla.firefox\torg.freedesktop.Platform/x86_64/23.08\n";
+
+        let unused = parse_unused_runtimes(runtimes, apps);
+        let refs: Vec<&str> = unused.iter().map(|r| r.reference.as_str()).collect();
+        assert_eq!(
+            refs,
+            vec![
+                "org.gnome.Platform/x86_64/44",
+                "org.gnome.Platform.Locale/x86_64/44",
+                "org.kde.Platform/x86_64/5.15-23.08"
+            ]
+        );
+        assert_eq!(unused[0].size, 1_100_000_000);
+        assert_eq!(unused[1].size, 12_000);
+    }
+
+    #[test]
+    fn test_parse_disabled_revisions() {
+        let output = "Name    Version    Rev    Tracking       Publisher     Notes\n\
+                      core20  20240227   2264   latest/stable  canonical✓    base,disabled\n\
+                      core20  20240416   2318   latest/stable  canonical✓    base\n\
+                      firefox 125.0.2-1  4173   latest/stable  mozilla✓      disabled\n\
+                      firefox 126.0-2    4259   latest/stable  mozilla✓      -\n";
+
+        let revisions = parse_disabled_revisions(output);
+        assert_eq!(
+            revisions,
+            vec![
+                SnapRevision {
+                    name: "core20".to_string(),
+                    revision: "2264".to_string(),
+                },
+                SnapRevision {
+                    name: "firefox".to_string(),
+                    revision: "4173".to_string(),
+                },
+            ]
+        );
+
+        let cleaner = SnapCleaner {
+            snaps_dir: PathBuf::from(SNAPS_DIR),
+        };
+        assert_eq!(
+            cleaner.image(&revisions[0]),
+            Path::new("/var/lib/snapd/snaps/core20_2264.snap")
+        );
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::cleaner::git::GitState;
+
 /// Represents a detected project with its metadata.
 #[derive(Debug, Clone)]
 pub struct DetectedProject {
@@ -15,6 +17,107 @@ pub struct DetectedProject {
     pub artifact_size: u64,
     /// List of artifact directories found.
     pub artifact_paths: Vec<PathBuf>,
+    /// Git state of the enclosing repository (`None` outside a repository
+    /// or when not checked).
+    pub git: Option<GitState>,
+    /// Artifacts of every detector that matched the directory, in detection
+    /// order (e.g. a Tauri app is both Cargo and npm). May be empty for a
+    /// project built by hand, whose only group is then the fields above.
+    pub artifact_groups: Vec<ArtifactGroup>,
+    /// Root of the nearest enclosing project, for a project found inside
+    /// another one by a nested scan (see `ScanOptions::nested`).
+    pub parent: Option<PathBuf>,
+    /// Other projects using some of this project's artifact directories
+    /// (e.g. workspaces pointing at one Cargo target dir). A shared
+    /// directory is only reported, and cleaned, with this project.
+    pub shared_with: Vec<PathBuf>,
+}
+
+/// The artifacts one project type claims in a project directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactGroup {
+    /// Type identifier (e.g., "npm").
+    pub project_type: String,
+    /// Human-readable name (e.g., "npm/Node.js").
+    pub display_name: String,
+    /// Total size of the group's artifact directories in bytes.
+    pub artifact_size: u64,
+    /// The group's artifact directories.
+    pub artifact_paths: Vec<PathBuf>,
+}
+
+impl DetectedProject {
+    /// Combine the artifact groups of every type found at `path`.
+    ///
+    /// The first group is the primary type, which the journal uses; the
+    /// display name lists all of them and the size and paths cover all of
+    /// them. Returns `None` without groups.
+    pub fn from_groups(path: PathBuf, groups: Vec<ArtifactGroup>) -> Option<Self> {
+        let primary = groups.first()?;
+        Some(Self {
+            path,
+            project_type: primary.project_type.clone(),
+            display_name: groups
+                .iter()
+                .map(|g| g.display_name.as_str())
+                .collect::<Vec<_>>()
+                .join(" + "),
+            artifact_size: groups.iter().map(|g| g.artifact_size).sum(),
+            artifact_paths: groups
+                .iter()
+                .flat_map(|g| g.artifact_paths.iter().cloned())
+                .collect(),
+            git: None,
+            artifact_groups: groups,
+            parent: None,
+            shared_with: Vec::new(),
+        })
+    }
+
+    /// The project's artifact groups, one per matching type.
+    pub fn groups(&self) -> Vec<ArtifactGroup> {
+        if !self.artifact_groups.is_empty() {
+            return self.artifact_groups.clone();
+        }
+        vec![ArtifactGroup {
+            project_type: self.project_type.clone(),
+            display_name: self.display_name.clone(),
+            artifact_size: self.artifact_size,
+            artifact_paths: self.artifact_paths.clone(),
+        }]
+    }
+
+    /// Whether more than one project type matched the directory.
+    pub fn is_composite(&self) -> bool {
+        self.artifact_groups.len() > 1
+    }
+
+    /// Ids of all matching types joined with `+` (e.g. `cargo+npm`).
+    pub fn type_label(&self) -> String {
+        if !self.is_composite() {
+            return self.project_type.clone();
+        }
+        self.artifact_groups
+            .iter()
+            .map(|g| g.project_type.as_str())
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    /// The part of this project that belongs to one of its groups.
+    pub fn for_group(&self, group: &ArtifactGroup) -> Self {
+        Self {
+            path: self.path.clone(),
+            project_type: group.project_type.clone(),
+            display_name: group.display_name.clone(),
+            artifact_size: group.artifact_size,
+            artifact_paths: group.artifact_paths.clone(),
+            git: self.git.clone(),
+            artifact_groups: vec![group.clone()],
+            parent: self.parent.clone(),
+            shared_with: self.shared_with.clone(),
+        }
+    }
 }
 
 /// Trait for project type detectors.
@@ -26,23 +129,32 @@ pub struct DetectedProject {
 /// - Providing the clean command (if a native one exists)
 pub trait ProjectDetector: Send + Sync {
     /// Unique identifier for this project type (e.g., "cargo").
-    fn id(&self) -> &'static str;
+    fn id(&self) -> &str;
 
     /// Human-readable name (e.g., "Rust/Cargo").
-    fn display_name(&self) -> &'static str;
+    fn display_name(&self) -> &str;
 
     /// Files that indicate this project type exists.
     ///
     /// Returns true if ANY of these files/directories exist.
-    fn detection_files(&self) -> &'static [&'static str];
+    fn detection_files(&self) -> Vec<&str>;
 
     /// Directories containing build artifacts.
-    fn artifact_dirs(&self) -> &'static [&'static str];
+    fn artifact_dirs(&self) -> Vec<&str>;
+
+    /// Artifact directories found by reading the project at `path`, relative
+    /// to it, in addition to `artifact_dirs` (e.g. the output directory of
+    /// every module of a multi-module build).
+    ///
+    /// Default implementation discovers nothing.
+    fn discover_artifact_dirs(&self, _path: &Path) -> Vec<PathBuf> {
+        Vec::new()
+    }
 
     /// Command to clean the project.
     ///
     /// Returns `None` if direct deletion should be used instead.
-    fn clean_command(&self) -> Option<&'static str>;
+    fn clean_command(&self) -> Option<&str>;
 
     /// Check if this project type exists at the given path.
     ///
@@ -51,15 +163,24 @@ pub trait ProjectDetector: Send + Sync {
         self.detection_files().iter().any(|f| path.join(f).exists())
     }
 
-    /// Get existing artifact directories at the given path.
+    /// Get existing artifact directories at the given path, the fixed
+    /// `artifact_dirs` first and then the discovered ones.
     ///
-    /// Only returns directories that actually exist.
+    /// Only returns directories that actually exist, each once.
     fn find_artifacts(&self, path: &Path) -> Vec<PathBuf> {
-        self.artifact_dirs()
-            .iter()
-            .map(|d| path.join(d))
-            .filter(|p| p.exists())
-            .collect()
+        let mut artifacts: Vec<PathBuf> = Vec::new();
+        let candidates = self
+            .artifact_dirs()
+            .into_iter()
+            .map(PathBuf::from)
+            .chain(self.discover_artifact_dirs(path));
+        for candidate in candidates {
+            let candidate = path.join(candidate);
+            if candidate.exists() && !artifacts.contains(&candidate) {
+                artifacts.push(candidate);
+            }
+        }
+        artifacts
     }
 }
 
@@ -70,23 +191,23 @@ mod tests {
     struct MockDetector;
 
     impl ProjectDetector for MockDetector {
-        fn id(&self) -> &'static str {
+        fn id(&self) -> &str {
             "mock"
         }
 
-        fn display_name(&self) -> &'static str {
+        fn display_name(&self) -> &str {
             "Mock Project"
         }
 
-        fn detection_files(&self) -> &'static [&'static str] {
-            &["mock.toml"]
+        fn detection_files(&self) -> Vec<&str> {
+            vec!["mock.toml"]
         }
 
-        fn artifact_dirs(&self) -> &'static [&'static str] {
-            &["build"]
+        fn artifact_dirs(&self) -> Vec<&str> {
+            vec!["build"]
         }
 
-        fn clean_command(&self) -> Option<&'static str> {
+        fn clean_command(&self) -> Option<&str> {
             Some("mock clean")
         }
     }
@@ -99,12 +220,50 @@ mod tests {
             display_name: "Mock".to_string(),
             artifact_size: 1024,
             artifact_paths: vec![PathBuf::from("/test/build")],
+            git: None,
+            artifact_groups: vec![],
+            parent: None,
+            shared_with: vec![],
         };
 
         assert_eq!(project.project_type, "mock");
         assert_eq!(project.artifact_size, 1024);
         assert_eq!(project.path, PathBuf::from("/test"));
         assert_eq!(project.artifact_paths.len(), 1);
+        assert!(!project.is_composite());
+        assert_eq!(project.groups()[0].artifact_paths, project.artifact_paths);
+    }
+
+    fn group(project_type: &str, display_name: &str, size: u64, dir: &str) -> ArtifactGroup {
+        ArtifactGroup {
+            project_type: project_type.to_string(),
+            display_name: display_name.to_string(),
+            artifact_size: size,
+            artifact_paths: vec![PathBuf::from("/app").join(dir)],
+        }
+    }
+
+    #[test]
+    fn test_composite_project_from_groups() {
+        let groups = vec![
+            group("cargo", "Rust/Cargo", 1000, "target"),
+            group("npm", "npm/Node.js", 500, "node_modules"),
+        ];
+        let project = DetectedProject::from_groups(PathBuf::from("/app"), groups).unwrap();
+
+        assert!(project.is_composite());
+        assert_eq!(project.project_type, "cargo");
+        assert_eq!(project.type_label(), "cargo+npm");
+        assert_eq!(project.display_name, "Rust/Cargo + npm/Node.js");
+        assert_eq!(project.artifact_size, 1500);
+        assert_eq!(project.artifact_paths.len(), 2);
+
+        let npm = project.for_group(&project.artifact_groups[1]);
+        assert_eq!(npm.project_type, "npm");
+        assert_eq!(npm.artifact_paths, vec![PathBuf::from("/app/node_modules")]);
+        assert!(!npm.is_composite());
+
+        assert!(DetectedProject::from_groups(PathBuf::from("/app"), vec![]).is_none());
     }
 
     #[test]
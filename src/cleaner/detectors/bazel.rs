@@ -9,23 +9,23 @@ use crate::cleaner::ProjectDetector;
 pub struct BazelDetector;
 
 impl ProjectDetector for BazelDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "bazel"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "Bazel"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["WORKSPACE", "WORKSPACE.bazel"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["WORKSPACE", "WORKSPACE.bazel"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &[] // bazel clean handles it
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec![] // bazel clean handles it
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         Some("bazel clean --expunge")
     }
 }
@@ -1,35 +1,192 @@
 //! Cargo/Rust project detector.
 
 use crate::cleaner::ProjectDetector;
+use crate::scanner::ExcludeMatcher;
+use std::path::{Component, Path, PathBuf};
+
+/// Environment variables setting the target directory, in Cargo's order.
+const TARGET_DIR_VARS: [&str; 2] = ["CARGO_TARGET_DIR", "CARGO_BUILD_TARGET_DIR"];
 
 /// Detector for Rust/Cargo projects.
 ///
 /// Identifies projects by the presence of `Cargo.toml` and cleans
-/// the `target/` directory using `cargo clean`.
+/// the `target/` directory using `cargo clean`. The target directory Cargo
+/// actually builds into is found too: the one set by `CARGO_TARGET_DIR` or
+/// `build.target-dir` in a `.cargo/config.toml`, or the workspace root's
+/// `target/` for a workspace member.
 pub struct CargoDetector;
 
 impl ProjectDetector for CargoDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "cargo"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "Rust/Cargo"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["Cargo.toml"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["Cargo.toml"]
+    }
+
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec!["target"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &["target"]
+    fn discover_artifact_dirs(&self, path: &Path) -> Vec<PathBuf> {
+        let home = dirs::home_dir();
+        let target = target_dir(path, |key| std::env::var(key).ok(), home.clone());
+        if !is_deletable_target(&target, path, home.as_deref()) {
+            tracing::warn!(
+                target_dir = %target.display(),
+                project = %path.display(),
+                "Ignoring a Cargo target directory that contains the project or the home directory"
+            );
+            return Vec::new();
+        }
+        // `target/` itself is one of the `artifact_dirs`
+        if target == path.join("target") {
+            Vec::new()
+        } else {
+            vec![target]
+        }
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         Some("cargo clean")
     }
 }
 
+/// The target directory Cargo uses for the package or workspace at
+/// `project`, as `cargo` run there would resolve it.
+fn target_dir(
+    project: &Path,
+    var: impl Fn(&str) -> Option<String>,
+    home: Option<PathBuf>,
+) -> PathBuf {
+    if let Some(dir) = TARGET_DIR_VARS
+        .iter()
+        .find_map(|key| var(key).filter(|v| !v.is_empty()))
+    {
+        // Relative to the directory cargo runs in
+        return normalize(&project.join(dir));
+    }
+
+    let cargo_home = var("CARGO_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|h| h.join(".cargo")));
+    let dir = configured_target_dir(project, cargo_home)
+        .unwrap_or_else(|| workspace_root(project).join("target"));
+    normalize(&dir)
+}
+
+/// Whether `target` can be deleted without taking `project` or the home
+/// directory with it. A target dir set to `.`, `..` or `/` is refused, as
+/// custom `artifact_dirs` outside the project are.
+fn is_deletable_target(target: &Path, project: &Path, home: Option<&Path>) -> bool {
+    let inside_target = |path: &Path| normalize(path).starts_with(target);
+    target.parent().is_some() && !inside_target(project) && !home.is_some_and(inside_target)
+}
+
+/// Resolve `.` and `..` without touching the filesystem, so a target dir
+/// reached through different relative paths compares equal.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// `build.target-dir` from the nearest `.cargo/config.toml` at or above
+/// `project`, then from the one in Cargo's home. A relative directory is
+/// relative to the directory holding `.cargo`.
+fn configured_target_dir(project: &Path, cargo_home: Option<PathBuf>) -> Option<PathBuf> {
+    let config_dirs = project
+        .ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .chain(cargo_home);
+
+    for config_dir in config_dirs {
+        // `config` is the pre-1.39 name of `config.toml`
+        let Some(config) = ["config.toml", "config"]
+            .iter()
+            .find_map(|name| read_toml(&config_dir.join(name)))
+        else {
+            continue;
+        };
+        let target_dir = config
+            .get("build")
+            .and_then(|build| build.get("target-dir"))
+            .and_then(|dir| dir.as_str());
+        if let Some(dir) = target_dir {
+            let base = config_dir.parent().unwrap_or(&config_dir);
+            return Some(base.join(dir));
+        }
+    }
+    None
+}
+
+/// The root of the workspace `project` belongs to: the package's
+/// `package.workspace`, or the nearest enclosing manifest with a
+/// `[workspace]` table whose `members` globs list it and that doesn't
+/// `exclude` it. Like Cargo, only the nearest workspace is considered; a
+/// package it doesn't list, or outside any workspace, is its own root.
+fn workspace_root(project: &Path) -> PathBuf {
+    if let Some(manifest) = read_toml(&project.join("Cargo.toml")) {
+        if manifest.contains_key("workspace") {
+            return project.to_path_buf();
+        }
+        let explicit = manifest
+            .get("package")
+            .and_then(|package| package.get("workspace"))
+            .and_then(|root| root.as_str());
+        if let Some(root) = explicit {
+            return project.join(root);
+        }
+    }
+
+    for dir in project.ancestors().skip(1) {
+        let Some(workspace) =
+            read_toml(&dir.join("Cargo.toml")).and_then(|m| m.get("workspace").cloned())
+        else {
+            continue;
+        };
+        let member = project.strip_prefix(dir).unwrap_or(project);
+        let entries = |key: &str| -> Vec<String> {
+            workspace
+                .get(key)
+                .and_then(|entries| entries.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.as_str())
+                // Anchored to the workspace root
+                .map(|e| format!("/{}", e))
+                .collect()
+        };
+        let listed = ExcludeMatcher::new(&entries("members")).is_excluded(member, true);
+        let excluded = ExcludeMatcher::new(&entries("exclude")).is_excluded(member, true);
+        return if listed && !excluded {
+            dir.to_path_buf()
+        } else {
+            project.to_path_buf()
+        };
+    }
+    project.to_path_buf()
+}
+
+/// Read and parse a TOML file, if it exists and is valid.
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,6 +234,159 @@ mod tests {
         assert!(artifacts[0].ends_with("target"));
     }
 
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_target_dir_from_environment() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("app");
+        let env = |key: &str| match key {
+            "CARGO_TARGET_DIR" => Some("/srv/targets/shared".to_string()),
+            "CARGO_BUILD_TARGET_DIR" => Some("ignored".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            target_dir(&project, env, None),
+            PathBuf::from("/srv/targets/shared")
+        );
+        let relative = |key: &str| (key == "CARGO_BUILD_TARGET_DIR").then(|| "out".to_string());
+        assert_eq!(target_dir(&project, relative, None), project.join("out"));
+        assert_eq!(target_dir(&project, no_env, None), project.join("target"));
+    }
+
+    #[test]
+    fn test_target_dir_from_nearest_cargo_config() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("work/app");
+        fs::create_dir_all(tmp.path().join("work/.cargo")).unwrap();
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(
+            tmp.path().join("work/.cargo/config.toml"),
+            "[build]\ntarget-dir = \"../cargo-target\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            target_dir(&project, no_env, None),
+            tmp.path().join("cargo-target")
+        );
+
+        // A closer config wins over the parent's, and Cargo's home comes last
+        fs::create_dir_all(project.join(".cargo")).unwrap();
+        fs::write(
+            project.join(".cargo/config"),
+            "[build]\ntarget-dir = \"/tmp/t\"",
+        )
+        .unwrap();
+        assert_eq!(target_dir(&project, no_env, None), PathBuf::from("/tmp/t"));
+
+        let home = TempDir::new().unwrap();
+        fs::create_dir_all(home.path().join(".cargo")).unwrap();
+        fs::write(
+            home.path().join(".cargo/config.toml"),
+            "[build]\ntarget-dir = \"t\"",
+        )
+        .unwrap();
+        let elsewhere = home.path().join("elsewhere");
+        assert_eq!(
+            target_dir(&elsewhere, no_env, Some(home.path().to_path_buf())),
+            home.path().join("t")
+        );
+    }
+
+    #[test]
+    fn test_target_dirs_holding_the_project_or_home_are_refused() {
+        let tmp = TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        let project = home.join("src/app");
+        fs::create_dir_all(project.join(".cargo")).unwrap();
+        fs::write(
+            project.join(".cargo/config.toml"),
+            "[build]\ntarget-dir = \".\"\n",
+        )
+        .unwrap();
+        let target = target_dir(&project, no_env, None);
+        assert_eq!(target, project);
+        assert!(!is_deletable_target(&target, &project, Some(&home)));
+
+        for dir in ["..", "../..", "/"] {
+            let env = |key: &str| (key == "CARGO_TARGET_DIR").then(|| dir.to_string());
+            let target = target_dir(&project, env, None);
+            assert!(
+                !is_deletable_target(&target, &project, Some(&home)),
+                "{}",
+                dir
+            );
+        }
+        // The home directory is kept even for a project outside it
+        assert!(!is_deletable_target(
+            &home,
+            &tmp.path().join("elsewhere"),
+            Some(&home)
+        ));
+
+        for target in [
+            project.join("target"),
+            home.join("src/target"),
+            PathBuf::from("/srv/t"),
+        ] {
+            assert!(is_deletable_target(&target, &project, Some(&home)));
+        }
+    }
+
+    #[test]
+    fn test_workspace_members_use_the_workspace_target() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/standalone\"]\n",
+        );
+        write("crates/core/Cargo.toml", "[package]\nname = \"core\"\n");
+        write(
+            "crates/standalone/Cargo.toml",
+            "[package]\nname = \"standalone\"\n",
+        );
+        write(
+            "tools/gen/Cargo.toml",
+            "[package]\nname = \"gen\"\nworkspace = \"../..\"\n",
+        );
+        write("tools/lint/Cargo.toml", "[package]\nname = \"lint\"\n");
+        fs::create_dir_all(root.join("target")).unwrap();
+
+        assert_eq!(workspace_root(root), root);
+        assert_eq!(workspace_root(&root.join("crates/core")), root);
+        assert_eq!(
+            workspace_root(&root.join("crates/standalone")),
+            root.join("crates/standalone")
+        );
+        assert_eq!(
+            workspace_root(&root.join("tools/gen")),
+            root.join("tools/gen/../..")
+        );
+        // Not listed in `members`, so not part of the workspace
+        assert_eq!(
+            workspace_root(&root.join("tools/lint")),
+            root.join("tools/lint")
+        );
+        assert_eq!(
+            target_dir(&root.join("tools/gen"), no_env, None),
+            root.join("target")
+        );
+        assert_eq!(
+            target_dir(&root.join("crates/core"), no_env, None),
+            root.join("target")
+        );
+    }
+
     #[test]
     fn test_cargo_no_artifacts_without_target() {
         let tmp = TempDir::new().unwrap();
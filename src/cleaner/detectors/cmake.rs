@@ -11,23 +11,23 @@ use std::path::Path;
 pub struct CMakeDetector;
 
 impl ProjectDetector for CMakeDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "cmake"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "CMake"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["CMakeLists.txt"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["CMakeLists.txt"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &["build"]
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec!["build"]
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         None // Direct deletion
     }
 
@@ -0,0 +1,307 @@
+//! Project detector defined in the configuration file.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::cleaner::ProjectDetector;
+use crate::config::ProjectTypeConfig;
+
+use super::all_detectors;
+
+/// Detector built from a `[project_types.<id>]` config section.
+///
+/// Either defines a new project type or overrides the fields of a built-in
+/// detector. An override that keeps the built-in detection files also keeps
+/// its detection logic (e.g. CMake's "CMakeLists.txt and build/" rule), and
+/// one that keeps the built-in artifact directories also keeps its artifact
+/// discovery (e.g. Gradle's module `build/` directories).
+pub struct CustomDetector {
+    id: String,
+    display_name: String,
+    detection_files: Vec<String>,
+    artifact_dirs: Vec<String>,
+    clean_command: Option<String>,
+    /// Built-in detector whose `detect` is reused
+    base: Option<Arc<dyn ProjectDetector>>,
+    /// Built-in detector whose `discover_artifact_dirs` is reused
+    discovery: Option<Arc<dyn ProjectDetector>>,
+}
+
+impl CustomDetector {
+    /// Define a new project type.
+    pub fn new(id: &str, config: &ProjectTypeConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            display_name: config
+                .display_name
+                .clone()
+                .unwrap_or_else(|| id.to_string()),
+            detection_files: config.detection_files.clone().unwrap_or_default(),
+            artifact_dirs: config.artifact_dirs.clone().unwrap_or_default(),
+            clean_command: config
+                .clean_command
+                .clone()
+                .filter(|c| !c.trim().is_empty()),
+            base: None,
+            discovery: None,
+        }
+    }
+
+    /// Override the fields of a built-in detector that are set in `config`.
+    pub fn overriding(base: Arc<dyn ProjectDetector>, config: &ProjectTypeConfig) -> Self {
+        let to_strings = |v: Vec<&str>| v.into_iter().map(String::from).collect();
+        Self {
+            id: base.id().to_string(),
+            display_name: config
+                .display_name
+                .clone()
+                .unwrap_or_else(|| base.display_name().to_string()),
+            detection_files: config
+                .detection_files
+                .clone()
+                .unwrap_or_else(|| to_strings(base.detection_files())),
+            artifact_dirs: config
+                .artifact_dirs
+                .clone()
+                .unwrap_or_else(|| to_strings(base.artifact_dirs())),
+            clean_command: match &config.clean_command {
+                Some(c) => Some(c.clone()).filter(|c| !c.trim().is_empty()),
+                None => base.clean_command().map(String::from),
+            },
+            discovery: config.artifact_dirs.is_none().then(|| base.clone()),
+            base: config.detection_files.is_none().then_some(base),
+        }
+    }
+}
+
+impl ProjectDetector for CustomDetector {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn detection_files(&self) -> Vec<&str> {
+        self.detection_files.iter().map(String::as_str).collect()
+    }
+
+    fn artifact_dirs(&self) -> Vec<&str> {
+        self.artifact_dirs.iter().map(String::as_str).collect()
+    }
+
+    fn discover_artifact_dirs(&self, path: &Path) -> Vec<PathBuf> {
+        self.discovery
+            .as_ref()
+            .map(|base| base.discover_artifact_dirs(path))
+            .unwrap_or_default()
+    }
+
+    fn clean_command(&self) -> Option<&str> {
+        self.clean_command.as_deref()
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        match &self.base {
+            Some(base) => base.detect(path),
+            None => self.detection_files.iter().any(|f| path.join(f).exists()),
+        }
+    }
+}
+
+/// Built-in detectors with the `[project_types]` config applied.
+///
+/// Overridden built-ins keep their position; disabled ones are dropped.
+/// New project types come first, so they win over a built-in that
+/// recognizes the same directory.
+pub fn configured_detectors(
+    project_types: &BTreeMap<String, ProjectTypeConfig>,
+) -> Vec<Arc<dyn ProjectDetector>> {
+    let builtins: Vec<Arc<dyn ProjectDetector>> =
+        all_detectors().into_iter().map(Arc::from).collect();
+
+    let mut detectors: Vec<Arc<dyn ProjectDetector>> = project_types
+        .iter()
+        .filter(|(id, config)| config.enabled && !builtins.iter().any(|d| d.id() == *id))
+        .map(|(id, config)| Arc::new(CustomDetector::new(id, config)) as Arc<dyn ProjectDetector>)
+        .collect();
+
+    for builtin in builtins {
+        match project_types.get(builtin.id()) {
+            None => detectors.push(builtin),
+            Some(config) if !config.enabled => {}
+            Some(config) => detectors.push(Arc::new(CustomDetector::overriding(builtin, config))),
+        }
+    }
+    detectors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::detectors::{CMakeDetector, CargoDetector, GradleDetector};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn blaze() -> ProjectTypeConfig {
+        ProjectTypeConfig {
+            display_name: Some("Blaze".to_string()),
+            detection_files: Some(vec!["BLAZE".to_string()]),
+            artifact_dirs: Some(vec!["blaze-out".to_string()]),
+            clean_command: Some("blaze clean".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn ids(detectors: &[Arc<dyn ProjectDetector>]) -> Vec<&str> {
+        detectors.iter().map(|d| d.id()).collect()
+    }
+
+    #[test]
+    fn test_custom_detector_properties() {
+        let detector = CustomDetector::new("blaze", &blaze());
+
+        assert_eq!(detector.id(), "blaze");
+        assert_eq!(detector.display_name(), "Blaze");
+        assert_eq!(detector.detection_files(), &["BLAZE"]);
+        assert_eq!(detector.artifact_dirs(), &["blaze-out"]);
+        assert_eq!(detector.clean_command(), Some("blaze clean"));
+    }
+
+    #[test]
+    fn test_custom_detector_defaults() {
+        let config = ProjectTypeConfig {
+            detection_files: Some(vec!["BUILD.mk".to_string()]),
+            artifact_dirs: Some(vec!["out".to_string()]),
+            clean_command: Some("  ".to_string()),
+            ..Default::default()
+        };
+        let detector = CustomDetector::new("mk", &config);
+
+        assert_eq!(detector.display_name(), "mk");
+        assert_eq!(detector.clean_command(), None);
+    }
+
+    #[test]
+    fn test_custom_detection_and_artifacts() {
+        let tmp = TempDir::new().unwrap();
+        let detector = CustomDetector::new("blaze", &blaze());
+        assert!(!detector.detect(tmp.path()));
+
+        fs::write(tmp.path().join("BLAZE"), "").unwrap();
+        fs::create_dir(tmp.path().join("blaze-out")).unwrap();
+
+        assert!(detector.detect(tmp.path()));
+        assert_eq!(
+            detector.find_artifacts(tmp.path()),
+            vec![tmp.path().join("blaze-out")]
+        );
+    }
+
+    #[test]
+    fn test_override_keeps_unset_builtin_fields() {
+        let config = ProjectTypeConfig {
+            artifact_dirs: Some(vec!["target".to_string(), "target-ra".to_string()]),
+            clean_command: Some(String::new()),
+            ..Default::default()
+        };
+        let detector = CustomDetector::overriding(Arc::new(CargoDetector), &config);
+
+        assert_eq!(detector.id(), "cargo");
+        assert_eq!(detector.display_name(), "Rust/Cargo");
+        assert_eq!(detector.detection_files(), &["Cargo.toml"]);
+        assert_eq!(detector.artifact_dirs(), &["target", "target-ra"]);
+        // An empty command switches to direct deletion
+        assert_eq!(detector.clean_command(), None);
+    }
+
+    #[test]
+    fn test_override_keeps_builtin_detection_logic() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("CMakeLists.txt"), "").unwrap();
+
+        let keep =
+            CustomDetector::overriding(Arc::new(CMakeDetector), &ProjectTypeConfig::default());
+        // CMake also requires build/ to exist
+        assert!(!keep.detect(tmp.path()));
+
+        let replaced = CustomDetector::overriding(
+            Arc::new(CMakeDetector),
+            &ProjectTypeConfig {
+                detection_files: Some(vec!["CMakeLists.txt".to_string()]),
+                ..Default::default()
+            },
+        );
+        assert!(replaced.detect(tmp.path()));
+    }
+
+    #[test]
+    fn test_override_keeps_builtin_artifact_discovery() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("settings.gradle"), "include ':app'").unwrap();
+        fs::create_dir_all(tmp.path().join("app/build")).unwrap();
+
+        let keep = CustomDetector::overriding(
+            Arc::new(GradleDetector),
+            &ProjectTypeConfig {
+                clean_command: Some(String::new()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            keep.find_artifacts(tmp.path()),
+            vec![tmp.path().join("app/build")]
+        );
+
+        let replaced = CustomDetector::overriding(
+            Arc::new(GradleDetector),
+            &ProjectTypeConfig {
+                artifact_dirs: Some(vec!["out".to_string()]),
+                ..Default::default()
+            },
+        );
+        assert!(replaced.find_artifacts(tmp.path()).is_empty());
+    }
+
+    #[test]
+    fn test_configured_detectors_without_config() {
+        let detectors = configured_detectors(&BTreeMap::new());
+        let builtins = all_detectors();
+        let builtin_ids: Vec<&str> = builtins.iter().map(|d| d.id()).collect();
+
+        assert_eq!(ids(&detectors), builtin_ids);
+    }
+
+    #[test]
+    fn test_configured_detectors_apply_config() {
+        let project_types = BTreeMap::from([
+            ("blaze".to_string(), blaze()),
+            (
+                "bazel".to_string(),
+                ProjectTypeConfig {
+                    enabled: false,
+                    ..Default::default()
+                },
+            ),
+            (
+                "npm".to_string(),
+                ProjectTypeConfig {
+                    display_name: Some("Node".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let detectors = configured_detectors(&project_types);
+        let ids = ids(&detectors);
+
+        assert_eq!(ids[0], "blaze");
+        assert!(!ids.contains(&"bazel"));
+        assert_eq!(ids.len(), all_detectors().len());
+
+        let npm = detectors.iter().find(|d| d.id() == "npm").unwrap();
+        assert_eq!(npm.display_name(), "Node");
+        assert_eq!(npm.artifact_dirs(), &["node_modules"]);
+    }
+}
@@ -10,23 +10,23 @@ use std::path::Path;
 pub struct DotnetDetector;
 
 impl ProjectDetector for DotnetDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "dotnet"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         ".NET"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &[] // Uses custom detection
+    fn detection_files(&self) -> Vec<&str> {
+        vec![] // Uses custom detection
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &["bin", "obj"]
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec!["bin", "obj"]
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         Some("dotnet clean")
     }
 
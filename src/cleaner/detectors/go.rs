@@ -5,27 +5,28 @@ use crate::cleaner::ProjectDetector;
 /// Detector for Go module projects.
 ///
 /// Identifies projects by the presence of `go.mod`. Uses `go clean -cache`
-/// which cleans the global Go build cache.
+/// which cleans the global Go build cache; with no local artifacts, projects
+/// aren't reported, and the caches are handled by `GoCacheCleaner` instead.
 pub struct GoDetector;
 
 impl ProjectDetector for GoDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "go"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "Go"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["go.mod"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["go.mod"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &[] // Go uses global cache, no local artifacts
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec![] // Go uses global cache, no local artifacts
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         Some("go clean -cache")
     }
 }
@@ -1,35 +1,154 @@
 //! Gradle/Android project detector.
 
 use crate::cleaner::ProjectDetector;
+use std::path::{Path, PathBuf};
+
+use super::{module_dir, module_dirs_with};
+
+/// Settings scripts listing the modules of a multi-project build.
+const SETTINGS_FILES: [&str; 2] = ["settings.gradle.kts", "settings.gradle"];
+
+/// Build scripts marking a module when no settings script lists them.
+const BUILD_FILES: [&str; 2] = ["build.gradle", "build.gradle.kts"];
 
 /// Detector for Gradle/Android projects.
 ///
 /// Identifies projects by the presence of `build.gradle`, `build.gradle.kts`,
-/// or `gradlew` and cleans build directories.
+/// or `gradlew` and cleans build directories. The `build/` directory of every
+/// module `include`d by `settings.gradle(.kts)` is cleaned too, or of every
+/// subdirectory with a build script when the settings list none.
 pub struct GradleDetector;
 
 impl ProjectDetector for GradleDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "gradle"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "Gradle/Android"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["build.gradle", "build.gradle.kts", "gradlew"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["build.gradle", "build.gradle.kts", "gradlew"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &["build", ".gradle", "app/build"]
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec!["build", ".gradle"]
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn discover_artifact_dirs(&self, path: &Path) -> Vec<PathBuf> {
+        let included: Vec<PathBuf> = SETTINGS_FILES
+            .iter()
+            .find_map(|f| std::fs::read_to_string(path.join(f)).ok())
+            .map(|settings| parse_includes(&settings))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|project| module_dir(&project.trim_start_matches(':').replace(':', "/")))
+            .collect();
+
+        // Settings that compute their includes can't be read
+        let modules = if included.is_empty() {
+            module_dirs_with(path, &BUILD_FILES)
+        } else {
+            included
+        };
+        modules.into_iter().map(|m| m.join("build")).collect()
+    }
+
+    fn clean_command(&self) -> Option<&str> {
         Some("./gradlew clean")
     }
 }
 
+/// Project paths `include`d by a settings script, e.g. `:core:data` from
+/// `include(":app", ":core:data")` or `include ':app', ':core:data'`.
+fn parse_includes(settings: &str) -> Vec<String> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let settings = strip_comments(settings);
+    let mut projects = Vec::new();
+    let mut rest = settings.as_str();
+
+    while let Some(i) = rest.find("include") {
+        let before = rest[..i].chars().next_back();
+        rest = &rest[i + "include".len()..];
+        // Skip `includeBuild`, `rootProject.name = "include-me"` and the like
+        if before.is_some_and(is_word) || rest.chars().next().is_some_and(is_word) {
+            continue;
+        }
+        projects.extend(include_arguments(rest));
+    }
+    projects
+}
+
+/// The string arguments of one `include` call, which may be parenthesized or
+/// continue over several lines after a trailing comma.
+fn include_arguments(call: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut continued = true;
+    let mut chars = call.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth <= 0 {
+                    break;
+                }
+            }
+            '"' | '\'' => {
+                arguments.push(chars.by_ref().take_while(|&n| n != c).collect());
+                continued = false;
+            }
+            ',' => continued = true,
+            '\n' if depth == 0 && !continued => break,
+            c if c.is_whitespace() => {}
+            _ if depth == 0 => break,
+            _ => {}
+        }
+    }
+    arguments
+}
+
+/// Remove `//` and `/* */` comments outside string literals.
+fn strip_comments(script: &str) -> String {
+    let mut stripped = String::with_capacity(script.len());
+    let mut chars = script.chars().peekable();
+    let mut quote = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            stripped.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => while chars.next_if(|&n| n != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+                stripped.push(' ');
+            }
+            _ => {
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                stripped.push(c);
+            }
+        }
+    }
+    stripped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,4 +197,73 @@ mod tests {
         let artifacts = GradleDetector.find_artifacts(tmp.path());
         assert_eq!(artifacts.len(), 2);
     }
+
+    #[test]
+    fn test_parse_includes() {
+        let kotlin = r#"
+rootProject.name = "include-demo"
+includeBuild("build-logic")
+include(":app")
+include(
+    ":core:data", // the data layer
+    ":feature-x",
+)
+/* include(":old") */
+"#;
+        assert_eq!(
+            parse_includes(kotlin),
+            vec![":app", ":core:data", ":feature-x"]
+        );
+
+        let groovy =
+            "include ':app', ':lib',\n        ':core:ui'\ninclude 'tools'\nprintln 'done'\n";
+        assert_eq!(
+            parse_includes(groovy),
+            vec![":app", ":lib", ":core:ui", "tools"]
+        );
+    }
+
+    #[test]
+    fn test_gradle_finds_included_module_builds() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("build.gradle.kts"), "").unwrap();
+        fs::write(
+            tmp.path().join("settings.gradle.kts"),
+            "include(\":app\", \":core:data\", \":missing\")",
+        )
+        .unwrap();
+        for dir in ["build", "app/build", "core/data/build", "docs/build"] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+
+        let artifacts = GradleDetector.find_artifacts(tmp.path());
+        assert_eq!(
+            artifacts,
+            vec![
+                tmp.path().join("build"),
+                tmp.path().join("app/build"),
+                tmp.path().join("core/data/build"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gradle_falls_back_to_module_build_scripts() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("build.gradle"), "").unwrap();
+        for dir in ["module-a/build", "module-b/build", "docs/build"] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        fs::write(tmp.path().join("module-a/build.gradle"), "").unwrap();
+        fs::write(tmp.path().join("module-b/build.gradle.kts"), "").unwrap();
+
+        let artifacts = GradleDetector.find_artifacts(tmp.path());
+        assert_eq!(
+            artifacts,
+            vec![
+                tmp.path().join("module-a/build"),
+                tmp.path().join("module-b/build"),
+            ]
+        );
+    }
 }
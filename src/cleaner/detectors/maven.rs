@@ -1,35 +1,104 @@
 //! Maven project detector.
 
 use crate::cleaner::ProjectDetector;
+use std::path::{Path, PathBuf};
+
+use super::{module_dir, module_dirs_with};
+
+/// How deep `<modules>` of aggregator modules are followed.
+const MAX_MODULE_DEPTH: usize = 8;
 
 /// Detector for Maven projects.
 ///
 /// Identifies projects by the presence of `pom.xml` and cleans
-/// the `target/` directory.
+/// the `target/` directory, and that of every module listed in `<modules>`
+/// (following nested aggregators), or of every subdirectory with a
+/// `pom.xml` when the root lists none.
 pub struct MavenDetector;
 
 impl ProjectDetector for MavenDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "maven"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "Maven"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["pom.xml"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["pom.xml"]
+    }
+
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec!["target"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &["target"]
+    fn discover_artifact_dirs(&self, path: &Path) -> Vec<PathBuf> {
+        let mut modules = Vec::new();
+        collect_modules(path, Path::new(""), 0, &mut modules);
+        if modules.is_empty() {
+            modules = module_dirs_with(path, &["pom.xml"]);
+        }
+        modules.into_iter().map(|m| m.join("target")).collect()
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         Some("mvn clean")
     }
 }
 
+/// Add the modules of the POM in `root/dir` to `modules`, relative to
+/// `root`, followed by their own modules.
+fn collect_modules(root: &Path, dir: &Path, depth: usize, modules: &mut Vec<PathBuf>) {
+    if depth >= MAX_MODULE_DEPTH {
+        return;
+    }
+    let Ok(pom) = std::fs::read_to_string(root.join(dir).join("pom.xml")) else {
+        return;
+    };
+
+    for module in parse_modules(&pom) {
+        // A module may name its POM file instead of its directory
+        let module = match module.strip_suffix(".xml") {
+            Some(file) => file.rsplit_once('/').map_or("", |(dir, _)| dir).to_string(),
+            None => module,
+        };
+        let Some(module) = module_dir(&module).map(|m| dir.join(m)) else {
+            continue;
+        };
+        if !modules.contains(&module) {
+            modules.push(module.clone());
+            collect_modules(root, &module, depth + 1, modules);
+        }
+    }
+}
+
+/// The `<module>` entries of a POM, including those of its profiles.
+fn parse_modules(pom: &str) -> Vec<String> {
+    // Drop comments, which often hold disabled modules
+    let mut xml = String::with_capacity(pom.len());
+    let mut rest = pom;
+    while let Some(start) = rest.find("<!--") {
+        xml.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("-->")
+            .map_or("", |end| &rest[start + end + "-->".len()..]);
+    }
+    xml.push_str(rest);
+
+    let mut modules = Vec::new();
+    let mut rest = xml.as_str();
+    while let Some(start) = rest.find("<module>") {
+        rest = &rest[start + "<module>".len()..];
+        let Some(end) = rest.find("</module>") else {
+            break;
+        };
+        modules.push(rest[..end].trim().to_string());
+        rest = &rest[end..];
+    }
+    modules
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,4 +130,74 @@ mod tests {
 
         assert!(!MavenDetector.detect(tmp.path()));
     }
+
+    #[test]
+    fn test_parse_modules() {
+        let pom = r#"<project>
+  <modules>
+    <module>core</module>
+    <!-- <module>legacy</module> -->
+    <module> web/pom.xml </module>
+  </modules>
+  <profiles><profile><modules><module>bench</module></modules></profile></profiles>
+</project>"#;
+
+        assert_eq!(parse_modules(pom), vec!["core", "web/pom.xml", "bench"]);
+        assert!(parse_modules("<project/>").is_empty());
+    }
+
+    #[test]
+    fn test_maven_finds_nested_module_targets() {
+        let tmp = TempDir::new().unwrap();
+        let write = |rel: &str, content: &str| {
+            let path = tmp.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "pom.xml",
+            "<modules><module>services</module><module>../shared</module></modules>",
+        );
+        write(
+            "services/pom.xml",
+            "<modules><module>module-a</module></modules>",
+        );
+        write("services/module-a/pom.xml", "<project/>");
+        for dir in [
+            "target",
+            "services/target",
+            "services/module-a/target",
+            "docs/target",
+        ] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+
+        assert_eq!(
+            MavenDetector.find_artifacts(tmp.path()),
+            vec![
+                tmp.path().join("target"),
+                tmp.path().join("services/target"),
+                tmp.path().join("services/module-a/target"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_maven_falls_back_to_module_poms() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("pom.xml"), "<project/>").unwrap();
+        for dir in ["module-a/target", "module-b/target", "docs/target"] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        fs::write(tmp.path().join("module-a/pom.xml"), "<project/>").unwrap();
+        fs::write(tmp.path().join("module-b/pom.xml"), "<project/>").unwrap();
+
+        assert_eq!(
+            MavenDetector.find_artifacts(tmp.path()),
+            vec![
+                tmp.path().join("module-a/target"),
+                tmp.path().join("module-b/target"),
+            ]
+        );
+    }
 }
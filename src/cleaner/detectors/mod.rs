@@ -1,8 +1,9 @@
-//! Built-in project detectors.
+//! Built-in project detectors, and detectors defined in the config file.
 
 mod bazel;
 mod cargo;
 mod cmake;
+mod custom;
 mod dotnet;
 mod go;
 mod gradle;
@@ -13,6 +14,7 @@ mod python;
 pub use bazel::BazelDetector;
 pub use cargo::CargoDetector;
 pub use cmake::CMakeDetector;
+pub use custom::{configured_detectors, CustomDetector};
 pub use dotnet::DotnetDetector;
 pub use go::GoDetector;
 pub use gradle::GradleDetector;
@@ -21,6 +23,7 @@ pub use npm::NpmDetector;
 pub use python::PythonDetector;
 
 use crate::cleaner::ProjectDetector;
+use std::path::{Component, Path, PathBuf};
 
 /// Returns all built-in detectors.
 pub fn all_detectors() -> Vec<Box<dyn ProjectDetector>> {
@@ -36,3 +39,61 @@ pub fn all_detectors() -> Vec<Box<dyn ProjectDetector>> {
         Box::new(DotnetDetector),
     ]
 }
+
+/// Subdirectories of `path` holding any of `build_files`, relative to it:
+/// the modules of a multi-module build that doesn't list them.
+fn module_dirs_with(path: &Path, build_files: &[&str]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut modules: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|e| build_files.iter().any(|f| e.path().join(f).is_file()))
+        .map(|e| PathBuf::from(e.file_name()))
+        .collect();
+    modules.sort();
+    modules
+}
+
+/// A module directory named by a build file, if it stays inside the project.
+fn module_dir(name: &str) -> Option<PathBuf> {
+    let dir = PathBuf::from(name.trim());
+    let inside = dir
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    let named = dir.components().any(|c| matches!(c, Component::Normal(_)));
+    (inside && named).then_some(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_module_dirs_with() {
+        let tmp = TempDir::new().unwrap();
+        for dir in ["lib", "app", "docs"] {
+            fs::create_dir(tmp.path().join(dir)).unwrap();
+        }
+        fs::write(tmp.path().join("app/pom.xml"), "").unwrap();
+        fs::write(tmp.path().join("lib/pom.xml"), "").unwrap();
+        fs::write(tmp.path().join("pom.xml"), "").unwrap();
+
+        assert_eq!(
+            module_dirs_with(tmp.path(), &["pom.xml"]),
+            vec![PathBuf::from("app"), PathBuf::from("lib")]
+        );
+    }
+
+    #[test]
+    fn test_module_dir_stays_inside_project() {
+        assert_eq!(module_dir(" core/data "), Some(PathBuf::from("core/data")));
+        assert_eq!(module_dir("../shared"), None);
+        assert_eq!(module_dir("/opt/lib"), None);
+        assert_eq!(module_dir("."), None);
+        assert_eq!(module_dir(""), None);
+    }
+}
@@ -9,23 +9,23 @@ use crate::cleaner::ProjectDetector;
 pub struct NpmDetector;
 
 impl ProjectDetector for NpmDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "npm"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "npm/Node.js"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["package.json"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["package.json"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &["node_modules"]
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec!["node_modules"]
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         None // Direct deletion is more reliable
     }
 }
@@ -9,23 +9,23 @@ use crate::cleaner::ProjectDetector;
 pub struct PythonDetector;
 
 impl ProjectDetector for PythonDetector {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "python"
     }
 
-    fn display_name(&self) -> &'static str {
+    fn display_name(&self) -> &str {
         "Python venv"
     }
 
-    fn detection_files(&self) -> &'static [&'static str] {
-        &["venv", ".venv"]
+    fn detection_files(&self) -> Vec<&str> {
+        vec!["venv", ".venv"]
     }
 
-    fn artifact_dirs(&self) -> &'static [&'static str] {
-        &["venv", ".venv", "__pycache__"]
+    fn artifact_dirs(&self) -> Vec<&str> {
+        vec!["venv", ".venv", "__pycache__"]
     }
 
-    fn clean_command(&self) -> Option<&'static str> {
+    fn clean_command(&self) -> Option<&str> {
         None // Direct deletion
     }
 }
@@ -1,12 +1,30 @@
 //! Docker system cleaner.
 //!
-//! Detects and cleans Docker build cache and dangling images via the Docker CLI.
+//! Detects and cleans Docker build cache, dangling and unused images, stopped
+//! containers and unused volumes via the Docker CLI. Images, containers and
+//! volumes can also be listed and removed one at a time.
+//!
+//! The parsing of Docker-style JSON output and the resource kinds are shared
+//! with the Podman and nerdctl cleaners.
 
-use crate::cleaner::system_cleaner::{DetectedSystemResource, SystemCleanResult, SystemCleaner};
-use std::process::Command;
+use crate::cleaner::measure::SpaceProbe;
+use crate::cleaner::system_cleaner::{
+    dry_run_result, run_quietly, DetectedSystemResource, SystemCleanResult, SystemCleaner,
+    SystemResourceItem,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Docker system cleaner.
-pub struct DockerCleaner;
+#[derive(Default)]
+pub struct DockerCleaner {
+    /// Set once stopped containers were pruned; the volumes they released
+    /// are kept until the next run
+    containers_pruned: AtomicBool,
+}
 
 impl SystemCleaner for DockerCleaner {
     fn id(&self) -> &'static str {
@@ -17,73 +35,712 @@ impl SystemCleaner for DockerCleaner {
         "Docker"
     }
 
+    fn runs_by_default(&self) -> bool {
+        true
+    }
+
+    fn cleans_by_default(&self, resource: &DetectedSystemResource) -> bool {
+        // Pruning stopped containers deletes their writable layers, and
+        // volumes hold data: both need `--types docker`
+        !matches!(
+            EngineResource::of(resource, "docker"),
+            Some(EngineResource::Containers | EngineResource::Volumes)
+        )
+    }
+
     fn is_available(&self) -> bool {
-        Command::new("docker")
-            .arg("info")
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .status()
-            .map(|s| s.success())
-            .unwrap_or(false)
+        // podman-docker installs a `docker` that runs Podman, whose store the
+        // Podman cleaner already reports
+        run_quietly(&["docker", "info"])
+            && !command_stdout("docker", &["--version"])
+                .is_some_and(|version| version.to_lowercase().contains("podman"))
     }
 
     fn detect(&self) -> Vec<DetectedSystemResource> {
-        let output = Command::new("docker")
-            .args(["system", "df", "--format", "{{json .}}"])
-            .output();
-
-        match output {
-            Ok(output) if output.status.success() => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                parse_system_df_output(&stdout)
+        let Some(stdout) = command_stdout("docker", &["system", "df", "--format", "{{json .}}"])
+        else {
+            return Vec::new();
+        };
+        let mut resources = parse_system_df_output(&stdout);
+
+        // Dangling images are a subset of the unused ones, listed separately
+        // since removing them never costs a re-pull
+        if let Some(usage) = verbose_disk_usage() {
+            insert_dangling_images(&mut resources, &usage.images, "docker", "Docker");
+
+            // `system df` counts named volumes too, which are only removed
+            // one by one
+            let anonymous: Vec<&DockerVolume> = usage
+                .volumes
+                .iter()
+                .filter(|v| v.links == 0 && is_anonymous_volume(&v.name))
+                .collect();
+            if let Some(volumes) = resources
+                .iter_mut()
+                .find(|r| EngineResource::of(r, "docker") == Some(EngineResource::Volumes))
+            {
+                volumes.size = anonymous.iter().map(|v| v.size).sum();
+                volumes.item_count = Some(anonymous.len() as u64);
             }
-            _ => Vec::new(),
         }
+
+        resources
     }
 
     fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
         if dry_run {
-            return SystemCleanResult::Success {
-                resource: resource.clone(),
-                freed_bytes: resource.size,
-            };
+            return dry_run_result(resource);
         }
 
-        let result = match resource.resource_id.as_str() {
-            "docker-build-cache" => Command::new("docker")
-                .args(["builder", "prune", "-f"])
-                .output(),
-            "docker-images" => Command::new("docker")
-                .args(["image", "prune", "-f", "-a"])
-                .output(),
-            _ => {
-                return SystemCleanResult::Skipped {
-                    resource: resource.clone(),
-                    reason: format!("Unknown resource: {}", resource.resource_id),
-                };
+        let probe =
+            info_dir("docker", "{{.DockerRootDir}}").map(|root| SpaceProbe::start(&[], &root));
+        let result = match EngineResource::of(resource, "docker") {
+            Some(EngineResource::BuildCache) => docker(&["builder", "prune", "-f"]),
+            Some(EngineResource::DanglingImages) => docker(&["image", "prune", "-f"]),
+            Some(EngineResource::Images) => docker(&["image", "prune", "-f", "-a"]),
+            Some(EngineResource::Containers) => {
+                if resource.item_count != Some(0) {
+                    self.containers_pruned.store(true, Ordering::Relaxed);
+                }
+                docker(&["container", "prune", "-f"])
+            }
+            Some(EngineResource::Volumes) => {
+                let containers_pruned = self.containers_pruned.load(Ordering::Relaxed);
+                return clean_anonymous_volumes(resource, "docker", containers_pruned, |_| {
+                    probe.and_then(|p| p.freed())
+                });
             }
+            None => return unknown_resource(resource),
         };
 
-        match result {
-            Ok(output) if output.status.success() => SystemCleanResult::Success {
-                resource: resource.clone(),
-                freed_bytes: resource.size,
-            },
-            Ok(output) => {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                SystemCleanResult::Failed {
-                    resource: resource.clone(),
-                    error: format!("Command failed: {}", stderr.trim()),
-                }
+        // Prefer Docker's own figure; the filesystem delta also sees
+        // unrelated writes
+        command_result(resource, result, resource.size, |stdout| {
+            parse_reclaimed_space(stdout).or_else(|| probe.and_then(|p| p.freed()))
+        })
+    }
+
+    fn list_items(&self, resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        let Some(usage) = verbose_disk_usage() else {
+            return Vec::new();
+        };
+
+        let mut items = match EngineResource::of(resource, "docker") {
+            Some(kind @ (EngineResource::Images | EngineResource::DanglingImages)) => {
+                let images = unused_images(&usage.images, kind == EngineResource::DanglingImages);
+                let ids: Vec<&str> = images.iter().map(|i| i.id.as_str()).collect();
+                let last_used = inspect_times(&["image", "inspect"], &ids, parse_image_inspect);
+                image_items(&images, &last_used)
+            }
+            Some(EngineResource::Containers) => container_items(&usage.containers),
+            Some(EngineResource::Volumes) => {
+                let volumes: Vec<&DockerVolume> =
+                    usage.volumes.iter().filter(|v| v.links == 0).collect();
+                let names: Vec<&str> = volumes.iter().map(|v| v.name.as_str()).collect();
+                let created = inspect_times(&["volume", "inspect"], &names, parse_volume_inspect);
+                volumes
+                    .iter()
+                    .map(|v| SystemResourceItem {
+                        id: v.name.clone(),
+                        name: v.name.clone(),
+                        size: v.size,
+                        last_used: created.get(&v.name).copied(),
+                    })
+                    .collect()
             }
-            Err(e) => SystemCleanResult::Failed {
+            _ => Vec::new(),
+        };
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items
+    }
+
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        let Some(kind) = EngineResource::of(resource, "docker").and_then(|k| k.object_kind())
+        else {
+            return items_unsupported(resource);
+        };
+
+        // Docker doesn't report what a single removal frees
+        command_result(resource, docker(&[kind, "rm", &item.id]), item.size, |_| {
+            None
+        })
+    }
+}
+
+/// The kinds of resource a container engine reports, each a system resource
+/// with the id `<engine>-<suffix>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EngineResource {
+    BuildCache,
+    DanglingImages,
+    Images,
+    Containers,
+    Volumes,
+}
+
+impl EngineResource {
+    const ALL: [EngineResource; 5] = [
+        EngineResource::BuildCache,
+        EngineResource::DanglingImages,
+        EngineResource::Images,
+        EngineResource::Containers,
+        EngineResource::Volumes,
+    ];
+
+    /// The kind of a `Type` reported by `system df`.
+    fn from_df_type(type_name: &str) -> Option<Self> {
+        match type_name {
+            "Build Cache" => Some(EngineResource::BuildCache),
+            "Images" => Some(EngineResource::Images),
+            "Containers" => Some(EngineResource::Containers),
+            "Local Volumes" => Some(EngineResource::Volumes),
+            _ => None,
+        }
+    }
+
+    /// The kind of one of `category`'s resources.
+    pub(crate) fn of(resource: &DetectedSystemResource, category: &str) -> Option<Self> {
+        let suffix = resource
+            .resource_id
+            .strip_prefix(category)?
+            .strip_prefix('-')?;
+        Self::ALL.into_iter().find(|kind| kind.suffix() == suffix)
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            EngineResource::BuildCache => "build-cache",
+            EngineResource::DanglingImages => "dangling-images",
+            EngineResource::Images => "images",
+            EngineResource::Containers => "containers",
+            EngineResource::Volumes => "volumes",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            EngineResource::BuildCache => "Build Cache",
+            EngineResource::DanglingImages => "Dangling Images",
+            EngineResource::Images => "Unused Images",
+            EngineResource::Containers => "Stopped Containers",
+            EngineResource::Volumes => "Unused Volumes",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            EngineResource::BuildCache => "Cached build layers",
+            EngineResource::DanglingImages => "Untagged images not used by any container",
+            EngineResource::Images => "Tagged images not used by any container",
+            EngineResource::Containers => "Containers that are not running",
+            EngineResource::Volumes => "Anonymous volumes not used by any container",
+        }
+    }
+
+    /// The CLI object (`<engine> <object> rm`) of the resource's items.
+    pub(crate) fn object_kind(self) -> Option<&'static str> {
+        match self {
+            EngineResource::BuildCache => None,
+            EngineResource::DanglingImages | EngineResource::Images => Some("image"),
+            EngineResource::Containers => Some("container"),
+            EngineResource::Volumes => Some("volume"),
+        }
+    }
+
+    /// A resource of this kind for `category`, named after `engine_name`.
+    pub(crate) fn resource(
+        self,
+        category: &str,
+        engine_name: &str,
+        size: u64,
+        item_count: Option<u64>,
+    ) -> DetectedSystemResource {
+        DetectedSystemResource {
+            resource_id: format!("{}-{}", category, self.suffix()),
+            display_name: format!("{} {}", engine_name, self.label()),
+            category: category.to_string(),
+            size,
+            description: self.description().to_string(),
+            item_count,
+        }
+    }
+}
+
+/// Stdout of a command that exits successfully.
+pub(crate) fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+/// Turn the output of a prune or rm command into a clean result. `freed`
+/// reads the reclaimed space from stdout; without it the estimate is used.
+pub(crate) fn command_result(
+    resource: &DetectedSystemResource,
+    result: std::io::Result<Output>,
+    estimated: u64,
+    freed: impl FnOnce(&str) -> Option<u64>,
+) -> SystemCleanResult {
+    match result {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            SystemCleanResult::Success {
                 resource: resource.clone(),
-                error: e.to_string(),
-            },
+                freed_bytes: freed(&stdout).unwrap_or(estimated),
+                estimated_bytes: estimated,
+            }
+        }
+        Ok(output) => SystemCleanResult::Failed {
+            resource: resource.clone(),
+            error: format!(
+                "Command failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        },
+        Err(e) => SystemCleanResult::Failed {
+            resource: resource.clone(),
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Remove the unused anonymous volumes of `program` with `volume rm`.
+///
+/// `volume prune` isn't used: with Docker's `--all`, on Docker before 23.0
+/// and on Podman it removes named volumes too, which usually hold data
+/// rather than a cache. Named volumes are left to per-item removal. If
+/// `containers_pruned`, the volumes those containers released would go as
+/// well, so nothing is removed until the next run.
+pub(crate) fn clean_anonymous_volumes(
+    resource: &DetectedSystemResource,
+    program: &str,
+    containers_pruned: bool,
+    freed: impl FnOnce(&str) -> Option<u64>,
+) -> SystemCleanResult {
+    if containers_pruned {
+        return SystemCleanResult::Skipped {
+            resource: resource.clone(),
+            reason: "containers were pruned in this run, volumes are kept until the next"
+                .to_string(),
+        };
+    }
+    let Some(names) = unused_anonymous_volumes(program) else {
+        return SystemCleanResult::Failed {
+            resource: resource.clone(),
+            error: format!("Could not list {} volumes", program),
+        };
+    };
+
+    let mut args = vec!["volume", "rm"];
+    args.extend(names.iter().map(String::as_str));
+    if names.is_empty() {
+        return SystemCleanResult::Success {
+            resource: resource.clone(),
+            freed_bytes: 0,
+            estimated_bytes: resource.size,
+        };
+    }
+    let result = Command::new(program).args(&args).output();
+    command_result(resource, result, resource.size, freed)
+}
+
+/// Names of the anonymous volumes no container of `program` uses.
+pub(crate) fn unused_anonymous_volumes(program: &str) -> Option<Vec<String>> {
+    let stdout = command_stdout(
+        program,
+        &["volume", "ls", "--quiet", "--filter", "dangling=true"],
+    )?;
+    Some(
+        stdout
+            .lines()
+            .map(str::trim)
+            .filter(|name| is_anonymous_volume(name))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Whether `name` was generated for an anonymous volume: 64 hex digits.
+/// Docker before 23.0 and Podman don't label anonymous volumes, so the name
+/// is the only sign.
+pub(crate) fn is_anonymous_volume(name: &str) -> bool {
+    name.len() == 64 && name.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Result for a resource id the cleaner doesn't know.
+pub(crate) fn unknown_resource(resource: &DetectedSystemResource) -> SystemCleanResult {
+    SystemCleanResult::Skipped {
+        resource: resource.clone(),
+        reason: format!("Unknown resource: {}", resource.resource_id),
+    }
+}
+
+/// Result for removing an item of a resource without removable items.
+pub(crate) fn items_unsupported(resource: &DetectedSystemResource) -> SystemCleanResult {
+    SystemCleanResult::Skipped {
+        resource: resource.clone(),
+        reason: format!(
+            "{} items can't be removed individually",
+            resource.display_name
+        ),
+    }
+}
+
+/// Split the dangling subset of `images` out of the unused images into its
+/// own resource, inserted before them. The unused images' size and count
+/// include the dangling ones, which would otherwise be counted twice.
+pub(crate) fn insert_dangling_images(
+    resources: &mut Vec<DetectedSystemResource>,
+    images: &[DockerImage],
+    category: &str,
+    engine_name: &str,
+) {
+    let dangling = unused_images(images, true);
+    let size: u64 = dangling.iter().map(|i| i.size).sum();
+    let count = dangling.len() as u64;
+    let images_id = format!("{}-{}", category, EngineResource::Images.suffix());
+    let position = match resources.iter().position(|r| r.resource_id == images_id) {
+        Some(position) => {
+            let unused = &mut resources[position];
+            unused.size = unused.size.saturating_sub(size);
+            unused.item_count = unused.item_count.map(|c| c.saturating_sub(count));
+            position
+        }
+        None => resources.len(),
+    };
+    resources.insert(
+        position,
+        EngineResource::DanglingImages.resource(category, engine_name, size, Some(count)),
+    );
+}
+
+/// Images not used by any container that are dangling, or that are tagged.
+pub(crate) fn unused_images(images: &[DockerImage], dangling: bool) -> Vec<&DockerImage> {
+    images
+        .iter()
+        .filter(|i| i.containers == 0 && i.is_dangling() == dangling)
+        .collect()
+}
+
+/// Items for `images`, last used when they were last tagged if known, else
+/// when they were created.
+pub(crate) fn image_items(
+    images: &[&DockerImage],
+    last_used: &HashMap<String, SystemTime>,
+) -> Vec<SystemResourceItem> {
+    images
+        .iter()
+        .map(|image| SystemResourceItem {
+            id: image.id.clone(),
+            name: image.name(),
+            size: image.size,
+            last_used: last_used.get(&image.id).copied().or(image.created),
+        })
+        .collect()
+}
+
+/// Items for the containers that are not running.
+pub(crate) fn container_items(containers: &[DockerContainer]) -> Vec<SystemResourceItem> {
+    containers
+        .iter()
+        .filter(|c| !c.is_active())
+        .map(|c| SystemResourceItem {
+            id: c.id.clone(),
+            name: c.name.clone(),
+            size: c.size,
+            last_used: c.created,
+        })
+        .collect()
+}
+
+/// Run a Docker CLI command.
+fn docker(args: &[&str]) -> std::io::Result<Output> {
+    Command::new("docker").args(args).output()
+}
+
+/// Per-item disk usage from `docker system df -v`.
+fn verbose_disk_usage() -> Option<DiskUsage> {
+    command_stdout("docker", &["system", "df", "-v", "--format", "{{json .}}"])
+        .and_then(|stdout| parse_verbose_df_output(&stdout))
+}
+
+/// Run `docker <command> --format '{{json .}}' <names>` and collect a time per
+/// item from each output line.
+fn inspect_times(
+    command: &[&str],
+    names: &[&str],
+    parse: fn(&serde_json::Value) -> Option<(String, SystemTime)>,
+) -> HashMap<String, SystemTime> {
+    if names.is_empty() {
+        return HashMap::new();
+    }
+    let mut args = command.to_vec();
+    args.extend(["--format", "{{json .}}"]);
+    args.extend(names);
+    // Inspect fails as a whole if one item vanished; times are optional anyway
+    let Ok(output) = docker(&args) else {
+        return HashMap::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line.trim()).ok())
+        .filter_map(|value| parse(&value))
+        .collect()
+}
+
+/// Image id and when it was last tagged (pulled, built or retagged),
+/// from a `docker image inspect` object.
+fn parse_image_inspect(value: &serde_json::Value) -> Option<(String, SystemTime)> {
+    let id = value["Id"].as_str()?;
+    let tagged = value["Metadata"]["LastTagTime"]
+        .as_str()
+        .and_then(parse_docker_time)
+        .filter(|t| *t > UNIX_EPOCH);
+    let created = value["Created"].as_str().and_then(parse_docker_time);
+    let time = tagged.max(created)?;
+    Some((short_id(id), time))
+}
+
+/// The 12-character form of an image or container id that `docker system df`
+/// shows.
+pub(crate) fn short_id(id: &str) -> String {
+    id.strip_prefix("sha256:")
+        .unwrap_or(id)
+        .chars()
+        .take(12)
+        .collect()
+}
+
+/// Volume name and creation time from a `docker volume inspect` object.
+fn parse_volume_inspect(value: &serde_json::Value) -> Option<(String, SystemTime)> {
+    let name = value["Name"].as_str()?;
+    let created = value["CreatedAt"].as_str().and_then(parse_docker_time)?;
+    Some((name.to_string(), created))
+}
+
+/// Items reported by `docker system df -v`.
+#[derive(Debug, Default)]
+pub(crate) struct DiskUsage {
+    pub(crate) images: Vec<DockerImage>,
+    pub(crate) containers: Vec<DockerContainer>,
+    pub(crate) volumes: Vec<DockerVolume>,
+}
+
+#[derive(Debug)]
+pub(crate) struct DockerImage {
+    pub(crate) id: String,
+    pub(crate) repository: String,
+    pub(crate) tag: String,
+    pub(crate) containers: u64,
+    /// Space only this image uses, i.e. what removing it frees.
+    pub(crate) size: u64,
+    pub(crate) created: Option<SystemTime>,
+}
+
+impl DockerImage {
+    /// Untagged and not used by any container.
+    fn is_dangling(&self) -> bool {
+        self.repository == "<none>" && self.containers == 0
+    }
+
+    pub(crate) fn name(&self) -> String {
+        match (self.repository.as_str(), self.tag.as_str()) {
+            ("<none>", _) => self.id.clone(),
+            (repo, "<none>") => repo.to_string(),
+            (repo, tag) => format!("{}:{}", repo, tag),
         }
     }
 }
 
+#[derive(Debug)]
+pub(crate) struct DockerContainer {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) image: String,
+    pub(crate) state: String,
+    pub(crate) size: u64,
+    pub(crate) created: Option<SystemTime>,
+}
+
+impl DockerContainer {
+    fn is_active(&self) -> bool {
+        matches!(self.state.as_str(), "running" | "paused" | "restarting")
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DockerVolume {
+    name: String,
+    links: u64,
+    size: u64,
+}
+
+/// String field of a Docker-style JSON object, or "".
+fn text(value: &serde_json::Value, key: &str) -> String {
+    value[key].as_str().unwrap_or("").to_string()
+}
+
+/// Count field that Docker prints as a string and Podman as a number.
+fn count(value: &serde_json::Value, key: &str) -> Option<u64> {
+    value[key]
+        .as_u64()
+        .or_else(|| value[key].as_str()?.trim().parse().ok())
+}
+
+/// An image from Docker-style `{{json .}}` output, where every field is a
+/// string.
+pub(crate) fn parse_image_json(value: &serde_json::Value) -> DockerImage {
+    DockerImage {
+        id: short_id(&text(value, "ID")),
+        repository: text(value, "Repository"),
+        tag: text(value, "Tag"),
+        containers: count(value, "Containers").unwrap_or(0),
+        size: value["UniqueSize"]
+            .as_str()
+            .or(value["Size"].as_str())
+            .map(parse_docker_size)
+            .unwrap_or(0),
+        created: value["CreatedAt"].as_str().and_then(parse_docker_time),
+    }
+}
+
+/// A container from Docker-style `{{json .}}` output. Without a `State`
+/// field, it is derived from the `Up ...`/`Exited ...` status.
+pub(crate) fn parse_container_json(value: &serde_json::Value) -> DockerContainer {
+    let state = match value["State"].as_str() {
+        Some(state) if !state.is_empty() => state.to_string(),
+        _ if text(value, "Status").starts_with("Up") => "running".to_string(),
+        _ => "exited".to_string(),
+    };
+    DockerContainer {
+        id: text(value, "ID"),
+        name: text(value, "Names"),
+        image: text(value, "Image"),
+        state,
+        size: parse_docker_size(&text(value, "Size")),
+        created: value["CreatedAt"].as_str().and_then(parse_docker_time),
+    }
+}
+
+/// Parse the output of `docker system df -v --format '{{json .}}'`, a single
+/// JSON object holding `Images`, `Containers`, `Volumes` and `BuildCache` arrays
+/// whose fields are all strings.
+fn parse_verbose_df_output(output: &str) -> Option<DiskUsage> {
+    let parsed: serde_json::Value = serde_json::from_str(output.trim()).ok()?;
+    let entries = |key: &str| parsed[key].as_array().cloned().unwrap_or_default();
+
+    let images = entries("Images").iter().map(parse_image_json).collect();
+    let containers = entries("Containers")
+        .iter()
+        .map(parse_container_json)
+        .collect();
+    let volumes = entries("Volumes")
+        .iter()
+        .map(|v| DockerVolume {
+            name: text(v, "Name"),
+            // Docker reports "N/A" for volumes it couldn't inspect; keep them
+            // out of the unused list
+            links: count(v, "Links").unwrap_or(u64::MAX),
+            size: parse_docker_size(&text(v, "Size")),
+        })
+        .collect();
+
+    Some(DiskUsage {
+        images,
+        containers,
+        volumes,
+    })
+}
+
+/// Parse a Docker timestamp: `2024-01-15 10:30:00 +0000 UTC` as printed by
+/// `--format`, or RFC 3339 (`2024-01-15T10:30:00.123Z`) as in `inspect`.
+pub(crate) fn parse_docker_time(s: &str) -> Option<SystemTime> {
+    let s = s.trim();
+    let (date, rest) = s.split_at_checked(10)?;
+    let rest = rest.strip_prefix(['T', ' '])?;
+    let (time, zone) = rest.split_at_checked(8)?;
+
+    let mut date_parts = date.split('-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (
+        date_parts.next()??,
+        date_parts.next()??,
+        date_parts.next()??,
+    );
+    let mut time_parts = time.split(':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (
+        time_parts.next()??,
+        time_parts.next()??,
+        time_parts.next()??,
+    );
+
+    // Skip fractional seconds, then read the offset
+    let zone = zone.trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let zone = zone.trim_start();
+    let offset = match zone.chars().next() {
+        None | Some('Z') => 0,
+        Some(sign @ ('+' | '-')) => {
+            let digits: String = zone[1..]
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .take(4)
+                .collect();
+            let hours: i64 = digits.get(..2)?.parse().ok()?;
+            let minutes: i64 = digits.get(2..4).unwrap_or("00").parse().ok()?;
+            let offset = hours * 3600 + minutes * 60;
+            if sign == '-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return None,
+    };
+
+    let secs =
+        days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+    Some(if secs >= 0 {
+        UNIX_EPOCH + Duration::from_secs(secs as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+    })
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// A directory from `<program> info --format <template>`, such as Docker's
+/// data directory (`{{.DockerRootDir}}`, e.g. `/var/lib/docker`).
+pub(crate) fn info_dir(program: &str, template: &str) -> Option<PathBuf> {
+    let root = command_stdout(program, &["info", "--format", template])?;
+    let root = root.trim();
+    (!root.is_empty()).then(|| PathBuf::from(root))
+}
+
+/// Parse the `Total reclaimed space: 1.2GB` line printed by `docker * prune`
+/// and its imitators.
+pub(crate) fn parse_reclaimed_space(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Total reclaimed space:"))
+        .map(|size| parse_docker_size(size.trim()))
+}
+
 /// Parse the output of `docker system df --format '{{json .}}'`.
 ///
 /// Each line is a JSON object like:
@@ -92,50 +749,46 @@ impl SystemCleaner for DockerCleaner {
 /// {"Type":"Images","TotalCount":"5","Active":"2","Size":"1.2GB","Reclaimable":"500MB (41%)"}
 /// ```
 ///
-/// We extract Build Cache and Images, skipping Containers and Volumes.
+/// Each type becomes one resource holding its reclaimable space.
 pub fn parse_system_df_output(output: &str) -> Vec<DetectedSystemResource> {
-    let mut resources = Vec::new();
+    parse_df_summary(output, "docker", "Docker")
+}
 
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+/// Parse a `system df` summary into `category` resources named after
+/// `engine_name`. Docker prints one object per line with string fields;
+/// Podman prints a JSON array with numeric counts and `RawReclaimable` bytes.
+pub(crate) fn parse_df_summary(
+    output: &str,
+    category: &str,
+    engine_name: &str,
+) -> Vec<DetectedSystemResource> {
+    let output = output.trim();
+    let rows: Vec<serde_json::Value> = if output.starts_with('[') {
+        serde_json::from_str(output).unwrap_or_default()
+    } else {
+        output
+            .lines()
+            .filter_map(|line| serde_json::from_str(line.trim()).ok())
+            .collect()
+    };
 
-        let parsed: serde_json::Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
+    let mut resources = Vec::new();
+    for row in rows {
+        let Some(kind) = row["Type"].as_str().and_then(EngineResource::from_df_type) else {
+            continue;
         };
 
-        let type_name = parsed["Type"].as_str().unwrap_or("");
-        let reclaimable_str = parsed["Reclaimable"].as_str().unwrap_or("0B");
-        let total_count_str = parsed["TotalCount"].as_str().unwrap_or("0");
-
-        let (resource_id, display_name, description) = match type_name {
-            "Build Cache" => (
-                "docker-build-cache",
-                "Docker Build Cache",
-                "Cached build layers",
-            ),
-            "Images" => (
-                "docker-images",
-                "Docker Images",
-                "Dangling images (reclaimable)",
-            ),
-            _ => continue, // Skip Containers, Volumes
+        let size = row["RawReclaimable"]
+            .as_u64()
+            .unwrap_or_else(|| parse_docker_size(row["Reclaimable"].as_str().unwrap_or("0B")));
+        let total_count = count(&row, "TotalCount");
+        // Images, containers and volumes in use aren't reclaimable
+        let item_count = match kind {
+            EngineResource::BuildCache => total_count,
+            _ => total_count.map(|total| total.saturating_sub(count(&row, "Active").unwrap_or(0))),
         };
 
-        let size = parse_docker_size(reclaimable_str);
-        let item_count = total_count_str.parse::<u64>().ok();
-
-        resources.push(DetectedSystemResource {
-            resource_id: resource_id.to_string(),
-            display_name: display_name.to_string(),
-            category: "docker".to_string(),
-            size,
-            description: description.to_string(),
-            item_count,
-        });
+        resources.push(kind.resource(category, engine_name, size, item_count));
     }
 
     resources
@@ -148,7 +801,8 @@ pub fn parse_system_df_output(output: &str) -> Vec<DetectedSystemResource> {
 /// - `"500MB (41%)"`
 /// - `"0B"`
 /// - `"1.5kB"`
-fn parse_docker_size(s: &str) -> u64 {
+/// - `"7.8 MiB"` (nerdctl)
+pub(crate) fn parse_docker_size(s: &str) -> u64 {
     // Strip any parenthetical suffix like " (41%)"
     let size_part = s.split('(').next().unwrap_or(s).trim();
 
@@ -163,7 +817,7 @@ fn parse_docker_size(s: &str) -> u64 {
 
     let (num_str, unit) = size_part.split_at(unit_start);
 
-    let number: f64 = match num_str.parse() {
+    let number: f64 = match num_str.trim().parse() {
         Ok(n) => n,
         Err(_) => return 0,
     };
@@ -174,6 +828,10 @@ fn parse_docker_size(s: &str) -> u64 {
         "MB" => 1_000_000.0,
         "GB" => 1_000_000_000.0,
         "TB" => 1_000_000_000_000.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
         _ => return 0,
     };
 
@@ -184,6 +842,14 @@ fn parse_docker_size(s: &str) -> u64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_reclaimed_space() {
+        let output = "Deleted Images:\nuntagged: alpine:latest\n\nTotal reclaimed space: 7.8MB\n";
+        assert_eq!(parse_reclaimed_space(output), Some(7_800_000));
+        assert_eq!(parse_reclaimed_space("Total reclaimed space: 0B"), Some(0));
+        assert_eq!(parse_reclaimed_space("nothing here"), None);
+    }
+
     #[test]
     fn test_parse_docker_size_bytes() {
         assert_eq!(parse_docker_size("0B"), 0);
@@ -213,6 +879,14 @@ mod tests {
         assert_eq!(parse_docker_size("1TB"), 1_000_000_000_000);
     }
 
+    #[test]
+    fn test_parse_docker_size_binary_units() {
+        assert_eq!(parse_docker_size("12.0 KiB"), 12 * 1024);
+        assert_eq!(parse_docker_size("7.5 MiB"), 7_864_320);
+        assert_eq!(parse_docker_size("2 GiB"), 2 * 1024 * 1024 * 1024);
+        assert_eq!(parse_docker_size("0 B"), 0);
+    }
+
     #[test]
     fn test_parse_docker_size_with_percentage() {
         assert_eq!(parse_docker_size("500MB (41%)"), 500_000_000);
@@ -239,20 +913,30 @@ mod tests {
 
         let resources = parse_system_df_output(output);
 
-        assert_eq!(resources.len(), 2);
+        let ids: Vec<&str> = resources.iter().map(|r| r.resource_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "docker-images",
+                "docker-containers",
+                "docker-volumes",
+                "docker-build-cache"
+            ]
+        );
+        assert!(resources.iter().all(|r| r.category == "docker"));
 
-        // Images should be first (in output order)
-        assert_eq!(resources[0].resource_id, "docker-images");
-        assert_eq!(resources[0].display_name, "Docker Images");
-        assert_eq!(resources[0].category, "docker");
+        // Counts exclude images, containers and volumes in use
+        assert_eq!(resources[0].display_name, "Docker Unused Images");
         assert_eq!(resources[0].size, 500_000_000);
-        assert_eq!(resources[0].item_count, Some(5));
+        assert_eq!(resources[0].item_count, Some(3));
+        assert_eq!(resources[1].size, 30_000_000);
+        assert_eq!(resources[1].item_count, Some(2));
+        assert_eq!(resources[2].size, 50_000_000);
+        assert_eq!(resources[2].item_count, Some(1));
 
-        // Build Cache second
-        assert_eq!(resources[1].resource_id, "docker-build-cache");
-        assert_eq!(resources[1].display_name, "Docker Build Cache");
-        assert_eq!(resources[1].size, 2_547_000_000);
-        assert_eq!(resources[1].item_count, Some(10));
+        assert_eq!(resources[3].display_name, "Docker Build Cache");
+        assert_eq!(resources[3].size, 2_547_000_000);
+        assert_eq!(resources[3].item_count, Some(10));
     }
 
     #[test]
@@ -276,12 +960,101 @@ mod tests {
         assert_eq!(resources[0].size, 0);
     }
 
+    const VERBOSE_DF: &str = r#"{"BuildCache":[],"Containers":[{"Command":"\"sh\"","CreatedAt":"2024-03-01 12:00:00 +0000 UTC","ID":"c0ffee000001","Image":"alpine","LocalVolumes":"1","Mounts":"data","Names":"old_job","Networks":"bridge","Ports":"","RunningFor":"3 months ago","Size":"1.5kB","State":"exited","Status":"Exited (0) 3 months ago"},{"Command":"\"nginx\"","CreatedAt":"2024-05-01 12:00:00 +0000 UTC","ID":"c0ffee000002","Image":"nginx:latest","LocalVolumes":"0","Mounts":"","Names":"web","Networks":"bridge","Ports":"80/tcp","RunningFor":"1 month ago","Size":"2B","State":"running","Status":"Up 2 days"}],"Images":[{"Containers":"1","CreatedAt":"2024-01-10 08:00:00 +0000 UTC","CreatedSince":"5 months ago","Digest":"<none>","ID":"1111aaaa2222","Repository":"nginx","SharedSize":"0B","Size":"187MB","Tag":"latest","UniqueSize":"187MB","VirtualSize":"187MB"},{"Containers":"0","CreatedAt":"2024-01-05 08:00:00 +0000 UTC","CreatedSince":"5 months ago","Digest":"<none>","ID":"3333bbbb4444","Repository":"<none>","SharedSize":"5MB","Size":"80MB","Tag":"<none>","UniqueSize":"75MB","VirtualSize":"80MB"},{"Containers":"0","CreatedAt":"2023-12-01 08:00:00 +0000 UTC","CreatedSince":"6 months ago","Digest":"<none>","ID":"5555cccc6666","Repository":"postgres","SharedSize":"0B","Size":"400MB","Tag":"15","UniqueSize":"400MB","VirtualSize":"400MB"}],"Volumes":[{"Driver":"local","Labels":"","Links":"0","Mountpoint":"","Name":"pgdata","Scope":"local","Size":"1.2GB"},{"Driver":"local","Labels":"","Links":"1","Mountpoint":"","Name":"data","Scope":"local","Size":"10MB"}]}"#;
+
     #[test]
-    fn test_parse_system_df_output_only_containers_and_volumes() {
-        let output = r#"{"Type":"Containers","TotalCount":"3","Active":"1","Size":"50MB","Reclaimable":"30MB (60%)"}
-{"Type":"Local Volumes","TotalCount":"2","Active":"1","Size":"100MB","Reclaimable":"50MB (50%)"}"#;
+    fn test_parse_verbose_df_output() {
+        let usage = parse_verbose_df_output(VERBOSE_DF).unwrap();
 
-        let resources = parse_system_df_output(output);
-        assert!(resources.is_empty());
+        assert_eq!(usage.images.len(), 3);
+        let dangling: Vec<&str> = usage
+            .images
+            .iter()
+            .filter(|i| i.is_dangling())
+            .map(|i| i.id.as_str())
+            .collect();
+        assert_eq!(dangling, vec!["3333bbbb4444"]);
+        // Removing an image frees only its unique layers
+        assert_eq!(usage.images[1].size, 75_000_000);
+        assert_eq!(usage.images[0].name(), "nginx:latest");
+        assert_eq!(usage.images[1].name(), "3333bbbb4444");
+        assert_eq!(usage.images[2].name(), "postgres:15");
+
+        let stopped: Vec<&str> = usage
+            .containers
+            .iter()
+            .filter(|c| !c.is_active())
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(stopped, vec!["old_job"]);
+        assert_eq!(usage.containers[0].size, 1500);
+
+        assert_eq!(usage.volumes[0].name, "pgdata");
+        assert_eq!(usage.volumes[0].links, 0);
+        assert_eq!(usage.volumes[0].size, 1_200_000_000);
+
+        assert!(parse_verbose_df_output("not json").is_none());
+    }
+
+    #[test]
+    fn test_parse_docker_time() {
+        let epoch = |secs: u64| Some(UNIX_EPOCH + Duration::from_secs(secs));
+
+        assert_eq!(parse_docker_time("1970-01-01 00:00:00 +0000 UTC"), epoch(0));
+        assert_eq!(
+            parse_docker_time("2024-01-15 10:30:00 +0000 UTC"),
+            epoch(1_705_314_600)
+        );
+        assert_eq!(
+            parse_docker_time("2024-01-15 12:30:00 +0200 CEST"),
+            epoch(1_705_314_600)
+        );
+        assert_eq!(
+            parse_docker_time("2024-01-15T10:30:00.123456789Z"),
+            epoch(1_705_314_600)
+        );
+        assert_eq!(
+            parse_docker_time("2024-01-15T05:30:00-05:00"),
+            epoch(1_705_314_600)
+        );
+        assert_eq!(parse_docker_time("yesterday"), None);
+        assert_eq!(parse_docker_time(""), None);
+    }
+
+    #[test]
+    fn test_parse_image_inspect() {
+        let value: serde_json::Value = serde_json::from_str(
+            r#"{"Id":"sha256:5555cccc6666dddd","Created":"2023-12-01T08:00:00Z","Metadata":{"LastTagTime":"2024-02-01T00:00:00Z"}}"#,
+        )
+        .unwrap();
+        let (id, time) = parse_image_inspect(&value).unwrap();
+        assert_eq!(id, "5555cccc6666");
+        assert_eq!(time, parse_docker_time("2024-02-01T00:00:00Z").unwrap());
+
+        // Never tagged: fall back to the creation time
+        let value: serde_json::Value = serde_json::from_str(
+            r#"{"Id":"sha256:3333bbbb4444","Created":"2024-01-05T08:00:00Z","Metadata":{"LastTagTime":"0001-01-01T00:00:00Z"}}"#,
+        )
+        .unwrap();
+        let (_, time) = parse_image_inspect(&value).unwrap();
+        assert_eq!(time, parse_docker_time("2024-01-05T08:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn test_is_anonymous_volume() {
+        assert!(is_anonymous_volume(
+            "3f2a9c0b7e1d4a6f8b2c5e9d0a1b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d"
+        ));
+        assert!(!is_anonymous_volume("pgdata"));
+        assert!(!is_anonymous_volume("project_db_data"));
+        // Right length, but not hex
+        assert!(!is_anonymous_volume(&"z".repeat(64)));
+    }
+
+    #[test]
+    fn test_volumes_are_kept_after_containers_were_pruned() {
+        let resource = EngineResource::Volumes.resource("docker", "Docker", 1000, Some(1));
+        let result = clean_anonymous_volumes(&resource, "docker", true, |_| None);
+        assert!(matches!(result, SystemCleanResult::Skipped { .. }));
     }
 }
@@ -1,11 +1,15 @@
 //! Executor for cleaning project artifacts.
 
+use crate::cleaner::cargo_prune::CargoPrune;
 use crate::cleaner::detector::DetectedProject;
+use crate::cleaner::measure::{reclaimable_usage, SpaceProbe};
+use crate::journal::{CleanMethod, Journal, JournalRecord};
+use crate::quarantine::Quarantine;
 use std::fs;
 use std::io;
 use std::path::Path;
 use std::process::Command;
-use walkdir::WalkDir;
+use std::sync::Arc;
 
 /// Result of a clean operation.
 #[derive(Debug, Clone)]
@@ -13,7 +17,10 @@ pub enum CleanResult {
     /// Cleaning succeeded.
     Success {
         project: DetectedProject,
+        /// Disk usage actually reclaimed (or moved into the quarantine).
         freed_bytes: u64,
+        /// Pre-scan estimate of the artifacts' size.
+        estimated_bytes: u64,
     },
     /// Cleaning failed.
     Failed {
@@ -34,6 +41,14 @@ pub struct CleanOptions {
     pub dry_run: bool,
     /// If true, try native clean commands before direct deletion.
     pub use_native_commands: bool,
+    /// If set, move artifacts into this quarantine instead of deleting them.
+    /// Native clean commands are skipped, since their output can't be restored.
+    pub quarantine: Option<Arc<Quarantine>>,
+    /// If set, append a record of every deletion to this journal.
+    pub journal: Option<Arc<Journal>>,
+    /// If set, Cargo target dirs are pruned of their stale parts instead of
+    /// being removed; `cargo clean` isn't run.
+    pub cargo_prune: Option<CargoPrune>,
 }
 
 impl Default for CleanOptions {
@@ -41,6 +56,9 @@ impl Default for CleanOptions {
         Self {
             dry_run: false,
             use_native_commands: true,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         }
     }
 }
@@ -60,22 +78,43 @@ impl CleanExecutor {
     ///
     /// If `clean_command` is provided and native commands are enabled,
     /// it will be tried first. Falls back to direct deletion on failure.
+    ///
+    /// Freed space is measured as the disk usage of the artifact paths before
+    /// and after cleaning; dry runs report the estimate.
     pub fn clean(&self, project: &DetectedProject, clean_command: Option<&str>) -> CleanResult {
+        if let Some(prune) = &self.options.cargo_prune {
+            if project.project_type == "cargo" {
+                return self.prune(project, prune);
+            }
+        }
+
         if self.options.dry_run {
             return CleanResult::Success {
                 project: project.clone(),
                 freed_bytes: project.artifact_size,
+                estimated_bytes: project.artifact_size,
             };
         }
 
         // Try native command first if available and enabled
-        if self.options.use_native_commands {
+        if self.options.use_native_commands && self.options.quarantine.is_none() {
             if let Some(cmd) = clean_command {
+                let probe = SpaceProbe::start(&project.artifact_paths, &project.path);
                 match self.run_clean_command(&project.path, cmd) {
                     Ok(()) => {
+                        let freed = probe.freed().unwrap_or(0);
+                        self.record(
+                            JournalRecord::new(
+                                &project.path,
+                                Some(&project.project_type),
+                                CleanMethod::Native,
+                            )
+                            .freed(freed),
+                        );
                         return CleanResult::Success {
                             project: project.clone(),
-                            freed_bytes: project.artifact_size,
+                            freed_bytes: freed,
+                            estimated_bytes: project.artifact_size,
                         };
                     }
                     Err(e) => {
@@ -94,6 +133,7 @@ impl CleanExecutor {
             Ok(freed) => CleanResult::Success {
                 project: project.clone(),
                 freed_bytes: freed,
+                estimated_bytes: project.artifact_size,
             },
             Err(e) => CleanResult::Failed {
                 project: project.clone(),
@@ -102,6 +142,86 @@ impl CleanExecutor {
         }
     }
 
+    /// Clean every artifact group of a project, running each type's native
+    /// command from `clean_command` in turn.
+    ///
+    /// A single-type project is cleaned as by [`clean`](Self::clean). For a
+    /// composite one, the freed space is summed over the groups and the first
+    /// failing group fails the project; groups after it are left alone.
+    pub fn clean_groups<'a>(
+        &self,
+        project: &DetectedProject,
+        clean_command: impl Fn(&str) -> Option<&'a str>,
+    ) -> CleanResult {
+        if !project.is_composite() {
+            return self.clean(project, clean_command(&project.project_type));
+        }
+
+        let mut freed_bytes = 0;
+        for group in &project.artifact_groups {
+            let part = project.for_group(group);
+            match self.clean(&part, clean_command(&group.project_type)) {
+                CleanResult::Success {
+                    freed_bytes: freed, ..
+                } => freed_bytes += freed,
+                CleanResult::Failed { error, .. } => {
+                    return CleanResult::Failed {
+                        project: project.clone(),
+                        error: format!("{}: {}", group.project_type, error),
+                    };
+                }
+                CleanResult::Skipped { .. } => {}
+            }
+        }
+
+        CleanResult::Success {
+            project: project.clone(),
+            freed_bytes,
+            estimated_bytes: project.artifact_size,
+        }
+    }
+
+    /// Remove only the stale parts of a Cargo project's target dirs. The
+    /// estimate is the apparent size of what the plan removes.
+    fn prune(&self, project: &DetectedProject, prune: &CargoPrune) -> CleanResult {
+        let plans: Vec<_> = project
+            .artifact_paths
+            .iter()
+            .map(|target| prune.plan(target))
+            .collect();
+        let estimated_bytes = plans.iter().map(|plan| plan.size()).sum();
+        if self.options.dry_run {
+            return CleanResult::Success {
+                project: project.clone(),
+                freed_bytes: estimated_bytes,
+                estimated_bytes,
+            };
+        }
+
+        let mut freed_bytes = 0;
+        let paths = plans
+            .iter()
+            .flat_map(|plan| &plan.items)
+            .flat_map(|item| &item.paths);
+        for path in paths {
+            match self.remove_path(path, &project.project_type) {
+                Ok(freed) => freed_bytes += freed,
+                Err(e) => {
+                    return CleanResult::Failed {
+                        project: project.clone(),
+                        error: e.to_string(),
+                    }
+                }
+            }
+        }
+
+        CleanResult::Success {
+            project: project.clone(),
+            freed_bytes,
+            estimated_bytes,
+        }
+    }
+
     fn run_clean_command(&self, project_path: &Path, command: &str) -> io::Result<()> {
         let parts: Vec<&str> = command.split_whitespace().collect();
         if parts.is_empty() {
@@ -127,30 +247,62 @@ impl CleanExecutor {
         let mut freed = 0u64;
 
         for artifact_path in &project.artifact_paths {
-            if artifact_path.exists() {
-                let size = Self::dir_size(artifact_path);
-                fs::remove_dir_all(artifact_path)?;
-                freed += size;
+            if !artifact_path.exists() {
+                continue;
             }
+            freed += self.remove_path(artifact_path, &project.project_type)?;
         }
 
         Ok(freed)
     }
 
-    fn dir_size(path: &Path) -> u64 {
-        WalkDir::new(path)
-            .into_iter()
-            .flatten()
-            .filter(|e| e.file_type().is_file())
-            .filter_map(|e| e.metadata().ok())
-            .map(|m| m.len())
-            .sum()
+    /// Delete or quarantine one file or directory and journal it. Returns
+    /// the disk usage it reclaimed.
+    fn remove_path(&self, path: &Path, project_type: &str) -> io::Result<u64> {
+        let usage = reclaimable_usage(&[path.to_path_buf()]);
+        let (method, result) = match &self.options.quarantine {
+            Some(quarantine) => (
+                CleanMethod::Quarantine,
+                quarantine
+                    .quarantine(path)
+                    .map(|_| usage)
+                    .map_err(io::Error::other),
+            ),
+            None => {
+                let is_dir = fs::symlink_metadata(path).is_ok_and(|m| m.is_dir());
+                let removed = if is_dir {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                (CleanMethod::Delete, removed.map(|()| usage))
+            }
+        };
+
+        let record = JournalRecord::new(path, Some(project_type), method);
+        match result {
+            Ok(size) => {
+                self.record(record.freed(size));
+                Ok(size)
+            }
+            Err(e) => {
+                self.record(record.failed(&e));
+                Err(e)
+            }
+        }
+    }
+
+    fn record(&self, record: JournalRecord) {
+        if let Some(journal) = &self.options.journal {
+            journal.record(record);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cleaner::detector::ArtifactGroup;
     use std::path::PathBuf;
     use tempfile::TempDir;
 
@@ -167,6 +319,10 @@ mod tests {
             display_name: "Test".to_string(),
             artifact_size: 1000,
             artifact_paths: vec![target],
+            git: None,
+            artifact_groups: vec![],
+            parent: None,
+            shared_with: vec![],
         };
 
         (tmp, project)
@@ -179,6 +335,9 @@ mod tests {
         let executor = CleanExecutor::new(CleanOptions {
             dry_run: true,
             use_native_commands: false,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         });
 
         let result = executor.clean(&project, None);
@@ -195,13 +354,23 @@ mod tests {
         let executor = CleanExecutor::new(CleanOptions {
             dry_run: false,
             use_native_commands: false,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         });
 
+        let usage = reclaimable_usage(&project.artifact_paths);
         let result = executor.clean(&project, None);
 
         match result {
-            CleanResult::Success { freed_bytes, .. } => {
-                assert_eq!(freed_bytes, 1000);
+            CleanResult::Success {
+                freed_bytes,
+                estimated_bytes,
+                ..
+            } => {
+                // Measured in allocated blocks, not the apparent size
+                assert_eq!(freed_bytes, usage);
+                assert_eq!(estimated_bytes, 1000);
             }
             _ => panic!("Expected success"),
         }
@@ -220,11 +389,18 @@ mod tests {
             display_name: "Test".to_string(),
             artifact_size: 0,
             artifact_paths: vec![tmp.path().join("nonexistent")],
+            git: None,
+            artifact_groups: vec![],
+            parent: None,
+            shared_with: vec![],
         };
 
         let executor = CleanExecutor::new(CleanOptions {
             dry_run: false,
             use_native_commands: false,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         });
 
         let result = executor.clean(&project, None);
@@ -253,18 +429,31 @@ mod tests {
             display_name: "Test".to_string(),
             artifact_size: 800,
             artifact_paths: vec![target.clone(), build.clone()],
+            git: None,
+            artifact_groups: vec![],
+            parent: None,
+            shared_with: vec![],
         };
 
         let executor = CleanExecutor::new(CleanOptions {
             dry_run: false,
             use_native_commands: false,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         });
 
+        let usage = reclaimable_usage(&project.artifact_paths);
         let result = executor.clean(&project, None);
 
         match result {
-            CleanResult::Success { freed_bytes, .. } => {
-                assert_eq!(freed_bytes, 800);
+            CleanResult::Success {
+                freed_bytes,
+                estimated_bytes,
+                ..
+            } => {
+                assert_eq!(freed_bytes, usage);
+                assert_eq!(estimated_bytes, 800);
             }
             _ => panic!("Expected success"),
         }
@@ -273,6 +462,246 @@ mod tests {
         assert!(!build.exists());
     }
 
+    #[test]
+    fn test_clean_quarantines_instead_of_deleting() {
+        let (tmp, project) = create_test_project();
+        let quarantine = Arc::new(Quarantine::with_dirs(
+            tmp.path().join("data"),
+            tmp.path().join("Trash"),
+        ));
+
+        let executor = CleanExecutor::new(CleanOptions {
+            dry_run: false,
+            use_native_commands: true,
+            quarantine: Some(Arc::clone(&quarantine)),
+            journal: None,
+            cargo_prune: None,
+        });
+
+        // The native command would fail; it must not even be attempted
+        let usage = reclaimable_usage(&project.artifact_paths);
+        let result = executor.clean(&project, Some("false"));
+
+        assert!(matches!(
+            result,
+            CleanResult::Success { freed_bytes, .. } if freed_bytes == usage
+        ));
+        assert!(!project.artifact_paths[0].exists());
+
+        let entries = quarantine.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        quarantine.restore(&entries[0].id).unwrap();
+        assert!(project.artifact_paths[0].join("artifact.bin").exists());
+    }
+
+    #[test]
+    fn test_native_command_measures_freed_space() {
+        let (_tmp, project) = create_test_project();
+        let usage = reclaimable_usage(&project.artifact_paths);
+
+        let executor = CleanExecutor::new(CleanOptions::default());
+        let command = format!("rm -r {}", project.artifact_paths[0].display());
+
+        match executor.clean(&project, Some(&command)) {
+            CleanResult::Success {
+                freed_bytes,
+                estimated_bytes,
+                ..
+            } => {
+                assert_eq!(freed_bytes, usage);
+                assert_eq!(estimated_bytes, 1000);
+            }
+            other => panic!("Expected success, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_clean_groups_runs_each_native_command() {
+        let tmp = TempDir::new().unwrap();
+        let target = tmp.path().join("target");
+        let node_modules = tmp.path().join("node_modules");
+        fs::create_dir_all(&target).unwrap();
+        fs::create_dir_all(&node_modules).unwrap();
+        fs::write(target.join("app.bin"), "x".repeat(1000)).unwrap();
+        fs::write(node_modules.join("dep.js"), "x".repeat(500)).unwrap();
+        let group = |project_type: &str, path: &PathBuf, size| ArtifactGroup {
+            project_type: project_type.to_string(),
+            display_name: project_type.to_string(),
+            artifact_size: size,
+            artifact_paths: vec![path.clone()],
+        };
+        let project = DetectedProject::from_groups(
+            tmp.path().to_path_buf(),
+            vec![
+                group("cargo", &target, 1000),
+                group("npm", &node_modules, 500),
+            ],
+        )
+        .unwrap();
+        let usage = reclaimable_usage(&project.artifact_paths);
+
+        let executor = CleanExecutor::new(CleanOptions::default());
+        let result = executor.clean_groups(&project, |project_type| match project_type {
+            "cargo" => Some("rm -r target"),
+            _ => None,
+        });
+
+        match result {
+            CleanResult::Success {
+                project: cleaned,
+                freed_bytes,
+                estimated_bytes,
+            } => {
+                assert_eq!(cleaned.type_label(), "cargo+npm");
+                assert_eq!(freed_bytes, usage);
+                assert_eq!(estimated_bytes, 1500);
+            }
+            other => panic!("Expected success, got {:?}", other),
+        }
+        assert!(!target.exists());
+        assert!(!node_modules.exists());
+    }
+
+    #[test]
+    fn test_native_command_that_frees_nothing() {
+        let (_tmp, project) = create_test_project();
+
+        let executor = CleanExecutor::new(CleanOptions::default());
+
+        // `true` succeeds without touching the artifacts
+        match executor.clean(&project, Some("true")) {
+            CleanResult::Success { freed_bytes, .. } => assert_eq!(freed_bytes, 0),
+            other => panic!("Expected success, got {:?}", other),
+        }
+        assert!(project.artifact_paths[0].exists());
+    }
+
+    #[test]
+    fn test_prune_keeps_current_cargo_units() {
+        let tmp = TempDir::new().unwrap();
+        let target = tmp.path().join("target");
+        let write = |rel: &str, content: &str| {
+            let path = target.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        // `old` was built by a previous toolchain, `app` by the current one
+        write(
+            "debug/.fingerprint/old-0000000000000001/lib-old.json",
+            r#"{"rustc":1}"#,
+        );
+        write("debug/deps/libold-0000000000000001.rlib", &"x".repeat(600));
+        let last_week = std::time::SystemTime::now() - std::time::Duration::from_secs(7 * 86400);
+        fs::File::options()
+            .write(true)
+            .open(target.join("debug/.fingerprint/old-0000000000000001/lib-old.json"))
+            .unwrap()
+            .set_times(
+                fs::FileTimes::new()
+                    .set_accessed(last_week)
+                    .set_modified(last_week),
+            )
+            .unwrap();
+        write(
+            "debug/.fingerprint/app-0000000000000002/bin-app.json",
+            r#"{"rustc":2}"#,
+        );
+        write("debug/deps/app-0000000000000002", &"x".repeat(400));
+
+        let project = DetectedProject {
+            path: tmp.path().to_path_buf(),
+            project_type: "cargo".to_string(),
+            display_name: "Rust/Cargo".to_string(),
+            artifact_size: 1000,
+            artifact_paths: vec![target.clone()],
+            git: None,
+            artifact_groups: vec![],
+            parent: None,
+            shared_with: vec![],
+        };
+        let prune = CargoPrune {
+            old_toolchains: true,
+            ..Default::default()
+        };
+        let executor = |dry_run| {
+            CleanExecutor::new(CleanOptions {
+                dry_run,
+                cargo_prune: Some(prune.clone()),
+                ..Default::default()
+            })
+        };
+
+        let estimate = match executor(true).clean(&project, Some("false")) {
+            CleanResult::Success {
+                freed_bytes,
+                estimated_bytes,
+                ..
+            } => {
+                assert_eq!(freed_bytes, estimated_bytes);
+                estimated_bytes
+            }
+            other => panic!("Expected success, got {:?}", other),
+        };
+        assert_eq!(estimate, 11 + 600);
+        assert!(target
+            .join("debug/deps/libold-0000000000000001.rlib")
+            .exists());
+
+        // `cargo clean` isn't run; only the stale unit goes
+        assert!(matches!(
+            executor(false).clean(&project, Some("false")),
+            CleanResult::Success { estimated_bytes, .. } if estimated_bytes == estimate
+        ));
+        assert!(!target
+            .join("debug/.fingerprint/old-0000000000000001")
+            .exists());
+        assert!(!target
+            .join("debug/deps/libold-0000000000000001.rlib")
+            .exists());
+        assert!(target.join("debug/deps/app-0000000000000002").exists());
+    }
+
+    #[test]
+    fn test_clean_records_journal() {
+        let (tmp, project) = create_test_project();
+        let journal = Arc::new(Journal::with_path(tmp.path().join("journal.jsonl")));
+
+        let executor = CleanExecutor::new(CleanOptions {
+            dry_run: false,
+            use_native_commands: false,
+            quarantine: None,
+            journal: Some(Arc::clone(&journal)),
+            cargo_prune: None,
+        });
+        let usage = reclaimable_usage(&project.artifact_paths);
+        executor.clean(&project, None);
+
+        let records = journal.query(&Default::default()).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].path, project.artifact_paths[0]);
+        assert_eq!(records[0].project_type.as_deref(), Some("test"));
+        assert_eq!(records[0].method, CleanMethod::Delete);
+        assert_eq!(records[0].freed_bytes, usage);
+        assert!(records[0].error.is_none());
+    }
+
+    #[test]
+    fn test_dry_run_records_nothing() {
+        let (tmp, project) = create_test_project();
+        let journal = Arc::new(Journal::with_path(tmp.path().join("journal.jsonl")));
+
+        let executor = CleanExecutor::new(CleanOptions {
+            dry_run: true,
+            use_native_commands: false,
+            quarantine: None,
+            journal: Some(Arc::clone(&journal)),
+            cargo_prune: None,
+        });
+        executor.clean(&project, None);
+
+        assert!(journal.query(&Default::default()).unwrap().is_empty());
+    }
+
     #[test]
     fn test_clean_result_variants() {
         let project = DetectedProject {
@@ -281,12 +710,17 @@ mod tests {
             display_name: "Test".to_string(),
             artifact_size: 100,
             artifact_paths: vec![],
+            git: None,
+            artifact_groups: vec![],
+            parent: None,
+            shared_with: vec![],
         };
 
         // Test all variants can be constructed
         let _success = CleanResult::Success {
             project: project.clone(),
             freed_bytes: 100,
+            estimated_bytes: 100,
         };
 
         let _failed = CleanResult::Failed {
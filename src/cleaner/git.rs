@@ -0,0 +1,330 @@
+//! Git state of a project, checked before its artifacts are cleaned.
+//!
+//! Uses the local `git` binary plus a few files in the git directory. No
+//! command here talks to a remote: "unpushed" means commits on local
+//! branches that no remote-tracking ref contains, as of the last fetch. A
+//! repository without any remote has nothing to push to, so its commits are
+//! not counted as unpushed.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A multi-step git operation left unfinished in the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitOperation {
+    Rebase,
+    Merge,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+impl GitOperation {
+    /// Files or directories in the git directory marking each operation.
+    const MARKERS: [(&'static str, GitOperation); 6] = [
+        ("rebase-merge", GitOperation::Rebase),
+        ("rebase-apply", GitOperation::Rebase),
+        ("MERGE_HEAD", GitOperation::Merge),
+        ("CHERRY_PICK_HEAD", GitOperation::CherryPick),
+        ("REVERT_HEAD", GitOperation::Revert),
+        ("BISECT_LOG", GitOperation::Bisect),
+    ];
+
+    /// Detect an operation in progress from the markers in `git_dir`.
+    pub fn in_progress(git_dir: &Path) -> Option<Self> {
+        Self::MARKERS
+            .iter()
+            .find(|(marker, _)| git_dir.join(marker).exists())
+            .map(|(_, op)| *op)
+    }
+}
+
+impl fmt::Display for GitOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GitOperation::Rebase => "rebase",
+            GitOperation::Merge => "merge",
+            GitOperation::CherryPick => "cherry-pick",
+            GitOperation::Revert => "revert",
+            GitOperation::Bisect => "bisect",
+        };
+        write!(f, "{} in progress", name)
+    }
+}
+
+/// Git state of the repository containing a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitState {
+    /// Uncommitted changes (including untracked files) below the project root,
+    /// not counting its artifact directories
+    pub dirty: bool,
+    /// Commits on local branches not contained in any remote-tracking branch,
+    /// always 0 without a remote
+    pub unpushed_commits: usize,
+    /// The repository has no remote configured
+    pub no_remote: bool,
+    /// Entries in the stash
+    pub stashes: usize,
+    /// Unfinished rebase, merge, etc.
+    pub operation: Option<GitOperation>,
+}
+
+impl GitState {
+    /// Inspect the repository containing `path`.
+    ///
+    /// Returns `None` if `path` is not inside a git work tree or `git` is
+    /// unavailable. Changes inside `artifact_paths` are ignored, so an
+    /// artifact directory missing from `.gitignore` doesn't count as dirty.
+    pub fn inspect(path: &Path, artifact_paths: &[PathBuf]) -> Option<Self> {
+        let git_dir = PathBuf::from(git(path, &["rev-parse", "--absolute-git-dir"])?.trim());
+
+        let mut status_args = vec![
+            "status".to_string(),
+            "--porcelain".to_string(),
+            "--untracked-files=normal".to_string(),
+            "--".to_string(),
+            ".".to_string(),
+        ];
+        status_args.extend(
+            artifact_paths
+                .iter()
+                .filter_map(|p| p.strip_prefix(path).ok())
+                .map(|rel| format!(":(exclude){}", rel.display())),
+        );
+        let status_args: Vec<&str> = status_args.iter().map(String::as_str).collect();
+        let dirty = !git(path, &status_args)?.trim().is_empty();
+
+        // Without a remote every local commit would count as unpushed
+        let no_remote = git(path, &["remote"]).is_some_and(|out| out.trim().is_empty());
+        let unpushed_commits = if no_remote {
+            0
+        } else {
+            git(
+                path,
+                &["rev-list", "--count", "--branches", "--not", "--remotes"],
+            )
+            .and_then(|out| out.trim().parse().ok())
+            .unwrap_or(0)
+        };
+
+        // Fails when there is no stash
+        let stashes = git(
+            path,
+            &["rev-list", "--walk-reflogs", "--count", "refs/stash"],
+        )
+        .and_then(|out| out.trim().parse().ok())
+        .unwrap_or(0);
+
+        Some(Self {
+            dirty,
+            unpushed_commits,
+            no_remote,
+            stashes,
+            operation: GitOperation::in_progress(&git_dir),
+        })
+    }
+
+    /// Returns true if cleaning can't get in the way of unsaved git work.
+    ///
+    /// A missing remote is reported but not counted as unsaved work.
+    pub fn is_clean(&self) -> bool {
+        !self.dirty && self.unpushed_commits == 0 && self.stashes == 0 && self.operation.is_none()
+    }
+}
+
+impl fmt::Display for GitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return if self.no_remote {
+                write!(f, "clean, no remote")
+            } else {
+                write!(f, "clean")
+            };
+        }
+        let mut flags = Vec::new();
+        if self.dirty {
+            flags.push("dirty".to_string());
+        }
+        if self.unpushed_commits > 0 {
+            flags.push(format!("{} unpushed", self.unpushed_commits));
+        }
+        if self.stashes > 0 {
+            flags.push(format!(
+                "{} stash{}",
+                self.stashes,
+                if self.stashes == 1 { "" } else { "es" }
+            ));
+        }
+        if let Some(op) = self.operation {
+            flags.push(op.to_string());
+        }
+        if self.no_remote {
+            flags.push("no remote".to_string());
+        }
+        write!(f, "{}", flags.join(", "))
+    }
+}
+
+/// Run a read-only git command in `dir`, returning stdout on success.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("--no-optional-locks")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A repository with one commit, pushed to a local bare "origin".
+    fn pushed_repo() -> (TempDir, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let origin = tmp.path().join("origin.git");
+        let repo = tmp.path().join("repo");
+        run(
+            tmp.path(),
+            &["init", "-q", "--bare", origin.to_str().unwrap()],
+        );
+        run(
+            tmp.path(),
+            &["init", "-q", "-b", "main", repo.to_str().unwrap()],
+        );
+        fs::write(repo.join("Cargo.toml"), "[package]").unwrap();
+        run(&repo, &["add", "."]);
+        run(&repo, &["commit", "-q", "-m", "init"]);
+        run(
+            &repo,
+            &["remote", "add", "origin", origin.to_str().unwrap()],
+        );
+        run(&repo, &["push", "-q", "origin", "main"]);
+        (tmp, repo)
+    }
+
+    #[test]
+    fn test_not_a_repository() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(GitState::inspect(tmp.path(), &[]), None);
+    }
+
+    #[test]
+    fn test_clean_repository() {
+        let (_tmp, repo) = pushed_repo();
+        let state = GitState::inspect(&repo, &[]).unwrap();
+        assert!(state.is_clean());
+        assert_eq!(state.to_string(), "clean");
+    }
+
+    #[test]
+    fn test_untracked_artifacts_are_not_dirty() {
+        let (_tmp, repo) = pushed_repo();
+        fs::create_dir(repo.join("target")).unwrap();
+        fs::write(repo.join("target/out.bin"), "x").unwrap();
+
+        let state = GitState::inspect(&repo, &[repo.join("target")]).unwrap();
+        assert!(!state.dirty);
+
+        fs::write(repo.join("new.rs"), "").unwrap();
+        let state = GitState::inspect(&repo, &[repo.join("target")]).unwrap();
+        assert!(state.dirty);
+    }
+
+    #[test]
+    fn test_dirty_is_scoped_to_project() {
+        let (_tmp, repo) = pushed_repo();
+        fs::create_dir_all(repo.join("a")).unwrap();
+        fs::create_dir_all(repo.join("b")).unwrap();
+        fs::write(repo.join("b/notes.txt"), "").unwrap();
+
+        let a = GitState::inspect(&repo.join("a"), &[]).unwrap();
+        let b = GitState::inspect(&repo.join("b"), &[]).unwrap();
+        assert!(!a.dirty);
+        assert!(b.dirty);
+    }
+
+    #[test]
+    fn test_unpushed_and_stashed() {
+        let (_tmp, repo) = pushed_repo();
+        fs::write(repo.join("lib.rs"), "").unwrap();
+        run(&repo, &["add", "."]);
+        run(&repo, &["commit", "-q", "-m", "local"]);
+        fs::write(repo.join("lib.rs"), "fn main() {}").unwrap();
+        run(&repo, &["stash", "-q"]);
+
+        let state = GitState::inspect(&repo, &[]).unwrap();
+        assert!(!state.dirty);
+        assert_eq!(state.unpushed_commits, 1);
+        assert_eq!(state.stashes, 1);
+        assert_eq!(state.to_string(), "1 unpushed, 1 stash");
+    }
+
+    #[test]
+    fn test_local_only_repository_has_nothing_unpushed() {
+        let tmp = TempDir::new().unwrap();
+        run(tmp.path(), &["init", "-q", "-b", "main"]);
+        fs::write(tmp.path().join("Cargo.toml"), "[package]").unwrap();
+        run(tmp.path(), &["add", "."]);
+        run(tmp.path(), &["commit", "-q", "-m", "init"]);
+
+        let state = GitState::inspect(tmp.path(), &[]).unwrap();
+        assert!(state.no_remote);
+        assert_eq!(state.unpushed_commits, 0);
+        assert!(state.is_clean());
+        assert_eq!(state.to_string(), "clean, no remote");
+
+        fs::write(tmp.path().join("lib.rs"), "").unwrap();
+        let state = GitState::inspect(tmp.path(), &[]).unwrap();
+        assert_eq!(state.to_string(), "dirty, no remote");
+    }
+
+    #[test]
+    fn test_operation_in_progress() {
+        let (_tmp, repo) = pushed_repo();
+        fs::write(repo.join(".git/MERGE_HEAD"), "").unwrap();
+
+        let state = GitState::inspect(&repo, &[]).unwrap();
+        assert_eq!(state.operation, Some(GitOperation::Merge));
+        assert_eq!(state.to_string(), "merge in progress");
+    }
+
+    #[test]
+    fn test_display_combines_flags() {
+        let state = GitState {
+            dirty: true,
+            unpushed_commits: 3,
+            no_remote: false,
+            stashes: 2,
+            operation: Some(GitOperation::Rebase),
+        };
+        assert_eq!(
+            state.to_string(),
+            "dirty, 3 unpushed, 2 stashes, rebase in progress"
+        );
+    }
+}
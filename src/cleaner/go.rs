@@ -0,0 +1,407 @@
+//! Go toolchain cache cleaner.
+//!
+//! Go keeps no per-project artifacts; builds and downloads land in global
+//! caches (`GOCACHE`, `GOMODCACHE` and the rest of `GOPATH/pkg`). This cleaner
+//! reports those directories as system resources and clears them with
+//! `go clean`, deleting them directly when the toolchain is missing or fails.
+
+use crate::cleaner::measure::{apparent_size, reclaimable_usage};
+use crate::cleaner::system_cleaner::{
+    dry_run_result, run_quietly, DetectedSystemResource, SystemCleanResult, SystemCleaner,
+};
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use walkdir::WalkDir;
+
+/// Go cache system cleaner.
+pub struct GoCacheCleaner;
+
+impl SystemCleaner for GoCacheCleaner {
+    fn id(&self) -> &'static str {
+        "go"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Go"
+    }
+
+    fn is_available(&self) -> bool {
+        go_available() || GoPaths::resolve().existing().next().is_some()
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        detect_in(&GoPaths::resolve())
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        clean_in(&GoPaths::resolve(), resource, go_available())
+    }
+}
+
+/// Locations of the Go caches.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GoPaths {
+    /// Build cache (`GOCACHE`), unless it is turned off.
+    pub cache: Option<PathBuf>,
+    /// Module download cache (`GOMODCACHE`).
+    pub mod_cache: Option<PathBuf>,
+    /// First `GOPATH` entry.
+    pub gopath: Option<PathBuf>,
+}
+
+impl GoPaths {
+    /// Resolve the cache locations from `go env`, or from the environment and
+    /// Go's defaults when the toolchain isn't installed.
+    pub fn resolve() -> Self {
+        Command::new("go")
+            .args(["env", "-json", "GOCACHE", "GOMODCACHE", "GOPATH"])
+            .stderr(Stdio::null())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| Self::from_go_env(&String::from_utf8_lossy(&o.stdout)))
+            .unwrap_or_else(|| Self::from_defaults(|key| std::env::var(key).ok(), dirs::home_dir()))
+    }
+
+    /// Parse the output of `go env -json GOCACHE GOMODCACHE GOPATH`.
+    fn from_go_env(output: &str) -> Option<Self> {
+        let parsed: serde_json::Value = serde_json::from_str(output).ok()?;
+        let var = |key: &str| parsed[key].as_str().map(String::from);
+        Some(Self::build(
+            var("GOCACHE"),
+            var("GOMODCACHE"),
+            var("GOPATH"),
+        ))
+    }
+
+    /// Apply Go's defaults: `$XDG_CACHE_HOME/go-build`, `~/go` and
+    /// `GOPATH/pkg/mod`.
+    fn from_defaults(var: impl Fn(&str) -> Option<String>, home: Option<PathBuf>) -> Self {
+        let non_empty = |key: &str| var(key).filter(|v| !v.is_empty());
+        let cache = non_empty("GOCACHE").or_else(|| {
+            non_empty("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|h| h.join(".cache")))
+                .map(|dir| dir.join("go-build").to_string_lossy().into_owned())
+        });
+        let gopath = non_empty("GOPATH").or_else(|| {
+            home.as_ref()
+                .map(|h| h.join("go").to_string_lossy().into_owned())
+        });
+        Self::build(cache, non_empty("GOMODCACHE"), gopath)
+    }
+
+    fn build(cache: Option<String>, mod_cache: Option<String>, gopath: Option<String>) -> Self {
+        let gopath = gopath
+            .as_deref()
+            .and_then(|p| p.split(':').find(|entry| !entry.is_empty()))
+            .map(PathBuf::from);
+        let mod_cache = mod_cache
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| gopath.as_ref().map(|g| g.join("pkg").join("mod")));
+        Self {
+            cache: cache
+                .filter(|c| !c.is_empty() && c != "off")
+                .map(PathBuf::from),
+            mod_cache,
+            gopath,
+        }
+    }
+
+    /// Contents of `GOPATH/pkg` other than the module cache, such as the
+    /// checksum database and pre-module package archives.
+    fn pkg_entries(&self) -> Vec<PathBuf> {
+        let Some(pkg) = self.gopath.as_ref().map(|g| g.join("pkg")) else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(&pkg) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| self.mod_cache.as_ref() != Some(p))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Cache directories that exist on disk.
+    fn existing(&self) -> impl Iterator<Item = &PathBuf> {
+        [&self.cache, &self.mod_cache]
+            .into_iter()
+            .flatten()
+            .filter(|p| p.is_dir())
+    }
+}
+
+/// Report the Go caches found at `paths`.
+pub fn detect_in(paths: &GoPaths) -> Vec<DetectedSystemResource> {
+    let mut resources = Vec::new();
+
+    if let Some(cache) = paths.cache.as_ref().filter(|p| p.is_dir()) {
+        resources.push(DetectedSystemResource {
+            resource_id: "go-build-cache".to_string(),
+            display_name: "Go Build Cache".to_string(),
+            category: "go".to_string(),
+            size: apparent_size(std::slice::from_ref(cache)),
+            description: format!("Compiled packages and test results ({})", cache.display()),
+            item_count: None,
+        });
+    }
+
+    if let Some(mod_cache) = paths.mod_cache.as_ref().filter(|p| p.is_dir()) {
+        resources.push(DetectedSystemResource {
+            resource_id: "go-mod-cache".to_string(),
+            display_name: "Go Module Cache".to_string(),
+            category: "go".to_string(),
+            size: apparent_size(std::slice::from_ref(mod_cache)),
+            description: format!("Downloaded module sources ({})", mod_cache.display()),
+            item_count: None,
+        });
+    }
+
+    let pkg_entries = paths.pkg_entries();
+    if !pkg_entries.is_empty() {
+        resources.push(DetectedSystemResource {
+            resource_id: "go-pkg".to_string(),
+            display_name: "Go Package Dir".to_string(),
+            category: "go".to_string(),
+            size: apparent_size(&pkg_entries),
+            description: "Checksum database and package archives (GOPATH/pkg)".to_string(),
+            item_count: Some(pkg_entries.len() as u64),
+        });
+    }
+
+    resources
+}
+
+/// Clean `resource` from the caches at `paths`, through `go clean` when
+/// `use_go` is set and by deleting the directory contents otherwise.
+pub fn clean_in(
+    paths: &GoPaths,
+    resource: &DetectedSystemResource,
+    use_go: bool,
+) -> SystemCleanResult {
+    let (targets, go_flag) = match resource.resource_id.as_str() {
+        "go-build-cache" => (dir_contents(paths.cache.as_deref()), Some("-cache")),
+        "go-mod-cache" => (dir_contents(paths.mod_cache.as_deref()), Some("-modcache")),
+        "go-pkg" => (paths.pkg_entries(), None),
+        _ => {
+            return SystemCleanResult::Skipped {
+                resource: resource.clone(),
+                reason: format!("Unknown resource: {}", resource.resource_id),
+            };
+        }
+    };
+
+    let usage = reclaimable_usage(&targets);
+    let cleaned_by_go = use_go
+        && go_flag.is_some_and(|flag| {
+            Command::new("go")
+                .args(["clean", flag])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false)
+        });
+
+    if !cleaned_by_go {
+        for target in &targets {
+            if let Err(e) = force_remove(target) {
+                return SystemCleanResult::Failed {
+                    resource: resource.clone(),
+                    error: format!("Failed to delete {}: {}", target.display(), e),
+                };
+            }
+        }
+    }
+
+    SystemCleanResult::Success {
+        resource: resource.clone(),
+        freed_bytes: usage.saturating_sub(reclaimable_usage(&targets)),
+        estimated_bytes: resource.size,
+    }
+}
+
+/// Whether the `go` toolchain can be run.
+fn go_available() -> bool {
+    run_quietly(&["go", "version"])
+}
+
+/// Entries directly inside `dir`, which is kept so Go can reuse it.
+fn dir_contents(dir: Option<&Path>) -> Vec<PathBuf> {
+    dir.and_then(|d| fs::read_dir(d).ok())
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Delete `path`, first making directories writable; the module cache is
+/// read-only to keep builds from editing dependencies.
+fn force_remove(path: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return fs::remove_file(path);
+    }
+    for entry in WalkDir::new(path).into_iter().flatten() {
+        if entry.file_type().is_dir() {
+            let mut perms = entry.metadata()?.permissions();
+            perms.set_mode(perms.mode() | 0o700);
+            fs::set_permissions(entry.path(), perms)?;
+        }
+    }
+    fs::remove_dir_all(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    fn setup_gopath() -> (TempDir, GoPaths) {
+        let tmp = TempDir::new().unwrap();
+        let cache = tmp.path().join("go-build");
+        let gopath = tmp.path().join("go");
+        let mod_cache = gopath.join("pkg").join("mod");
+
+        fs::create_dir_all(cache.join("0a")).unwrap();
+        fs::write(cache.join("0a").join("obj-d"), "x".repeat(100)).unwrap();
+        let module = mod_cache.join("example.com").join("lib@v1.0.0");
+        fs::create_dir_all(&module).unwrap();
+        fs::write(module.join("lib.go"), "x".repeat(200)).unwrap();
+        fs::set_permissions(&module, fs::Permissions::from_mode(0o555)).unwrap();
+        fs::create_dir_all(gopath.join("pkg").join("sumdb")).unwrap();
+        fs::write(
+            gopath.join("pkg").join("sumdb").join("latest"),
+            "x".repeat(50),
+        )
+        .unwrap();
+
+        let paths = GoPaths {
+            cache: Some(cache),
+            mod_cache: Some(mod_cache),
+            gopath: Some(gopath),
+        };
+        (tmp, paths)
+    }
+
+    #[test]
+    fn test_paths_from_go_env() {
+        let output = r#"{
+	"GOCACHE": "/home/u/.cache/go-build",
+	"GOMODCACHE": "/home/u/go/pkg/mod",
+	"GOPATH": "/home/u/go:/opt/go"
+}"#;
+        let paths = GoPaths::from_go_env(output).unwrap();
+        assert_eq!(paths.cache, Some(PathBuf::from("/home/u/.cache/go-build")));
+        assert_eq!(paths.mod_cache, Some(PathBuf::from("/home/u/go/pkg/mod")));
+        assert_eq!(paths.gopath, Some(PathBuf::from("/home/u/go")));
+
+        let off = GoPaths::from_go_env(r#"{"GOCACHE": "off", "GOPATH": "/g"}"#).unwrap();
+        assert_eq!(off.cache, None);
+        assert_eq!(off.mod_cache, Some(PathBuf::from("/g/pkg/mod")));
+
+        assert!(GoPaths::from_go_env("not json").is_none());
+    }
+
+    #[test]
+    fn test_paths_from_defaults() {
+        let home = Some(PathBuf::from("/home/u"));
+        let paths = GoPaths::from_defaults(env(&[]), home.clone());
+        assert_eq!(paths.cache, Some(PathBuf::from("/home/u/.cache/go-build")));
+        assert_eq!(paths.mod_cache, Some(PathBuf::from("/home/u/go/pkg/mod")));
+        assert_eq!(paths.gopath, Some(PathBuf::from("/home/u/go")));
+
+        let paths = GoPaths::from_defaults(
+            env(&[
+                ("XDG_CACHE_HOME", "/xdg"),
+                ("GOPATH", "/g"),
+                ("GOMODCACHE", "/m"),
+            ]),
+            home,
+        );
+        assert_eq!(paths.cache, Some(PathBuf::from("/xdg/go-build")));
+        assert_eq!(paths.mod_cache, Some(PathBuf::from("/m")));
+        assert_eq!(paths.gopath, Some(PathBuf::from("/g")));
+    }
+
+    #[test]
+    fn test_detect_in() {
+        let (tmp, paths) = setup_gopath();
+        let resources = detect_in(&paths);
+
+        let ids: Vec<&str> = resources.iter().map(|r| r.resource_id.as_str()).collect();
+        assert_eq!(ids, vec!["go-build-cache", "go-mod-cache", "go-pkg"]);
+        assert!(resources.iter().all(|r| r.category == "go"));
+        assert_eq!(resources[0].size, 100);
+        assert_eq!(resources[1].size, 200);
+        // GOPATH/pkg excludes the module cache
+        assert_eq!(resources[2].size, 50);
+        assert_eq!(resources[2].item_count, Some(1));
+
+        // The module cache is read-only
+        force_remove(tmp.path()).unwrap();
+    }
+
+    #[test]
+    fn test_detect_in_missing_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let paths = GoPaths::from_defaults(env(&[]), Some(tmp.path().to_path_buf()));
+        assert!(detect_in(&paths).is_empty());
+    }
+
+    #[test]
+    fn test_clean_in_deletes_without_go() {
+        let (_tmp, paths) = setup_gopath();
+
+        for resource in detect_in(&paths) {
+            let result = clean_in(&paths, &resource, false);
+            match result {
+                SystemCleanResult::Success { freed_bytes, .. } => assert!(freed_bytes > 0),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+
+        // The cache roots are kept, their contents are gone
+        let cache = paths.cache.as_ref().unwrap();
+        let mod_cache = paths.mod_cache.as_ref().unwrap();
+        assert!(cache.is_dir() && dir_contents(Some(cache)).is_empty());
+        assert!(mod_cache.is_dir() && dir_contents(Some(mod_cache)).is_empty());
+        assert!(paths.pkg_entries().is_empty());
+    }
+
+    #[test]
+    fn test_clean_in_unknown_resource() {
+        let (tmp, paths) = setup_gopath();
+        let resource = DetectedSystemResource {
+            resource_id: "go-unknown".to_string(),
+            display_name: "Unknown".to_string(),
+            category: "go".to_string(),
+            size: 0,
+            description: String::new(),
+            item_count: None,
+        };
+        assert!(matches!(
+            clean_in(&paths, &resource, false),
+            SystemCleanResult::Skipped { .. }
+        ));
+        force_remove(tmp.path()).unwrap();
+    }
+}
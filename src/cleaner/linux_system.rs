@@ -0,0 +1,554 @@
+//! Linux system-level cleaners.
+//!
+//! Outside home directories, the usual space hogs are the systemd journal,
+//! the package caches of apt, dnf and pacman, and systemd-coredump's crash
+//! dumps. They live in root-owned directories, so every cleaner checks write
+//! access first and reports `Skipped` with the reason instead of failing when
+//! it lacks the privileges. With a `min_age_days` policy rule for its id, only
+//! entries older than that are removed.
+
+use crate::cleaner::measure::{apparent_size, reclaimable_usage};
+use crate::cleaner::policy::CleanPolicy;
+use crate::cleaner::system_cleaner::{
+    dry_run_result, run_quietly, DetectedSystemResource, SystemCleanResult, SystemCleaner,
+};
+use nix::unistd::{access, AccessFlags};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Directories journald writes to: persistent, then volatile.
+const JOURNAL_DIRS: &[&str] = &["/var/log/journal", "/run/log/journal"];
+
+/// A root-owned cache of files that a command or plain deletion clears.
+struct SystemCacheSpec {
+    id: &'static str,
+    display_name: &'static str,
+    description: &'static str,
+    dirs: &'static [&'static str],
+    /// Whether a file in the cache is an entry.
+    is_entry: fn(&str) -> bool,
+    /// The tool's own command for clearing the cache, if it has one.
+    command: Option<&'static [&'static str]>,
+}
+
+const SYSTEM_CACHES: &[SystemCacheSpec] = &[
+    SystemCacheSpec {
+        id: "apt-cache",
+        display_name: "apt Package Cache",
+        description: "Downloaded .deb packages",
+        dirs: &["/var/cache/apt/archives"],
+        is_entry: |name| name.ends_with(".deb"),
+        command: Some(&["apt-get", "clean"]),
+    },
+    SystemCacheSpec {
+        id: "dnf-cache",
+        display_name: "dnf Package Cache",
+        description: "Downloaded .rpm packages",
+        dirs: &["/var/cache/dnf", "/var/cache/libdnf5"],
+        is_entry: |name| name.ends_with(".rpm"),
+        command: Some(&["dnf", "clean", "packages"]),
+    },
+    SystemCacheSpec {
+        id: "pacman-cache",
+        display_name: "pacman Package Cache",
+        description: "Downloaded packages",
+        dirs: &["/var/cache/pacman/pkg"],
+        is_entry: |name| name.contains(".pkg.tar"),
+        command: Some(&["pacman", "-Scc", "--noconfirm"]),
+    },
+    SystemCacheSpec {
+        id: "coredump",
+        display_name: "Core Dumps",
+        description: "Crash dumps kept by systemd-coredump",
+        dirs: &["/var/lib/systemd/coredump"],
+        is_entry: |name| name.starts_with("core."),
+        command: None,
+    },
+];
+
+/// Ids of all Linux system cleaners.
+pub fn linux_system_ids() -> Vec<&'static str> {
+    let mut ids = vec!["journald"];
+    ids.extend(SYSTEM_CACHES.iter().map(|c| c.id));
+    ids
+}
+
+/// The journald cleaner and one cleaner per system cache, limited by each
+/// id's `min_age_days` in `policy`.
+pub fn linux_system_cleaners(policy: &CleanPolicy) -> Vec<Box<dyn SystemCleaner>> {
+    let mut cleaners: Vec<Box<dyn SystemCleaner>> = vec![Box::new(JournaldCleaner {
+        dirs: JOURNAL_DIRS.iter().map(PathBuf::from).collect(),
+        min_age_days: policy.explicit_min_age_days("journald"),
+        use_journalctl: true,
+    })];
+    for spec in SYSTEM_CACHES {
+        cleaners.push(Box::new(SystemCacheCleaner {
+            spec,
+            dirs: spec.dirs.iter().map(PathBuf::from).collect(),
+            min_age_days: policy.explicit_min_age_days(spec.id),
+            use_command: true,
+        }));
+    }
+    cleaners
+}
+
+/// Cleaner for the systemd journal.
+///
+/// The reclaimable part is the archived journal files; journald never removes
+/// the active ones. They are vacuumed with `journalctl --vacuum-time`, or
+/// `--vacuum-size` when every archived file goes.
+pub struct JournaldCleaner {
+    dirs: Vec<PathBuf>,
+    min_age_days: Option<u32>,
+    use_journalctl: bool,
+}
+
+impl JournaldCleaner {
+    fn existing_dirs(&self) -> Vec<PathBuf> {
+        self.dirs.iter().filter(|d| d.is_dir()).cloned().collect()
+    }
+
+    /// Archived journal files older than the age limit, if any.
+    fn archived(&self, now: SystemTime) -> Vec<PathBuf> {
+        let cutoff = self
+            .min_age_days
+            .map(|days| now - Duration::from_secs(u64::from(days) * 86400));
+        find_files(&self.existing_dirs(), is_archived_journal)
+            .into_iter()
+            .filter(|path| cutoff.is_none_or(|cutoff| modified(path) < cutoff))
+            .collect()
+    }
+
+    fn detect_at(&self, now: SystemTime) -> Vec<DetectedSystemResource> {
+        let dirs = self.existing_dirs();
+        if dirs.is_empty() {
+            return Vec::new();
+        }
+
+        let archived = self.archived(now);
+        // journalctl counts every journal it can read, active ones included
+        let total = journalctl_stdout(&["--disk-usage"], self.use_journalctl)
+            .and_then(|out| parse_journal_disk_usage(&out))
+            .unwrap_or_else(|| apparent_size(&dirs));
+        let age = match self.min_age_days {
+            Some(days) => format!(" older than {} days", days),
+            None => String::new(),
+        };
+        let description = format!(
+            "Archived journals{}; {} in total{}",
+            age,
+            humansize::format_size(total, humansize::BINARY),
+            privilege_note(&dirs)
+        );
+
+        vec![DetectedSystemResource {
+            resource_id: "journald".to_string(),
+            display_name: "Systemd Journal".to_string(),
+            category: "journald".to_string(),
+            size: apparent_size(&archived),
+            description,
+            item_count: Some(archived.len() as u64),
+        }]
+    }
+
+    fn clean_at(&self, resource: &DetectedSystemResource, now: SystemTime) -> SystemCleanResult {
+        let dirs = self.existing_dirs();
+        if let Some(reason) = missing_privilege(&dirs) {
+            return SystemCleanResult::Skipped {
+                resource: resource.clone(),
+                reason,
+            };
+        }
+
+        let archived = self.archived(now);
+        let usage = reclaimable_usage(&archived);
+
+        // Vacuuming to one byte drops every archived file
+        let vacuum = match self.min_age_days {
+            Some(days) => format!("--vacuum-time={}d", days),
+            None => "--vacuum-size=1".to_string(),
+        };
+        let vacuumed =
+            self.use_journalctl && journalctl(&[&vacuum]).is_ok_and(|o| o.status.success());
+        if !vacuumed {
+            if let Err(e) = remove_all(&archived) {
+                return SystemCleanResult::Failed {
+                    resource: resource.clone(),
+                    error: e,
+                };
+            }
+        }
+
+        SystemCleanResult::Success {
+            resource: resource.clone(),
+            freed_bytes: usage.saturating_sub(reclaimable_usage(&archived)),
+            estimated_bytes: resource.size,
+        }
+    }
+}
+
+impl SystemCleaner for JournaldCleaner {
+    fn id(&self) -> &'static str {
+        "journald"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Systemd Journal"
+    }
+
+    fn is_available(&self) -> bool {
+        !self.existing_dirs().is_empty()
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        self.detect_at(SystemTime::now())
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        self.clean_at(resource, SystemTime::now())
+    }
+}
+
+/// Cleaner for a root-owned package or crash dump cache.
+pub struct SystemCacheCleaner {
+    spec: &'static SystemCacheSpec,
+    dirs: Vec<PathBuf>,
+    min_age_days: Option<u32>,
+    use_command: bool,
+}
+
+impl SystemCacheCleaner {
+    fn existing_dirs(&self) -> Vec<PathBuf> {
+        self.dirs.iter().filter(|d| d.is_dir()).cloned().collect()
+    }
+
+    /// Cache entries older than the age limit, if any.
+    fn entries(&self, now: SystemTime) -> Vec<PathBuf> {
+        let cutoff = self
+            .min_age_days
+            .map(|days| now - Duration::from_secs(u64::from(days) * 86400));
+        find_files(&self.existing_dirs(), self.spec.is_entry)
+            .into_iter()
+            .filter(|path| cutoff.is_none_or(|cutoff| modified(path) < cutoff))
+            .collect()
+    }
+
+    fn detect_at(&self, now: SystemTime) -> Vec<DetectedSystemResource> {
+        let dirs = self.existing_dirs();
+        if dirs.is_empty() {
+            return Vec::new();
+        }
+
+        let entries = self.entries(now);
+        let age = match self.min_age_days {
+            Some(days) => format!(" older than {} days", days),
+            None => String::new(),
+        };
+        vec![DetectedSystemResource {
+            resource_id: self.spec.id.to_string(),
+            display_name: self.spec.display_name.to_string(),
+            category: self.spec.id.to_string(),
+            size: apparent_size(&entries),
+            description: format!("{}{}{}", self.spec.description, age, privilege_note(&dirs)),
+            item_count: Some(entries.len() as u64),
+        }]
+    }
+
+    fn clean_at(&self, resource: &DetectedSystemResource, now: SystemTime) -> SystemCleanResult {
+        let dirs = self.existing_dirs();
+        if let Some(reason) = missing_privilege(&dirs) {
+            return SystemCleanResult::Skipped {
+                resource: resource.clone(),
+                reason,
+            };
+        }
+
+        let entries = self.entries(now);
+        let usage = reclaimable_usage(&entries);
+
+        // The package managers' commands clear everything, so they can't
+        // honor an age limit
+        let cleared = self.min_age_days.is_none()
+            && self.use_command
+            && self.spec.command.is_some_and(run_quietly);
+        if !cleared {
+            if let Err(e) = remove_all(&entries) {
+                return SystemCleanResult::Failed {
+                    resource: resource.clone(),
+                    error: e,
+                };
+            }
+        }
+
+        SystemCleanResult::Success {
+            resource: resource.clone(),
+            freed_bytes: usage.saturating_sub(reclaimable_usage(&entries)),
+            estimated_bytes: resource.size,
+        }
+    }
+}
+
+impl SystemCleaner for SystemCacheCleaner {
+    fn id(&self) -> &'static str {
+        self.spec.id
+    }
+
+    fn display_name(&self) -> &'static str {
+        self.spec.display_name
+    }
+
+    fn is_available(&self) -> bool {
+        !self.existing_dirs().is_empty()
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        self.detect_at(SystemTime::now())
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        self.clean_at(resource, SystemTime::now())
+    }
+}
+
+/// Why cleaning `dirs` isn't possible for this user, if it isn't.
+pub(crate) fn missing_privilege(dirs: &[PathBuf]) -> Option<String> {
+    let dir = dirs
+        .iter()
+        .find(|dir| access(dir.as_path(), AccessFlags::W_OK).is_err())?;
+    Some(format!(
+        "requires root: {} is not writable (run with sudo)",
+        dir.display()
+    ))
+}
+
+/// Description suffix flagging a resource this user can't clean.
+pub(crate) fn privilege_note(dirs: &[PathBuf]) -> &'static str {
+    if missing_privilege(dirs).is_some() {
+        " (requires root)"
+    } else {
+        ""
+    }
+}
+
+/// Run `journalctl`, unless disabled.
+fn journalctl_stdout(args: &[&str], enabled: bool) -> Option<String> {
+    if !enabled {
+        return None;
+    }
+    journalctl(args)
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+fn journalctl(args: &[&str]) -> io::Result<Output> {
+    Command::new("journalctl")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+}
+
+/// Parse `journalctl --disk-usage` output, e.g. `Archived and active journals
+/// take up 1.2G in the file system.`
+fn parse_journal_disk_usage(output: &str) -> Option<u64> {
+    let size = output.split("take up ").nth(1)?.split_whitespace().next()?;
+    let unit_start = size
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(unit_start);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit {
+        "" | "B" => 1u64,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+/// Archived journal files: rotated (`system@....journal`) or left behind after
+/// an unclean shutdown (`....journal~`).
+fn is_archived_journal(name: &str) -> bool {
+    (name.ends_with(".journal") && name.contains('@')) || name.ends_with(".journal~")
+}
+
+/// Files under `dirs` whose name passes `matches`.
+fn find_files(dirs: &[PathBuf], matches: fn(&str) -> bool) -> Vec<PathBuf> {
+    dirs.iter()
+        .flat_map(|dir| WalkDir::new(dir).into_iter().flatten())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.file_name().to_str().is_some_and(matches))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+fn modified(path: &Path) -> SystemTime {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Delete files, ignoring ones that are already gone.
+fn remove_all(paths: &[PathBuf]) -> Result<(), String> {
+    for path in paths {
+        match fs::remove_file(path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to delete {}: {}", path.display(), e)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::test_util::write_file;
+    use tempfile::TempDir;
+
+    fn spec(id: &str) -> &'static SystemCacheSpec {
+        SYSTEM_CACHES.iter().find(|c| c.id == id).unwrap()
+    }
+
+    fn journald(dir: &Path, min_age_days: Option<u32>) -> JournaldCleaner {
+        JournaldCleaner {
+            dirs: vec![dir.to_path_buf(), dir.join("missing")],
+            min_age_days,
+            use_journalctl: false,
+        }
+    }
+
+    fn setup_journal() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let machine = tmp.path().join("0123456789abcdef");
+        write_file(&machine.join("system.journal"), 800, 0);
+        write_file(&machine.join("user-1000.journal"), 400, 0);
+        write_file(
+            &machine.join("system@0005f1-0000000000000001.journal"),
+            1000,
+            30,
+        );
+        write_file(
+            &machine.join("user-1000@0005f1-0000000000000002.journal"),
+            500,
+            2,
+        );
+        write_file(&machine.join("system@0005f2.journal~"), 200, 40);
+        tmp
+    }
+
+    #[test]
+    fn test_parse_journal_disk_usage() {
+        let output = "Archived and active journals take up 1.5G in the file system.\n";
+        assert_eq!(parse_journal_disk_usage(output), Some(1_610_612_736));
+        assert_eq!(
+            parse_journal_disk_usage("Journals take up 24.0M in the file system."),
+            Some(25_165_824)
+        );
+        assert_eq!(
+            parse_journal_disk_usage("No journal files were found."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_journald_reports_archived_journals() {
+        let tmp = setup_journal();
+        let resources = journald(tmp.path(), None).detect_at(SystemTime::now());
+
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].resource_id, "journald");
+        assert_eq!(resources[0].size, 1700);
+        assert_eq!(resources[0].item_count, Some(3));
+        // Without journalctl, the total is the size of the directories
+        assert!(resources[0].description.contains("2.83 KiB in total"));
+    }
+
+    #[test]
+    fn test_journald_clean_by_age_keeps_active_journals() {
+        let tmp = setup_journal();
+        let machine = tmp.path().join("0123456789abcdef");
+        let cleaner = journald(tmp.path(), Some(7));
+        let resource = cleaner.detect_at(SystemTime::now()).remove(0);
+        assert_eq!(resource.size, 1200);
+
+        let result = cleaner.clean_at(&resource, SystemTime::now());
+        assert!(matches!(result, SystemCleanResult::Success { .. }));
+        assert!(!machine
+            .join("system@0005f1-0000000000000001.journal")
+            .exists());
+        assert!(!machine.join("system@0005f2.journal~").exists());
+        assert!(machine
+            .join("user-1000@0005f1-0000000000000002.journal")
+            .exists());
+        assert!(machine.join("system.journal").exists());
+    }
+
+    #[test]
+    fn test_system_cache_entries_and_clean() {
+        let tmp = TempDir::new().unwrap();
+        let archives = tmp.path().join("archives");
+        write_file(&archives.join("curl_8.5.0_amd64.deb"), 1000, 90);
+        write_file(&archives.join("vim_9.1_amd64.deb"), 3000, 1);
+        write_file(&archives.join("lock"), 0, 1);
+        fs::create_dir_all(archives.join("partial")).unwrap();
+
+        let cleaner = SystemCacheCleaner {
+            spec: spec("apt-cache"),
+            dirs: vec![archives.clone()],
+            min_age_days: Some(30),
+            use_command: false,
+        };
+        let resource = cleaner.detect_at(SystemTime::now()).remove(0);
+        assert_eq!(resource.category, "apt-cache");
+        assert_eq!(resource.size, 1000);
+        assert_eq!(resource.item_count, Some(1));
+
+        let result = cleaner.clean_at(&resource, SystemTime::now());
+        assert!(matches!(result, SystemCleanResult::Success { .. }));
+        assert!(!archives.join("curl_8.5.0_amd64.deb").exists());
+        assert!(archives.join("vim_9.1_amd64.deb").exists());
+        assert!(archives.join("lock").exists());
+        assert!(archives.join("partial").exists());
+    }
+
+    #[test]
+    fn test_system_cache_entry_names() {
+        assert!((spec("dnf-cache").is_entry)(
+            "bash-5.2.26-3.fc40.x86_64.rpm"
+        ));
+        assert!((spec("pacman-cache").is_entry)(
+            "linux-6.9.1.arch1-1-x86_64.pkg.tar.zst"
+        ));
+        assert!((spec("coredump").is_entry)(
+            "core.firefox.1000.abc.4242.1718000000000000.zst"
+        ));
+        assert!(!(spec("coredump").is_entry)(".#core.tmp"));
+    }
+
+    #[test]
+    fn test_missing_directories_are_unavailable() {
+        let tmp = TempDir::new().unwrap();
+        let cleaner = SystemCacheCleaner {
+            spec: spec("coredump"),
+            dirs: vec![tmp.path().join("missing")],
+            min_age_days: None,
+            use_command: false,
+        };
+        assert!(!cleaner.is_available());
+        assert!(cleaner.detect_at(SystemTime::now()).is_empty());
+        assert!(missing_privilege(&[tmp.path().to_path_buf()]).is_none());
+    }
+}
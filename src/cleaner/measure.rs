@@ -0,0 +1,183 @@
+//! Measuring the space a clean really frees.
+//!
+//! Pre-scan sizes are apparent sizes, which overstate sparse files and count
+//! hardlinked data more than once. Cleaners instead measure the disk usage
+//! that removing a set of paths can reclaim, before and after cleaning, and
+//! fall back to the change in free space on the filesystem when there are no
+//! paths to measure (e.g. for Docker, whose data dir is usually unreadable).
+
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use nix::sys::statvfs::statvfs;
+use walkdir::WalkDir;
+
+/// Disk usage that deleting `paths` would reclaim.
+///
+/// Counts allocated blocks rather than apparent size, and counts a
+/// hardlinked file only if every one of its links is inside `paths`.
+pub fn reclaimable_usage(paths: &[PathBuf]) -> u64 {
+    let mut total = 0u64;
+    // (dev, ino) -> (links seen, nlink, usage)
+    let mut linked: HashMap<(u64, u64), (u64, u64, u64)> = HashMap::new();
+
+    for path in paths {
+        for entry in WalkDir::new(path).into_iter().flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let usage = metadata.blocks() * 512;
+            if metadata.is_dir() || metadata.nlink() <= 1 {
+                total += usage;
+                continue;
+            }
+            linked
+                .entry((metadata.dev(), metadata.ino()))
+                .or_insert((0, metadata.nlink(), usage))
+                .0 += 1;
+        }
+    }
+
+    total
+        + linked
+            .values()
+            .filter(|(seen, nlink, _)| seen >= nlink)
+            .map(|(_, _, usage)| usage)
+            .sum::<u64>()
+}
+
+/// Total apparent size of the files under `paths`, as pre-scan estimates use.
+pub(crate) fn apparent_size(paths: &[PathBuf]) -> u64 {
+    paths
+        .iter()
+        .flat_map(|p| WalkDir::new(p).into_iter().flatten())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// When the files of an entry were last read or written.
+///
+/// Directory times are ignored, since listing a directory updates its atime.
+pub(crate) fn last_used(path: &Path) -> SystemTime {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.atime().max(m.mtime()).max(0) as u64)
+        .max()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap_or(UNIX_EPOCH)
+}
+
+/// Free bytes on the filesystem holding `path`, including root-reserved blocks.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let stat = statvfs(path).ok()?;
+    Some(stat.blocks_free() * stat.fragment_size())
+}
+
+/// Snapshot of disk usage taken before a clean, to measure what it freed.
+#[derive(Debug)]
+pub struct SpaceProbe {
+    paths: Vec<PathBuf>,
+    usage: u64,
+    filesystem: PathBuf,
+    free: Option<u64>,
+}
+
+impl SpaceProbe {
+    /// Record the usage of `paths` and the free space on the filesystem
+    /// holding `filesystem`.
+    pub fn start(paths: &[PathBuf], filesystem: &Path) -> Self {
+        Self {
+            paths: paths.to_vec(),
+            usage: reclaimable_usage(paths),
+            filesystem: filesystem.to_path_buf(),
+            free: free_space(filesystem),
+        }
+    }
+
+    /// Bytes freed since the probe started, or `None` if nothing could be
+    /// measured.
+    ///
+    /// Uses the usage of the paths when the probe has any, and the growth of
+    /// free space otherwise. The free-space delta is not combined with the
+    /// path usage: other writes to the filesystem, quarantine renames and
+    /// copy-on-write filesystems make it unreliable where the paths are
+    /// measurable (see SPEC.md, "Measuring Freed Space").
+    pub fn freed(&self) -> Option<u64> {
+        if !self.paths.is_empty() {
+            return Some(self.usage.saturating_sub(reclaimable_usage(&self.paths)));
+        }
+        let before = self.free?;
+        let after = free_space(&self.filesystem)?;
+        Some(after.saturating_sub(before))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reclaimable_usage_counts_blocks() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("target");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("a.bin"), vec![1u8; 64 * 1024]).unwrap();
+
+        let usage = reclaimable_usage(std::slice::from_ref(&dir));
+        let file_blocks = fs::metadata(dir.join("a.bin")).unwrap().blocks() * 512;
+        assert!(usage >= file_blocks);
+        assert_eq!(reclaimable_usage(&[tmp.path().join("missing")]), 0);
+    }
+
+    #[test]
+    fn test_reclaimable_usage_skips_links_from_outside() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("target");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("shared.bin"), vec![1u8; 64 * 1024]).unwrap();
+        fs::hard_link(dir.join("shared.bin"), dir.join("inner-link")).unwrap();
+        let dir_only = reclaimable_usage(std::slice::from_ref(&dir));
+
+        // A link outside the set keeps the data alive
+        fs::hard_link(dir.join("shared.bin"), tmp.path().join("outside")).unwrap();
+        let with_outside = reclaimable_usage(std::slice::from_ref(&dir));
+        assert!(with_outside < dir_only);
+
+        // ...unless that link is part of the set too
+        let both = reclaimable_usage(&[dir, tmp.path().join("outside")]);
+        assert_eq!(both, dir_only);
+    }
+
+    #[test]
+    fn test_probe_measures_removed_paths() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("target");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("a.bin"), vec![1u8; 64 * 1024]).unwrap();
+
+        let probe = SpaceProbe::start(std::slice::from_ref(&dir), tmp.path());
+        let before = reclaimable_usage(std::slice::from_ref(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(probe.freed(), Some(before));
+    }
+
+    #[test]
+    fn test_probe_without_paths_uses_free_space() {
+        let tmp = TempDir::new().unwrap();
+        let probe = SpaceProbe::start(&[], tmp.path());
+        assert!(probe.freed().is_some());
+
+        let probe = SpaceProbe::start(&[], &tmp.path().join("missing"));
+        assert_eq!(probe.freed(), None);
+    }
+}
@@ -4,22 +4,47 @@
 //! - Detection of various project types (Cargo, Gradle, npm, etc.)
 //! - Cleanup of build artifacts
 //! - Parallel cleaning orchestration
+//! - Cleanup policies (age, keep-newest, size floors, protected paths, git state)
 
+pub mod cargo_prune;
+pub mod desktop;
 mod detector;
 pub mod detectors;
 pub mod docker;
 mod executor;
+mod git;
+pub mod go;
+pub mod linux_system;
+mod measure;
+pub mod nerdctl;
 mod orchestrator;
+pub mod package_cache;
+pub mod podman;
+mod policy;
 mod project_scanner;
 mod registry;
 pub mod system_cleaner;
 pub mod system_registry;
+#[cfg(test)]
+mod test_util;
 
-pub use detector::{DetectedProject, ProjectDetector};
-pub use detectors::all_detectors;
+pub use cargo_prune::{CargoPrune, PrunePlan, PruneReason, PruneSummary};
+pub use desktop::{FlatpakCleaner, SnapCleaner, ThumbnailCleaner, TrashCleaner, XdgCacheCleaner};
+pub use detector::{ArtifactGroup, DetectedProject, ProjectDetector};
+pub use detectors::{all_detectors, configured_detectors};
 pub use executor::{CleanExecutor, CleanOptions, CleanResult};
+pub use git::{GitOperation, GitState};
+pub use go::GoCacheCleaner;
+pub use linux_system::{JournaldCleaner, SystemCacheCleaner};
+pub use measure::{free_space, reclaimable_usage, SpaceProbe};
+pub use nerdctl::NerdctlCleaner;
 pub use orchestrator::{CleanOrchestrator, CleanProgress, CleanSummary};
+pub use package_cache::PackageCacheCleaner;
+pub use podman::PodmanCleaner;
+pub use policy::{CleanPolicy, PolicyRule, PolicyVerdict};
 pub use project_scanner::{ProjectScanner, ScanOptions};
 pub use registry::{all_valid_type_ids, DetectorRegistry};
-pub use system_cleaner::{DetectedSystemResource, SystemCleanResult, SystemCleaner};
+pub use system_cleaner::{
+    DetectedSystemResource, SystemCleanResult, SystemCleaner, SystemResourceItem,
+};
 pub use system_registry::SystemCleanerRegistry;
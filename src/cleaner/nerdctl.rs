@@ -0,0 +1,219 @@
+//! nerdctl (containerd) system cleaner.
+//!
+//! Detects and cleans dangling and unused images and stopped containers in
+//! containerd through nerdctl, in its current namespace (`default` unless
+//! `CONTAINERD_NAMESPACE` is set). nerdctl has no `system df`, so sizes are
+//! summed from its Docker-style `--format '{{json .}}'` image and container
+//! lists, which the Docker cleaner's code parses.
+
+use crate::cleaner::docker::{
+    command_result, command_stdout, container_items, image_items, insert_dangling_images,
+    items_unsupported, parse_container_json, parse_image_json, parse_reclaimed_space,
+    unknown_resource, unused_images, DiskUsage, DockerImage, EngineResource,
+};
+use crate::cleaner::system_cleaner::{
+    dry_run_result, run_quietly, DetectedSystemResource, SystemCleanResult, SystemCleaner,
+    SystemResourceItem,
+};
+use std::collections::HashMap;
+use std::process::{Command, Output};
+
+/// nerdctl system cleaner.
+pub struct NerdctlCleaner;
+
+impl SystemCleaner for NerdctlCleaner {
+    fn id(&self) -> &'static str {
+        "nerdctl"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "nerdctl"
+    }
+
+    fn is_available(&self) -> bool {
+        run_quietly(&["nerdctl", "info"])
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        let Some(usage) = disk_usage() else {
+            return Vec::new();
+        };
+
+        // Dangling images are split out by `insert_dangling_images`
+        let unused: Vec<&DockerImage> = usage.images.iter().filter(|i| i.containers == 0).collect();
+        let stopped = container_items(&usage.containers);
+        let mut resources = vec![
+            EngineResource::Images.resource(
+                "nerdctl",
+                "nerdctl",
+                unused.iter().map(|i| i.size).sum(),
+                Some(unused.len() as u64),
+            ),
+            EngineResource::Containers.resource(
+                "nerdctl",
+                "nerdctl",
+                stopped.iter().map(|c| c.size).sum(),
+                Some(stopped.len() as u64),
+            ),
+        ];
+        insert_dangling_images(&mut resources, &usage.images, "nerdctl", "nerdctl");
+        resources
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+
+        let result = match EngineResource::of(resource, "nerdctl") {
+            Some(EngineResource::DanglingImages) => nerdctl(&["image", "prune", "-f"]),
+            Some(EngineResource::Images) => nerdctl(&["image", "prune", "-f", "-a"]),
+            Some(EngineResource::Containers) => nerdctl(&["container", "prune", "-f"]),
+            _ => return unknown_resource(resource),
+        };
+
+        // containerd's content store is shared between namespaces, so a
+        // free-space delta can't be attributed; use the listed sizes instead
+        command_result(resource, result, resource.size, parse_reclaimed_space)
+    }
+
+    fn list_items(&self, resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        let Some(usage) = disk_usage() else {
+            return Vec::new();
+        };
+
+        let mut items = match EngineResource::of(resource, "nerdctl") {
+            Some(kind @ (EngineResource::Images | EngineResource::DanglingImages)) => image_items(
+                &unused_images(&usage.images, kind == EngineResource::DanglingImages),
+                &HashMap::new(),
+            ),
+            Some(EngineResource::Containers) => container_items(&usage.containers),
+            _ => Vec::new(),
+        };
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items
+    }
+
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        let Some(kind) = EngineResource::of(resource, "nerdctl").and_then(|k| k.object_kind())
+        else {
+            return items_unsupported(resource);
+        };
+
+        command_result(
+            resource,
+            nerdctl(&[kind, "rm", &item.id]),
+            item.size,
+            |_| None,
+        )
+    }
+}
+
+/// Run a nerdctl command.
+fn nerdctl(args: &[&str]) -> std::io::Result<Output> {
+    Command::new("nerdctl").args(args).output()
+}
+
+/// Images and containers from `nerdctl images` and `nerdctl ps`.
+fn disk_usage() -> Option<DiskUsage> {
+    let images = command_stdout("nerdctl", &["images", "--format", "{{json .}}"])?;
+    let containers = command_stdout(
+        "nerdctl",
+        &["ps", "--all", "--size", "--format", "{{json .}}"],
+    )?;
+    Some(parse_disk_usage(&images, &containers))
+}
+
+/// Parse nerdctl's image and container lists, one JSON object per line.
+/// nerdctl doesn't count an image's containers, so they are matched by the
+/// image reference each container was created from.
+fn parse_disk_usage(images: &str, containers: &str) -> DiskUsage {
+    let lines = |output: &str| -> Vec<serde_json::Value> {
+        output
+            .lines()
+            .filter_map(|line| serde_json::from_str(line.trim()).ok())
+            .collect()
+    };
+
+    let containers: Vec<_> = lines(containers).iter().map(parse_container_json).collect();
+    let mut images: Vec<_> = lines(images).iter().map(parse_image_json).collect();
+    for image in &mut images {
+        let reference = familiar_reference(&format!("{}:{}", image.repository, image.tag));
+        image.containers = containers
+            .iter()
+            .filter(|c| familiar_reference(&c.image) == reference || c.image == image.id)
+            .count() as u64;
+    }
+
+    DiskUsage {
+        images,
+        containers,
+        ..Default::default()
+    }
+}
+
+/// Short form of an image reference, as `nerdctl images` prints it:
+/// `docker.io/library/nginx` becomes `nginx:latest`.
+fn familiar_reference(reference: &str) -> String {
+    let name = reference
+        .strip_prefix("docker.io/library/")
+        .or_else(|| reference.strip_prefix("docker.io/"))
+        .unwrap_or(reference);
+    let name_start = name.rfind('/').map_or(0, |i| i + 1);
+    if name[name_start..].contains(':') {
+        name.to_string()
+    } else {
+        format!("{}:latest", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGES: &str = r#"{"CreatedAt":"2024-01-10 08:00:00 +0000 UTC","CreatedSince":"5 months ago","Digest":"sha256:aaaa","ID":"1111aaaa2222","Platform":"linux/amd64","Repository":"nginx","Size":"187.0 MiB","Tag":"latest"}
+{"CreatedAt":"2024-01-05 08:00:00 +0000 UTC","CreatedSince":"5 months ago","Digest":"sha256:bbbb","ID":"3333bbbb4444","Platform":"linux/amd64","Repository":"<none>","Size":"75.0 MiB","Tag":"<none>"}
+{"CreatedAt":"2023-12-01 08:00:00 +0000 UTC","CreatedSince":"6 months ago","Digest":"sha256:cccc","ID":"5555cccc6666","Platform":"linux/amd64","Repository":"ghcr.io/acme/worker","Size":"1.5 GiB","Tag":"v2"}"#;
+
+    const CONTAINERS: &str = r#"{"Command":"\"nginx\"","CreatedAt":"2024-05-01 12:00:00 +0000 UTC","ID":"c0ffee000002","Image":"docker.io/library/nginx:latest","Names":"web","Ports":"","Size":"2.0 B","Status":"Up"}
+{"Command":"\"sh\"","CreatedAt":"2024-03-01 12:00:00 +0000 UTC","ID":"c0ffee000001","Image":"docker.io/library/alpine:latest","Names":"old_job","Ports":"","Size":"12.0 KiB","Status":"Exited (0) 3 months ago"}"#;
+
+    #[test]
+    fn test_parse_disk_usage() {
+        let usage = parse_disk_usage(IMAGES, CONTAINERS);
+
+        // nginx is used by the running container
+        assert_eq!(usage.images[0].containers, 1);
+        assert_eq!(usage.images[0].size, 187 * 1024 * 1024);
+        let unused: Vec<String> = unused_images(&usage.images, false)
+            .iter()
+            .map(|i| i.name())
+            .collect();
+        assert_eq!(unused, vec!["ghcr.io/acme/worker:v2"]);
+        assert_eq!(unused_images(&usage.images, true).len(), 1);
+
+        let stopped = container_items(&usage.containers);
+        assert_eq!(stopped.len(), 1);
+        assert_eq!(stopped[0].name, "old_job");
+        assert_eq!(stopped[0].size, 12 * 1024);
+    }
+
+    #[test]
+    fn test_familiar_reference() {
+        assert_eq!(
+            familiar_reference("docker.io/library/nginx"),
+            "nginx:latest"
+        );
+        assert_eq!(familiar_reference("docker.io/acme/api:1.0"), "acme/api:1.0");
+        assert_eq!(familiar_reference("nginx:latest"), "nginx:latest");
+        assert_eq!(
+            familiar_reference("localhost:5000/web"),
+            "localhost:5000/web:latest"
+        );
+    }
+}
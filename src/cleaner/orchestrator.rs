@@ -58,17 +58,24 @@ pub struct CleanSummary {
     pub failed_count: usize,
     /// Number of skipped projects.
     pub skipped_count: usize,
-    /// Total bytes freed.
+    /// Total bytes freed, as measured.
     pub total_freed: u64,
+    /// Total bytes the pre-scan estimated would be freed.
+    pub total_estimated: u64,
 }
 
 impl CleanSummary {
     /// Accumulate a system clean result into this summary.
     pub fn add_system_result(&mut self, result: &SystemCleanResult) {
         match result {
-            SystemCleanResult::Success { freed_bytes, .. } => {
+            SystemCleanResult::Success {
+                freed_bytes,
+                estimated_bytes,
+                ..
+            } => {
                 self.success_count += 1;
                 self.total_freed += freed_bytes;
+                self.total_estimated += estimated_bytes;
             }
             SystemCleanResult::Failed { .. } => {
                 self.failed_count += 1;
@@ -116,12 +123,11 @@ impl CleanOrchestrator {
                         prog.set_current(project.path.display().to_string());
                     }
 
-                    let clean_cmd = self
-                        .registry
-                        .get(&project.project_type)
-                        .and_then(|d| d.clean_command());
-
-                    let result = self.executor.clean(&project, clean_cmd);
+                    let result = self.executor.clean_groups(&project, |project_type| {
+                        self.registry
+                            .get(project_type)
+                            .and_then(|d| d.clean_command())
+                    });
 
                     if let Some(ref prog) = progress {
                         prog.increment();
@@ -139,9 +145,14 @@ impl CleanOrchestrator {
 
         for result in results {
             match result {
-                CleanResult::Success { freed_bytes, .. } => {
+                CleanResult::Success {
+                    freed_bytes,
+                    estimated_bytes,
+                    ..
+                } => {
                     summary.success_count += 1;
                     summary.total_freed += freed_bytes;
+                    summary.total_estimated += estimated_bytes;
                 }
                 CleanResult::Failed { .. } => {
                     summary.failed_count += 1;
@@ -181,6 +192,10 @@ mod tests {
                 display_name: "Rust/Cargo".to_string(),
                 artifact_size: 100,
                 artifact_paths: vec![target],
+                git: None,
+                artifact_groups: vec![],
+                parent: None,
+                shared_with: vec![],
             });
         }
 
@@ -195,6 +210,9 @@ mod tests {
         let options = CleanOptions {
             dry_run: false,
             use_native_commands: false,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         };
         let orchestrator = CleanOrchestrator::new(registry, options, 4);
 
@@ -216,6 +234,9 @@ mod tests {
         let options = CleanOptions {
             dry_run: true,
             use_native_commands: false,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         };
         let orchestrator = CleanOrchestrator::new(registry, options, 2);
 
@@ -236,8 +257,13 @@ mod tests {
                     display_name: "T".into(),
                     artifact_size: 100,
                     artifact_paths: vec![],
+                    git: None,
+                    artifact_groups: vec![],
+                    parent: None,
+                    shared_with: vec![],
                 },
-                freed_bytes: 100,
+                freed_bytes: 4096,
+                estimated_bytes: 100,
             },
             CleanResult::Success {
                 project: DetectedProject {
@@ -246,8 +272,13 @@ mod tests {
                     display_name: "T".into(),
                     artifact_size: 200,
                     artifact_paths: vec![],
+                    git: None,
+                    artifact_groups: vec![],
+                    parent: None,
+                    shared_with: vec![],
                 },
-                freed_bytes: 200,
+                freed_bytes: 0,
+                estimated_bytes: 200,
             },
             CleanResult::Failed {
                 project: DetectedProject {
@@ -256,6 +287,10 @@ mod tests {
                     display_name: "T".into(),
                     artifact_size: 0,
                     artifact_paths: vec![],
+                    git: None,
+                    artifact_groups: vec![],
+                    parent: None,
+                    shared_with: vec![],
                 },
                 error: "oops".into(),
             },
@@ -266,6 +301,10 @@ mod tests {
                     display_name: "T".into(),
                     artifact_size: 0,
                     artifact_paths: vec![],
+                    git: None,
+                    artifact_groups: vec![],
+                    parent: None,
+                    shared_with: vec![],
                 },
                 reason: "skipped".into(),
             },
@@ -276,7 +315,8 @@ mod tests {
         assert_eq!(summary.success_count, 2);
         assert_eq!(summary.failed_count, 1);
         assert_eq!(summary.skipped_count, 1);
-        assert_eq!(summary.total_freed, 300);
+        assert_eq!(summary.total_freed, 4096);
+        assert_eq!(summary.total_estimated, 300);
     }
 
     #[test]
@@ -312,6 +352,9 @@ mod tests {
         let options = CleanOptions {
             dry_run: true,
             use_native_commands: false,
+            quarantine: None,
+            journal: None,
+            cargo_prune: None,
         };
         let orchestrator = CleanOrchestrator::new(registry, options, 1);
 
@@ -336,10 +379,12 @@ mod tests {
 
         summary.add_system_result(&SystemCleanResult::Success {
             resource: resource.clone(),
-            freed_bytes: 500,
+            freed_bytes: 450,
+            estimated_bytes: 500,
         });
         assert_eq!(summary.success_count, 1);
-        assert_eq!(summary.total_freed, 500);
+        assert_eq!(summary.total_freed, 450);
+        assert_eq!(summary.total_estimated, 500);
 
         summary.add_system_result(&SystemCleanResult::Failed {
             resource: resource.clone(),
@@ -357,6 +402,6 @@ mod tests {
         assert_eq!(summary.success_count, 1);
         assert_eq!(summary.failed_count, 1);
         assert_eq!(summary.skipped_count, 1);
-        assert_eq!(summary.total_freed, 500);
+        assert_eq!(summary.total_freed, 450);
     }
 }
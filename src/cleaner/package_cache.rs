@@ -0,0 +1,583 @@
+//! Global package cache cleaners.
+//!
+//! Package managers keep downloads in per-user caches outside any project:
+//! `~/.cargo/registry`, `~/.npm/_cacache`, the pnpm store, the yarn cache,
+//! `~/.cache/pip`, `~/.m2/repository` and `~/.gradle/caches`. Each gets a
+//! `PackageCacheCleaner` that finds the cache (honoring the tool's env-var
+//! override) and either runs the tool's own prune command or deletes the
+//! cache contents. With a `min_age_days` policy rule for its id, only entries
+//! unused for that long are removed.
+
+use crate::cleaner::measure::{apparent_size, last_used, reclaimable_usage};
+use crate::cleaner::policy::CleanPolicy;
+use crate::cleaner::system_cleaner::{
+    dry_run_result, run_quietly, DetectedSystemResource, SystemCleanResult, SystemCleaner,
+};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// How a cache is split into entries, for item counts and age-based pruning.
+#[derive(Debug, Clone, Copy)]
+enum Items {
+    /// Paths matching these patterns relative to the cache root, where `*`
+    /// matches any single name.
+    Matching(&'static [&'static str]),
+    /// Every file in the cache.
+    Files,
+    /// Maven artifact versions: directories holding a `.pom` file.
+    MavenVersions,
+}
+
+/// Environment used to locate caches.
+struct CacheEnv<'a> {
+    var: &'a dyn Fn(&str) -> Option<String>,
+    home: Option<&'a Path>,
+}
+
+impl CacheEnv<'_> {
+    /// A non-empty environment variable as a path.
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        (self.var)(key).filter(|v| !v.is_empty()).map(PathBuf::from)
+    }
+
+    /// A path relative to the home directory.
+    fn home(&self, rel: &str) -> Option<PathBuf> {
+        self.home.map(|h| h.join(rel))
+    }
+
+    /// An XDG base directory, falling back to its default under home.
+    fn xdg(&self, key: &str, default: &str) -> Option<PathBuf> {
+        self.path(key).or_else(|| self.home(default))
+    }
+}
+
+/// A known package cache.
+struct CacheSpec {
+    id: &'static str,
+    display_name: &'static str,
+    description: &'static str,
+    locate: fn(&CacheEnv) -> Option<PathBuf>,
+    items: Items,
+    /// The tool's own command for clearing its cache, if it has one.
+    prune: Option<&'static [&'static str]>,
+}
+
+const CACHES: &[CacheSpec] = &[
+    CacheSpec {
+        id: "cargo-cache",
+        display_name: "Cargo Registry",
+        description: "Downloaded crates and sources",
+        locate: |env| {
+            Some(
+                env.path("CARGO_HOME")
+                    .or_else(|| env.home(".cargo"))?
+                    .join("registry"),
+            )
+        },
+        items: Items::Matching(&["cache/*/*", "src/*/*"]),
+        prune: None,
+    },
+    CacheSpec {
+        id: "npm-cache",
+        display_name: "npm Cache",
+        description: "Downloaded package tarballs",
+        locate: |env| {
+            Some(
+                env.path("npm_config_cache")
+                    .or_else(|| env.home(".npm"))?
+                    .join("_cacache"),
+            )
+        },
+        items: Items::Matching(&["content-v2/*/*/*/*"]),
+        prune: Some(&["npm", "cache", "clean", "--force"]),
+    },
+    CacheSpec {
+        id: "pnpm-store",
+        display_name: "pnpm Store",
+        description: "Content-addressable package store",
+        locate: |env| {
+            env.path("npm_config_store_dir").or_else(|| {
+                Some(
+                    env.xdg("XDG_DATA_HOME", ".local/share")?
+                        .join("pnpm")
+                        .join("store"),
+                )
+            })
+        },
+        items: Items::Matching(&["*/files/*/*"]),
+        prune: Some(&["pnpm", "store", "prune"]),
+    },
+    CacheSpec {
+        id: "yarn-cache",
+        display_name: "Yarn Cache",
+        description: "Downloaded packages",
+        locate: |env| {
+            env.path("YARN_CACHE_FOLDER")
+                .or_else(|| Some(env.xdg("XDG_CACHE_HOME", ".cache")?.join("yarn")))
+        },
+        items: Items::Matching(&["*/*"]),
+        prune: Some(&["yarn", "cache", "clean"]),
+    },
+    CacheSpec {
+        id: "pip-cache",
+        display_name: "pip Cache",
+        description: "Downloaded and built wheels",
+        locate: |env| {
+            env.path("PIP_CACHE_DIR")
+                .or_else(|| Some(env.xdg("XDG_CACHE_HOME", ".cache")?.join("pip")))
+        },
+        items: Items::Files,
+        prune: Some(&["pip", "cache", "purge"]),
+    },
+    CacheSpec {
+        id: "maven-repo",
+        display_name: "Maven Repository",
+        description: "Downloaded artifacts",
+        locate: |env| {
+            (env.var)("MAVEN_OPTS")
+                .and_then(|opts| maven_repo_from_opts(&opts))
+                .or_else(|| env.home(".m2/repository"))
+        },
+        items: Items::MavenVersions,
+        prune: None,
+    },
+    CacheSpec {
+        id: "gradle-cache",
+        display_name: "Gradle Caches",
+        description: "Downloaded dependencies and build caches",
+        locate: |env| {
+            Some(
+                env.path("GRADLE_USER_HOME")
+                    .or_else(|| env.home(".gradle"))?
+                    .join("caches"),
+            )
+        },
+        items: Items::Matching(&["modules-2/files-2.1/*/*/*"]),
+        prune: None,
+    },
+];
+
+/// Ids of all package cache cleaners.
+pub fn package_cache_ids() -> Vec<&'static str> {
+    CACHES.iter().map(|c| c.id).collect()
+}
+
+/// One cleaner per known package cache, located from the process environment
+/// and limited by each id's `min_age_days` in `policy`.
+pub fn package_cache_cleaners(policy: &CleanPolicy) -> Vec<PackageCacheCleaner> {
+    let var = |key: &str| std::env::var(key).ok();
+    let home = dirs::home_dir();
+    let env = CacheEnv {
+        var: &var,
+        home: home.as_deref(),
+    };
+    CACHES
+        .iter()
+        .map(|spec| PackageCacheCleaner {
+            spec,
+            root: (spec.locate)(&env),
+            min_age_days: policy.explicit_min_age_days(spec.id),
+            use_prune_command: true,
+        })
+        .collect()
+}
+
+/// Cleaner for one global package cache.
+pub struct PackageCacheCleaner {
+    spec: &'static CacheSpec,
+    root: Option<PathBuf>,
+    min_age_days: Option<u32>,
+    use_prune_command: bool,
+}
+
+impl PackageCacheCleaner {
+    /// The cache directory, if it could be located.
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Entries that would be removed: the stale ones under an age limit,
+    /// otherwise the whole contents of the cache.
+    fn targets(&self, root: &Path, now: SystemTime) -> Vec<PathBuf> {
+        match self.min_age_days {
+            Some(days) => {
+                let cutoff = now - Duration::from_secs(u64::from(days) * 86400);
+                find_items(root, self.spec.items)
+                    .into_iter()
+                    .filter(|item| last_used(item) < cutoff)
+                    .collect()
+            }
+            None => fs::read_dir(root)
+                .map(|entries| entries.flatten().map(|e| e.path()).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn detect_at(&self, now: SystemTime) -> Vec<DetectedSystemResource> {
+        let Some(root) = self.root.as_deref().filter(|r| r.is_dir()) else {
+            return Vec::new();
+        };
+
+        let (size, item_count, description) = match self.min_age_days {
+            Some(days) => {
+                let stale = self.targets(root, now);
+                let description = format!("{} unused for {}+ days", self.spec.description, days);
+                (apparent_size(&stale), stale.len(), description)
+            }
+            None => (
+                apparent_size(&[root.to_path_buf()]),
+                find_items(root, self.spec.items).len(),
+                self.spec.description.to_string(),
+            ),
+        };
+
+        vec![DetectedSystemResource {
+            resource_id: self.spec.id.to_string(),
+            display_name: self.spec.display_name.to_string(),
+            category: self.spec.id.to_string(),
+            size,
+            description,
+            item_count: Some(item_count as u64),
+        }]
+    }
+
+    fn clean_at(&self, resource: &DetectedSystemResource, now: SystemTime) -> SystemCleanResult {
+        let Some(root) = self.root.as_deref().filter(|r| r.is_dir()) else {
+            return SystemCleanResult::Skipped {
+                resource: resource.clone(),
+                reason: "Cache directory not found".to_string(),
+            };
+        };
+
+        let targets = self.targets(root, now);
+        let usage = reclaimable_usage(&targets);
+
+        // Prune commands clear everything, so they can't honor an age limit
+        let pruned = self.min_age_days.is_none()
+            && self.use_prune_command
+            && self.spec.prune.is_some_and(run_quietly);
+
+        if !pruned {
+            for target in &targets {
+                let removed = if target.is_dir() {
+                    fs::remove_dir_all(target)
+                } else {
+                    fs::remove_file(target)
+                };
+                if let Err(e) = removed.or_else(|e| match e.kind() {
+                    io::ErrorKind::NotFound => Ok(()),
+                    _ => Err(e),
+                }) {
+                    return SystemCleanResult::Failed {
+                        resource: resource.clone(),
+                        error: format!("Failed to delete {}: {}", target.display(), e),
+                    };
+                }
+            }
+        }
+
+        SystemCleanResult::Success {
+            resource: resource.clone(),
+            freed_bytes: usage.saturating_sub(reclaimable_usage(&targets)),
+            estimated_bytes: resource.size,
+        }
+    }
+}
+
+impl SystemCleaner for PackageCacheCleaner {
+    fn id(&self) -> &'static str {
+        self.spec.id
+    }
+
+    fn display_name(&self) -> &'static str {
+        self.spec.display_name
+    }
+
+    fn is_available(&self) -> bool {
+        self.root.as_deref().is_some_and(Path::is_dir)
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        self.detect_at(SystemTime::now())
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+        self.clean_at(resource, SystemTime::now())
+    }
+}
+
+/// The `-Dmaven.repo.local=...` value in `MAVEN_OPTS`.
+fn maven_repo_from_opts(opts: &str) -> Option<PathBuf> {
+    opts.split_whitespace()
+        .find_map(|opt| opt.strip_prefix("-Dmaven.repo.local="))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Split a cache into its entries.
+fn find_items(root: &Path, items: Items) -> Vec<PathBuf> {
+    match items {
+        Items::Matching(patterns) => patterns
+            .iter()
+            .flat_map(|pattern| expand_pattern(root, pattern))
+            .collect(),
+        Items::Files => WalkDir::new(root)
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect(),
+        Items::MavenVersions => WalkDir::new(root)
+            .into_iter()
+            .flatten()
+            .filter(|e| {
+                e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "pom")
+            })
+            .filter_map(|e| e.path().parent().map(Path::to_path_buf))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Paths under `root` matching `pattern`, where a `*` segment matches any name.
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![root.to_path_buf()];
+    for segment in pattern.split('/') {
+        paths = paths
+            .into_iter()
+            .flat_map(|dir| -> Vec<PathBuf> {
+                if segment == "*" {
+                    fs::read_dir(&dir)
+                        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+                        .unwrap_or_default()
+                } else {
+                    let path = dir.join(segment);
+                    if path.exists() {
+                        vec![path]
+                    } else {
+                        Vec::new()
+                    }
+                }
+            })
+            .collect();
+    }
+    paths.sort();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::test_util::write_file;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn spec(id: &str) -> &'static CacheSpec {
+        CACHES.iter().find(|c| c.id == id).unwrap()
+    }
+
+    fn cleaner(id: &str, root: &Path, min_age_days: Option<u32>) -> PackageCacheCleaner {
+        PackageCacheCleaner {
+            spec: spec(id),
+            root: Some(root.to_path_buf()),
+            min_age_days,
+            use_prune_command: false,
+        }
+    }
+
+    fn locate(id: &str, vars: &[(&str, &str)]) -> Option<PathBuf> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        let var = |key: &str| vars.get(key).map(|v| v.to_string());
+        let env = CacheEnv {
+            var: &var,
+            home: Some(Path::new("/home/u")),
+        };
+        (spec(id).locate)(&env)
+    }
+
+    fn setup_maven_repo() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let lib = tmp.path().join("org/example/lib");
+        write_file(&lib.join("1.0/lib-1.0.pom"), 100, 200);
+        write_file(&lib.join("1.0/lib-1.0.jar"), 1000, 200);
+        write_file(&lib.join("2.0/lib-2.0.pom"), 100, 1);
+        write_file(&lib.join("2.0/lib-2.0.jar"), 2000, 1);
+        write_file(&lib.join("maven-metadata-central.xml"), 10, 1);
+        tmp
+    }
+
+    #[test]
+    fn test_locate_defaults() {
+        let expected = [
+            ("cargo-cache", "/home/u/.cargo/registry"),
+            ("npm-cache", "/home/u/.npm/_cacache"),
+            ("pnpm-store", "/home/u/.local/share/pnpm/store"),
+            ("yarn-cache", "/home/u/.cache/yarn"),
+            ("pip-cache", "/home/u/.cache/pip"),
+            ("maven-repo", "/home/u/.m2/repository"),
+            ("gradle-cache", "/home/u/.gradle/caches"),
+        ];
+        for (id, path) in expected {
+            assert_eq!(locate(id, &[]), Some(PathBuf::from(path)), "{}", id);
+        }
+    }
+
+    #[test]
+    fn test_locate_env_overrides() {
+        let vars = [
+            ("CARGO_HOME", "/c"),
+            ("npm_config_cache", "/n"),
+            ("npm_config_store_dir", "/p"),
+            ("YARN_CACHE_FOLDER", "/y"),
+            ("PIP_CACHE_DIR", "/pip"),
+            ("MAVEN_OPTS", "-Xmx1g -Dmaven.repo.local=/m2"),
+            ("GRADLE_USER_HOME", "/g"),
+        ];
+        let expected = [
+            ("cargo-cache", "/c/registry"),
+            ("npm-cache", "/n/_cacache"),
+            ("pnpm-store", "/p"),
+            ("yarn-cache", "/y"),
+            ("pip-cache", "/pip"),
+            ("maven-repo", "/m2"),
+            ("gradle-cache", "/g/caches"),
+        ];
+        for (id, path) in expected {
+            assert_eq!(locate(id, &vars), Some(PathBuf::from(path)), "{}", id);
+        }
+
+        let xdg = [("XDG_CACHE_HOME", "/xdg")];
+        assert_eq!(locate("pip-cache", &xdg), Some(PathBuf::from("/xdg/pip")));
+        assert_eq!(locate("yarn-cache", &xdg), Some(PathBuf::from("/xdg/yarn")));
+    }
+
+    #[test]
+    fn test_expand_pattern() {
+        let tmp = TempDir::new().unwrap();
+        write_file(&tmp.path().join("cache/index.crates.io/a-1.0.crate"), 10, 0);
+        write_file(&tmp.path().join("cache/index.crates.io/b-1.0.crate"), 10, 0);
+        write_file(&tmp.path().join("src/index.crates.io/a-1.0/lib.rs"), 10, 0);
+        fs::create_dir_all(tmp.path().join("index/index.crates.io")).unwrap();
+
+        let items = find_items(tmp.path(), spec("cargo-cache").items);
+        let names: Vec<_> = items
+            .iter()
+            .map(|p| p.strip_prefix(tmp.path()).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                PathBuf::from("cache/index.crates.io/a-1.0.crate"),
+                PathBuf::from("cache/index.crates.io/b-1.0.crate"),
+                PathBuf::from("src/index.crates.io/a-1.0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_whole_cache() {
+        let tmp = setup_maven_repo();
+        let resources = cleaner("maven-repo", tmp.path(), None).detect();
+
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].resource_id, "maven-repo");
+        assert_eq!(resources[0].category, "maven-repo");
+        assert_eq!(resources[0].size, 3210);
+        assert_eq!(resources[0].item_count, Some(2));
+    }
+
+    #[test]
+    fn test_detect_missing_cache() {
+        let tmp = TempDir::new().unwrap();
+        let cleaner = cleaner("maven-repo", &tmp.path().join("missing"), None);
+        assert!(!cleaner.is_available());
+        assert!(cleaner.detect().is_empty());
+    }
+
+    #[test]
+    fn test_age_limit_prunes_stale_entries() {
+        let tmp = setup_maven_repo();
+        let cleaner = cleaner("maven-repo", tmp.path(), Some(90));
+
+        let resources = cleaner.detect();
+        assert_eq!(resources[0].size, 1100);
+        assert_eq!(resources[0].item_count, Some(1));
+        assert!(resources[0].description.contains("90+ days"));
+
+        match cleaner.clean(&resources[0], false) {
+            SystemCleanResult::Success { freed_bytes, .. } => assert!(freed_bytes > 0),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let lib = tmp.path().join("org/example/lib");
+        assert!(!lib.join("1.0").exists());
+        assert!(lib.join("2.0/lib-2.0.jar").exists());
+        assert!(lib.join("maven-metadata-central.xml").exists());
+    }
+
+    #[test]
+    fn test_clean_deletes_contents() {
+        let tmp = setup_maven_repo();
+        let cleaner = cleaner("maven-repo", tmp.path(), None);
+        let resource = cleaner.detect().remove(0);
+
+        assert!(matches!(
+            cleaner.clean(&resource, true),
+            SystemCleanResult::Success {
+                freed_bytes: 3210,
+                ..
+            }
+        ));
+        assert!(tmp.path().join("org").exists());
+
+        assert!(matches!(
+            cleaner.clean(&resource, false),
+            SystemCleanResult::Success { .. }
+        ));
+        assert!(tmp.path().exists());
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_cleaners_follow_policy() {
+        use crate::config::{PolicyConfig, PolicyRules};
+
+        // Neither the global rule nor --age applies to caches
+        let mut config = PolicyConfig {
+            min_age_days: Some(14),
+            ..Default::default()
+        };
+        config.types.insert(
+            "maven-repo".to_string(),
+            PolicyRules {
+                min_age_days: Some(90),
+                ..Default::default()
+            },
+        );
+        let policy = CleanPolicy::from_config(&config).with_min_age_days(3);
+        let cleaners = package_cache_cleaners(&policy);
+
+        let ids: Vec<&str> = cleaners.iter().map(|c| c.id()).collect();
+        assert_eq!(ids, package_cache_ids());
+        for cleaner in &cleaners {
+            let expected = (cleaner.id() == "maven-repo").then_some(90);
+            assert_eq!(cleaner.min_age_days, expected, "{}", cleaner.id());
+        }
+    }
+
+    #[test]
+    fn test_maven_repo_from_opts() {
+        assert_eq!(
+            maven_repo_from_opts("-Dmaven.repo.local=/tmp/m2 -Xmx1g"),
+            Some(PathBuf::from("/tmp/m2"))
+        );
+        assert_eq!(maven_repo_from_opts("-Xmx1g"), None);
+        assert_eq!(maven_repo_from_opts("-Dmaven.repo.local="), None);
+    }
+}
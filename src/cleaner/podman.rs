@@ -0,0 +1,384 @@
+//! Podman system cleaner.
+//!
+//! Detects and cleans dangling and unused images, stopped containers and
+//! unused anonymous volumes in the (usually rootless) Podman store via the
+//! Podman CLI.
+//! `podman system df --format json` uses Docker's field names, so the summary
+//! is parsed by the Docker cleaner's code. Images and containers can be listed
+//! and removed one at a time.
+
+use crate::cleaner::docker::{
+    clean_anonymous_volumes, command_result, command_stdout, container_items, image_items,
+    info_dir, insert_dangling_images, items_unsupported, parse_df_summary, parse_docker_time,
+    parse_reclaimed_space, short_id, unknown_resource, unused_anonymous_volumes, unused_images,
+    DockerContainer, DockerImage, EngineResource,
+};
+use crate::cleaner::measure::{reclaimable_usage, SpaceProbe};
+use crate::cleaner::system_cleaner::{
+    dry_run_result, run_quietly, DetectedSystemResource, SystemCleanResult, SystemCleaner,
+    SystemResourceItem,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Podman system cleaner.
+#[derive(Default)]
+pub struct PodmanCleaner {
+    /// Set once stopped containers were pruned; the volumes they released
+    /// are kept until the next run
+    containers_pruned: AtomicBool,
+}
+
+impl SystemCleaner for PodmanCleaner {
+    fn id(&self) -> &'static str {
+        "podman"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Podman"
+    }
+
+    fn is_available(&self) -> bool {
+        // Rootless Podman has no daemon; `info` only needs the user's store
+        run_quietly(&["podman", "info"])
+    }
+
+    fn detect(&self) -> Vec<DetectedSystemResource> {
+        let Some(stdout) = command_stdout("podman", &["system", "df", "--format", "json"]) else {
+            return Vec::new();
+        };
+        let mut resources = parse_df_summary(&stdout, "podman", "Podman");
+        if let Some(images) = list_images() {
+            insert_dangling_images(&mut resources, &images, "podman", "Podman");
+        }
+
+        // `system df` counts named volumes too, which aren't cleaned
+        if let Some((size, count)) = anonymous_volume_usage() {
+            if let Some(volumes) = resources
+                .iter_mut()
+                .find(|r| EngineResource::of(r, "podman") == Some(EngineResource::Volumes))
+            {
+                volumes.size = size;
+                volumes.item_count = Some(count);
+            }
+        }
+        resources
+    }
+
+    fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult {
+        if dry_run {
+            return dry_run_result(resource);
+        }
+
+        let probe =
+            info_dir("podman", "{{.Store.GraphRoot}}").map(|root| SpaceProbe::start(&[], &root));
+        let result = match EngineResource::of(resource, "podman") {
+            Some(EngineResource::DanglingImages) => podman(&["image", "prune", "-f"]),
+            Some(EngineResource::Images) => podman(&["image", "prune", "-f", "-a"]),
+            Some(EngineResource::Containers) => {
+                if resource.item_count != Some(0) {
+                    self.containers_pruned.store(true, Ordering::Relaxed);
+                }
+                podman(&["container", "prune", "-f"])
+            }
+            // `volume prune` removes named volumes too
+            Some(EngineResource::Volumes) => {
+                let containers_pruned = self.containers_pruned.load(Ordering::Relaxed);
+                return clean_anonymous_volumes(resource, "podman", containers_pruned, |_| {
+                    probe.and_then(|p| p.freed())
+                });
+            }
+            // `podman system df` reports no build cache
+            Some(EngineResource::BuildCache) | None => return unknown_resource(resource),
+        };
+
+        // Podman's prune commands print what they removed, not its size
+        command_result(resource, result, resource.size, |stdout| {
+            parse_reclaimed_space(stdout).or_else(|| probe.and_then(|p| p.freed()))
+        })
+    }
+
+    fn list_items(&self, resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        let mut items = match EngineResource::of(resource, "podman") {
+            Some(kind @ (EngineResource::Images | EngineResource::DanglingImages)) => {
+                let images = list_images().unwrap_or_default();
+                image_items(
+                    &unused_images(&images, kind == EngineResource::DanglingImages),
+                    &HashMap::new(),
+                )
+            }
+            Some(EngineResource::Containers) => {
+                command_stdout("podman", &["ps", "--all", "--size", "--format", "json"])
+                    .and_then(|stdout| parse_podman_containers(&stdout))
+                    .map(|containers| container_items(&containers))
+                    .unwrap_or_default()
+            }
+            // Named volumes are only removed from here
+            Some(EngineResource::Volumes) => command_stdout(
+                "podman",
+                &[
+                    "volume",
+                    "ls",
+                    "--filter",
+                    "dangling=true",
+                    "--format",
+                    "json",
+                ],
+            )
+            .and_then(|stdout| parse_podman_volumes(&stdout))
+            .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
+        items
+    }
+
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        let Some(kind) = EngineResource::of(resource, "podman").and_then(|k| k.object_kind())
+        else {
+            return items_unsupported(resource);
+        };
+
+        command_result(resource, podman(&[kind, "rm", &item.id]), item.size, |_| {
+            None
+        })
+    }
+}
+
+/// Run a Podman CLI command.
+fn podman(args: &[&str]) -> std::io::Result<Output> {
+    Command::new("podman").args(args).output()
+}
+
+/// Disk usage and number of the unused anonymous volumes. `podman volume ls`
+/// has no sizes, so their mount points are measured.
+fn anonymous_volume_usage() -> Option<(u64, u64)> {
+    let names = unused_anonymous_volumes("podman")?;
+    if names.is_empty() {
+        return Some((0, 0));
+    }
+    let mut args = vec!["volume", "inspect", "--format", "{{.Mountpoint}}"];
+    args.extend(names.iter().map(String::as_str));
+    let mount_points: Vec<PathBuf> = command_stdout("podman", &args)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| PathBuf::from(line.trim()))
+        .collect();
+    Some((reclaimable_usage(&mount_points), names.len() as u64))
+}
+
+/// Parse the output of `podman volume ls --format json`. It has no sizes, so
+/// each volume's mount point is measured.
+fn parse_podman_volumes(output: &str) -> Option<Vec<SystemResourceItem>> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(output.trim()).ok()?;
+    let volumes = values
+        .iter()
+        .map(|v| {
+            let name = v["Name"].as_str().unwrap_or("").to_string();
+            let mount_point = PathBuf::from(v["Mountpoint"].as_str().unwrap_or(""));
+            SystemResourceItem {
+                id: name.clone(),
+                name,
+                size: reclaimable_usage(&[mount_point]),
+                last_used: podman_time(&v["CreatedAt"]),
+            }
+        })
+        .collect();
+    Some(volumes)
+}
+
+/// Images from `podman images`.
+fn list_images() -> Option<Vec<DockerImage>> {
+    command_stdout("podman", &["images", "--format", "json"])
+        .and_then(|stdout| parse_podman_images(&stdout))
+}
+
+/// A time Podman prints as Unix seconds or as an RFC 3339 string, depending
+/// on the version.
+fn podman_time(value: &serde_json::Value) -> Option<SystemTime> {
+    match value.as_u64() {
+        Some(secs) => Some(UNIX_EPOCH + Duration::from_secs(secs)),
+        None => value.as_str().and_then(parse_docker_time),
+    }
+}
+
+/// Split a reference like `docker.io/library/alpine:3.19` into repository and
+/// tag. A `:` before the last `/` belongs to a registry port.
+fn split_reference(reference: &str) -> (String, String) {
+    let name_start = reference.rfind('/').map_or(0, |i| i + 1);
+    match reference[name_start..].rfind(':') {
+        Some(i) => (
+            reference[..name_start + i].to_string(),
+            reference[name_start + i + 1..].to_string(),
+        ),
+        None => (reference.to_string(), "<none>".to_string()),
+    }
+}
+
+/// Parse the output of `podman images --format json`, a JSON array with
+/// numeric sizes and container counts.
+fn parse_podman_images(output: &str) -> Option<Vec<DockerImage>> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(output.trim()).ok()?;
+    let images = values
+        .iter()
+        .map(|v| {
+            let reference = v["Names"]
+                .as_array()
+                .or(v["RepoTags"].as_array())
+                .and_then(|names| names.first())
+                .and_then(|name| name.as_str());
+            let (repository, tag) = reference
+                .map(split_reference)
+                .unwrap_or_else(|| ("<none>".to_string(), "<none>".to_string()));
+            DockerImage {
+                id: short_id(v["Id"].as_str().unwrap_or("")),
+                repository,
+                tag,
+                containers: v["Containers"].as_u64().unwrap_or(0),
+                size: v["Size"].as_u64().unwrap_or(0),
+                created: podman_time(&v["Created"]),
+            }
+        })
+        .collect();
+    Some(images)
+}
+
+/// Parse the output of `podman ps --all --size --format json`. A container's
+/// size is its writable layer.
+fn parse_podman_containers(output: &str) -> Option<Vec<DockerContainer>> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(output.trim()).ok()?;
+    let containers = values
+        .iter()
+        .map(|v| DockerContainer {
+            id: short_id(v["Id"].as_str().unwrap_or("")),
+            name: v["Names"][0].as_str().unwrap_or("").to_string(),
+            image: v["Image"].as_str().unwrap_or("").to_string(),
+            state: v["State"].as_str().unwrap_or("").to_string(),
+            size: v["Size"]["rwSize"].as_u64().unwrap_or(0),
+            created: podman_time(&v["Created"]),
+        })
+        .collect();
+    Some(containers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_podman_df_summary() {
+        let output = r#"[
+ {"Type":"Images","Total":3,"Active":1,"RawSize":700000000,"RawReclaimable":480000000,"TotalCount":3,"Size":"700MB","Reclaimable":"480MB (69%)"},
+ {"Type":"Containers","Total":2,"Active":1,"RawSize":3000,"RawReclaimable":1500,"TotalCount":2,"Size":"3kB","Reclaimable":"1.5kB (50%)"},
+ {"Type":"Local Volumes","Total":1,"Active":0,"RawSize":20000000,"RawReclaimable":20000000,"TotalCount":1,"Size":"20MB","Reclaimable":"20MB (100%)"}
+]"#;
+
+        let resources = parse_df_summary(output, "podman", "Podman");
+
+        let ids: Vec<&str> = resources.iter().map(|r| r.resource_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["podman-images", "podman-containers", "podman-volumes"]
+        );
+        assert!(resources.iter().all(|r| r.category == "podman"));
+        assert_eq!(resources[0].display_name, "Podman Unused Images");
+        assert_eq!(resources[0].size, 480_000_000);
+        assert_eq!(resources[0].item_count, Some(2));
+        assert_eq!(resources[1].size, 1500);
+        assert_eq!(resources[2].item_count, Some(1));
+    }
+
+    #[test]
+    fn test_parse_podman_images() {
+        let output = r#"[
+ {"Id":"5555cccc6666dddd","RepoTags":["docker.io/library/postgres:15"],"Names":["docker.io/library/postgres:15"],"Size":400000000,"Containers":0,"Created":1701417600},
+ {"Id":"3333bbbb4444eeee","RepoTags":null,"Size":75000000,"Containers":0,"Created":1704441600,"Dangling":true},
+ {"Id":"1111aaaa2222ffff","Names":["localhost:5000/web"],"Size":187000000,"Containers":1,"Created":1704873600}
+]"#;
+
+        let images = parse_podman_images(output).unwrap();
+
+        assert_eq!(images[0].id, "5555cccc6666");
+        assert_eq!(images[0].name(), "docker.io/library/postgres:15");
+        assert_eq!(
+            images[0].created,
+            Some(UNIX_EPOCH + Duration::from_secs(1_701_417_600))
+        );
+        assert_eq!(images[1].name(), "3333bbbb4444");
+        assert_eq!(images[2].repository, "localhost:5000/web");
+        assert_eq!(images[2].tag, "<none>");
+
+        let dangling: Vec<&str> = unused_images(&images, true)
+            .iter()
+            .map(|i| i.id.as_str())
+            .collect();
+        assert_eq!(dangling, vec!["3333bbbb4444"]);
+        assert_eq!(unused_images(&images, false).len(), 1);
+
+        assert!(parse_podman_images("not json").is_none());
+    }
+
+    #[test]
+    fn test_parse_podman_containers() {
+        let output = r#"[
+ {"Id":"c0ffee000001aaaa","Names":["old_job"],"Image":"docker.io/library/alpine:latest","State":"exited","Created":1709294400,"Size":{"rootFsSize":7800000,"rwSize":1500}},
+ {"Id":"c0ffee000002bbbb","Names":["web"],"Image":"localhost:5000/web","State":"running","Created":"2024-05-01T12:00:00Z","Size":{"rootFsSize":187000000,"rwSize":2}}
+]"#;
+
+        let containers = parse_podman_containers(output).unwrap();
+        assert_eq!(
+            containers[1].created,
+            parse_docker_time("2024-05-01T12:00:00Z")
+        );
+
+        let items = container_items(&containers);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "c0ffee000001");
+        assert_eq!(items[0].name, "old_job");
+        assert_eq!(items[0].size, 1500);
+    }
+
+    #[test]
+    fn test_parse_podman_volumes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let data = tmp.path().join("pgdata/_data");
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(data.join("base"), vec![1u8; 64 * 1024]).unwrap();
+        let output = format!(
+            r#"[{{"Name":"pgdata","Mountpoint":"{}","CreatedAt":"2024-02-10T09:00:00Z"}}]"#,
+            data.display()
+        );
+
+        let volumes = parse_podman_volumes(&output).unwrap();
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].id, "pgdata");
+        assert!(volumes[0].size >= 64 * 1024);
+        assert_eq!(
+            volumes[0].last_used,
+            parse_docker_time("2024-02-10T09:00:00Z")
+        );
+        assert!(parse_podman_volumes("not json").is_none());
+    }
+
+    #[test]
+    fn test_split_reference() {
+        let split = |r: &str| {
+            let (repo, tag) = split_reference(r);
+            format!("{} {}", repo, tag)
+        };
+        assert_eq!(split("alpine:3.19"), "alpine 3.19");
+        assert_eq!(
+            split("docker.io/library/alpine"),
+            "docker.io/library/alpine <none>"
+        );
+        assert_eq!(split("localhost:5000/web:v2"), "localhost:5000/web v2");
+    }
+}
@@ -0,0 +1,863 @@
+//! Cleanup policies deciding which detected projects may be cleaned.
+//!
+//! A policy is built from `[cleaner.policy]` in the configuration file.
+//! Rules apply to every project type and can be overridden per type id.
+//! Each project is checked in a fixed order and the first rule that
+//! decides its fate is reported:
+//!
+//! 1. `protect` globs: matching projects (or projects below a matching
+//!    directory) are never cleaned
+//! 2. `dirty_repos = "skip"`: projects whose git repository holds unsaved
+//!    work (see [`GitState`]) are kept
+//! 3. `keep_newest`: the N most recently modified projects of a type are kept
+//! 4. `min_age_days`: projects modified more recently are kept
+//! 5. `min_artifact_size`: projects with less to free are kept
+//!
+//! With `dirty_repos = "confirm"`, a project with unsaved git work that
+//! passes every other rule is included, but needs confirmation.
+//!
+//! A project matching several types is checked once per [`ArtifactGroup`],
+//! with that type's rules and artifacts, and ranked among the projects of
+//! each of its types. The strictest outcome decides: the project is kept if
+//! any group is kept.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use humansize::{format_size, BINARY};
+
+use crate::cleaner::detector::{ArtifactGroup, DetectedProject};
+use crate::cleaner::git::GitState;
+use crate::cleaner::project_scanner::ProjectScanner;
+use crate::config::{DirtyRepoAction, PolicyConfig, PolicyRules};
+use crate::scanner::ExcludeMatcher;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The rule that included or skipped a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyRule {
+    /// No rule restricts this project type
+    Unrestricted,
+    /// Untouched for at least the configured minimum age
+    OldEnough { age_days: u64, min_age_days: u32 },
+    /// Path matches a protect pattern
+    Protected { pattern: String },
+    /// Among the most recently modified projects of its type
+    KeepNewest { rank: usize, keep: usize },
+    /// Modified more recently than the configured minimum age
+    TooRecent { age_days: u64, min_age_days: u32 },
+    /// Artifacts are smaller than the configured floor
+    BelowSizeFloor { size: u64, min_size: u64 },
+    /// The git repository holds unsaved work; `confirm` if cleaning is
+    /// allowed after asking
+    DirtyRepo { git: GitState, confirm: bool },
+}
+
+impl PolicyRule {
+    /// Returns true if the project may be cleaned under this rule.
+    pub fn allows_clean(&self) -> bool {
+        matches!(
+            self,
+            PolicyRule::Unrestricted
+                | PolicyRule::OldEnough { .. }
+                | PolicyRule::DirtyRepo { confirm: true, .. }
+        )
+    }
+}
+
+impl fmt::Display for PolicyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyRule::Unrestricted => write!(f, "no policy"),
+            PolicyRule::OldEnough {
+                age_days,
+                min_age_days,
+            } => write!(f, "idle {}d >= {}d", age_days, min_age_days),
+            PolicyRule::Protected { pattern } => write!(f, "protected by '{}'", pattern),
+            PolicyRule::KeepNewest { rank, keep } => {
+                write!(f, "#{} newest of its type (keep {})", rank, keep)
+            }
+            PolicyRule::TooRecent {
+                age_days,
+                min_age_days,
+            } => write!(f, "modified {}d ago (< {}d)", age_days, min_age_days),
+            PolicyRule::BelowSizeFloor { size, min_size } => write!(
+                f,
+                "artifacts {} < {}",
+                format_size(*size, BINARY),
+                format_size(*min_size, BINARY)
+            ),
+            PolicyRule::DirtyRepo { git, confirm } => {
+                write!(f, "git: {}", git)?;
+                if *confirm {
+                    write!(f, " (confirm)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Outcome of evaluating the policy for one project.
+#[derive(Debug, Clone)]
+pub struct PolicyVerdict {
+    /// The evaluated project
+    pub project: DetectedProject,
+    /// The rule that decided the outcome
+    pub rule: PolicyRule,
+}
+
+impl PolicyVerdict {
+    /// Returns true if the project should be cleaned.
+    pub fn is_included(&self) -> bool {
+        self.rule.allows_clean()
+    }
+
+    /// Returns true if the project may only be cleaned after confirmation.
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(self.rule, PolicyRule::DirtyRepo { confirm: true, .. })
+    }
+}
+
+/// A protect glob, kept with its source text for reporting.
+#[derive(Debug, Clone)]
+struct ProtectPattern {
+    pattern: String,
+    matcher: ExcludeMatcher,
+}
+
+impl ProtectPattern {
+    fn new(pattern: &str) -> Self {
+        let expanded = match (pattern.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => format!("{}/{}", home.display(), rest),
+            _ => pattern.to_string(),
+        };
+        Self {
+            pattern: pattern.to_string(),
+            matcher: ExcludeMatcher::new(&[expanded]),
+        }
+    }
+
+    /// Matches the path itself or any of its ancestors.
+    fn matches(&self, path: &Path) -> bool {
+        path.ancestors()
+            .any(|p| self.matcher.is_excluded_under(Path::new("/"), p, true))
+    }
+}
+
+/// Rules resolved for a single project type.
+#[derive(Debug, Clone, Default)]
+struct ResolvedRules {
+    min_age_days: Option<u32>,
+    keep_newest: Option<usize>,
+    min_artifact_size: Option<u64>,
+    dirty_repos: DirtyRepoAction,
+    protect: Vec<ProtectPattern>,
+}
+
+/// Cleanup policy evaluated against detected projects.
+#[derive(Debug, Clone, Default)]
+pub struct CleanPolicy {
+    defaults: PolicyRules,
+    types: HashMap<String, PolicyRules>,
+    /// Minimum age from `--age`, overriding every project type's rule
+    min_age_override: Option<u32>,
+}
+
+impl CleanPolicy {
+    /// Build a policy from the `[cleaner.policy]` configuration.
+    pub fn from_config(config: &PolicyConfig) -> Self {
+        Self {
+            defaults: PolicyRules {
+                min_age_days: config.min_age_days,
+                keep_newest: config.keep_newest,
+                min_artifact_size: config.min_artifact_size,
+                dirty_repos: config.dirty_repos,
+                protect: config.protect.clone(),
+            },
+            types: config
+                .types
+                .iter()
+                .map(|(id, rules)| (id.clone(), rules.clone()))
+                .collect(),
+            min_age_override: None,
+        }
+    }
+
+    /// Override the minimum age for all project types (e.g. from `--age`).
+    ///
+    /// System cleaners are not affected (see [`Self::explicit_min_age_days`]).
+    pub fn with_min_age_days(mut self, days: u32) -> Self {
+        self.min_age_override = Some(days);
+        self
+    }
+
+    /// Returns true if no rule restricts any project type.
+    pub fn is_empty(&self) -> bool {
+        self.min_age_override.is_none()
+            && std::iter::once(&self.defaults)
+                .chain(self.types.values())
+                .all(|r| {
+                    r.min_age_days.is_none()
+                        && r.keep_newest.is_none()
+                        && r.min_artifact_size.is_none()
+                        && matches!(r.dirty_repos, None | Some(DirtyRepoAction::Allow))
+                        && r.protect.is_empty()
+                })
+    }
+
+    /// Returns true if some project type has a `dirty_repos` rule other than
+    /// `allow`, so projects need their git state inspected.
+    pub fn checks_git(&self) -> bool {
+        std::iter::once(&self.defaults)
+            .chain(self.types.values())
+            .any(|r| {
+                matches!(
+                    r.dirty_repos,
+                    Some(DirtyRepoAction::Skip | DirtyRepoAction::Confirm)
+                )
+            })
+    }
+
+    /// Effective rules for a project type: per-type values override the
+    /// defaults, protect patterns are combined.
+    pub fn rules_for(&self, project_type: &str) -> PolicyRules {
+        let mut rules = self.defaults.clone();
+        if let Some(overrides) = self.types.get(project_type) {
+            rules.min_age_days = overrides.min_age_days.or(rules.min_age_days);
+            rules.keep_newest = overrides.keep_newest.or(rules.keep_newest);
+            rules.min_artifact_size = overrides.min_artifact_size.or(rules.min_artifact_size);
+            rules.dirty_repos = overrides.dirty_repos.or(rules.dirty_repos);
+            rules.protect.extend(overrides.protect.iter().cloned());
+        }
+        if let Some(days) = self.min_age_override {
+            rules.min_age_days = Some(days);
+        }
+        rules
+    }
+
+    /// Minimum age set for `id` itself in `[cleaner.policy.types]`.
+    ///
+    /// Unlike [`Self::rules_for`] this ignores the global rule and `--age`,
+    /// which are meant for projects: system cleaners use it, so they only
+    /// prune by age when configured to.
+    pub fn explicit_min_age_days(&self, id: &str) -> Option<u32> {
+        self.types.get(id).and_then(|rules| rules.min_age_days)
+    }
+
+    fn resolve(&self, project_type: &str) -> ResolvedRules {
+        let rules = self.rules_for(project_type);
+        ResolvedRules {
+            min_age_days: rules.min_age_days,
+            keep_newest: rules.keep_newest,
+            min_artifact_size: rules.min_artifact_size,
+            dirty_repos: rules.dirty_repos.unwrap_or_default(),
+            protect: rules
+                .protect
+                .iter()
+                .map(|p| ProtectPattern::new(p))
+                .collect(),
+        }
+    }
+
+    /// Evaluate the policy for each project, using the modification time of
+    /// its source files.
+    ///
+    /// `keep_newest` ranks projects among the given list, so pass every
+    /// project of a type rather than a pre-filtered subset.
+    pub fn evaluate(&self, projects: Vec<DetectedProject>) -> Vec<PolicyVerdict> {
+        self.evaluate_at(
+            projects,
+            SystemTime::now(),
+            ProjectScanner::project_last_modified,
+        )
+    }
+
+    /// Like [`evaluate`](Self::evaluate), with an explicit clock and source
+    /// of modification times. Verdicts are returned in input order.
+    pub fn evaluate_at<F>(
+        &self,
+        projects: Vec<DetectedProject>,
+        now: SystemTime,
+        last_modified: F,
+    ) -> Vec<PolicyVerdict>
+    where
+        F: Fn(&Path) -> SystemTime,
+    {
+        if self.is_empty() {
+            return projects
+                .into_iter()
+                .map(|project| PolicyVerdict {
+                    project,
+                    rule: PolicyRule::Unrestricted,
+                })
+                .collect();
+        }
+
+        let modified: Vec<SystemTime> = projects.iter().map(|p| last_modified(&p.path)).collect();
+        let groups: Vec<Vec<ArtifactGroup>> =
+            projects.iter().map(DetectedProject::groups).collect();
+
+        // Rank projects by recency within each of their types, newest first
+        let mut by_type: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, project_groups) in groups.iter().enumerate() {
+            for group in project_groups {
+                by_type.entry(&group.project_type).or_default().push(i);
+            }
+        }
+        let mut rank: HashMap<(&str, usize), usize> = HashMap::new();
+        for (&project_type, indices) in by_type.iter_mut() {
+            indices.sort_by(|&a, &b| {
+                modified[b]
+                    .cmp(&modified[a])
+                    .then_with(|| projects[a].path.cmp(&projects[b].path))
+            });
+            for (position, &i) in indices.iter().enumerate() {
+                rank.insert((project_type, i), position + 1);
+            }
+        }
+
+        let mut resolved: HashMap<String, ResolvedRules> = HashMap::new();
+        let rules: Vec<PolicyRule> = projects
+            .iter()
+            .enumerate()
+            .map(|(i, project)| {
+                let age = now.duration_since(modified[i]).unwrap_or(Duration::ZERO);
+                let group_rules = groups[i].iter().map(|group| {
+                    let rules = resolved
+                        .entry(group.project_type.clone())
+                        .or_insert_with(|| self.resolve(&group.project_type));
+                    Self::decide(
+                        rules,
+                        &project.for_group(group),
+                        rank[&(group.project_type.as_str(), i)],
+                        age.as_secs() / SECS_PER_DAY,
+                    )
+                });
+                Self::strictest(group_rules)
+            })
+            .collect();
+
+        projects
+            .into_iter()
+            .zip(rules)
+            .map(|(project, rule)| PolicyVerdict { project, rule })
+            .collect()
+    }
+
+    /// The rule deciding a project from the rules of its groups: the first
+    /// that keeps it, else the first asking for confirmation, else the first
+    /// that restricts it at all.
+    fn strictest(rules: impl Iterator<Item = PolicyRule>) -> PolicyRule {
+        rules
+            .min_by_key(|rule| match rule {
+                rule if !rule.allows_clean() => 0,
+                PolicyRule::DirtyRepo { confirm: true, .. } => 1,
+                PolicyRule::Unrestricted => 3,
+                _ => 2,
+            })
+            .unwrap_or(PolicyRule::Unrestricted)
+    }
+
+    fn decide(
+        rules: &ResolvedRules,
+        project: &DetectedProject,
+        rank: usize,
+        age_days: u64,
+    ) -> PolicyRule {
+        if let Some(p) = rules.protect.iter().find(|p| p.matches(&project.path)) {
+            return PolicyRule::Protected {
+                pattern: p.pattern.clone(),
+            };
+        }
+        let unsaved_git = project.git.as_ref().filter(|g| !g.is_clean());
+        if let (Some(git), DirtyRepoAction::Skip) = (unsaved_git, rules.dirty_repos) {
+            return PolicyRule::DirtyRepo {
+                git: git.clone(),
+                confirm: false,
+            };
+        }
+        if let Some(keep) = rules.keep_newest {
+            if rank <= keep {
+                return PolicyRule::KeepNewest { rank, keep };
+            }
+        }
+        if let Some(min_age_days) = rules.min_age_days {
+            if age_days < u64::from(min_age_days) {
+                return PolicyRule::TooRecent {
+                    age_days,
+                    min_age_days,
+                };
+            }
+        }
+        if let Some(min_size) = rules.min_artifact_size {
+            if project.artifact_size < min_size {
+                return PolicyRule::BelowSizeFloor {
+                    size: project.artifact_size,
+                    min_size,
+                };
+            }
+        }
+        if let (Some(git), DirtyRepoAction::Confirm) = (unsaved_git, rules.dirty_repos) {
+            return PolicyRule::DirtyRepo {
+                git: git.clone(),
+                confirm: true,
+            };
+        }
+        match rules.min_age_days {
+            Some(min_age_days) => PolicyRule::OldEnough {
+                age_days,
+                min_age_days,
+            },
+            None => PolicyRule::Unrestricted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    const NOW: u64 = 1_000 * SECS_PER_DAY;
+
+    fn project(path: &str, project_type: &str, size: u64) -> DetectedProject {
+        DetectedProject {
+            path: PathBuf::from(path),
+            project_type: project_type.to_string(),
+            display_name: project_type.to_string(),
+            artifact_size: size,
+            artifact_paths: vec![],
+            git: None,
+            artifact_groups: vec![],
+            parent: None,
+            shared_with: vec![],
+        }
+    }
+
+    /// Modification time encoded in the project's directory name: `/p/<days-ago>-...`
+    fn days_ago(path: &Path) -> SystemTime {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let days: u64 = name.split('-').next().unwrap().parse().unwrap();
+        SystemTime::UNIX_EPOCH + Duration::from_secs(NOW - days * SECS_PER_DAY)
+    }
+
+    fn evaluate(policy: &CleanPolicy, projects: Vec<DetectedProject>) -> Vec<PolicyRule> {
+        policy
+            .evaluate_at(
+                projects,
+                SystemTime::UNIX_EPOCH + Duration::from_secs(NOW),
+                days_ago,
+            )
+            .into_iter()
+            .map(|v| v.rule)
+            .collect()
+    }
+
+    fn config(f: impl FnOnce(&mut PolicyConfig)) -> CleanPolicy {
+        let mut config = PolicyConfig::default();
+        f(&mut config);
+        CleanPolicy::from_config(&config)
+    }
+
+    #[test]
+    fn test_empty_policy_includes_everything() {
+        let policy = CleanPolicy::default();
+        assert!(policy.is_empty());
+
+        let rules = evaluate(&policy, vec![project("/p/0-a", "cargo", 1)]);
+        assert_eq!(rules, vec![PolicyRule::Unrestricted]);
+    }
+
+    #[test]
+    fn test_min_age_days() {
+        let policy = config(|c| c.min_age_days = Some(10));
+        let rules = evaluate(
+            &policy,
+            vec![
+                project("/p/3-a", "cargo", 1),
+                project("/p/30-b", "cargo", 1),
+            ],
+        );
+
+        assert_eq!(
+            rules,
+            vec![
+                PolicyRule::TooRecent {
+                    age_days: 3,
+                    min_age_days: 10
+                },
+                PolicyRule::OldEnough {
+                    age_days: 30,
+                    min_age_days: 10
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_per_type_rules_override_defaults() {
+        let policy = config(|c| {
+            c.min_age_days = Some(10);
+            c.types.insert(
+                "npm".to_string(),
+                PolicyRules {
+                    min_age_days: Some(1),
+                    ..Default::default()
+                },
+            );
+        });
+        let rules = evaluate(
+            &policy,
+            vec![project("/p/5-a", "cargo", 1), project("/p/5-b", "npm", 1)],
+        );
+
+        assert!(!rules[0].allows_clean());
+        assert!(rules[1].allows_clean());
+    }
+
+    #[test]
+    fn test_keep_newest_per_type() {
+        let policy = config(|c| c.keep_newest = Some(1));
+        let rules = evaluate(
+            &policy,
+            vec![
+                project("/p/50-old", "cargo", 1),
+                project("/p/2-new", "cargo", 1),
+                project("/p/90-only", "npm", 1),
+            ],
+        );
+
+        assert_eq!(rules[0], PolicyRule::Unrestricted);
+        assert_eq!(rules[1], PolicyRule::KeepNewest { rank: 1, keep: 1 });
+        assert_eq!(rules[2], PolicyRule::KeepNewest { rank: 1, keep: 1 });
+    }
+
+    fn composite(path: &str, groups: &[(&str, u64)]) -> DetectedProject {
+        let groups = groups
+            .iter()
+            .map(|&(project_type, size)| ArtifactGroup {
+                project_type: project_type.to_string(),
+                display_name: project_type.to_string(),
+                artifact_size: size,
+                artifact_paths: vec![],
+            })
+            .collect();
+        DetectedProject::from_groups(PathBuf::from(path), groups).unwrap()
+    }
+
+    #[test]
+    fn test_composite_projects_follow_the_strictest_group() {
+        let policy = config(|c| {
+            c.types.insert(
+                "npm".to_string(),
+                PolicyRules {
+                    min_age_days: Some(30),
+                    min_artifact_size: Some(1000),
+                    ..Default::default()
+                },
+            );
+            c.types.insert(
+                "cargo".to_string(),
+                PolicyRules {
+                    keep_newest: Some(1),
+                    ..Default::default()
+                },
+            );
+        });
+        let rules = evaluate(
+            &policy,
+            vec![
+                composite("/p/5-recent", &[("cargo", 5000), ("npm", 5000)]),
+                composite("/p/60-small", &[("cargo", 5000), ("npm", 10)]),
+                composite("/p/60-large", &[("cargo", 10), ("npm", 5000)]),
+                project("/p/1-newest", "cargo", 1),
+            ],
+        );
+
+        assert_eq!(
+            rules[0],
+            PolicyRule::TooRecent {
+                age_days: 5,
+                min_age_days: 30
+            }
+        );
+        // Each type's size floor applies to that type's artifacts
+        assert_eq!(
+            rules[1],
+            PolicyRule::BelowSizeFloor {
+                size: 10,
+                min_size: 1000
+            }
+        );
+        assert_eq!(
+            rules[2],
+            PolicyRule::OldEnough {
+                age_days: 60,
+                min_age_days: 30
+            }
+        );
+        // Ranked among the cargo projects, composite or not
+        assert_eq!(rules[3], PolicyRule::KeepNewest { rank: 1, keep: 1 });
+    }
+
+    #[test]
+    fn test_size_floor() {
+        let policy = config(|c| c.min_artifact_size = Some(1000));
+        let rules = evaluate(
+            &policy,
+            vec![
+                project("/p/1-a", "cargo", 999),
+                project("/p/1-b", "cargo", 1000),
+            ],
+        );
+
+        assert_eq!(
+            rules[0],
+            PolicyRule::BelowSizeFloor {
+                size: 999,
+                min_size: 1000
+            }
+        );
+        assert_eq!(rules[1], PolicyRule::Unrestricted);
+    }
+
+    #[test]
+    fn test_protect_matches_project_and_ancestors() {
+        let policy = config(|c| {
+            c.protect = vec!["/p/work/**".to_string()];
+            c.types = BTreeMap::from([(
+                "cargo".to_string(),
+                PolicyRules {
+                    protect: vec!["*-keep".to_string()],
+                    ..Default::default()
+                },
+            )]);
+        });
+        let rules = evaluate(
+            &policy,
+            vec![
+                project("/p/work/nested/1-a", "npm", 1),
+                project("/p/1-keep", "cargo", 1),
+                project("/p/1-keep", "npm", 1),
+            ],
+        );
+
+        assert_eq!(
+            rules[0],
+            PolicyRule::Protected {
+                pattern: "/p/work/**".to_string()
+            }
+        );
+        assert_eq!(
+            rules[1],
+            PolicyRule::Protected {
+                pattern: "*-keep".to_string()
+            }
+        );
+        // Per-type patterns don't leak into other types
+        assert_eq!(rules[2], PolicyRule::Unrestricted);
+    }
+
+    #[test]
+    fn test_protect_expands_home() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let pattern = ProtectPattern::new("~/keep");
+        assert!(pattern.matches(&home.join("keep/app")));
+        assert!(!pattern.matches(Path::new("/elsewhere/keep")));
+    }
+
+    #[test]
+    fn test_protect_takes_precedence() {
+        let policy = config(|c| {
+            c.protect = vec!["/p".to_string()];
+            c.min_age_days = Some(1);
+        });
+        let rules = evaluate(&policy, vec![project("/p/100-a", "cargo", 1)]);
+
+        assert!(matches!(rules[0], PolicyRule::Protected { .. }));
+    }
+
+    #[test]
+    fn test_with_min_age_days_overrides_all_types() {
+        let policy = config(|c| {
+            c.types.insert(
+                "cargo".to_string(),
+                PolicyRules {
+                    min_age_days: Some(100),
+                    ..Default::default()
+                },
+            );
+        })
+        .with_min_age_days(5);
+
+        assert_eq!(policy.rules_for("cargo").min_age_days, Some(5));
+        assert_eq!(policy.rules_for("npm").min_age_days, Some(5));
+    }
+
+    #[test]
+    fn test_explicit_min_age_days_ignores_defaults_and_override() {
+        let policy = config(|c| {
+            c.min_age_days = Some(14);
+            c.types.insert(
+                "maven-repo".to_string(),
+                PolicyRules {
+                    min_age_days: Some(90),
+                    ..Default::default()
+                },
+            );
+        });
+        assert_eq!(policy.explicit_min_age_days("maven-repo"), Some(90));
+        assert_eq!(policy.explicit_min_age_days("trash"), None);
+
+        let policy = policy.with_min_age_days(5);
+        assert!(!CleanPolicy::default().with_min_age_days(5).is_empty());
+        assert_eq!(policy.explicit_min_age_days("maven-repo"), Some(90));
+        assert_eq!(policy.explicit_min_age_days("trash"), None);
+    }
+
+    #[test]
+    fn test_verdicts_preserve_order() {
+        let policy = config(|c| c.keep_newest = Some(1));
+        let verdicts = policy.evaluate_at(
+            vec![project("/p/9-a", "cargo", 1), project("/p/1-b", "cargo", 1)],
+            SystemTime::UNIX_EPOCH + Duration::from_secs(NOW),
+            days_ago,
+        );
+
+        let paths: Vec<&Path> = verdicts.iter().map(|v| v.project.path.as_path()).collect();
+        assert_eq!(paths, vec![Path::new("/p/9-a"), Path::new("/p/1-b")]);
+        assert!(verdicts[0].is_included());
+        assert!(!verdicts[1].is_included());
+    }
+
+    #[test]
+    fn test_rule_display() {
+        assert_eq!(
+            PolicyRule::TooRecent {
+                age_days: 2,
+                min_age_days: 7
+            }
+            .to_string(),
+            "modified 2d ago (< 7d)"
+        );
+        assert_eq!(
+            PolicyRule::KeepNewest { rank: 1, keep: 3 }.to_string(),
+            "#1 newest of its type (keep 3)"
+        );
+        assert_eq!(
+            PolicyRule::BelowSizeFloor {
+                size: 512,
+                min_size: 1024
+            }
+            .to_string(),
+            "artifacts 512 B < 1 KiB"
+        );
+    }
+
+    fn dirty(mut project: DetectedProject) -> DetectedProject {
+        project.git = Some(GitState {
+            dirty: true,
+            ..Default::default()
+        });
+        project
+    }
+
+    #[test]
+    fn test_dirty_repos_skip() {
+        let policy = config(|c| c.dirty_repos = Some(DirtyRepoAction::Skip));
+        assert!(!policy.is_empty());
+
+        let mut clean_repo = project("/p/0-b", "cargo", 1);
+        clean_repo.git = Some(GitState::default());
+        let rules = evaluate(
+            &policy,
+            vec![
+                dirty(project("/p/0-a", "cargo", 1)),
+                clean_repo,
+                project("/p/0-c", "cargo", 1),
+            ],
+        );
+
+        assert!(matches!(
+            &rules[0],
+            PolicyRule::DirtyRepo { confirm: false, .. }
+        ));
+        assert_eq!(rules[0].to_string(), "git: dirty");
+        assert_eq!(rules[1], PolicyRule::Unrestricted);
+        // Outside a repository there is nothing to lose
+        assert_eq!(rules[2], PolicyRule::Unrestricted);
+    }
+
+    #[test]
+    fn test_dirty_repos_confirm_after_other_rules() {
+        let policy = config(|c| {
+            c.dirty_repos = Some(DirtyRepoAction::Confirm);
+            c.min_age_days = Some(5);
+        });
+        let verdicts = policy.evaluate_at(
+            vec![
+                dirty(project("/p/9-a", "cargo", 1)),
+                dirty(project("/p/1-b", "cargo", 1)),
+            ],
+            SystemTime::UNIX_EPOCH + Duration::from_secs(NOW),
+            days_ago,
+        );
+
+        assert!(verdicts[0].is_included());
+        assert!(verdicts[0].needs_confirmation());
+        assert_eq!(verdicts[0].rule.to_string(), "git: dirty (confirm)");
+        // Too recent wins over asking
+        assert!(!verdicts[1].is_included());
+        assert!(!verdicts[1].needs_confirmation());
+    }
+
+    #[test]
+    fn test_dirty_repos_allow_is_empty_and_per_type() {
+        assert!(config(|c| c.dirty_repos = Some(DirtyRepoAction::Allow)).is_empty());
+
+        let policy = config(|c| {
+            c.dirty_repos = Some(DirtyRepoAction::Skip);
+            c.types.insert(
+                "npm".to_string(),
+                PolicyRules {
+                    dirty_repos: Some(DirtyRepoAction::Allow),
+                    ..Default::default()
+                },
+            );
+        });
+        let rules = evaluate(
+            &policy,
+            vec![
+                dirty(project("/p/0-a", "cargo", 1)),
+                dirty(project("/p/0-b", "npm", 1)),
+            ],
+        );
+        assert!(!rules[0].allows_clean());
+        assert_eq!(rules[1], PolicyRule::Unrestricted);
+    }
+
+    #[test]
+    fn test_checks_git_only_for_non_allow_dirty_repos() {
+        assert!(!CleanPolicy::default().checks_git());
+        assert!(!config(|c| c.dirty_repos = Some(DirtyRepoAction::Allow)).checks_git());
+        assert!(config(|c| c.dirty_repos = Some(DirtyRepoAction::Confirm)).checks_git());
+
+        let per_type = config(|c| {
+            c.types.insert(
+                "npm".to_string(),
+                PolicyRules {
+                    dirty_repos: Some(DirtyRepoAction::Skip),
+                    ..Default::default()
+                },
+            );
+        });
+        assert!(per_type.checks_git());
+    }
+}
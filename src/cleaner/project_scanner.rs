@@ -1,10 +1,13 @@
 //! Project scanner for discovering projects in a directory tree.
 
-use crate::cleaner::detector::DetectedProject;
+use crate::cleaner::detector::{ArtifactGroup, DetectedProject};
+use crate::cleaner::git::GitState;
 use crate::cleaner::registry::DetectorRegistry;
-use std::collections::HashSet;
+use crate::scanner::ExcludeMatcher;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime};
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
 /// Options for scanning.
@@ -12,10 +15,18 @@ use walkdir::WalkDir;
 pub struct ScanOptions {
     /// Maximum directory depth to scan.
     pub max_depth: usize,
-    /// Patterns to exclude from scanning.
+    /// Glob patterns to exclude from scanning (see [`ExcludeMatcher`]).
     pub exclude_patterns: Vec<String>,
     /// Whether to follow symbolic links.
     pub follow_symlinks: bool,
+    /// Whether to inspect the git state of each project (see [`GitState`]).
+    /// Off by default, since it runs `git` for every project; `clean` turns it
+    /// on when the policy has a `dirty_repos` rule.
+    pub check_git: bool,
+    /// Whether to keep looking for projects inside detected projects (e.g. a
+    /// Gradle service in an npm workspace). Artifact directories are never
+    /// entered, and nested projects record their `parent`.
+    pub nested: bool,
 }
 
 impl Default for ScanOptions {
@@ -24,6 +35,8 @@ impl Default for ScanOptions {
             max_depth: 10,
             exclude_patterns: vec![".git".to_string(), "node_modules".to_string()],
             follow_symlinks: false,
+            check_git: false,
+            nested: false,
         }
     }
 }
@@ -32,105 +45,175 @@ impl Default for ScanOptions {
 pub struct ProjectScanner {
     registry: DetectorRegistry,
     options: ScanOptions,
+    excludes: ExcludeMatcher,
 }
 
 impl ProjectScanner {
     /// Create a new scanner with the given registry and options.
     pub fn new(registry: DetectorRegistry, options: ScanOptions) -> Self {
-        Self { registry, options }
+        let excludes = ExcludeMatcher::new(&options.exclude_patterns);
+        Self {
+            registry,
+            options,
+            excludes,
+        }
     }
 
     /// Scan a directory tree for projects.
     ///
-    /// Returns a list of detected projects with their artifact information.
+    /// Returns a list of detected projects with their artifact information,
+    /// parents before the projects nested in them. An artifact directory
+    /// several projects use (e.g. a shared Cargo target dir) is reported by
+    /// the first one, which lists the others in `shared_with`.
     pub fn scan(&self, root: &Path) -> Vec<DetectedProject> {
-        let mut projects = Vec::new();
-        let mut skip_dirs: Vec<PathBuf> = Vec::new();
-
-        let walker = WalkDir::new(root)
+        let mut projects: Vec<DetectedProject> = Vec::new();
+        // Artifact directories found so far, with the project reporting them
+        let mut owners: HashMap<PathBuf, usize> = HashMap::new();
+        // Projects enclosing the current directory, outermost first
+        let mut enclosing: Vec<PathBuf> = Vec::new();
+
+        // Excluded subtrees are pruned before walkdir descends into them.
+        // Sorting makes the owner of a shared artifact dir stable.
+        let mut walker = WalkDir::new(root)
             .max_depth(self.options.max_depth)
             .follow_links(self.options.follow_symlinks)
-            .into_iter();
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0 || !self.is_excluded(root, e.path(), e.file_type().is_dir())
+            });
 
-        for entry in walker.flatten() {
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
             if !entry.file_type().is_dir() {
                 continue;
             }
 
             let path = entry.path();
 
-            // Check exclusion patterns
-            if !self.should_visit_path(path, &skip_dirs) {
+            // Artifacts are cleaned as a whole, never searched for projects,
+            // so no artifact is counted by two projects
+            if owners.contains_key(path) {
+                walker.skip_current_dir();
                 continue;
             }
 
-            // Check if this directory is a project
-            if let Some(project) = self.detect_project(path) {
-                // Don't recurse into this project
-                skip_dirs.push(path.to_path_buf());
-                projects.push(project);
+            // Check if this directory is a project. Artifacts already
+            // reported, such as a module's build dir in a multi-module build
+            // or a shared target dir, are left to the project reporting them.
+            let (project, sharing) = self.detect_unclaimed(path, &owners);
+            for &owner in &sharing {
+                let owner = &mut projects[owner];
+                // Workspace members and modules are part of their owner
+                let inside = path.starts_with(&owner.path)
+                    || owner.shared_with.iter().any(|p| path.starts_with(p));
+                if !inside {
+                    owner.shared_with.push(path.to_path_buf());
+                }
             }
-        }
 
-        projects
-    }
-
-    fn should_visit_path(&self, path: &Path, skip_dirs: &[PathBuf]) -> bool {
-        // Check if any path component matches an exclude pattern
-        for component in path.components() {
-            if let std::path::Component::Normal(name) = component {
-                let name = name.to_string_lossy();
-                for pattern in &self.options.exclude_patterns {
-                    if name == pattern.as_str() {
-                        return false;
-                    }
+            let Some(mut project) = project else {
+                // A project whose artifacts are all reported elsewhere
+                if !self.options.nested && !sharing.is_empty() {
+                    walker.skip_current_dir();
                 }
+                continue;
+            };
+
+            if self.options.nested {
+                enclosing.retain(|parent| path.starts_with(parent));
+                project.parent = enclosing.last().cloned();
+                enclosing.push(path.to_path_buf());
+            } else {
+                // Don't recurse into this project
+                walker.skip_current_dir();
             }
+            for artifact in &project.artifact_paths {
+                owners.insert(artifact.clone(), projects.len());
+            }
+            projects.push(project);
         }
 
-        // Check if inside a detected project
-        for skip in skip_dirs {
-            if path.starts_with(skip) && path != skip {
-                return false;
-            }
+        if self.options.check_git {
+            projects
+                .par_iter_mut()
+                .for_each(|p| p.git = GitState::inspect(&p.path, &p.artifact_paths));
         }
 
-        true
+        projects
     }
 
-    fn detect_project(&self, path: &Path) -> Option<DetectedProject> {
-        for detector in self.registry.detectors() {
-            if detector.detect(path) {
-                let artifact_paths = detector.find_artifacts(path);
+    fn is_excluded(&self, root: &Path, path: &Path, is_dir: bool) -> bool {
+        self.excludes.is_excluded_under(root, path, is_dir)
+    }
 
-                // Only report if there are actual artifacts OR it's a command-only detector
-                let has_local_artifacts = !artifact_paths.is_empty();
-                let has_artifact_dirs = !detector.artifact_dirs().is_empty();
-                let has_clean_command = detector.clean_command().is_some();
+    /// Detect the project at `path`, combining every detector that matches
+    /// it (e.g. `Cargo.toml` next to `package.json`).
+    ///
+    /// An artifact directory claimed by an earlier detector isn't claimed
+    /// again, so a Cargo and a Maven project sharing `target/` clean it once.
+    /// The git state is left unset.
+    pub fn detect_project(&self, path: &Path) -> Option<DetectedProject> {
+        self.detect_unclaimed(path, &HashMap::new()).0
+    }
 
-                // Skip if no artifacts to clean
-                if !has_local_artifacts && has_artifact_dirs {
-                    continue;
-                }
+    /// Detect the project at `path`, leaving out artifacts already reported
+    /// by the projects in `claimed`. Also returns the indices of the
+    /// projects whose artifacts it left out.
+    fn detect_unclaimed(
+        &self,
+        path: &Path,
+        claimed: &HashMap<PathBuf, usize>,
+    ) -> (Option<DetectedProject>, Vec<usize>) {
+        let mut taken: HashSet<PathBuf> = HashSet::new();
+        let mut sharing: Vec<usize> = Vec::new();
+        let mut groups = Vec::new();
 
-                // Skip command-only detectors (like Go) unless explicitly enabled
-                if !has_local_artifacts && !has_artifact_dirs && has_clean_command {
-                    // Go projects use global cache - skip for now
-                    continue;
+        for detector in self.registry.detectors() {
+            if !detector.detect(path) {
+                continue;
+            }
+
+            let mut artifact_paths: Vec<PathBuf> = Vec::new();
+            for artifact in detector.find_artifacts(path) {
+                match claimed.get(&artifact) {
+                    Some(owner) if !sharing.contains(owner) => sharing.push(*owner),
+                    None if !taken.contains(&artifact) => artifact_paths.push(artifact),
+                    _ => {}
                 }
+            }
 
-                let artifact_size = self.calculate_artifact_size(&artifact_paths);
+            // Only report if there are actual artifacts OR it's a command-only detector
+            let has_local_artifacts = !artifact_paths.is_empty();
+            let has_artifact_dirs = !detector.artifact_dirs().is_empty();
+            let has_clean_command = detector.clean_command().is_some();
+
+            // Skip if no artifacts to clean
+            if !has_local_artifacts && has_artifact_dirs {
+                continue;
+            }
 
-                return Some(DetectedProject {
-                    path: path.to_path_buf(),
-                    project_type: detector.id().to_string(),
-                    display_name: detector.display_name().to_string(),
-                    artifact_size,
-                    artifact_paths,
-                });
+            // Skip command-only detectors (like Go) unless explicitly enabled
+            if !has_local_artifacts && !has_artifact_dirs && has_clean_command {
+                // Go projects use global cache - skip for now
+                continue;
             }
+
+            taken.extend(artifact_paths.iter().cloned());
+            groups.push(ArtifactGroup {
+                project_type: detector.id().to_string(),
+                display_name: detector.display_name().to_string(),
+                artifact_size: self.calculate_artifact_size(&artifact_paths),
+                artifact_paths,
+            });
         }
-        None
+
+        (
+            DetectedProject::from_groups(path.to_path_buf(), groups),
+            sharing,
+        )
     }
 
     fn calculate_artifact_size(&self, paths: &[PathBuf]) -> u64 {
@@ -147,26 +230,10 @@ impl ProjectScanner {
             .sum()
     }
 
-    /// Filter projects by age, keeping only those not modified in `min_age_days`.
-    ///
-    /// This checks the modification time of source files (excluding artifacts)
-    /// to determine when the project was last actively worked on.
-    pub fn filter_by_age(
-        projects: Vec<DetectedProject>,
-        min_age_days: u64,
-    ) -> Vec<DetectedProject> {
-        let cutoff = SystemTime::now() - Duration::from_secs(min_age_days * 24 * 60 * 60);
-
-        projects
-            .into_iter()
-            .filter(|p| Self::project_last_modified(&p.path) < cutoff)
-            .collect()
-    }
-
     /// Get the last modification time of source files in a project.
     ///
     /// Excludes common artifact directories to focus on actual source code.
-    fn project_last_modified(path: &Path) -> SystemTime {
+    pub fn project_last_modified(path: &Path) -> SystemTime {
         let artifact_names: HashSet<&str> = [
             "target",
             "build",
@@ -259,6 +326,34 @@ mod tests {
         assert_eq!(cargo.artifact_size, 1000);
     }
 
+    #[test]
+    fn test_scan_combines_detectors_in_one_directory() {
+        let tmp = TempDir::new().unwrap();
+        let app = tmp.path().join("tauri-app");
+        fs::create_dir_all(app.join("target")).unwrap();
+        fs::create_dir_all(app.join("node_modules")).unwrap();
+        fs::write(app.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(app.join("package.json"), "{}").unwrap();
+        fs::write(app.join("pom.xml"), "<project/>").unwrap();
+        fs::write(app.join("target/app.bin"), "x".repeat(1000)).unwrap();
+        fs::write(app.join("node_modules/dep.js"), "x".repeat(500)).unwrap();
+
+        let scanner = ProjectScanner::new(DetectorRegistry::new(), ScanOptions::default());
+        let projects = scanner.scan(tmp.path());
+
+        assert_eq!(projects.len(), 1);
+        let project = &projects[0];
+        assert_eq!(project.type_label(), "cargo+npm");
+        assert_eq!(project.artifact_size, 1500);
+        let groups: Vec<(&str, u64)> = project
+            .artifact_groups
+            .iter()
+            .map(|g| (g.project_type.as_str(), g.artifact_size))
+            .collect();
+        // Maven's target/ is already Cargo's
+        assert_eq!(groups, vec![("cargo", 1000), ("npm", 500)]);
+    }
+
     #[test]
     fn test_scan_respects_max_depth() {
         let tmp = TempDir::new().unwrap();
@@ -280,6 +375,40 @@ mod tests {
         assert!(projects.is_empty()); // Too deep
     }
 
+    #[test]
+    fn test_scan_records_git_state() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        fs::create_dir_all(repo.join("target")).unwrap();
+        fs::write(repo.join("Cargo.toml"), "[package]").unwrap();
+        let git_init = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .arg(&repo)
+            .status()
+            .unwrap();
+        assert!(git_init.success());
+        let plain = tmp.path().join("plain");
+        fs::create_dir_all(plain.join("target")).unwrap();
+        fs::write(plain.join("Cargo.toml"), "[package]").unwrap();
+
+        let options = ScanOptions {
+            check_git: true,
+            ..Default::default()
+        };
+        let scanner = ProjectScanner::new(DetectorRegistry::new(), options);
+        let mut projects = scanner.scan(tmp.path());
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
+
+        assert_eq!(projects.len(), 2);
+        // Cargo.toml is untracked, target/ is not counted
+        assert!(projects[1].git.as_ref().is_some_and(|g| g.dirty));
+        assert_eq!(projects[0].git, None);
+
+        let projects =
+            ProjectScanner::new(DetectorRegistry::new(), ScanOptions::default()).scan(&repo);
+        assert_eq!(projects[0].git, None);
+    }
+
     #[test]
     fn test_scan_excludes_patterns() {
         let tmp = TempDir::new().unwrap();
@@ -297,6 +426,72 @@ mod tests {
         assert!(projects.is_empty());
     }
 
+    #[test]
+    fn test_scan_excludes_glob_patterns() {
+        let tmp = setup_test_tree();
+
+        // Vendored copy of a project, excluded at any depth
+        let vendored = tmp.path().join("vendor/rust-app");
+        fs::create_dir_all(vendored.join("target")).unwrap();
+        fs::write(vendored.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(vendored.join("target/lib.rlib"), "x").unwrap();
+
+        let registry = DetectorRegistry::new();
+        let options = ScanOptions {
+            exclude_patterns: vec!["**/vendor".to_string(), "web-*".to_string()],
+            ..Default::default()
+        };
+        let scanner = ProjectScanner::new(registry, options);
+
+        let projects = scanner.scan(tmp.path());
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].path, tmp.path().join("rust-app"));
+    }
+
+    #[test]
+    fn test_scan_exclude_negation() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["old-app", "old-keep"] {
+            let proj = tmp.path().join(name);
+            fs::create_dir_all(proj.join("target")).unwrap();
+            fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
+            fs::write(proj.join("target/bin"), "x").unwrap();
+        }
+
+        let registry = DetectorRegistry::new();
+        let options = ScanOptions {
+            exclude_patterns: vec!["old-*".to_string(), "!old-keep".to_string()],
+            ..Default::default()
+        };
+        let scanner = ProjectScanner::new(registry, options);
+
+        let projects = scanner.scan(tmp.path());
+
+        assert_eq!(projects.len(), 1);
+        assert!(projects[0].path.ends_with("old-keep"));
+    }
+
+    #[test]
+    fn test_scan_exclude_matches_relative_to_root() {
+        // The root's own name must not be matched by unanchored patterns
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("build");
+        let proj = root.join("app");
+        fs::create_dir_all(proj.join("target")).unwrap();
+        fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(proj.join("target/bin"), "x").unwrap();
+
+        let registry = DetectorRegistry::new();
+        let options = ScanOptions {
+            exclude_patterns: vec!["build".to_string()],
+            ..Default::default()
+        };
+        let scanner = ProjectScanner::new(registry, options);
+
+        assert_eq!(scanner.scan(&root).len(), 1);
+    }
+
     #[test]
     fn test_scan_skips_projects_without_artifacts() {
         let tmp = TempDir::new().unwrap();
@@ -346,29 +541,157 @@ mod tests {
         assert!(projects[0].path.ends_with("util"));
     }
 
+    fn setup_monorepo() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("monorepo");
+        let write = |rel: &str, len: usize| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "x".repeat(len)).unwrap();
+        };
+        write("package.json", 2);
+        write("node_modules/dep/index.js", 500);
+        // A package inside an artifact directory is not a project
+        write("node_modules/dep/package.json", 2);
+        write("node_modules/dep/node_modules/inner.js", 50);
+        write("services/api/build.gradle", 2);
+        write("services/api/build/classes/App.class", 1000);
+        write("services/api/tools/Cargo.toml", 2);
+        write("services/api/tools/target/tool", 300);
+        write("apps/mobile/package.json", 2);
+        write("apps/mobile/node_modules/rn.js", 200);
+        tmp
+    }
+
     #[test]
-    fn test_filter_by_age_recent_project() {
-        // Create a project that was just modified (should be filtered out)
+    fn test_scan_stops_at_projects_by_default() {
+        let tmp = setup_monorepo();
+        let options = ScanOptions {
+            exclude_patterns: vec![],
+            ..Default::default()
+        };
+        let projects = ProjectScanner::new(DetectorRegistry::new(), options).scan(tmp.path());
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].parent, None);
+    }
+
+    #[test]
+    fn test_scan_nested_projects() {
+        let tmp = setup_monorepo();
+        let root = tmp.path().join("monorepo");
+        let options = ScanOptions {
+            exclude_patterns: vec![],
+            nested: true,
+            ..Default::default()
+        };
+        let projects = ProjectScanner::new(DetectorRegistry::new(), options).scan(tmp.path());
+
+        let mut found: Vec<(PathBuf, &str, u64, Option<PathBuf>)> = projects
+            .iter()
+            .map(|p| {
+                let rel = p.path.strip_prefix(&root).unwrap().to_path_buf();
+                let parent = p
+                    .parent
+                    .as_ref()
+                    .map(|parent| parent.strip_prefix(&root).unwrap().to_path_buf());
+                (rel, p.project_type.as_str(), p.artifact_size, parent)
+            })
+            .collect();
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![
+                (PathBuf::new(), "npm", 552, None),
+                (
+                    PathBuf::from("apps/mobile"),
+                    "npm",
+                    200,
+                    Some(PathBuf::new())
+                ),
+                (
+                    PathBuf::from("services/api"),
+                    "gradle",
+                    1000,
+                    Some(PathBuf::new())
+                ),
+                (
+                    PathBuf::from("services/api/tools"),
+                    "cargo",
+                    300,
+                    Some(PathBuf::from("services/api"))
+                ),
+            ]
+        );
+        // Parents come before the projects nested in them
+        assert_eq!(projects[0].path, root);
+    }
+
+    #[test]
+    fn test_scan_nested_counts_module_builds_once() {
         let tmp = TempDir::new().unwrap();
-        let proj = tmp.path().join("recent");
-        fs::create_dir_all(&proj).unwrap();
-        fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
-        fs::create_dir(proj.join("target")).unwrap();
-        fs::write(proj.join("target/artifact"), "x".repeat(100)).unwrap();
+        let root = tmp.path();
+        fs::write(root.join("settings.gradle"), "include ':app'").unwrap();
+        fs::write(root.join("build.gradle"), "").unwrap();
+        fs::create_dir_all(root.join("app/build")).unwrap();
+        fs::write(root.join("app/build.gradle"), "").unwrap();
+        fs::write(root.join("app/build/app.apk"), "x".repeat(400)).unwrap();
+        fs::create_dir_all(root.join("app/.gradle")).unwrap();
+        fs::write(root.join("app/.gradle/cache"), "x".repeat(10)).unwrap();
 
-        let projects = vec![DetectedProject {
-            path: proj,
-            project_type: "cargo".to_string(),
-            display_name: "Cargo".to_string(),
-            artifact_size: 100,
-            artifact_paths: vec![],
-        }];
+        let options = ScanOptions {
+            exclude_patterns: vec![],
+            nested: true,
+            ..Default::default()
+        };
+        let projects = ProjectScanner::new(DetectorRegistry::new(), options).scan(root);
 
-        // Filter for projects older than 7 days
-        let filtered = ProjectScanner::filter_by_age(projects, 7);
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].artifact_paths, vec![root.join("app/build")]);
+        assert_eq!(projects[0].artifact_size, 400);
+        // The module keeps only what the root build didn't claim
+        assert_eq!(projects[1].artifact_paths, vec![root.join("app/.gradle")]);
+        assert_eq!(projects[1].parent.as_deref(), Some(root));
+    }
 
-        // Recent project should be filtered out
-        assert!(filtered.is_empty());
+    #[test]
+    fn test_scan_reports_shared_target_dir_once() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            ".cargo/config.toml",
+            "[build]\ntarget-dir = \"shared-target\"\n",
+        );
+        write("shared-target/debug/app", &"x".repeat(700));
+        write("alpha/Cargo.toml", "[workspace]\nmembers = [\"core\"]\n");
+        write("alpha/core/Cargo.toml", "[package]\nname = \"core\"\n");
+        write("beta/Cargo.toml", "[package]\nname = \"beta\"\n");
+        write("gamma/Cargo.toml", "[package]\nname = \"gamma\"\n");
+
+        for nested in [false, true] {
+            let options = ScanOptions {
+                exclude_patterns: vec![],
+                nested,
+                ..Default::default()
+            };
+            let projects = ProjectScanner::new(DetectorRegistry::new(), options).scan(root);
+
+            assert_eq!(projects.len(), 1);
+            assert_eq!(projects[0].path, root.join("alpha"));
+            assert_eq!(projects[0].artifact_paths, vec![root.join("shared-target")]);
+            assert_eq!(projects[0].artifact_size, 700);
+            // The workspace member is part of alpha, not a separate owner
+            assert_eq!(
+                projects[0].shared_with,
+                vec![root.join("beta"), root.join("gamma")]
+            );
+        }
     }
 
     #[test]
@@ -391,10 +714,4 @@ mod tests {
         // Should return a valid time (not UNIX_EPOCH for a project with files)
         assert!(mtime > SystemTime::UNIX_EPOCH);
     }
-
-    #[test]
-    fn test_filter_by_age_empty_list() {
-        let filtered = ProjectScanner::filter_by_age(vec![], 7);
-        assert!(filtered.is_empty());
-    }
 }
@@ -1,23 +1,34 @@
 //! Detector registry for managing project detectors.
 
-use crate::cleaner::detectors::all_detectors;
+use crate::cleaner::detectors::{all_detectors, configured_detectors};
 use crate::cleaner::system_registry::all_system_cleaner_ids;
 use crate::cleaner::ProjectDetector;
-use std::collections::HashSet;
+use crate::config::ProjectTypeConfig;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
 
 /// Registry that manages project detectors.
 ///
 /// Provides functionality to filter detectors by type, which is useful
 /// for CLI options like `--types cargo,npm`.
+#[derive(Clone)]
 pub struct DetectorRegistry {
-    detectors: Vec<Box<dyn ProjectDetector>>,
+    detectors: Vec<Arc<dyn ProjectDetector>>,
 }
 
 impl DetectorRegistry {
     /// Create a registry with all built-in detectors.
     pub fn new() -> Self {
         Self {
-            detectors: all_detectors(),
+            detectors: all_detectors().into_iter().map(Arc::from).collect(),
+        }
+    }
+
+    /// Create a registry with the built-in detectors and the custom project
+    /// types and overrides from `[project_types]`.
+    pub fn from_config(project_types: &BTreeMap<String, ProjectTypeConfig>) -> Self {
+        Self {
+            detectors: configured_detectors(project_types),
         }
     }
 
@@ -31,28 +42,26 @@ impl DetectorRegistry {
     /// assert_eq!(registry.len(), 2);
     /// ```
     pub fn with_types(types: &[&str]) -> Self {
-        let type_set: HashSet<&str> = types.iter().copied().collect();
-        Self {
-            detectors: all_detectors()
-                .into_iter()
-                .filter(|d| type_set.contains(d.id()))
-                .collect(),
-        }
+        Self::new().retain_types(types)
     }
 
     /// Create a registry excluding the specified detector types.
     pub fn without_types(types: &[&str]) -> Self {
         let type_set: HashSet<&str> = types.iter().copied().collect();
-        Self {
-            detectors: all_detectors()
-                .into_iter()
-                .filter(|d| !type_set.contains(d.id()))
-                .collect(),
-        }
+        let mut registry = Self::new();
+        registry.detectors.retain(|d| !type_set.contains(d.id()));
+        registry
+    }
+
+    /// Keep only the specified detector types.
+    pub fn retain_types(mut self, types: &[&str]) -> Self {
+        let type_set: HashSet<&str> = types.iter().copied().collect();
+        self.detectors.retain(|d| type_set.contains(d.id()));
+        self
     }
 
     /// Get all registered detectors.
-    pub fn detectors(&self) -> &[Box<dyn ProjectDetector>] {
+    pub fn detectors(&self) -> &[Arc<dyn ProjectDetector>] {
         &self.detectors
     }
 
@@ -86,23 +95,18 @@ impl Default for DetectorRegistry {
     }
 }
 
-impl Clone for DetectorRegistry {
-    fn clone(&self) -> Self {
-        // Re-create from all_detectors filtered by current IDs
-        let current_ids: HashSet<&str> = self.ids().into_iter().collect();
-        Self {
-            detectors: all_detectors()
-                .into_iter()
-                .filter(|d| current_ids.contains(d.id()))
-                .collect(),
+/// Return all valid type IDs (enabled project detectors, including custom
+/// ones from `[project_types]`, and system cleaners).
+pub fn all_valid_type_ids(project_types: &BTreeMap<String, ProjectTypeConfig>) -> Vec<String> {
+    let mut ids: Vec<String> = configured_detectors(project_types)
+        .iter()
+        .map(|d| d.id().to_string())
+        .collect();
+    for id in all_system_cleaner_ids() {
+        if !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
         }
     }
-}
-
-/// Return all valid type IDs (project detectors + system cleaners).
-pub fn all_valid_type_ids() -> Vec<&'static str> {
-    let mut ids: Vec<&'static str> = all_detectors().iter().map(|d| d.id()).collect();
-    ids.extend(all_system_cleaner_ids());
     ids
 }
 
@@ -183,6 +187,14 @@ mod tests {
         assert_eq!(registry.len(), 9);
     }
 
+    #[test]
+    fn test_registry_clone_keeps_custom_detectors() {
+        let registry = DetectorRegistry::from_config(&custom_types());
+        let cloned = registry.clone();
+
+        assert_eq!(cloned.ids(), registry.ids());
+    }
+
     #[test]
     fn test_registry_clone() {
         let registry = DetectorRegistry::with_types(&["cargo", "npm"]);
@@ -195,10 +207,57 @@ mod tests {
 
     #[test]
     fn test_all_valid_type_ids() {
-        let ids = all_valid_type_ids();
-        assert!(ids.contains(&"cargo"));
-        assert!(ids.contains(&"npm"));
-        assert!(ids.contains(&"docker"));
-        assert_eq!(ids.len(), 10); // 9 project types + 1 system cleaner
+        let ids = all_valid_type_ids(&BTreeMap::new());
+        assert!(ids.iter().any(|id| id == "cargo"));
+        assert!(ids.iter().any(|id| id == "npm"));
+        assert!(ids.iter().any(|id| id == "docker"));
+        assert!(ids.iter().any(|id| id == "maven-repo"));
+        assert!(ids.iter().any(|id| id == "podman"));
+        assert!(ids.iter().any(|id| id == "journald"));
+        assert!(ids.iter().any(|id| id == "xdg-cache"));
+        // 9 project types + 3 container engines + 7 package caches + 5 Linux
+        // system cleaners + 5 desktop cleaners; go is both
+        assert_eq!(ids.len(), 29);
+    }
+
+    fn custom_types() -> BTreeMap<String, ProjectTypeConfig> {
+        BTreeMap::from([
+            (
+                "blaze".to_string(),
+                ProjectTypeConfig {
+                    detection_files: Some(vec!["BLAZE".to_string()]),
+                    artifact_dirs: Some(vec!["blaze-out".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            (
+                "go".to_string(),
+                ProjectTypeConfig {
+                    enabled: false,
+                    ..Default::default()
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_all_valid_type_ids_include_custom_types() {
+        let ids = all_valid_type_ids(&custom_types());
+        assert!(ids.iter().any(|id| id == "blaze"));
+        // Disabling the Go detector leaves the Go cache cleaner
+        assert_eq!(ids.iter().filter(|id| *id == "go").count(), 1);
+        assert_eq!(ids.len(), 30);
+    }
+
+    #[test]
+    fn test_registry_from_config() {
+        let registry = DetectorRegistry::from_config(&custom_types());
+
+        assert!(registry.get("blaze").is_some());
+        assert!(registry.get("go").is_none());
+        assert_eq!(registry.len(), 9);
+
+        let filtered = registry.retain_types(&["blaze", "cargo"]);
+        assert_eq!(filtered.ids(), vec!["blaze", "cargo"]);
     }
 }
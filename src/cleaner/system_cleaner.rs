@@ -4,6 +4,12 @@
 //! system cleaners target resources managed by system-level services
 //! (e.g., Docker daemon) that aren't tied to individual project directories.
 
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::SystemTime;
+
+use crate::journal::{CleanMethod, Journal, JournalRecord};
+
 /// A detected system-level resource that can be cleaned.
 #[derive(Debug, Clone)]
 pub struct DetectedSystemResource {
@@ -21,13 +27,29 @@ pub struct DetectedSystemResource {
     pub item_count: Option<u64>,
 }
 
+/// An individual item within a system resource (e.g., one Docker image).
+#[derive(Debug, Clone)]
+pub struct SystemResourceItem {
+    /// Identifier used to remove the item (e.g., an image ID).
+    pub id: String,
+    /// Human-readable name (e.g., "nginx:latest").
+    pub name: String,
+    /// Size in bytes.
+    pub size: u64,
+    /// When the item was last used, if known.
+    pub last_used: Option<SystemTime>,
+}
+
 /// Result of a system clean operation.
 #[derive(Debug)]
 pub enum SystemCleanResult {
     /// Cleaning succeeded.
     Success {
         resource: DetectedSystemResource,
+        /// Space actually reclaimed, as reported or measured.
         freed_bytes: u64,
+        /// Reclaimable size detected before cleaning.
+        estimated_bytes: u64,
     },
     /// Cleaning failed.
     Failed {
@@ -49,6 +71,20 @@ pub trait SystemCleaner: Send + Sync {
     /// Human-readable name (e.g., "Docker").
     fn display_name(&self) -> &'static str;
 
+    /// Whether `clean` runs this cleaner without `--types`. Cleaners that
+    /// empty the Trash, uninstall packages or need root have to be requested
+    /// by id.
+    fn runs_by_default(&self) -> bool {
+        false
+    }
+
+    /// Whether a `clean` without `--types` includes `resource`, when this
+    /// cleaner runs by default. Resources holding data rather than caches
+    /// are left to `--types`.
+    fn cleans_by_default(&self, _resource: &DetectedSystemResource) -> bool {
+        true
+    }
+
     /// Check if the underlying system service is available.
     fn is_available(&self) -> bool;
 
@@ -57,6 +93,105 @@ pub trait SystemCleaner: Send + Sync {
 
     /// Clean a specific resource.
     fn clean(&self, resource: &DetectedSystemResource, dry_run: bool) -> SystemCleanResult;
+
+    /// Clean a specific resource and append the outcome to `journal`.
+    ///
+    /// Dry runs and skipped resources are not recorded.
+    fn clean_recorded(
+        &self,
+        resource: &DetectedSystemResource,
+        dry_run: bool,
+        journal: Option<&Journal>,
+    ) -> SystemCleanResult {
+        let result = self.clean(resource, dry_run);
+        if let Some(journal) = journal.filter(|_| !dry_run) {
+            record_result(journal, &resource.resource_id, resource, &result);
+        }
+        result
+    }
+
+    /// List the individual items of a resource, for cleaners that support
+    /// removing them one by one.
+    fn list_items(&self, _resource: &DetectedSystemResource) -> Vec<SystemResourceItem> {
+        Vec::new()
+    }
+
+    /// Remove a single item of a resource.
+    fn remove_item(
+        &self,
+        resource: &DetectedSystemResource,
+        _item: &SystemResourceItem,
+    ) -> SystemCleanResult {
+        SystemCleanResult::Skipped {
+            resource: resource.clone(),
+            reason: format!(
+                "{} items can't be removed individually",
+                self.display_name()
+            ),
+        }
+    }
+
+    /// Remove a single item and append the outcome to `journal`, using
+    /// `<resource id>/<item name>` as the path.
+    fn remove_item_recorded(
+        &self,
+        resource: &DetectedSystemResource,
+        item: &SystemResourceItem,
+        journal: Option<&Journal>,
+    ) -> SystemCleanResult {
+        let result = self.remove_item(resource, item);
+        if let Some(journal) = journal {
+            let path = format!("{}/{}", resource.resource_id, item.name);
+            record_result(journal, &path, resource, &result);
+        }
+        result
+    }
+}
+
+/// Result of a dry run: the detected size, reported as both figures.
+pub(crate) fn dry_run_result(resource: &DetectedSystemResource) -> SystemCleanResult {
+    SystemCleanResult::Success {
+        resource: resource.clone(),
+        freed_bytes: resource.size,
+        estimated_bytes: resource.size,
+    }
+}
+
+/// Run `command` (program and arguments) without input or output and return
+/// whether it succeeded. Also used to probe whether a tool is installed.
+pub(crate) fn run_quietly(command: &[&str]) -> bool {
+    let Some((program, args)) = command.split_first() else {
+        return false;
+    };
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Append a system clean result to the journal; skipped results aren't recorded.
+fn record_result(
+    journal: &Journal,
+    path: &str,
+    resource: &DetectedSystemResource,
+    result: &SystemCleanResult,
+) {
+    let record = JournalRecord::new(
+        Path::new(path),
+        Some(&resource.category),
+        CleanMethod::Native,
+    );
+    match result {
+        SystemCleanResult::Success { freed_bytes, .. } => {
+            journal.record(record.freed(*freed_bytes))
+        }
+        SystemCleanResult::Failed { error, .. } => journal.record(record.failed(error)),
+        SystemCleanResult::Skipped { .. } => {}
+    }
 }
 
 #[cfg(test)]
@@ -82,6 +217,78 @@ mod tests {
         assert_eq!(resource.item_count, Some(5));
     }
 
+    struct FailingCleaner;
+
+    impl SystemCleaner for FailingCleaner {
+        fn id(&self) -> &'static str {
+            "failing"
+        }
+        fn display_name(&self) -> &'static str {
+            "Failing"
+        }
+        fn is_available(&self) -> bool {
+            true
+        }
+        fn detect(&self) -> Vec<DetectedSystemResource> {
+            Vec::new()
+        }
+        fn clean(&self, resource: &DetectedSystemResource, _dry_run: bool) -> SystemCleanResult {
+            SystemCleanResult::Failed {
+                resource: resource.clone(),
+                error: "daemon not running".to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_clean_recorded_appends_to_journal() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let journal = Journal::with_path(tmp.path().join("journal.jsonl"));
+        let resource = DetectedSystemResource {
+            resource_id: "failing-cache".to_string(),
+            display_name: "Failing Cache".to_string(),
+            category: "failing".to_string(),
+            size: 100,
+            description: "test".to_string(),
+            item_count: None,
+        };
+
+        FailingCleaner.clean_recorded(&resource, true, Some(&journal));
+        assert!(journal.query(&Default::default()).unwrap().is_empty());
+
+        FailingCleaner.clean_recorded(&resource, false, Some(&journal));
+        let records = journal.query(&Default::default()).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].path, Path::new("failing-cache"));
+        assert_eq!(records[0].project_type.as_deref(), Some("failing"));
+        assert_eq!(records[0].method, CleanMethod::Native);
+        assert_eq!(records[0].error.as_deref(), Some("daemon not running"));
+    }
+
+    #[test]
+    fn test_items_unsupported_by_default() {
+        let resource = DetectedSystemResource {
+            resource_id: "failing-cache".to_string(),
+            display_name: "Failing Cache".to_string(),
+            category: "failing".to_string(),
+            size: 100,
+            description: "test".to_string(),
+            item_count: None,
+        };
+        let item = SystemResourceItem {
+            id: "1".to_string(),
+            name: "one".to_string(),
+            size: 10,
+            last_used: None,
+        };
+
+        assert!(FailingCleaner.list_items(&resource).is_empty());
+        assert!(matches!(
+            FailingCleaner.remove_item(&resource, &item),
+            SystemCleanResult::Skipped { .. }
+        ));
+    }
+
     #[test]
     fn test_system_clean_result_variants() {
         let resource = DetectedSystemResource {
@@ -96,6 +303,7 @@ mod tests {
         let _success = SystemCleanResult::Success {
             resource: resource.clone(),
             freed_bytes: 100,
+            estimated_bytes: 100,
         };
 
         let _failed = SystemCleanResult::Failed {
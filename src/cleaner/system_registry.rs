@@ -1,32 +1,71 @@
 //! Registry for system-wide cleaners.
 
+use crate::cleaner::desktop::{desktop_cleaners, desktop_ids};
 use crate::cleaner::docker::DockerCleaner;
+use crate::cleaner::go::GoCacheCleaner;
+use crate::cleaner::linux_system::{linux_system_cleaners, linux_system_ids};
+use crate::cleaner::nerdctl::NerdctlCleaner;
+use crate::cleaner::package_cache::{package_cache_cleaners, package_cache_ids};
+use crate::cleaner::podman::PodmanCleaner;
+use crate::cleaner::policy::CleanPolicy;
 use crate::cleaner::system_cleaner::{DetectedSystemResource, SystemCleaner};
 use std::collections::HashSet;
 
 /// Registry that manages system-wide cleaners.
 pub struct SystemCleanerRegistry {
     cleaners: Vec<Box<dyn SystemCleaner>>,
+    /// Set by `retain_defaults`: detect only the resources a run without
+    /// `--types` cleans
+    defaults_only: bool,
 }
 
 impl SystemCleanerRegistry {
     /// Create a registry with all built-in system cleaners.
     pub fn new() -> Self {
+        Self::with_policy(&CleanPolicy::default())
+    }
+
+    /// Create a registry with all built-in system cleaners, limiting caches
+    /// to entries older than the `min_age_days` set for their own id in
+    /// `policy`.
+    pub fn with_policy(policy: &CleanPolicy) -> Self {
+        let mut cleaners: Vec<Box<dyn SystemCleaner>> = vec![
+            Box::new(DockerCleaner::default()),
+            Box::new(PodmanCleaner::default()),
+            Box::new(NerdctlCleaner),
+            Box::new(GoCacheCleaner),
+        ];
+        for cleaner in package_cache_cleaners(policy) {
+            cleaners.push(Box::new(cleaner));
+        }
+        cleaners.extend(linux_system_cleaners(policy));
+        cleaners.extend(desktop_cleaners(policy));
         Self {
-            cleaners: vec![Box::new(DockerCleaner)],
+            cleaners,
+            defaults_only: false,
         }
     }
 
     /// Create a registry with only cleaners matching the given type IDs.
     pub fn with_types(types: &[&str]) -> Self {
+        Self::new().retain_types(types)
+    }
+
+    /// Keep only cleaners matching the given type IDs.
+    pub fn retain_types(mut self, types: &[&str]) -> Self {
         let type_set: HashSet<&str> = types.iter().copied().collect();
-        let all_cleaners: Vec<Box<dyn SystemCleaner>> = vec![Box::new(DockerCleaner)];
-        Self {
-            cleaners: all_cleaners
-                .into_iter()
-                .filter(|c| type_set.contains(c.id()))
-                .collect(),
-        }
+        self.cleaners.retain(|c| type_set.contains(c.id()));
+        self
+    }
+
+    /// Keep only the cleaners that run without `--types`, and only the
+    /// resources they clean by default (see
+    /// [`SystemCleaner::runs_by_default`] and
+    /// [`SystemCleaner::cleans_by_default`]).
+    pub fn retain_defaults(mut self) -> Self {
+        self.cleaners.retain(|c| c.runs_by_default());
+        self.defaults_only = true;
+        self
     }
 
     /// List all system cleaner IDs.
@@ -41,7 +80,12 @@ impl SystemCleanerRegistry {
         let mut resources = Vec::new();
         for cleaner in &self.cleaners {
             if cleaner.is_available() {
-                resources.extend(cleaner.detect());
+                resources.extend(
+                    cleaner
+                        .detect()
+                        .into_iter()
+                        .filter(|r| !self.defaults_only || cleaner.cleans_by_default(r)),
+                );
             }
         }
         resources
@@ -69,7 +113,11 @@ impl Default for SystemCleanerRegistry {
 
 /// Return all known system cleaner IDs.
 pub fn all_system_cleaner_ids() -> Vec<&'static str> {
-    vec!["docker"]
+    let mut ids = vec!["docker", "podman", "nerdctl", "go"];
+    ids.extend(package_cache_ids());
+    ids.extend(linux_system_ids());
+    ids.extend(desktop_ids());
+    ids
 }
 
 #[cfg(test)]
@@ -79,13 +127,20 @@ mod tests {
     #[test]
     fn test_registry_new() {
         let registry = SystemCleanerRegistry::new();
-        assert_eq!(registry.ids(), vec!["docker"]);
+        assert_eq!(registry.ids(), all_system_cleaner_ids());
+        assert!(registry.ids().contains(&"maven-repo"));
     }
 
     #[test]
     fn test_registry_with_types_matching() {
         let registry = SystemCleanerRegistry::with_types(&["docker"]);
         assert_eq!(registry.ids(), vec!["docker"]);
+
+        let registry = SystemCleanerRegistry::with_types(&["podman", "nerdctl"]);
+        assert_eq!(registry.ids(), vec!["podman", "nerdctl"]);
+
+        let registry = SystemCleanerRegistry::with_types(&["npm-cache", "go", "cargo"]);
+        assert_eq!(registry.ids(), vec!["go", "npm-cache"]);
     }
 
     #[test]
@@ -100,16 +155,31 @@ mod tests {
         assert!(registry.is_empty());
     }
 
+    #[test]
+    fn test_registry_defaults_are_docker_only() {
+        let registry = SystemCleanerRegistry::new().retain_defaults();
+        assert_eq!(registry.ids(), vec!["docker"]);
+    }
+
     #[test]
     fn test_registry_get_cleaner() {
         let registry = SystemCleanerRegistry::new();
         assert!(registry.get_cleaner("docker").is_some());
+        assert!(registry.get_cleaner("podman").is_some());
+        assert!(registry.get_cleaner("nerdctl").is_some());
+        assert!(registry.get_cleaner("go").is_some());
         assert!(registry.get_cleaner("nonexistent").is_none());
     }
 
     #[test]
     fn test_all_system_cleaner_ids() {
         let ids = all_system_cleaner_ids();
-        assert_eq!(ids, vec!["docker"]);
+        assert_eq!(&ids[..4], &["docker", "podman", "nerdctl", "go"]);
+        let caches = package_cache_ids();
+        assert_eq!(&ids[4..4 + caches.len()], caches.as_slice());
+        let linux = linux_system_ids();
+        let desktop_start = 4 + caches.len() + linux.len();
+        assert_eq!(&ids[4 + caches.len()..desktop_start], linux.as_slice());
+        assert_eq!(&ids[desktop_start..], desktop_ids().as_slice());
     }
 }
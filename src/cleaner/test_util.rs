@@ -0,0 +1,19 @@
+//! Fixtures shared by the cleaner tests.
+
+use std::fs::{self, FileTimes};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Write a file of `len` bytes, creating its parents, last read and
+/// modified `age_days` ago.
+pub(crate) fn write_file(path: &Path, len: usize, age_days: u64) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, "x".repeat(len)).unwrap();
+    let time = SystemTime::now() - Duration::from_secs(age_days * 86400);
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_times(FileTimes::new().set_accessed(time).set_modified(time))
+        .unwrap();
+}
@@ -1,4 +1,4 @@
-use clap::{Args, Parser};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 /// Rusty Sweeper - A Linux disk usage management utility
@@ -19,12 +19,44 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
+    /// Subcommand to run (launches the TUI when omitted)
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Analyze disk usage of a directory
+    Scan(ScanArgs),
+
+    /// Find and clean build artifacts
+    Clean(CleanArgs),
+
+    /// Launch the interactive TUI
+    Tui(TuiArgs),
+
+    /// Find duplicate files and optionally replace them with links
+    #[command(visible_alias = "dupes")]
+    Duplicates(DuplicatesArgs),
+
+    /// List, restore or purge quarantined paths
+    Quarantine(QuarantineArgs),
+
+    /// Show the journal of past deletions
+    History(HistoryArgs),
+
+    /// List system resources and their items, and remove single items
+    Resources(ResourcesArgs),
 }
 
 /// Dedicated CLI for the monitor binary.
 #[derive(Parser, Debug)]
 #[command(name = "rusty-sweeper-monitor")]
-#[command(author, version, about = "Disk usage monitor daemon and alerting service")]
+#[command(
+    author,
+    version,
+    about = "Disk usage monitor daemon and alerting service"
+)]
 #[command(propagate_version = true)]
 pub struct MonitorCli {
     /// Path to configuration file
@@ -108,6 +140,10 @@ pub struct CleanArgs {
     #[arg(short, long, value_name = "DAYS")]
     pub age: Option<u32>,
 
+    /// Also find projects nested inside other projects
+    #[arg(long)]
+    pub nested: bool,
+
     /// Skip confirmation prompts
     #[arg(short, long)]
     pub force: bool,
@@ -119,6 +155,19 @@ pub struct CleanArgs {
     /// Only report sizes, don't clean
     #[arg(long)]
     pub size_only: bool,
+
+    /// Move artifacts into the quarantine instead of deleting them
+    #[arg(long)]
+    pub quarantine: bool,
+
+    /// Prune stale parts of Cargo target dirs (old toolchains, old
+    /// incremental sessions) instead of removing them
+    #[arg(long, conflicts_with = "quarantine")]
+    pub prune: bool,
+
+    /// With --prune, also remove target triples, profiles and units unused for N days
+    #[arg(long, value_name = "DAYS", requires = "prune")]
+    pub prune_days: Option<u32>,
 }
 
 #[derive(Args, Debug)]
@@ -147,15 +196,181 @@ pub struct ScanArgs {
     #[arg(short, long, value_name = "N")]
     pub jobs: Option<usize>,
 
+    /// Paths to exclude (glob patterns)
+    #[arg(short, long, value_name = "PATTERNS")]
+    pub exclude: Option<Vec<String>>,
+
     /// Output as JSON
     #[arg(long)]
     pub json: bool,
 
+    /// Output as a flat table instead of a tree
+    #[arg(long, conflicts_with = "json")]
+    pub table: bool,
+
+    /// Compare against a snapshot saved with --json (PATH may also be a snapshot)
+    #[arg(long, value_name = "SNAPSHOT")]
+    pub diff: Option<PathBuf>,
+
+    /// Summarize size by file category and extension instead of by directory
+    #[arg(long, conflicts_with_all = ["diff", "table"])]
+    pub by_type: bool,
+
     /// Sort by: size, name, mtime
     #[arg(long, default_value = "size", value_name = "BY")]
     pub sort: String,
 }
 
+#[derive(Args, Debug)]
+pub struct DuplicatesArgs {
+    /// Directory to search
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+
+    /// Ignore files smaller than SIZE (e.g. 4KB, 10MB)
+    #[arg(short = 's', long, default_value = "1", value_name = "SIZE", value_parser = parse_size_arg)]
+    pub min_size: u64,
+
+    /// Show top N groups by reclaimable size
+    #[arg(short = 'n', long, default_value = "20", value_name = "N")]
+    pub top: usize,
+
+    /// Include hidden files
+    #[arg(short, long)]
+    pub all: bool,
+
+    /// Don't cross filesystem boundaries
+    #[arg(short = 'x', long)]
+    pub one_file_system: bool,
+
+    /// Paths to exclude (glob patterns)
+    #[arg(short, long, value_name = "PATTERNS")]
+    pub exclude: Option<Vec<String>>,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Replace duplicates with links to the first copy: hardlink, reflink
+    #[arg(long, value_name = "MODE", value_parser = ["hardlink", "reflink"])]
+    pub link: Option<String>,
+
+    /// With --link, show what would be replaced without doing it
+    #[arg(long, requires = "link")]
+    pub dry_run: bool,
+}
+
+/// Parse a human-readable size argument (e.g. "10MB") into bytes
+fn parse_size_arg(s: &str) -> Result<u64, String> {
+    crate::scanner::parse_size(s).ok_or_else(|| format!("invalid size: {}", s))
+}
+
+#[derive(Args, Debug)]
+pub struct QuarantineArgs {
+    #[command(subcommand)]
+    pub action: QuarantineAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum QuarantineAction {
+    /// List quarantined paths
+    #[command(visible_alias = "ls")]
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Move quarantined paths back to where they came from
+    Restore {
+        /// Entry IDs, as shown by `quarantine list`
+        #[arg(required = true, value_name = "ID")]
+        ids: Vec<String>,
+    },
+
+    /// Permanently delete quarantined paths
+    Purge {
+        /// Only purge entries older than N days [default: quarantine.retention_days]
+        #[arg(long, value_name = "DAYS", conflicts_with = "all")]
+        older_than: Option<u32>,
+
+        /// Purge every entry regardless of age
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ResourcesArgs {
+    #[command(subcommand)]
+    pub action: ResourcesAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ResourcesAction {
+    /// List system resources, or the items of one (e.g. docker-images)
+    #[command(visible_alias = "ls")]
+    List {
+        /// Resource ID, as shown without arguments
+        #[arg(value_name = "RESOURCE")]
+        resource: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Remove single items of a resource
+    Rm {
+        /// Resource ID, as shown by `resources list`
+        #[arg(value_name = "RESOURCE")]
+        resource: String,
+
+        /// Item IDs or names, as shown by `resources list RESOURCE`
+        #[arg(required = true, value_name = "ITEM")]
+        items: Vec<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// Only show deletions at or after DATE (YYYY-MM-DD, or an age like 7d or 12h)
+    #[arg(long, value_name = "DATE", value_parser = parse_date_arg)]
+    pub since: Option<u64>,
+
+    /// Only show deletions before DATE (YYYY-MM-DD, or an age like 7d or 12h)
+    #[arg(long, value_name = "DATE", value_parser = parse_date_arg)]
+    pub until: Option<u64>,
+
+    /// Only show deletions at or below PATH
+    #[arg(short, long, value_name = "PATH")]
+    pub path: Option<PathBuf>,
+
+    /// Only show deletions of this project type or system category
+    #[arg(short = 't', long = "type", value_name = "TYPE")]
+    pub project_type: Option<String>,
+
+    /// Show only the N most recent deletions
+    #[arg(short = 'n', long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Parse a history date argument into seconds since the epoch
+fn parse_date_arg(s: &str) -> Result<u64, String> {
+    crate::journal::parse_date(s).ok_or_else(|| format!("invalid date: {}", s))
+}
+
+#[derive(Args, Debug)]
+pub struct TuiArgs {
+    /// Directory to explore
+    #[arg(default_value = "/")]
+    pub path: PathBuf,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +387,174 @@ mod tests {
         let cli = Cli::parse_from(["rusty-sweeper"]);
         assert!(cli.config.is_none());
         assert!(!cli.quiet);
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn parse_scan_subcommand() {
+        let cli = Cli::parse_from(["rusty-sweeper", "scan", "/tmp", "-d", "2", "--json"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.path, PathBuf::from("/tmp"));
+                assert_eq!(args.max_depth, 2);
+                assert!(args.json);
+                assert!(!args.table);
+            }
+            other => panic!("Expected scan command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_scan_exclude_patterns() {
+        let cli = Cli::parse_from([
+            "rusty-sweeper",
+            "scan",
+            "-e",
+            "**/.git",
+            "--exclude",
+            "*.log",
+        ]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(
+                    args.exclude,
+                    Some(vec!["**/.git".to_string(), "*.log".to_string()])
+                );
+            }
+            other => panic!("Expected scan command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_scan_diff() {
+        let cli = Cli::parse_from(["rusty-sweeper", "scan", "/data", "--diff", "old.json"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.diff, Some(PathBuf::from("old.json")));
+            }
+            other => panic!("Expected scan command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_scan_by_type() {
+        let cli = Cli::parse_from(["rusty-sweeper", "scan", "--by-type", "--json"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.by_type);
+                assert!(args.json);
+            }
+            other => panic!("Expected scan command, got {:?}", other),
+        }
+
+        let result = Cli::try_parse_from(["rusty-sweeper", "scan", "--by-type", "--table"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_duplicates_command() {
+        let cli = Cli::parse_from([
+            "rusty-sweeper",
+            "dupes",
+            "/data",
+            "--min-size",
+            "10MB",
+            "--link",
+            "reflink",
+            "--dry-run",
+        ]);
+        match cli.command {
+            Some(Commands::Duplicates(args)) => {
+                assert_eq!(args.path, PathBuf::from("/data"));
+                assert_eq!(args.min_size, 10 * 1024 * 1024);
+                assert_eq!(args.link.as_deref(), Some("reflink"));
+                assert!(args.dry_run);
+            }
+            other => panic!("Expected duplicates command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn duplicates_rejects_bad_arguments() {
+        assert!(
+            Cli::try_parse_from(["rusty-sweeper", "duplicates", "--min-size", "lots"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["rusty-sweeper", "duplicates", "--link", "symlink"]).is_err());
+        assert!(Cli::try_parse_from(["rusty-sweeper", "duplicates", "--dry-run"]).is_err());
+    }
+
+    #[test]
+    fn scan_json_conflicts_with_table() {
+        let result = Cli::try_parse_from(["rusty-sweeper", "scan", "--json", "--table"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_clean_subcommand() {
+        let cli = Cli::parse_from([
+            "rusty-sweeper",
+            "clean",
+            "/projects",
+            "--types",
+            "cargo,npm",
+            "--dry-run",
+        ]);
+        match cli.command {
+            Some(Commands::Clean(args)) => {
+                assert_eq!(args.path, PathBuf::from("/projects"));
+                assert_eq!(
+                    args.types,
+                    Some(vec!["cargo".to_string(), "npm".to_string()])
+                );
+                assert!(args.dry_run);
+                assert!(!args.force);
+            }
+            other => panic!("Expected clean command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_history_command() {
+        let cli = Cli::parse_from([
+            "rusty-sweeper",
+            "history",
+            "--since",
+            "2024-01-01",
+            "--type",
+            "cargo",
+            "-p",
+            "/src",
+            "-n",
+            "5",
+        ]);
+        match cli.command {
+            Some(Commands::History(args)) => {
+                assert!(args.since.is_some());
+                assert!(args.until.is_none());
+                assert_eq!(args.project_type.as_deref(), Some("cargo"));
+                assert_eq!(args.path, Some(PathBuf::from("/src")));
+                assert_eq!(args.limit, Some(5));
+            }
+            other => panic!("Expected history command, got {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["rusty-sweeper", "history", "--since", "soon"]).is_err());
+    }
+
+    #[test]
+    fn parse_tui_subcommand_default_path() {
+        let cli = Cli::parse_from(["rusty-sweeper", "tui"]);
+        match cli.command {
+            Some(Commands::Tui(args)) => assert_eq!(args.path, PathBuf::from("/")),
+            other => panic!("Expected tui command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn global_flags_after_subcommand() {
+        let cli = Cli::parse_from(["rusty-sweeper", "scan", "-q", "--config", "/tmp/c.toml"]);
+        assert!(cli.quiet);
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/c.toml")));
     }
 
     #[test]
@@ -1,18 +1,22 @@
 //! Clean command implementation.
 
 use crate::cleaner::{
-    all_valid_type_ids, CleanOptions, CleanOrchestrator, CleanProgress, CleanResult,
-    DetectedSystemResource, DetectorRegistry, ProjectScanner, ScanOptions, SystemCleanResult,
-    SystemCleanerRegistry,
+    all_valid_type_ids, CargoPrune, CleanOptions, CleanOrchestrator, CleanPolicy, CleanProgress,
+    CleanResult, DetectedProject, DetectedSystemResource, DetectorRegistry, PolicyVerdict,
+    ProjectScanner, ScanOptions, SystemCleanResult, SystemCleanerRegistry,
 };
 use crate::cli::CleanArgs;
+use crate::config::Config;
+use crate::journal::Journal;
+use crate::quarantine::Quarantine;
 use anyhow::Result;
 use humansize::{format_size, BINARY};
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Run the clean command.
-pub fn run(args: CleanArgs) -> Result<()> {
+pub fn run(args: CleanArgs, config: &Config) -> Result<()> {
     // Resolve to absolute path
     let path = args
         .path
@@ -28,31 +32,31 @@ pub fn run(args: CleanArgs) -> Result<()> {
     // Check if any requested types are system cleaner types
     let system_cleaner_ids: Vec<&str> = crate::cleaner::system_registry::all_system_cleaner_ids();
     let run_system_cleaners = match &requested_types {
-        None => true, // No filter: run everything
+        None => true, // No filter: run the default cleaners
         Some(types) => types.iter().any(|t| system_cleaner_ids.contains(t)),
     };
 
-    // Filter project types (exclude system cleaner IDs from detector registry)
-    let project_types: Option<Vec<&str>> = requested_types.as_ref().map(|types| {
-        types
-            .iter()
-            .filter(|t| !system_cleaner_ids.contains(t))
-            .copied()
-            .collect()
-    });
-
-    // Set up project detector registry
-    let registry = match &project_types {
-        Some(types) if !types.is_empty() => DetectorRegistry::with_types(types),
-        Some(_) => DetectorRegistry::with_types(&[]), // Only system types requested
-        None => DetectorRegistry::new(),
+    // Set up project detector registry, including custom types from the config.
+    // System cleaner IDs match no detector, except Go which is both.
+    let registry = DetectorRegistry::from_config(&config.project_types);
+    let registry = match &requested_types {
+        Some(types) => registry.retain_types(types), // Empty if only system types requested
+        None => registry,
     };
 
-    // Set up system cleaner registry
+    // Apply the cleanup policy; --age overrides the configured minimum age
+    let mut policy = CleanPolicy::from_config(&config.cleaner.policy);
+    if let Some(age_days) = args.age {
+        policy = policy.with_min_age_days(age_days);
+    }
+
+    // Set up system cleaner registry; package caches follow the policy's age rules.
+    // Without --types only the default cleaners run; the rest are opt-in.
     let system_registry = if run_system_cleaners {
+        let registry = SystemCleanerRegistry::with_policy(&policy);
         match &requested_types {
-            Some(types) => SystemCleanerRegistry::with_types(types),
-            None => SystemCleanerRegistry::new(),
+            Some(types) => registry.retain_types(types),
+            None => registry.retain_defaults(),
         }
     } else {
         SystemCleanerRegistry::with_types(&[])
@@ -65,13 +69,17 @@ pub fn run(args: CleanArgs) -> Result<()> {
                 "Error: No valid types found. Requested: {}",
                 types.join(", ")
             );
-            eprintln!("Valid types: {}", all_valid_type_ids().join(", "));
+            eprintln!(
+                "Valid types: {}",
+                all_valid_type_ids(&config.project_types).join(", ")
+            );
             std::process::exit(2);
         }
     }
 
     // Set up scanner options
-    let mut exclude_patterns = vec![".git".to_string()];
+    // --exclude adds to the configured patterns
+    let mut exclude_patterns = config.cleaner.exclude_patterns.clone();
     if let Some(excludes) = &args.exclude {
         exclude_patterns.extend(excludes.iter().cloned());
     }
@@ -80,27 +88,34 @@ pub fn run(args: CleanArgs) -> Result<()> {
         max_depth: args.max_depth,
         exclude_patterns,
         follow_symlinks: false,
+        check_git: policy.checks_git(),
+        nested: args.nested,
     };
 
     let scanner = ProjectScanner::new(registry.clone(), scan_options);
 
     // Scan for projects
     println!("Scanning for projects in {}...", path.display());
-    let mut projects = scanner.scan(&path);
+    let found = scanner.scan(&path);
 
-    // Apply age filter if specified
-    if let Some(age_days) = args.age {
-        let before_count = projects.len();
-        projects = ProjectScanner::filter_by_age(projects, age_days as u64);
-        if before_count > 0 && projects.is_empty() {
-            println!(
-                "Found {} project(s), but none older than {} days.",
-                before_count, age_days
-            );
-            // Don't return early if we also have system resources to check
-            if !run_system_cleaners {
-                return Ok(());
-            }
+    // Apply the cleanup policy
+    let show_rules = !policy.is_empty();
+    let (included, skipped): (Vec<PolicyVerdict>, Vec<PolicyVerdict>) = policy
+        .evaluate(found)
+        .into_iter()
+        .partition(PolicyVerdict::is_included);
+
+    if !skipped.is_empty() {
+        print_skipped_table(&skipped);
+    }
+    if included.is_empty() && !skipped.is_empty() {
+        println!(
+            "\nFound {} project(s), but all were skipped by the cleanup policy.",
+            skipped.len()
+        );
+        // Don't return early if we also have system resources to check
+        if !run_system_cleaners {
+            return Ok(());
         }
     }
 
@@ -112,14 +127,26 @@ pub fn run(args: CleanArgs) -> Result<()> {
     };
 
     // Check if there's anything to do
-    if projects.is_empty() && system_resources.is_empty() {
+    if included.is_empty() && system_resources.is_empty() {
         println!("No cleanable artifacts found.");
         return Ok(());
     }
 
     // Display found projects
-    if !projects.is_empty() {
-        print_projects_table(&projects);
+    if !included.is_empty() {
+        print_projects_table(&included, &path, show_rules);
+    }
+
+    // Pruning removes only part of each Cargo target dir
+    let cargo_prune = args.prune.then_some(CargoPrune {
+        unused_days: args.prune_days,
+        old_toolchains: true,
+        old_incremental: true,
+    });
+    let mut project_size: u64 = included.iter().map(|v| v.project.artifact_size).sum();
+    if let Some(prune) = &cargo_prune {
+        let (target_size, pruned_size) = print_prune_plans(&included, prune);
+        project_size = project_size - target_size + pruned_size;
     }
 
     // Display system resources
@@ -127,11 +154,10 @@ pub fn run(args: CleanArgs) -> Result<()> {
         print_system_resources_table(&system_resources);
     }
 
-    let project_size: u64 = projects.iter().map(|p| p.artifact_size).sum();
     let system_size: u64 = system_resources.iter().map(|r| r.size).sum();
     let total_size = project_size + system_size;
 
-    let total_items = projects.len() + system_resources.len();
+    let total_items = included.len() + system_resources.len();
     println!(
         "\nTotal: {} in {} item{}",
         format_size(total_size, BINARY),
@@ -157,10 +183,42 @@ pub fn run(args: CleanArgs) -> Result<()> {
         }
     }
 
+    // Projects with unsaved git work need their own confirmation under
+    // `dirty_repos = "confirm"`, and so do artifacts outside the scanned
+    // path (e.g. a member's workspace target); --force never cleans them
+    let mut projects = Vec::new();
+    for verdict in included {
+        if !args.dry_run && !confirm_outside(&verdict.project, &path, args.force)? {
+            continue;
+        }
+        if verdict.needs_confirmation() && !args.dry_run && !confirm_dirty(&verdict, args.force)? {
+            continue;
+        }
+        projects.push(verdict.project);
+    }
+
     // Execute cleanup
+    let quarantine = if (args.quarantine || config.quarantine.enabled) && !args.dry_run {
+        Some(Arc::new(Quarantine::open()?))
+    } else {
+        None
+    };
+    let expiring = quarantine.clone();
+    let quarantined = quarantine.is_some();
+    // A missing journal shouldn't block cleaning; deletions just go unrecorded
+    let journal = match Journal::open() {
+        Ok(journal) => Some(Arc::new(journal)),
+        Err(e) => {
+            tracing::warn!(error = %e, "Cleanup journal unavailable");
+            None
+        }
+    };
     let clean_options = CleanOptions {
         dry_run: args.dry_run,
         use_native_commands: true,
+        quarantine,
+        journal: journal.clone(),
+        cargo_prune: cargo_prune.clone(),
     };
     let orchestrator = CleanOrchestrator::new(registry, clean_options, args.jobs);
 
@@ -180,7 +238,7 @@ pub fn run(args: CleanArgs) -> Result<()> {
     let mut system_results = Vec::new();
     for resource in &system_resources {
         if let Some(cleaner) = system_registry.get_cleaner(&resource.category) {
-            let result = cleaner.clean(resource, args.dry_run);
+            let result = cleaner.clean_recorded(resource, args.dry_run, journal.as_deref());
             summary.add_system_result(&result);
             system_results.push(result);
         }
@@ -207,7 +265,50 @@ pub fn run(args: CleanArgs) -> Result<()> {
             if summary.skipped_count == 1 { "" } else { "s" }
         );
     }
-    println!("  Freed:   {}", format_size(summary.total_freed, BINARY));
+    if quarantined {
+        // System cleaners always free space directly
+        let moved: u64 = results
+            .iter()
+            .filter_map(|r| match r {
+                CleanResult::Success { freed_bytes, .. } => Some(*freed_bytes),
+                _ => None,
+            })
+            .sum();
+        println!(
+            "  Freed:   {}",
+            format_size(summary.total_freed - moved, BINARY)
+        );
+        println!(
+            "  Quarantined: {} (see `rusty-sweeper quarantine list`)",
+            format_size(moved, BINARY)
+        );
+    } else {
+        println!("  Freed:   {}", format_size(summary.total_freed, BINARY));
+    }
+    // Entries past the retention period go once the new ones are in
+    if let Some(quarantine) = expiring {
+        match quarantine.purge_expired(config.quarantine.retention_days) {
+            Ok(expired) if !expired.purged.is_empty() => println!(
+                "  Expired: {} purged from the quarantine ({} entr{})",
+                format_size(expired.freed, BINARY),
+                expired.purged.len(),
+                if expired.purged.len() == 1 {
+                    "y"
+                } else {
+                    "ies"
+                }
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to purge expired quarantine entries"),
+        }
+    }
+    // The pre-scan counts apparent sizes; show it when the measurement differs
+    if !args.dry_run && summary.total_estimated != summary.total_freed {
+        println!(
+            "  Estimated: {}",
+            format_size(summary.total_estimated, BINARY)
+        );
+    }
 
     // Print project failures
     for result in &results {
@@ -216,10 +317,16 @@ pub fn run(args: CleanArgs) -> Result<()> {
         }
     }
 
-    // Print system failures
+    // Print system failures and skips
     for result in &system_results {
-        if let SystemCleanResult::Failed { resource, error } = result {
-            eprintln!("  Error cleaning {}: {}", resource.display_name, error);
+        match result {
+            SystemCleanResult::Failed { resource, error } => {
+                eprintln!("  Error cleaning {}: {}", resource.display_name, error);
+            }
+            SystemCleanResult::Skipped { resource, reason } => {
+                println!("  Skipped {}: {}", resource.display_name, reason);
+            }
+            SystemCleanResult::Success { .. } => {}
         }
     }
 
@@ -230,24 +337,196 @@ pub fn run(args: CleanArgs) -> Result<()> {
     Ok(())
 }
 
-fn print_projects_table(projects: &[crate::cleaner::DetectedProject]) {
-    println!("\n  {:<10} {:<50} {:>10}", "TYPE", "PATH", "SIZE");
-    println!("  {}", "─".repeat(72));
+fn truncate_path(path: &std::path::Path) -> String {
+    let path_str = path.display().to_string();
+    if path_str.len() > 48 {
+        format!("...{}", &path_str[path_str.len() - 45..])
+    } else {
+        path_str
+    }
+}
 
-    for project in projects {
-        let path_str = project.path.display().to_string();
-        let path_display = if path_str.len() > 48 {
-            format!("...{}", &path_str[path_str.len() - 45..])
-        } else {
-            path_str
-        };
+/// The path column for a project, marking projects nested in another one.
+fn project_path(project: &DetectedProject) -> String {
+    match project.parent {
+        Some(_) => format!("↳ {}", truncate_path(&project.path)),
+        None => truncate_path(&project.path),
+    }
+}
 
+/// Artifact paths of `project` that lie outside the scanned `root`.
+fn outside_root<'a>(project: &'a DetectedProject, root: &Path) -> Vec<&'a Path> {
+    project
+        .artifact_paths
+        .iter()
+        .filter(|p| !p.starts_with(root))
+        .map(PathBuf::as_path)
+        .collect()
+}
+
+/// Ask whether to clean a project whose artifacts reach outside the scanned
+/// path. Projects entirely inside it need no confirmation.
+fn confirm_outside(project: &DetectedProject, root: &Path, force: bool) -> Result<bool> {
+    let outside = outside_root(project, root);
+    if outside.is_empty() {
+        return Ok(true);
+    }
+    let paths = outside
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if force {
+        println!(
+            "Skipping {} ({} is outside {}; needs confirmation)",
+            project.path.display(),
+            paths,
+            root.display()
+        );
+        return Ok(false);
+    }
+
+    print!(
+        "{} would clean {}, outside {}. Clean anyway? [y/N] ",
+        project.path.display(),
+        paths,
+        root.display()
+    );
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Ask whether to clean a project whose repository holds unsaved work.
+fn confirm_dirty(verdict: &PolicyVerdict, force: bool) -> Result<bool> {
+    let project = &verdict.project;
+    let state = project
+        .git
+        .as_ref()
+        .map(|g| g.to_string())
+        .unwrap_or_default();
+
+    if force {
         println!(
+            "Skipping {} (git: {}; needs confirmation)",
+            project.path.display(),
+            state
+        );
+        return Ok(false);
+    }
+
+    print!(
+        "{} has unsaved git work ({}). Clean anyway? [y/N] ",
+        project.path.display(),
+        state
+    );
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+fn print_projects_table(verdicts: &[PolicyVerdict], root: &Path, show_rules: bool) {
+    // Only show the git column if some project is in a repository
+    let show_git = verdicts.iter().any(|v| v.project.git.is_some());
+
+    let mut header = format!("\n  {:<10} {:<50} {:>10}", "TYPE", "PATH", "SIZE");
+    if show_git {
+        header.push_str(&format!("  {:<20}", "GIT"));
+    }
+    if show_rules {
+        header.push_str("  RULE");
+    }
+    println!("{}", header.trim_end());
+    println!("  {}", "─".repeat(72));
+
+    for verdict in verdicts {
+        let project = &verdict.project;
+        let mut line = format!(
             "  {:<10} {:<50} {:>10}",
-            project.project_type,
-            path_display,
+            project.type_label(),
+            project_path(project),
             format_size(project.artifact_size, BINARY),
         );
+        if show_git {
+            let git = project
+                .git
+                .as_ref()
+                .map(|g| g.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            line.push_str(&format!("  {:<20}", git));
+        }
+        if show_rules {
+            line.push_str(&format!("  {}", verdict.rule));
+        }
+        println!("{}", line.trim_end());
+        for owner in &project.shared_with {
+            println!("  {:<10} (shared with {})", "", truncate_path(owner));
+        }
+        for artifact in outside_root(project, root) {
+            println!(
+                "  {:<10} (outside scan root: {})",
+                "",
+                truncate_path(artifact)
+            );
+        }
+    }
+}
+
+/// Print what pruning would remove from each Cargo project's target dirs.
+/// Returns the size of those target dirs and of the parts to prune.
+fn print_prune_plans(verdicts: &[PolicyVerdict], prune: &CargoPrune) -> (u64, u64) {
+    let mut target_size = 0;
+    let mut pruned_size = 0;
+
+    println!("\n  Cargo target pruning:");
+    println!("  {:<30} {:<30} {:>10}", "PROFILE", "STALE", "SIZE");
+    println!("  {}", "─".repeat(72));
+
+    for verdict in verdicts {
+        for group in verdict.project.groups() {
+            if group.project_type != "cargo" {
+                continue;
+            }
+            target_size += group.artifact_size;
+            for target in &group.artifact_paths {
+                let plan = prune.plan(target);
+                pruned_size += plan.size();
+                println!("  {}", truncate_path(target));
+                if plan.is_empty() {
+                    println!("    nothing to prune");
+                }
+                for summary in plan.summary() {
+                    println!(
+                        "    {:<28} {:<30} {:>10}",
+                        summary.scope,
+                        summary.description,
+                        format_size(summary.size, BINARY),
+                    );
+                }
+            }
+        }
+    }
+
+    (target_size, pruned_size)
+}
+
+fn print_skipped_table(verdicts: &[PolicyVerdict]) {
+    println!("\n  Skipped by policy:");
+    println!("  {:<10} {:<50} REASON", "TYPE", "PATH");
+    println!("  {}", "─".repeat(72));
+
+    for verdict in verdicts {
+        println!(
+            "  {:<10} {:<50} {}",
+            verdict.project.type_label(),
+            project_path(&verdict.project),
+            verdict.rule,
+        );
     }
 }
 
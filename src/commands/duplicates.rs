@@ -0,0 +1,106 @@
+//! Duplicates command implementation
+
+use crate::cli::DuplicatesArgs;
+use crate::error::{Result, SweeperError};
+use crate::scanner::{
+    find_duplicates, format_duplicates_json, format_duplicates_table, format_size,
+    scan_directory_parallel, DuplicateOptions, DuplicateReport, FormatOptions, LinkMode,
+    ScanOptions,
+};
+
+use super::scan::create_spinner;
+
+/// Run the duplicates command
+pub fn run(args: DuplicatesArgs) -> Result<()> {
+    // clap restricts --link to valid modes
+    let link_mode = args.link.as_deref().and_then(LinkMode::parse);
+
+    let scan_options = ScanOptions::new()
+        .with_hidden(args.all)
+        .with_one_file_system(args.one_file_system)
+        .with_exclude(args.exclude.clone().unwrap_or_default());
+
+    let spinner = if !args.json {
+        create_spinner(&args.path)
+    } else {
+        None
+    };
+    let tree = scan_directory_parallel(&args.path, &scan_options)?;
+    if let Some(pb) = &spinner {
+        pb.set_message("Comparing file contents...");
+    }
+    let report = find_duplicates(&tree, &DuplicateOptions::new().with_min_size(args.min_size));
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+
+    match link_mode {
+        Some(mode) => replace(&args, &report, mode),
+        None => {
+            print_report(&args, &report)?;
+            Ok(())
+        }
+    }
+}
+
+fn print_report(args: &DuplicatesArgs, report: &DuplicateReport) -> Result<()> {
+    if args.json {
+        println!("{}", format_duplicates_json(report, true)?);
+        return Ok(());
+    }
+
+    if report.groups.is_empty() {
+        println!("No duplicate files found.");
+        return Ok(());
+    }
+
+    let format_options = FormatOptions::new().with_top_n(args.top);
+    println!("{}", format_duplicates_table(report, &format_options));
+    println!(
+        "Total: {} reclaimable in {} duplicate files ({} groups)",
+        format_size(report.reclaimable()),
+        report.duplicate_files(),
+        report.groups.len()
+    );
+    if report.hardlinks_skipped > 0 {
+        println!(
+            "Skipped {} paths already hard-linked to another copy",
+            report.hardlinks_skipped
+        );
+    }
+    Ok(())
+}
+
+fn replace(args: &DuplicatesArgs, report: &DuplicateReport, mode: LinkMode) -> Result<()> {
+    if args.dry_run {
+        print_report(args, report)?;
+        if !args.json && !report.groups.is_empty() {
+            println!("Dry run: the first file of each group would be kept, the others replaced");
+        }
+        return Ok(());
+    }
+
+    let summary = report.replace_with_links(mode);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        println!(
+            "Replaced {} duplicates, freeing {}",
+            summary.replaced,
+            format_size(summary.bytes)
+        );
+        for failure in &summary.failed {
+            eprintln!("  {}: {}", failure.path.display(), failure.error);
+        }
+    }
+
+    if summary.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(SweeperError::Other(format!(
+            "{} duplicates could not be replaced",
+            summary.failed.len()
+        )))
+    }
+}
@@ -0,0 +1,80 @@
+//! History command implementation
+
+use crate::cli::HistoryArgs;
+use crate::error::Result;
+use crate::journal::{format_timestamp, HistoryFilter, Journal};
+use crate::scanner::format_size;
+
+/// Run the history command
+pub fn run(args: HistoryArgs) -> Result<()> {
+    let journal = Journal::open()?;
+
+    // Cleaners record canonical paths; a deleted path can only be made absolute
+    let path = args.path.map(|p| {
+        p.canonicalize()
+            .or_else(|_| std::path::absolute(&p))
+            .unwrap_or(p)
+    });
+    let filter = HistoryFilter {
+        since: args.since,
+        until: args.until,
+        path,
+        project_type: args.project_type,
+    };
+
+    let mut records = journal.query(&filter)?;
+    if let Some(limit) = args.limit {
+        records.drain(..records.len().saturating_sub(limit));
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&records)?);
+        return Ok(());
+    }
+
+    if records.is_empty() {
+        println!("No deletions recorded.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<19} {:<10} {:<10} {:<10} {:>10} PATH",
+        "TIME", "USER", "TYPE", "METHOD", "FREED"
+    );
+    for record in &records {
+        let freed = match &record.error {
+            Some(_) => "failed".to_string(),
+            None => format_size(record.freed_bytes),
+        };
+        println!(
+            "{:<19} {:<10} {:<10} {:<10} {:>10} {}",
+            format_timestamp(record.timestamp),
+            record.user,
+            record.project_type.as_deref().unwrap_or("-"),
+            record.method,
+            freed,
+            record.path.display()
+        );
+        if let Some(error) = &record.error {
+            println!("    error: {}", error);
+        }
+    }
+
+    let freed: u64 = records
+        .iter()
+        .filter(|r| r.error.is_none())
+        .map(|r| r.freed_bytes)
+        .sum();
+    let failed = records.iter().filter(|r| r.error.is_some()).count();
+    print!(
+        "\nTotal: {} freed in {} record{}",
+        format_size(freed),
+        records.len(),
+        if records.len() == 1 { "" } else { "s" }
+    );
+    if failed > 0 {
+        print!(" ({} failed)", failed);
+    }
+    println!();
+    Ok(())
+}
@@ -1,3 +1,8 @@
 pub mod clean;
+pub mod duplicates;
+pub mod history;
 pub mod monitor;
+pub mod quarantine;
+pub mod resources;
 pub mod scan;
+pub mod tui;
@@ -0,0 +1,97 @@
+//! Quarantine command implementation
+
+use std::time::{Duration, SystemTime};
+
+use crate::cli::{QuarantineAction, QuarantineArgs};
+use crate::config::Config;
+use crate::error::Result;
+use crate::quarantine::{Quarantine, QuarantineEntry, StorageKind};
+use crate::scanner::format_size;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Run the quarantine command
+pub fn run(args: QuarantineArgs, config: &Config) -> Result<()> {
+    let quarantine = Quarantine::open()?;
+
+    match args.action {
+        QuarantineAction::List { json } => list(&quarantine, json),
+        QuarantineAction::Restore { ids } => {
+            for id in ids {
+                let entry = quarantine.restore(&id)?;
+                println!("Restored {}", entry.original_path.display());
+            }
+            Ok(())
+        }
+        QuarantineAction::Purge { older_than, all } => {
+            let age = if all {
+                Duration::ZERO
+            } else {
+                let days = older_than.unwrap_or(config.quarantine.retention_days);
+                Duration::from_secs(u64::from(days) * DAY)
+            };
+            let summary = quarantine.purge_older_than(age)?;
+            println!(
+                "Purged {} entr{}, freed {}",
+                summary.purged.len(),
+                if summary.purged.len() == 1 {
+                    "y"
+                } else {
+                    "ies"
+                },
+                format_size(summary.freed)
+            );
+            Ok(())
+        }
+    }
+}
+
+fn list(quarantine: &Quarantine, json: bool) -> Result<()> {
+    let entries = quarantine.list()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("Quarantine is empty.");
+        return Ok(());
+    }
+
+    let now = SystemTime::now();
+    println!(
+        "{:<24} {:>5} {:>10} {:<7} PATH",
+        "ID", "AGE", "SIZE", "STORAGE"
+    );
+    for entry in &entries {
+        println!(
+            "{:<24} {:>5} {:>10} {:<7} {}",
+            entry.id,
+            format_age(entry, now),
+            format_size(entry.size),
+            match entry.storage {
+                StorageKind::Holding => "holding",
+                StorageKind::Trash => "trash",
+            },
+            entry.original_path.display()
+        );
+    }
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    println!(
+        "\nTotal: {} in {} entr{}",
+        format_size(total),
+        entries.len(),
+        if entries.len() == 1 { "y" } else { "ies" }
+    );
+    Ok(())
+}
+
+/// Format an entry's age as whole days, or hours/minutes when under a day.
+fn format_age(entry: &QuarantineEntry, now: SystemTime) -> String {
+    let secs = entry.age(now).as_secs();
+    match secs {
+        0..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / DAY),
+    }
+}
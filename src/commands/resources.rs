@@ -0,0 +1,182 @@
+//! Resources command implementation
+
+use std::time::UNIX_EPOCH;
+
+use crate::cleaner::{
+    CleanPolicy, DetectedSystemResource, SystemCleanResult, SystemCleaner, SystemCleanerRegistry,
+    SystemResourceItem,
+};
+use crate::cli::{ResourcesAction, ResourcesArgs};
+use crate::config::Config;
+use crate::error::{Result, SweeperError};
+use crate::journal::{format_timestamp, Journal};
+use crate::scanner::format_size;
+
+/// Run the resources command
+pub fn run(args: ResourcesArgs, config: &Config) -> Result<()> {
+    let registry =
+        SystemCleanerRegistry::with_policy(&CleanPolicy::from_config(&config.cleaner.policy));
+
+    match args.action {
+        ResourcesAction::List {
+            resource: None,
+            json,
+        } => list_resources(&registry.detect_all(), json),
+        ResourcesAction::List {
+            resource: Some(id),
+            json,
+        } => {
+            let (cleaner, resource) = find_resource(&registry, &id)?;
+            list_items(&cleaner.list_items(&resource), json)
+        }
+        ResourcesAction::Rm { resource, items } => {
+            let (cleaner, resource) = find_resource(&registry, &resource)?;
+            remove_items(cleaner, &resource, &items)
+        }
+    }
+}
+
+/// Detect the resource with the given id and return it with its cleaner.
+fn find_resource<'a>(
+    registry: &'a SystemCleanerRegistry,
+    id: &str,
+) -> Result<(&'a dyn SystemCleaner, DetectedSystemResource)> {
+    registry
+        .detect_all()
+        .into_iter()
+        .find(|r| r.resource_id == id)
+        .and_then(|r| Some((registry.get_cleaner(&r.category)?, r)))
+        .ok_or_else(|| SweeperError::NotFound(format!("system resource '{}'", id)))
+}
+
+fn list_resources(resources: &[DetectedSystemResource], json: bool) -> Result<()> {
+    if json {
+        let values: Vec<serde_json::Value> = resources
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "id": r.resource_id,
+                    "name": r.display_name,
+                    "category": r.category,
+                    "size": r.size,
+                    "items": r.item_count,
+                    "description": r.description,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&values)?);
+        return Ok(());
+    }
+
+    if resources.is_empty() {
+        println!("No system resources found.");
+        return Ok(());
+    }
+
+    println!("{:<24} {:<28} {:>10} {:>7}", "ID", "NAME", "SIZE", "ITEMS");
+    for resource in resources {
+        println!(
+            "{:<24} {:<28} {:>10} {:>7}",
+            resource.resource_id,
+            resource.display_name,
+            format_size(resource.size),
+            resource
+                .item_count
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+    Ok(())
+}
+
+fn list_items(items: &[SystemResourceItem], json: bool) -> Result<()> {
+    let last_used_secs = |item: &SystemResourceItem| {
+        item.last_used
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+    };
+
+    if json {
+        let values: Vec<serde_json::Value> = items
+            .iter()
+            .map(|item| {
+                serde_json::json!({
+                    "id": item.id,
+                    "name": item.name,
+                    "size": item.size,
+                    "last_used": last_used_secs(item),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&values)?);
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        println!("No items found.");
+        return Ok(());
+    }
+
+    println!("{:<14} {:>10} {:<19} NAME", "ID", "SIZE", "LAST USED");
+    for item in items {
+        println!(
+            "{:<14} {:>10} {:<19} {}",
+            item.id,
+            format_size(item.size),
+            last_used_secs(item)
+                .map(format_timestamp)
+                .unwrap_or_else(|| "-".to_string()),
+            item.name
+        );
+    }
+    let total: u64 = items.iter().map(|i| i.size).sum();
+    println!(
+        "\nTotal: {} in {} item{}",
+        format_size(total),
+        items.len(),
+        if items.len() == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
+
+fn remove_items(
+    cleaner: &dyn SystemCleaner,
+    resource: &DetectedSystemResource,
+    wanted: &[String],
+) -> Result<()> {
+    let items = cleaner.list_items(resource);
+    let journal = Journal::open().ok();
+    let mut failed = 0;
+
+    for query in wanted {
+        // Match by ID, ID prefix or name
+        let Some(item) = items
+            .iter()
+            .find(|i| i.id == *query || i.name == *query)
+            .or_else(|| items.iter().find(|i| i.id.starts_with(query.as_str())))
+        else {
+            eprintln!("  No item '{}' in {}", query, resource.resource_id);
+            failed += 1;
+            continue;
+        };
+
+        match cleaner.remove_item_recorded(resource, item, journal.as_ref()) {
+            SystemCleanResult::Success { freed_bytes, .. } => {
+                println!("Removed {} ({})", item.name, format_size(freed_bytes));
+            }
+            SystemCleanResult::Failed { error, .. } => {
+                eprintln!("  Error removing {}: {}", item.name, error);
+                failed += 1;
+            }
+            SystemCleanResult::Skipped { reason, .. } => {
+                eprintln!("  Skipped {}: {}", item.name, reason);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        std::process::exit(5); // Partial failure
+    }
+    Ok(())
+}
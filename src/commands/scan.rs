@@ -1,6 +1,7 @@
 //! Scan command implementation
 
 use std::io::IsTerminal;
+use std::path::Path;
 use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
@@ -8,7 +9,9 @@ use indicatif::{ProgressBar, ProgressStyle};
 use crate::cli::ScanArgs;
 use crate::error::Result;
 use crate::scanner::{
-    format_json, format_table, format_tree, scan_directory_parallel, FormatOptions, ScanOptions,
+    format_breakdown_json, format_breakdown_table, format_delta, format_diff_json,
+    format_diff_table, format_diff_tree, format_json, format_size, format_table, format_tree,
+    read_snapshot, scan_directory_parallel, DirEntry, FormatOptions, ScanOptions,
 };
 
 /// Sort order for entries
@@ -30,7 +33,7 @@ impl SortOrder {
 }
 
 /// Create a progress spinner for scanning
-fn create_spinner(path: &std::path::Path) -> Option<ProgressBar> {
+pub fn create_spinner(path: &std::path::Path) -> Option<ProgressBar> {
     // Only show spinner if stderr is a terminal and we're not in JSON mode
     if !std::io::stderr().is_terminal() {
         return None;
@@ -47,16 +50,31 @@ fn create_spinner(path: &std::path::Path) -> Option<ProgressBar> {
     Some(pb)
 }
 
-/// Run the scan command
-pub fn run(args: ScanArgs) -> Result<()> {
-    // Build scan options
+/// Build scanner options from command arguments
+fn scan_options(args: &ScanArgs) -> ScanOptions {
     // Scan deeper than display depth to get accurate totals
     let scan_depth = args.max_depth.saturating_add(10);
-    let scan_options = ScanOptions::new()
+    ScanOptions::new()
         .with_max_depth(scan_depth)
         .with_hidden(args.all)
         .with_one_file_system(args.one_file_system)
-        .with_threads(args.jobs.unwrap_or(0));
+        .with_threads(args.jobs.unwrap_or(0))
+        .with_exclude(args.exclude.clone().unwrap_or_default())
+}
+
+/// Run the scan command
+pub fn run(args: ScanArgs) -> Result<()> {
+    if let Some(snapshot) = &args.diff {
+        return run_diff(&args, snapshot);
+    }
+    if args.by_type {
+        return run_breakdown(&args);
+    }
+    if args.table {
+        return run_table(args);
+    }
+
+    let scan_options = scan_options(&args);
 
     tracing::info!(path = %args.path.display(), "Scanning directory");
 
@@ -100,7 +118,7 @@ pub fn run(args: ScanArgs) -> Result<()> {
         println!();
         println!(
             "Total: {} in {} files, {} directories",
-            crate::scanner::format_size(entry.size),
+            format_size(entry.size),
             entry.file_count,
             entry.dir_count
         );
@@ -111,12 +129,7 @@ pub fn run(args: ScanArgs) -> Result<()> {
 
 /// Run scan with table output
 pub fn run_table(args: ScanArgs) -> Result<()> {
-    let scan_depth = args.max_depth.saturating_add(10);
-    let scan_options = ScanOptions::new()
-        .with_max_depth(scan_depth)
-        .with_hidden(args.all)
-        .with_one_file_system(args.one_file_system)
-        .with_threads(args.jobs.unwrap_or(0));
+    let scan_options = scan_options(&args);
 
     let mut entry = scan_directory_parallel(&args.path, &scan_options)?;
 
@@ -136,6 +149,89 @@ pub fn run_table(args: ScanArgs) -> Result<()> {
     Ok(())
 }
 
+/// Run scan and summarize it by file category and extension
+pub fn run_breakdown(args: &ScanArgs) -> Result<()> {
+    // Every file counts, so don't stop at the display depth
+    let mut scan_options = scan_options(args);
+    scan_options.max_depth = None;
+
+    let spinner = if !args.json {
+        create_spinner(&args.path)
+    } else {
+        None
+    };
+    let entry = scan_directory_parallel(&args.path, &scan_options)?;
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+
+    let breakdown = entry.type_breakdown();
+    if args.json {
+        println!("{}", format_breakdown_json(&breakdown, true)?);
+    } else {
+        let format_options = FormatOptions::new().with_top_n(args.top);
+        println!("{}", format_breakdown_table(&breakdown, &format_options));
+    }
+
+    Ok(())
+}
+
+/// Run scan and compare the result against a saved snapshot
+pub fn run_diff(args: &ScanArgs, snapshot: &Path) -> Result<()> {
+    let old = read_snapshot(snapshot)?;
+    let new = current_tree(args)?;
+    let diff = old.diff(&new);
+
+    let format_options = FormatOptions::new()
+        .with_max_depth(args.max_depth)
+        .with_top_n(args.top);
+
+    if args.json {
+        println!("{}", format_diff_json(&diff, true)?);
+        return Ok(());
+    }
+
+    let output = if args.table {
+        format_diff_table(&diff, &format_options)
+    } else {
+        format_diff_tree(&diff, &format_options)
+    };
+    println!("{}", output);
+
+    let percent = diff
+        .root
+        .percent
+        .map(|p| format!(", {:+.1}%", p))
+        .unwrap_or_default();
+    println!(
+        "Total: {} -> {} ({}{})",
+        format_size(diff.root.old_size),
+        format_size(diff.root.new_size),
+        format_delta(diff.root.delta),
+        percent
+    );
+
+    Ok(())
+}
+
+/// The newer side of a diff: a second snapshot, or a fresh scan of PATH
+fn current_tree(args: &ScanArgs) -> Result<DirEntry> {
+    if args.path.is_file() {
+        return read_snapshot(&args.path);
+    }
+
+    let spinner = if !args.json {
+        create_spinner(&args.path)
+    } else {
+        None
+    };
+    let entry = scan_directory_parallel(&args.path, &scan_options(args));
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+    entry
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,55 @@
+//! TUI command implementation.
+
+use crate::cli::TuiArgs;
+use crate::config::Config;
+use crate::error::SweeperError;
+
+/// Launch the interactive TUI rooted at the requested path.
+pub fn run(args: TuiArgs, config: &Config) -> anyhow::Result<()> {
+    let root = args.path;
+
+    if !root.exists() {
+        return Err(SweeperError::PathNotFound(root).into());
+    }
+    if !root.is_dir() {
+        return Err(SweeperError::NotADirectory(root).into());
+    }
+
+    tracing::info!(path = %root.display(), "Starting TUI");
+    crate::tui::run(root, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_run_rejects_missing_path() {
+        let err = run(
+            TuiArgs {
+                path: PathBuf::from("/nonexistent/path/12345"),
+            },
+            &Config::default(),
+        )
+        .unwrap_err();
+
+        let sweeper_err = err.downcast_ref::<SweeperError>().unwrap();
+        assert!(matches!(sweeper_err, SweeperError::PathNotFound(_)));
+    }
+
+    #[test]
+    fn test_run_rejects_file_path() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let err = run(
+            TuiArgs {
+                path: tmp.path().to_path_buf(),
+            },
+            &Config::default(),
+        )
+        .unwrap_err();
+
+        let sweeper_err = err.downcast_ref::<SweeperError>().unwrap();
+        assert!(matches!(sweeper_err, SweeperError::NotADirectory(_)));
+    }
+}
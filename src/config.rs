@@ -1,5 +1,6 @@
 use crate::error::{ConfigError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Root configuration structure
@@ -10,6 +11,10 @@ pub struct Config {
     pub cleaner: CleanerConfig,
     pub scanner: ScannerConfig,
     pub tui: TuiConfig,
+    pub quarantine: QuarantineConfig,
+    /// Custom project types and overrides of built-in ones, keyed by type id
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub project_types: BTreeMap<String, ProjectTypeConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,12 +39,79 @@ pub struct CleanerConfig {
     pub project_types: Vec<String>,
     /// Glob patterns to exclude
     pub exclude_patterns: Vec<String>,
-    /// Minimum age in days before cleanup
-    pub min_age_days: u32,
+    /// Deprecated alias for `policy.min_age_days`, which it sets when the
+    /// policy doesn't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_age_days: Option<u32>,
     /// Maximum scan depth
     pub max_depth: usize,
     /// Parallel clean jobs
     pub parallel_jobs: usize,
+    /// Rules deciding which detected projects may be cleaned
+    pub policy: PolicyConfig,
+}
+
+/// Cleanup policy: rules for all project types, with per-type overrides.
+///
+/// ```toml
+/// [cleaner.policy]
+/// protect = ["~/work/release-*"]
+///
+/// [cleaner.policy.types.cargo]
+/// min_age_days = 14
+/// keep_newest = 2
+/// min_artifact_size = 104857600  # 100 MB
+/// dirty_repos = "confirm"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PolicyConfig {
+    /// Only clean projects untouched for at least this many days
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_age_days: Option<u32>,
+    /// Never clean the N most recently touched projects of each type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_newest: Option<usize>,
+    /// Skip projects whose artifacts total fewer bytes than this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_artifact_size: Option<u64>,
+    /// What to do with projects in a repository with uncommitted changes,
+    /// unpushed commits, stashes or an unfinished rebase/merge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dirty_repos: Option<DirtyRepoAction>,
+    /// Glob patterns of paths never to clean (`~/` expands to home)
+    pub protect: Vec<String>,
+    /// Overrides per project type id (e.g. "cargo")
+    pub types: BTreeMap<String, PolicyRules>,
+}
+
+/// Policy rules for a single project type. Unset fields fall back to
+/// `[cleaner.policy]`; `protect` patterns are added to the global ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PolicyRules {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_age_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_newest: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_artifact_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dirty_repos: Option<DirtyRepoAction>,
+    pub protect: Vec<String>,
+}
+
+/// Handling of projects whose git repository holds unsaved work.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirtyRepoAction {
+    /// Clean them like any other project
+    #[default]
+    Allow,
+    /// Ask before cleaning each one; skipped with `clean --force`
+    Confirm,
+    /// Never clean them
+    Skip,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +127,54 @@ pub struct ScannerConfig {
     pub cache_ttl: u64,
 }
 
+/// A `[project_types.<id>]` section.
+///
+/// For a built-in id, the fields that are set replace the built-in values and
+/// `enabled = false` disables the detector. Any other id defines a new project
+/// type, which needs `detection_files` and either `artifact_dirs` or a
+/// `clean_command`.
+///
+/// ```toml
+/// [project_types.bazel]
+/// enabled = false
+///
+/// [project_types.blaze]
+/// display_name = "Blaze"
+/// detection_files = ["BLAZE"]
+/// artifact_dirs = ["blaze-out", ".blaze-cache"]
+/// clean_command = "blaze clean"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProjectTypeConfig {
+    /// Whether projects of this type are detected at all
+    pub enabled: bool,
+    /// Human-readable name (defaults to the id for new types)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Files or directories marking a project root (any one suffices)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detection_files: Option<Vec<String>>,
+    /// Artifact directories, relative to the project root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_dirs: Option<Vec<String>>,
+    /// Native clean command run in the project root ("" = delete directly)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clean_command: Option<String>,
+}
+
+impl Default for ProjectTypeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            display_name: None,
+            detection_files: None,
+            artifact_dirs: None,
+            clean_command: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct TuiConfig {
@@ -68,6 +188,15 @@ pub struct TuiConfig {
     pub large_dir_threshold: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuarantineConfig {
+    /// Quarantine deleted paths instead of removing them
+    pub enabled: bool,
+    /// Days to keep quarantined paths before purging
+    pub retention_days: u32,
+}
+
 impl Default for MonitorConfig {
     fn default() -> Self {
         Self {
@@ -90,9 +219,10 @@ impl Default for CleanerConfig {
                 "maven".to_string(),
             ],
             exclude_patterns: vec!["**/.git".to_string(), "**/vendor".to_string()],
-            min_age_days: 7,
+            min_age_days: None,
             max_depth: 10,
             parallel_jobs: 4,
+            policy: PolicyConfig::default(),
         }
     }
 }
@@ -119,6 +249,15 @@ impl Default for TuiConfig {
     }
 }
 
+impl Default for QuarantineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 30,
+        }
+    }
+}
+
 impl Config {
     /// Load configuration from file, falling back to defaults
     pub fn load(config_path: Option<&Path>) -> Result<Self> {
@@ -162,15 +301,27 @@ impl Config {
             source: e,
         })?;
 
-        let config: Config = toml::from_str(&contents).map_err(|e| ConfigError::ParseError {
-            path: path.to_path_buf(),
-            source: e,
-        })?;
+        let mut config: Config =
+            toml::from_str(&contents).map_err(|e| ConfigError::ParseError {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
 
+        config.apply_deprecated();
         config.validate()?;
         Ok(config)
     }
 
+    /// Move deprecated settings to their replacements, with a warning.
+    fn apply_deprecated(&mut self) {
+        if let Some(days) = self.cleaner.min_age_days {
+            tracing::warn!(
+                "[cleaner] min_age_days is deprecated, use min_age_days in [cleaner.policy]"
+            );
+            self.cleaner.policy.min_age_days.get_or_insert(days);
+        }
+    }
+
     /// Validate configuration values
     pub fn validate(&self) -> Result<()> {
         if self.monitor.warn_threshold > 100 {
@@ -187,6 +338,9 @@ impl Config {
             )
             .into());
         }
+        for (id, project_type) in &self.project_types {
+            validate_project_type(id, project_type)?;
+        }
         Ok(())
     }
 
@@ -196,6 +350,57 @@ impl Config {
     }
 }
 
+/// Check a `[project_types.<id>]` section for problems the detector can't recover from.
+fn validate_project_type(id: &str, config: &ProjectTypeConfig) -> Result<()> {
+    let invalid =
+        |msg: String| Err(ConfigError::Invalid(format!("project_types.{}: {}", id, msg)).into());
+
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return invalid("id may only contain letters, digits, '-' and '_'".to_string());
+    }
+
+    // Artifact directories are deleted, so they must stay inside the project
+    for dir in config.artifact_dirs.iter().flatten() {
+        let path = Path::new(dir);
+        let inside_project = !dir.is_empty()
+            && path
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)));
+        if !inside_project {
+            return invalid(format!(
+                "artifact_dirs entry '{}' must be a relative path inside the project",
+                dir
+            ));
+        }
+    }
+
+    let builtin = crate::cleaner::all_detectors().iter().any(|d| d.id() == id);
+    // Built-in detectors may share an id with a system cleaner (Go)
+    if !builtin && crate::cleaner::system_registry::all_system_cleaner_ids().contains(&id) {
+        return invalid("id is reserved for a system cleaner".to_string());
+    }
+    if !builtin && config.enabled {
+        if config.detection_files.as_ref().is_none_or(|f| f.is_empty()) {
+            return invalid("custom project types need detection_files".to_string());
+        }
+        let has_artifacts = config.artifact_dirs.as_ref().is_some_and(|d| !d.is_empty());
+        let has_command = config
+            .clean_command
+            .as_ref()
+            .is_some_and(|c| !c.trim().is_empty());
+        if !has_artifacts && !has_command {
+            return invalid(
+                "custom project types need artifact_dirs or a clean_command".to_string(),
+            );
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,6 +445,158 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn policy_config_parses_per_type_rules() {
+        let config: Config = toml::from_str(
+            r#"
+[cleaner.policy]
+min_age_days = 7
+protect = ["~/work/**"]
+
+[cleaner.policy.types.cargo]
+min_age_days = 30
+keep_newest = 2
+
+[cleaner.policy.types.npm]
+min_artifact_size = 1048576
+protect = ["**/keep-*"]
+"#,
+        )
+        .unwrap();
+
+        let policy = &config.cleaner.policy;
+        assert_eq!(policy.min_age_days, Some(7));
+        assert_eq!(policy.protect, vec!["~/work/**"]);
+        assert_eq!(policy.types["cargo"].min_age_days, Some(30));
+        assert_eq!(policy.types["cargo"].keep_newest, Some(2));
+        assert_eq!(policy.types["npm"].min_artifact_size, Some(1048576));
+        assert_eq!(policy.types["npm"].protect, vec!["**/keep-*"]);
+        // Sibling cleaner defaults are untouched
+        assert_eq!(config.cleaner.max_depth, 10);
+    }
+
+    #[test]
+    fn default_policy_round_trips_through_toml() {
+        let mut config = Config::default();
+        config.cleaner.policy.types.insert(
+            "cargo".to_string(),
+            PolicyRules {
+                keep_newest: Some(1),
+                ..Default::default()
+            },
+        );
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let parsed: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.cleaner.policy, config.cleaner.policy);
+    }
+
+    #[test]
+    fn deprecated_min_age_days_feeds_the_policy() {
+        let mut config: Config = toml::from_str("[cleaner]\nmin_age_days = 14\n").unwrap();
+        config.apply_deprecated();
+        assert_eq!(config.cleaner.policy.min_age_days, Some(14));
+
+        // The policy's own setting wins
+        let mut config: Config =
+            toml::from_str("[cleaner]\nmin_age_days = 14\n\n[cleaner.policy]\nmin_age_days = 3\n")
+                .unwrap();
+        config.apply_deprecated();
+        assert_eq!(config.cleaner.policy.min_age_days, Some(3));
+    }
+
+    #[test]
+    fn policy_parses_dirty_repos() {
+        let config: Config = toml::from_str(
+            "[cleaner.policy]\ndirty_repos = \"confirm\"\n\n[cleaner.policy.types.npm]\ndirty_repos = \"skip\"\n",
+        )
+        .unwrap();
+        let policy = &config.cleaner.policy;
+        assert_eq!(policy.dirty_repos, Some(DirtyRepoAction::Confirm));
+        assert_eq!(policy.types["npm"].dirty_repos, Some(DirtyRepoAction::Skip));
+
+        assert!(toml::from_str::<Config>("[cleaner.policy]\ndirty_repos = \"maybe\"\n").is_err());
+    }
+
+    #[test]
+    fn quarantine_defaults_to_disabled() {
+        let config: Config = toml::from_str("[quarantine]\nenabled = true\n").unwrap();
+        assert!(config.quarantine.enabled);
+        assert_eq!(config.quarantine.retention_days, 30);
+        assert!(!Config::default().quarantine.enabled);
+    }
+
+    #[test]
+    fn project_types_parse_custom_and_override_sections() {
+        let config: Config = toml::from_str(
+            r#"
+[project_types.bazel]
+enabled = false
+
+[project_types.cargo]
+artifact_dirs = ["target", "target-ra"]
+
+[project_types.blaze]
+display_name = "Blaze"
+detection_files = ["BLAZE"]
+artifact_dirs = ["blaze-out"]
+clean_command = "blaze clean"
+"#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        assert!(!config.project_types["bazel"].enabled);
+        assert_eq!(
+            config.project_types["cargo"].artifact_dirs,
+            Some(vec!["target".to_string(), "target-ra".to_string()])
+        );
+        assert_eq!(config.project_types["cargo"].detection_files, None);
+        assert!(config.project_types["blaze"].enabled);
+        assert_eq!(
+            config.project_types["blaze"].clean_command.as_deref(),
+            Some("blaze clean")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_incomplete_custom_project_types() {
+        let no_detection: Config =
+            toml::from_str("[project_types.blaze]\nartifact_dirs = [\"out\"]\n").unwrap();
+        assert!(no_detection.validate().is_err());
+
+        let nothing_to_clean: Config =
+            toml::from_str("[project_types.blaze]\ndetection_files = [\"BLAZE\"]\n").unwrap();
+        assert!(nothing_to_clean.validate().is_err());
+
+        // Disabled definitions don't need to be complete
+        let disabled: Config = toml::from_str("[project_types.blaze]\nenabled = false\n").unwrap();
+        assert!(disabled.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_artifact_dirs_outside_project() {
+        for dir in ["/", "../shared", "/tmp/out", "out/../..", ""] {
+            let config: Config = toml::from_str(&format!(
+                "[project_types.cargo]\nartifact_dirs = [{:?}]\n",
+                dir
+            ))
+            .unwrap();
+            assert!(config.validate().is_err(), "accepted {:?}", dir);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_reserved_or_malformed_ids() {
+        for id in ["docker", "\"a,b\"", "\"\""] {
+            let config: Config = toml::from_str(&format!(
+                "[project_types.{}]\ndetection_files = [\"x\"]\nartifact_dirs = [\"out\"]\n",
+                id
+            ))
+            .unwrap();
+            assert!(config.validate().is_err(), "accepted {}", id);
+        }
+    }
+
     #[test]
     fn validate_catches_invalid_thresholds() {
         let mut config = Config::default();
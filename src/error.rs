@@ -32,6 +32,9 @@ pub enum SweeperError {
     #[error("JSON serialization error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("Cache serialization error: {0}")]
+    Cache(#[from] bincode::Error),
+
     #[error("System call failed: {0}")]
     Nix(#[from] nix::Error),
 
@@ -63,6 +66,7 @@ impl SweeperError {
             SweeperError::NotADirectory(_) => 1,
             SweeperError::InvalidPath(_) => 1,
             SweeperError::Json(_) => 1,
+            SweeperError::Cache(_) => 1,
             SweeperError::Nix(_) => 1,
             SweeperError::Notification(_) => 1,
             SweeperError::AlreadyRunning(_) => 4,
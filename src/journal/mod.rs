@@ -0,0 +1,422 @@
+//! Cleanup audit journal.
+//!
+//! Every deletion made by `clean`, a system cleaner or the TUI is appended to
+//! `$XDG_STATE_HOME/rusty-sweeper/journal.jsonl`, one JSON record per line.
+//! Records are never rewritten, so several processes can append to the same
+//! journal and a crash loses at most the line being written.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SweeperError};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// How a path was removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanMethod {
+    /// A native clean command (`cargo clean`, `docker builder prune`, ...)
+    Native,
+    /// Direct deletion
+    Delete,
+    /// Moved into the quarantine
+    Quarantine,
+}
+
+impl std::fmt::Display for CleanMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CleanMethod::Native => "native",
+            CleanMethod::Delete => "delete",
+            CleanMethod::Quarantine => "quarantine",
+        })
+    }
+}
+
+/// A single journal entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    /// Time of the deletion, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Identifies the process that made the deletion (`<pid>@<start time>`)
+    pub invocation: String,
+    /// User running that process
+    pub user: String,
+    /// Removed path; the resource id for system resources
+    pub path: PathBuf,
+    /// Project type or system cleaner category, if known
+    pub project_type: Option<String>,
+    /// How the path was removed
+    pub method: CleanMethod,
+    /// Bytes freed (or moved into the quarantine)
+    pub freed_bytes: u64,
+    /// Why the deletion failed, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JournalRecord {
+    /// A record for `path`, stamped with the current time, user and invocation.
+    pub fn new(path: &Path, project_type: Option<&str>, method: CleanMethod) -> Self {
+        Self {
+            timestamp: unix_now(),
+            invocation: invocation_id().to_string(),
+            user: current_user(),
+            path: path.to_path_buf(),
+            project_type: project_type.map(str::to_string),
+            method,
+            freed_bytes: 0,
+            error: None,
+        }
+    }
+
+    /// Set the number of bytes freed.
+    pub fn freed(mut self, bytes: u64) -> Self {
+        self.freed_bytes = bytes;
+        self
+    }
+
+    /// Mark the deletion as failed.
+    pub fn failed(mut self, error: impl ToString) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+/// Criteria for [`Journal::query`]. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    /// Only records at or after this time (seconds since the epoch)
+    pub since: Option<u64>,
+    /// Only records before this time (seconds since the epoch)
+    pub until: Option<u64>,
+    /// Only records for this path or paths below it
+    pub path: Option<PathBuf>,
+    /// Only records with this project type or system category
+    pub project_type: Option<String>,
+}
+
+impl HistoryFilter {
+    /// Whether `record` satisfies every criterion.
+    pub fn matches(&self, record: &JournalRecord) -> bool {
+        self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp < until)
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|path| record.path.starts_with(path))
+            && self
+                .project_type
+                .as_ref()
+                .is_none_or(|t| record.project_type.as_ref() == Some(t))
+    }
+}
+
+/// Append-only journal of deletions.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    /// Keeps lines from parallel cleaners from interleaving
+    lock: Mutex<()>,
+}
+
+impl Journal {
+    /// Open the journal in the default XDG state directory.
+    pub fn open() -> Result<Self> {
+        let state_home = dirs::state_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("state")))
+            .ok_or_else(|| {
+                SweeperError::Other("Cannot determine the XDG state directory".to_string())
+            })?;
+        Ok(Self::with_path(
+            state_home.join("rusty-sweeper").join("journal.jsonl"),
+        ))
+    }
+
+    /// Open a journal stored at `path`.
+    pub fn with_path(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record.
+    pub fn append(&self, record: &JournalRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        // A single O_APPEND write keeps lines whole across processes too
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|e| io_error(&self.path, e))
+    }
+
+    /// Append a record, logging instead of failing; a journal problem must
+    /// never abort a cleanup that already happened.
+    pub fn record(&self, record: JournalRecord) {
+        if let Err(e) = self.append(&record) {
+            tracing::warn!(error = %e, path = %record.path.display(), "Failed to write journal");
+        }
+    }
+
+    /// Records matching `filter`, oldest first. Unreadable lines are skipped.
+    pub fn query(&self, filter: &HistoryFilter) -> Result<Vec<JournalRecord>> {
+        let data = match fs::read_to_string(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(&self.path, e)),
+        };
+
+        Ok(data
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<JournalRecord>(line) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    tracing::debug!(error = %e, "Skipping malformed journal line");
+                    None
+                }
+            })
+            .filter(|record| filter.matches(record))
+            .collect())
+    }
+}
+
+/// Parse a date for history queries: `YYYY-MM-DD` (local midnight) or a
+/// relative age such as `12h` or `7d`. Returns seconds since the epoch.
+pub fn parse_date(s: &str) -> Option<u64> {
+    let s = s.trim();
+    if let Some(hours) = s.strip_suffix('h').and_then(|n| n.parse::<u64>().ok()) {
+        return Some(unix_now().saturating_sub(hours * 60 * 60));
+    }
+    if let Some(days) = s.strip_suffix('d').and_then(|n| n.parse::<u64>().ok()) {
+        return Some(unix_now().saturating_sub(days * DAY));
+    }
+
+    let mut parts = s.splitn(3, '-').map(|p| p.parse::<i32>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // SAFETY: an all-zero `tm` is a valid value, and mktime only reads and
+    // normalizes the struct it is given.
+    let secs = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        tm.tm_year = year - 1900;
+        tm.tm_mon = month - 1;
+        tm.tm_mday = day;
+        tm.tm_isdst = -1;
+        libc::mktime(&mut tm)
+    };
+    u64::try_from(secs).ok()
+}
+
+/// Format seconds since the epoch as `YYYY-MM-DD hh:mm:ss` in the local time zone.
+pub fn format_timestamp(secs: u64) -> String {
+    let secs = secs as libc::time_t;
+    // SAFETY: an all-zero `tm` is a valid value, and localtime_r only writes
+    // to the struct it is given.
+    let tm = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&secs, &mut tm);
+        tm
+    };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+fn io_error(path: &Path, source: io::Error) -> SweeperError {
+    SweeperError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Identifier shared by every record this process writes.
+fn invocation_id() -> &'static str {
+    static ID: OnceLock<String> = OnceLock::new();
+    ID.get_or_init(|| format!("{}@{}", std::process::id(), unix_now()))
+}
+
+fn current_user() -> String {
+    std::env::var("USER").unwrap_or_else(|_| {
+        // SAFETY: getuid has no preconditions and cannot fail
+        unsafe { libc::getuid() }.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Journal) {
+        let tmp = TempDir::new().unwrap();
+        let journal = Journal::with_path(tmp.path().join("state/journal.jsonl"));
+        (tmp, journal)
+    }
+
+    fn record(path: &str, project_type: &str, timestamp: u64) -> JournalRecord {
+        let mut record =
+            JournalRecord::new(Path::new(path), Some(project_type), CleanMethod::Delete).freed(100);
+        record.timestamp = timestamp;
+        record
+    }
+
+    #[test]
+    fn test_append_and_query_roundtrip() {
+        let (_tmp, journal) = setup();
+        let first = record("/src/a/target", "cargo", 1_000);
+        let second = JournalRecord::new(
+            Path::new("/src/b/node_modules"),
+            Some("npm"),
+            CleanMethod::Quarantine,
+        )
+        .failed("permission denied");
+
+        journal.append(&first).unwrap();
+        journal.append(&second).unwrap();
+
+        let records = journal.query(&HistoryFilter::default()).unwrap();
+        assert_eq!(records, vec![first, second]);
+        assert_eq!(records[1].error.as_deref(), Some("permission denied"));
+        assert_eq!(records[0].invocation, records[1].invocation);
+    }
+
+    #[test]
+    fn test_query_missing_journal_is_empty() {
+        let (_tmp, journal) = setup();
+        assert!(journal.query(&HistoryFilter::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_skips_malformed_lines() {
+        let (_tmp, journal) = setup();
+        journal.append(&record("/a", "cargo", 1)).unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(journal.path())
+            .unwrap();
+        file.write_all(b"{not json\n\n").unwrap();
+        journal.append(&record("/b", "cargo", 2)).unwrap();
+
+        let records = journal.query(&HistoryFilter::default()).unwrap();
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_by_date_path_and_type() {
+        let (_tmp, journal) = setup();
+        journal
+            .append(&record("/src/a/target", "cargo", 100))
+            .unwrap();
+        journal
+            .append(&record("/src/b/node_modules", "npm", 200))
+            .unwrap();
+        journal
+            .append(&record("/other/target", "cargo", 300))
+            .unwrap();
+
+        let query = |filter: HistoryFilter| -> Vec<PathBuf> {
+            journal
+                .query(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.path)
+                .collect()
+        };
+
+        assert_eq!(
+            query(HistoryFilter {
+                since: Some(200),
+                ..Default::default()
+            }),
+            vec![
+                PathBuf::from("/src/b/node_modules"),
+                PathBuf::from("/other/target")
+            ]
+        );
+        assert_eq!(
+            query(HistoryFilter {
+                until: Some(200),
+                ..Default::default()
+            }),
+            vec![PathBuf::from("/src/a/target")]
+        );
+        assert_eq!(
+            query(HistoryFilter {
+                path: Some(PathBuf::from("/src")),
+                project_type: Some("cargo".to_string()),
+                ..Default::default()
+            }),
+            vec![PathBuf::from("/src/a/target")]
+        );
+    }
+
+    #[test]
+    fn test_path_filter_matches_components() {
+        let filter = HistoryFilter {
+            path: Some(PathBuf::from("/src/a")),
+            ..Default::default()
+        };
+        assert!(filter.matches(&record("/src/a/target", "cargo", 0)));
+        assert!(!filter.matches(&record("/src/ab/target", "cargo", 0)));
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let now = unix_now();
+        let week = parse_date("7d").unwrap();
+        assert!(now - week >= 7 * DAY && now - week < 7 * DAY + 5);
+        let hours = parse_date("12h").unwrap();
+        assert!(now - hours >= 12 * 3600 && now - hours < 12 * 3600 + 5);
+
+        let date = parse_date("2024-03-15").unwrap();
+        assert!(format_timestamp(date).starts_with("2024-03-15 00:00"));
+
+        assert!(parse_date("2024-13-01").is_none());
+        assert!(parse_date("yesterday").is_none());
+        assert!(parse_date("2024-03").is_none());
+    }
+
+    #[test]
+    fn test_method_serializes_lowercase() {
+        let json = serde_json::to_string(&record("/a", "cargo", 0)).unwrap();
+        assert!(json.contains("\"method\":\"delete\""));
+        assert!(!json.contains("\"error\""));
+        assert_eq!(CleanMethod::Quarantine.to_string(), "quarantine");
+    }
+}
@@ -3,6 +3,8 @@
 //! This crate provides functionality for:
 //! - Monitoring disk usage with desktop notifications
 //! - Discovering and cleaning build artifacts
+//! - Reversible deletion through a quarantine
+//! - An audit journal of every deletion
 //! - Interactive TUI for disk exploration
 
 pub mod cleaner;
@@ -10,7 +12,9 @@ pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod error;
+pub mod journal;
 pub mod monitor;
+pub mod quarantine;
 pub mod scanner;
 pub mod tui;
 
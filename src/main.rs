@@ -1,28 +1,56 @@
-use anyhow::Result;
 use clap::Parser;
 use std::path::PathBuf;
 
-use rusty_sweeper::cli::Cli;
+use rusty_sweeper::cli::{Cli, Commands, TuiArgs};
+use rusty_sweeper::commands;
 use rusty_sweeper::config::Config;
+use rusty_sweeper::SweeperError;
 
-fn main() -> Result<()> {
+fn main() {
     let cli = Cli::parse();
 
     // Initialize logging based on verbosity
     init_logging(cli.verbose, cli.quiet);
 
+    if let Err(err) = run(cli) {
+        eprintln!("Error: {:#}", err);
+        std::process::exit(exit_code(&err));
+    }
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
     // Load configuration
     let config = Config::load(cli.config.as_deref())?;
 
     tracing::debug!(?config, "Loaded configuration");
 
-    let root = PathBuf::from("/");
-    tracing::info!(path = %root.display(), "Starting TUI");
-    rusty_sweeper::tui::run(root)?;
+    match cli.command {
+        Some(Commands::Scan(args)) => commands::scan::run(args)?,
+        Some(Commands::Clean(args)) => commands::clean::run(args, &config)?,
+        Some(Commands::Tui(args)) => commands::tui::run(args, &config)?,
+        Some(Commands::Duplicates(args)) => commands::duplicates::run(args)?,
+        Some(Commands::Quarantine(args)) => commands::quarantine::run(args, &config)?,
+        Some(Commands::History(args)) => commands::history::run(args)?,
+        Some(Commands::Resources(args)) => commands::resources::run(args, &config)?,
+        None => commands::tui::run(
+            TuiArgs {
+                path: PathBuf::from("/"),
+            },
+            &config,
+        )?,
+    }
 
     Ok(())
 }
 
+/// Map an error to a process exit code, honoring `SweeperError::exit_code`.
+fn exit_code(err: &anyhow::Error) -> i32 {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<SweeperError>())
+        .map(SweeperError::exit_code)
+        .unwrap_or(1)
+}
+
 fn init_logging(verbosity: u8, quiet: bool) {
     use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
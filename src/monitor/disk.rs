@@ -201,7 +201,7 @@ mod tests {
 
         // Should find at least root
         assert!(!mounts.is_empty());
-        assert!(mounts.iter().any(|m| m.path == PathBuf::from("/")));
+        assert!(mounts.iter().any(|m| m.path == Path::new("/")));
     }
 
     #[test]
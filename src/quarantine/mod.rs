@@ -0,0 +1,695 @@
+//! Reversible deletion.
+//!
+//! Instead of removing a path, [`Quarantine::quarantine`] renames it into a
+//! holding area on the same filesystem, which is instant and needs no extra
+//! space. Every quarantined path is recorded in an index, so it can later be
+//! restored to where it came from or purged for good.
+//!
+//! Holding areas are tried in order:
+//! 1. `$XDG_DATA_HOME/rusty-sweeper/quarantine/holding`, if it is on the same
+//!    filesystem as the path
+//! 2. `<mount point>/.rusty-sweeper-quarantine-<uid>` on the path's filesystem
+//!
+//! If neither can be used (e.g. a read-only mount root), the path is moved to
+//! the freedesktop.org Trash in `$XDG_DATA_HOME/Trash`, copying across
+//! filesystems if needed. Trashed paths are indexed as well.
+//!
+//! The index is shared by every running rusty-sweeper process (a cron
+//! `clean` and the TUI, say), so updates hold an exclusive `flock` on
+//! `index.lock` next to it. The index itself is replaced by a rename, which
+//! would leave a lock on it behind on the old file.
+
+pub(crate) mod trash;
+
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use nix::fcntl::{Flock, FlockArg};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::error::{Result, SweeperError};
+
+use trash::Trash;
+
+/// Where a quarantined path is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    /// A rusty-sweeper holding area on the path's filesystem
+    Holding,
+    /// The freedesktop.org Trash
+    Trash,
+}
+
+/// A quarantined file or directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    /// Unique id, used to restore or purge the entry
+    pub id: String,
+    /// Where the path was before it was quarantined
+    pub original_path: PathBuf,
+    /// Where the path is now
+    pub stored_path: PathBuf,
+    /// Kind of storage holding the path
+    pub storage: StorageKind,
+    /// Quarantine time, in seconds since the Unix epoch
+    pub quarantined_at: u64,
+    /// Apparent size in bytes
+    pub size: u64,
+}
+
+impl QuarantineEntry {
+    /// Time spent in quarantine as of `now`.
+    pub fn age(&self, now: SystemTime) -> Duration {
+        let at = UNIX_EPOCH + Duration::from_secs(self.quarantined_at);
+        now.duration_since(at).unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Result of purging quarantined entries.
+#[derive(Debug, Clone, Default)]
+pub struct PurgeSummary {
+    /// Entries removed from quarantine
+    pub purged: Vec<QuarantineEntry>,
+    /// Bytes freed
+    pub freed: u64,
+}
+
+/// Quarantine index and storage locations.
+#[derive(Debug)]
+pub struct Quarantine {
+    /// Holds `index.json` and the home holding area
+    data_dir: PathBuf,
+    trash: Trash,
+    /// Whether holding areas are tried before the Trash
+    use_holding: bool,
+}
+
+/// Seconds in a day, the unit of retention periods.
+const DAY: u64 = 24 * 60 * 60;
+
+/// Distinguishes ids created within the same nanosecond, and temporary
+/// index files written by one process.
+static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+impl Quarantine {
+    /// Open the quarantine in the default XDG locations.
+    pub fn open() -> Result<Self> {
+        let data_home = dirs::data_local_dir().ok_or_else(|| {
+            SweeperError::Other("Cannot determine the XDG data directory".to_string())
+        })?;
+        Ok(Self::with_dirs(
+            data_home.join("rusty-sweeper").join("quarantine"),
+            data_home.join("Trash"),
+        ))
+    }
+
+    /// Open a quarantine with explicit index and Trash directories.
+    pub fn with_dirs(data_dir: PathBuf, trash_dir: PathBuf) -> Self {
+        Self {
+            data_dir,
+            trash: Trash::new(trash_dir),
+            use_holding: true,
+        }
+    }
+
+    /// Store everything in the Trash, as when no holding area is usable.
+    #[cfg(test)]
+    pub(crate) fn without_holding(mut self) -> Self {
+        self.use_holding = false;
+        self
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.data_dir.join("index.json")
+    }
+
+    /// Lock the index against other threads and processes until the
+    /// returned guard is dropped.
+    fn lock(&self) -> Result<Flock<fs::File>> {
+        fs::create_dir_all(&self.data_dir).map_err(|e| io_error(&self.data_dir, e))?;
+        let path = self.data_dir.join("index.lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| io_error(&path, e))?;
+        Flock::lock(file, FlockArg::LockExclusive)
+            .map_err(|(_, errno)| io_error(&path, errno.into()))
+    }
+
+    /// All quarantined entries, oldest first.
+    pub fn list(&self) -> Result<Vec<QuarantineEntry>> {
+        // The index is replaced atomically, so reading needs no lock
+        self.load()
+    }
+
+    /// Move a file or directory into quarantine.
+    pub fn quarantine(&self, path: &Path) -> Result<QuarantineEntry> {
+        let metadata = fs::symlink_metadata(path).map_err(|e| io_error(path, e))?;
+        let name = path
+            .file_name()
+            .ok_or_else(|| SweeperError::InvalidPath(path.display().to_string()))?;
+        let size = apparent_size(path, &metadata);
+        let now = SystemTime::now();
+        let id = new_id(now);
+
+        let holding = if self.use_holding {
+            self.candidate_areas(path, &metadata)
+                .into_iter()
+                .find_map(|area| match move_into_area(path, &area.join(&id), name) {
+                    Ok(stored) => Some(stored),
+                    Err(e) => {
+                        tracing::debug!(area = %area.display(), error = %e, "Holding area unusable");
+                        None
+                    }
+                })
+        } else {
+            None
+        };
+
+        let (stored_path, storage) = match holding {
+            Some(stored) => (stored, StorageKind::Holding),
+            None => {
+                let stored = self.trash.put(path, now).map_err(|e| io_error(path, e))?;
+                (stored, StorageKind::Trash)
+            }
+        };
+
+        let entry = QuarantineEntry {
+            id,
+            original_path: path.to_path_buf(),
+            stored_path,
+            storage,
+            quarantined_at: now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            size,
+        };
+
+        let _lock = self.lock()?;
+        let mut entries = self.load()?;
+        entries.push(entry.clone());
+        self.save(&entries)?;
+        Ok(entry)
+    }
+
+    /// Move a quarantined entry back to its original path.
+    ///
+    /// Fails if something now exists at the original path.
+    pub fn restore(&self, id: &str) -> Result<QuarantineEntry> {
+        let _lock = self.lock()?;
+        let mut entries = self.load()?;
+        let index = entries
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or_else(|| SweeperError::NotFound(format!("quarantine entry '{}'", id)))?;
+        let entry = entries[index].clone();
+
+        if fs::symlink_metadata(&entry.original_path).is_ok() {
+            return Err(SweeperError::Other(format!(
+                "Cannot restore '{}': path already exists",
+                entry.original_path.display()
+            )));
+        }
+        if let Some(parent) = entry.original_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        move_path(&entry.stored_path, &entry.original_path)
+            .map_err(|e| io_error(&entry.stored_path, e))?;
+        self.discard_storage(&entry);
+
+        entries.remove(index);
+        self.save(&entries)?;
+        Ok(entry)
+    }
+
+    /// Permanently delete entries quarantined at least `age` ago.
+    pub fn purge_older_than(&self, age: Duration) -> Result<PurgeSummary> {
+        self.purge_where(|entry, now| entry.age(now) >= age)
+    }
+
+    /// Permanently delete entries older than the retention period, so the
+    /// quarantine doesn't grow without bound. A retention of 0 days keeps
+    /// entries until they are purged by hand.
+    pub fn purge_expired(&self, retention_days: u32) -> Result<PurgeSummary> {
+        if retention_days == 0 {
+            return Ok(PurgeSummary::default());
+        }
+        self.purge_older_than(Duration::from_secs(u64::from(retention_days) * DAY))
+    }
+
+    /// Permanently delete a single entry.
+    pub fn purge(&self, id: &str) -> Result<PurgeSummary> {
+        let summary = self.purge_where(|entry, _| entry.id == id)?;
+        if summary.purged.is_empty() {
+            return Err(SweeperError::NotFound(format!("quarantine entry '{}'", id)));
+        }
+        Ok(summary)
+    }
+
+    fn purge_where<F>(&self, select: F) -> Result<PurgeSummary>
+    where
+        F: Fn(&QuarantineEntry, SystemTime) -> bool,
+    {
+        let _lock = self.lock()?;
+        let now = SystemTime::now();
+        let entries = self.load()?;
+        let mut kept = Vec::new();
+        let mut summary = PurgeSummary::default();
+
+        for entry in entries {
+            if !select(&entry, now) {
+                kept.push(entry);
+                continue;
+            }
+            match remove_path(&entry.stored_path) {
+                Ok(()) => {
+                    self.discard_storage(&entry);
+                    summary.freed += entry.size;
+                    summary.purged.push(entry);
+                }
+                // Already gone, e.g. the Trash was emptied
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    self.discard_storage(&entry);
+                    summary.purged.push(entry);
+                }
+                Err(e) => {
+                    tracing::warn!(path = %entry.stored_path.display(), error = %e, "Failed to purge");
+                    kept.push(entry);
+                }
+            }
+        }
+
+        self.save(&kept)?;
+        Ok(summary)
+    }
+
+    /// Remove bookkeeping left behind once an entry's path is gone.
+    fn discard_storage(&self, entry: &QuarantineEntry) {
+        match entry.storage {
+            StorageKind::Holding => {
+                if let Some(id_dir) = entry.stored_path.parent() {
+                    let _ = fs::remove_dir(id_dir);
+                }
+            }
+            StorageKind::Trash => self.trash.remove_info(&entry.stored_path),
+        }
+    }
+
+    /// Holding areas on the same filesystem as `path`, in order of preference.
+    fn candidate_areas(&self, path: &Path, metadata: &fs::Metadata) -> Vec<PathBuf> {
+        let mut areas = Vec::new();
+
+        let home_area = self.data_dir.join("holding");
+        if fs::create_dir_all(&home_area).is_ok()
+            && fs::metadata(&home_area).is_ok_and(|m| m.dev() == metadata.dev())
+        {
+            areas.push(home_area);
+        }
+
+        if let Some(root) = mount_root(path, metadata.dev()) {
+            // SAFETY: getuid has no preconditions and cannot fail
+            let uid = unsafe { libc::getuid() };
+            areas.push(root.join(format!(".rusty-sweeper-quarantine-{}", uid)));
+        }
+
+        areas
+    }
+
+    fn load(&self) -> Result<Vec<QuarantineEntry>> {
+        let path = self.index_path();
+        match fs::read(&path) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(io_error(&path, e)),
+        }
+    }
+
+    /// Write the index atomically, so a crash never leaves it truncated.
+    /// The temporary file is unique to this process and call.
+    fn save(&self, entries: &[QuarantineEntry]) -> Result<()> {
+        fs::create_dir_all(&self.data_dir).map_err(|e| io_error(&self.data_dir, e))?;
+        let path = self.index_path();
+        let tmp = path.with_extension(format!(
+            "json.{}-{}.tmp",
+            std::process::id(),
+            ID_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp, serde_json::to_vec_pretty(entries)?).map_err(|e| io_error(&tmp, e))?;
+        fs::rename(&tmp, &path).map_err(|e| io_error(&path, e))
+    }
+}
+
+fn io_error(path: &Path, source: io::Error) -> SweeperError {
+    SweeperError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn new_id(now: SystemTime) -> String {
+    let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}-{:09}-{}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_nanos(),
+        ID_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn apparent_size(path: &Path, metadata: &fs::Metadata) -> u64 {
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// The topmost ancestor of `path` that is still on device `dev`.
+fn mount_root(path: &Path, dev: u64) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .take_while(|p| fs::metadata(p).is_ok_and(|m| m.dev() == dev))
+        .last()
+        .map(Path::to_path_buf)
+}
+
+/// Rename `path` into `dir/name`, creating `dir`. Never copies.
+fn move_into_area(path: &Path, dir: &Path, name: &std::ffi::OsStr) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stored = dir.join(name);
+    if let Err(e) = fs::rename(path, &stored) {
+        let _ = fs::remove_dir(dir);
+        return Err(e);
+    }
+    Ok(stored)
+}
+
+/// Rename `from` to `to`, copying and deleting when they are on different filesystems.
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            copy_recursive(from, to)?;
+            remove_path(from)
+        }
+        result => result,
+    }
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    let file_type = fs::symlink_metadata(from)?.file_type();
+    if file_type.is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(from)?, to)
+    } else if file_type.is_dir() {
+        fs::create_dir(to)?;
+        fs::set_permissions(to, fs::metadata(from)?.permissions())?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Quarantine) {
+        let tmp = TempDir::new().unwrap();
+        let quarantine = Quarantine::with_dirs(tmp.path().join("data"), tmp.path().join("Trash"));
+        (tmp, quarantine)
+    }
+
+    fn make_dir(tmp: &TempDir, name: &str) -> PathBuf {
+        let dir = tmp.path().join("work").join(name);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.bin"), "x".repeat(100)).unwrap();
+        fs::write(dir.join("sub/b.bin"), "y".repeat(50)).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_quarantine_moves_into_holding_area() {
+        let (tmp, quarantine) = setup();
+        let dir = make_dir(&tmp, "target");
+
+        let entry = quarantine.quarantine(&dir).unwrap();
+
+        assert!(!dir.exists());
+        assert_eq!(entry.storage, StorageKind::Holding);
+        assert_eq!(entry.size, 150);
+        assert_eq!(entry.original_path, dir);
+        assert!(entry
+            .stored_path
+            .starts_with(tmp.path().join("data/holding")));
+        assert!(entry.stored_path.join("sub/b.bin").exists());
+        assert_eq!(quarantine.list().unwrap(), vec![entry]);
+    }
+
+    #[test]
+    fn test_restore_puts_path_back() {
+        let (tmp, quarantine) = setup();
+        let dir = make_dir(&tmp, "target");
+        let entry = quarantine.quarantine(&dir).unwrap();
+
+        let restored = quarantine.restore(&entry.id).unwrap();
+
+        assert_eq!(restored.id, entry.id);
+        assert_eq!(
+            fs::read_to_string(dir.join("sub/b.bin")).unwrap(),
+            "y".repeat(50)
+        );
+        assert!(!entry.stored_path.exists());
+        // The per-entry directory in the holding area is cleaned up too
+        assert!(!entry.stored_path.parent().unwrap().exists());
+        assert!(quarantine.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_recreates_missing_parent() {
+        let (tmp, quarantine) = setup();
+        let dir = make_dir(&tmp, "target");
+        let entry = quarantine.quarantine(&dir).unwrap();
+        fs::remove_dir_all(tmp.path().join("work")).unwrap();
+
+        quarantine.restore(&entry.id).unwrap();
+
+        assert!(dir.join("a.bin").exists());
+    }
+
+    #[test]
+    fn test_restore_refuses_to_overwrite() {
+        let (tmp, quarantine) = setup();
+        let dir = make_dir(&tmp, "target");
+        let entry = quarantine.quarantine(&dir).unwrap();
+        fs::create_dir(&dir).unwrap();
+
+        let err = quarantine.restore(&entry.id).unwrap_err();
+
+        assert!(err.to_string().contains("already exists"));
+        assert!(entry.stored_path.exists());
+        assert_eq!(quarantine.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_restore_unknown_id() {
+        let (_tmp, quarantine) = setup();
+        let err = quarantine.restore("nope").unwrap_err();
+        assert!(matches!(err, SweeperError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_quarantine_single_file() {
+        let (tmp, quarantine) = setup();
+        let file = tmp.path().join("big.iso");
+        fs::write(&file, "z".repeat(42)).unwrap();
+
+        let entry = quarantine.quarantine(&file).unwrap();
+        assert_eq!(entry.size, 42);
+        assert!(!file.exists());
+
+        quarantine.restore(&entry.id).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "z".repeat(42));
+    }
+
+    #[test]
+    fn test_same_name_entries_do_not_collide() {
+        let (tmp, quarantine) = setup();
+        let first = make_dir(&tmp, "target");
+        let a = quarantine.quarantine(&first).unwrap();
+        let second = make_dir(&tmp, "target");
+        let b = quarantine.quarantine(&second).unwrap();
+
+        assert_ne!(a.id, b.id);
+        assert_ne!(a.stored_path, b.stored_path);
+        assert!(a.stored_path.exists() && b.stored_path.exists());
+    }
+
+    #[test]
+    fn test_purge_older_than() {
+        let (tmp, quarantine) = setup();
+        let old = quarantine.quarantine(&make_dir(&tmp, "old")).unwrap();
+        let new = quarantine.quarantine(&make_dir(&tmp, "new")).unwrap();
+
+        // Backdate the first entry by two days
+        let mut entries = quarantine.list().unwrap();
+        entries[0].quarantined_at -= 2 * 24 * 60 * 60;
+        quarantine.save(&entries).unwrap();
+
+        let summary = quarantine
+            .purge_older_than(Duration::from_secs(24 * 60 * 60))
+            .unwrap();
+
+        assert_eq!(summary.purged.len(), 1);
+        assert_eq!(summary.purged[0].id, old.id);
+        assert_eq!(summary.freed, 150);
+        assert!(!old.stored_path.exists());
+        assert!(new.stored_path.exists());
+        assert_eq!(quarantine.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let (tmp, quarantine) = setup();
+        let old = quarantine.quarantine(&make_dir(&tmp, "old")).unwrap();
+        quarantine.quarantine(&make_dir(&tmp, "new")).unwrap();
+
+        let mut entries = quarantine.list().unwrap();
+        entries[0].quarantined_at -= 31 * DAY;
+        quarantine.save(&entries).unwrap();
+
+        // A retention of 0 days never expires anything
+        assert!(quarantine.purge_expired(0).unwrap().purged.is_empty());
+
+        let summary = quarantine.purge_expired(30).unwrap();
+        assert_eq!(summary.purged.len(), 1);
+        assert_eq!(summary.purged[0].id, old.id);
+        assert_eq!(quarantine.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_instances_keep_every_entry() {
+        let tmp = TempDir::new().unwrap();
+        let dirs: Vec<PathBuf> = (0..16)
+            .map(|i| make_dir(&tmp, &format!("target-{}", i)))
+            .collect();
+
+        // Separate instances stand in for separate processes
+        std::thread::scope(|scope| {
+            for chunk in dirs.chunks(2) {
+                let quarantine =
+                    Quarantine::with_dirs(tmp.path().join("data"), tmp.path().join("Trash"));
+                scope.spawn(move || {
+                    for dir in chunk {
+                        quarantine.quarantine(dir).unwrap();
+                    }
+                });
+            }
+        });
+
+        let quarantine = Quarantine::with_dirs(tmp.path().join("data"), tmp.path().join("Trash"));
+        assert_eq!(quarantine.list().unwrap().len(), 16);
+        let leftovers = fs::read_dir(tmp.path().join("data"))
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_purge_single_entry_and_missing_storage() {
+        let (tmp, quarantine) = setup();
+        let entry = quarantine.quarantine(&make_dir(&tmp, "target")).unwrap();
+        fs::remove_dir_all(&entry.stored_path).unwrap();
+
+        let summary = quarantine.purge(&entry.id).unwrap();
+
+        // Nothing left to free, but the entry is dropped from the index
+        assert_eq!(summary.purged.len(), 1);
+        assert_eq!(summary.freed, 0);
+        assert!(quarantine.list().unwrap().is_empty());
+        assert!(matches!(
+            quarantine.purge(&entry.id),
+            Err(SweeperError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_falls_back_to_trash() {
+        let (tmp, mut quarantine) = setup();
+        quarantine.use_holding = false;
+        let dir = make_dir(&tmp, "target");
+
+        let entry = quarantine.quarantine(&dir).unwrap();
+
+        assert_eq!(entry.storage, StorageKind::Trash);
+        assert_eq!(entry.stored_path, tmp.path().join("Trash/files/target"));
+        let info = fs::read_to_string(tmp.path().join("Trash/info/target.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\nPath="));
+        assert!(info.contains("DeletionDate="));
+
+        quarantine.restore(&entry.id).unwrap();
+        assert!(dir.join("a.bin").exists());
+        assert!(!tmp.path().join("Trash/info/target.trashinfo").exists());
+    }
+
+    #[test]
+    fn test_missing_path_is_an_error() {
+        let (tmp, quarantine) = setup();
+        let err = quarantine.quarantine(&tmp.path().join("nope")).unwrap_err();
+        assert!(matches!(err, SweeperError::Io { .. }));
+    }
+
+    #[test]
+    fn test_mount_root_stays_on_device() {
+        let tmp = TempDir::new().unwrap();
+        let dev = fs::metadata(tmp.path()).unwrap().dev();
+
+        let root = mount_root(&tmp.path().join("x"), dev).unwrap();
+
+        assert!(tmp.path().starts_with(&root));
+        assert_eq!(fs::metadata(&root).unwrap().dev(), dev);
+    }
+
+    #[test]
+    fn test_copy_recursive_preserves_tree_and_symlinks() {
+        let tmp = TempDir::new().unwrap();
+        let src = make_dir(&tmp, "src");
+        std::os::unix::fs::symlink("a.bin", src.join("link")).unwrap();
+        let dst = tmp.path().join("dst");
+
+        copy_recursive(&src, &dst).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dst.join("sub/b.bin")).unwrap(),
+            "y".repeat(50)
+        );
+        assert_eq!(
+            fs::read_link(dst.join("link")).unwrap(),
+            PathBuf::from("a.bin")
+        );
+    }
+}
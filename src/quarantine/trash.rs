@@ -0,0 +1,318 @@
+//! Minimal freedesktop.org Trash support (home trash only).
+//!
+//! See <https://specifications.freedesktop.org/trash-spec/latest/>.
+
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::move_path;
+
+/// A Trash directory containing `files/` and `info/`.
+#[derive(Debug)]
+pub struct Trash {
+    root: PathBuf,
+}
+
+impl Trash {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// The Trash directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn files_dir(&self) -> PathBuf {
+        self.root.join("files")
+    }
+
+    fn info_dir(&self) -> PathBuf {
+        self.root.join("info")
+    }
+
+    /// Move `path` into the Trash and write its `.trashinfo`.
+    ///
+    /// Returns the path inside `files/`.
+    pub fn put(&self, path: &Path, deleted_at: SystemTime) -> io::Result<PathBuf> {
+        let files = self.files_dir();
+        let info = self.info_dir();
+        fs::create_dir_all(&files)?;
+        fs::create_dir_all(&info)?;
+
+        let absolute = std::path::absolute(path)?;
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+            .to_string_lossy()
+            .into_owned();
+
+        // Reserve a unique name by creating its info file first, as the spec requires
+        let (stored_name, mut info_file) = (1..)
+            .map(|n| match n {
+                1 => name.clone(),
+                _ => format!("{}.{}", name, n),
+            })
+            .find_map(|candidate| {
+                if files.join(&candidate).exists() {
+                    return None;
+                }
+                OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(info.join(format!("{}.trashinfo", candidate)))
+                    .ok()
+                    .map(|file| (candidate, file))
+            })
+            .expect("an unused trash name exists");
+
+        let info_path = info.join(format!("{}.trashinfo", stored_name));
+        let contents = format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            percent_encode(&absolute),
+            format_local_time(deleted_at)
+        );
+        let stored = files.join(&stored_name);
+        let result = info_file
+            .write_all(contents.as_bytes())
+            .and_then(|()| move_path(path, &stored));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&info_path);
+            return Err(e);
+        }
+        Ok(stored)
+    }
+
+    /// Delete the `.trashinfo` belonging to a path in `files/`.
+    pub fn remove_info(&self, stored: &Path) {
+        if let Some(name) = stored.file_name() {
+            let mut info_name = name.to_os_string();
+            info_name.push(".trashinfo");
+            let _ = fs::remove_file(self.info_dir().join(info_name));
+        }
+    }
+
+    /// Everything in `files/`, with what its `.trashinfo` records.
+    pub fn entries(&self) -> Vec<TrashedEntry> {
+        let Ok(files) = fs::read_dir(self.files_dir()) else {
+            return Vec::new();
+        };
+        files
+            .flatten()
+            .map(|file| {
+                let mut info_name = file.file_name();
+                info_name.push(".trashinfo");
+                let info = fs::read_to_string(self.info_dir().join(info_name)).ok();
+                let (original, deleted_at) = info
+                    .as_deref()
+                    .map(parse_trash_info)
+                    .unwrap_or((None, None));
+                TrashedEntry {
+                    stored: file.path(),
+                    original,
+                    deleted_at,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A path in the Trash's `files/`.
+#[derive(Debug, Clone)]
+pub struct TrashedEntry {
+    /// The path inside `files/`
+    pub stored: PathBuf,
+    /// Where it was trashed from, if its `.trashinfo` says
+    pub original: Option<PathBuf>,
+    /// When it was trashed, if its `.trashinfo` says
+    pub deleted_at: Option<SystemTime>,
+}
+
+/// Read the `Path=` and `DeletionDate=` keys of a `.trashinfo` file.
+fn parse_trash_info(contents: &str) -> (Option<PathBuf>, Option<SystemTime>) {
+    let value = |key: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(str::trim)
+    };
+    (
+        value("Path").map(percent_decode),
+        value("DeletionDate").and_then(parse_local_time),
+    )
+}
+
+/// Undo [`percent_encode`]; malformed escapes are kept as they are.
+fn percent_decode(s: &str) -> PathBuf {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(OsString::from_vec(out))
+}
+
+/// URL-escape a path for the `Path=` key, keeping `/` and unreserved characters.
+fn percent_encode(path: &Path) -> String {
+    let mut out = String::new();
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Format a time as `YYYY-MM-DDThh:mm:ss` in the local time zone.
+fn format_local_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0) as libc::time_t;
+    // SAFETY: an all-zero `tm` is a valid value, and localtime_r only writes
+    // to the struct it is given.
+    let tm = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&secs, &mut tm);
+        tm
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// Parse a `YYYY-MM-DDThh:mm:ss` time in the local time zone.
+fn parse_local_time(s: &str) -> Option<SystemTime> {
+    let (date, time) = s.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|p| p.parse::<i32>().ok());
+    let mut time = time.splitn(3, ':').map(|p| p.parse::<i32>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (hour, min, sec) = (time.next()??, time.next()??, time.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // SAFETY: an all-zero `tm` is a valid value, and mktime only reads and
+    // normalizes the struct it is given.
+    let secs = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        tm.tm_year = year - 1900;
+        tm.tm_mon = month - 1;
+        tm.tm_mday = day;
+        tm.tm_hour = hour;
+        tm.tm_min = min;
+        tm.tm_sec = sec;
+        tm.tm_isdst = -1;
+        libc::mktime(&mut tm)
+    };
+    u64::try_from(secs)
+        .ok()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(
+            percent_encode(Path::new("/home/u/my dir/ä.txt")),
+            "/home/u/my%20dir/%C3%A4.txt"
+        );
+    }
+
+    #[test]
+    fn test_format_local_time_shape() {
+        let formatted = format_local_time(SystemTime::now());
+        assert_eq!(formatted.len(), 19);
+        assert_eq!(&formatted[10..11], "T");
+    }
+
+    #[test]
+    fn test_parse_trash_info_round_trips() {
+        let deleted_at = UNIX_EPOCH + Duration::from_secs(1_718_000_000);
+        let contents = format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            percent_encode(Path::new("/home/u/my dir/ä.txt")),
+            format_local_time(deleted_at)
+        );
+
+        let (original, parsed) = parse_trash_info(&contents);
+        assert_eq!(original, Some(PathBuf::from("/home/u/my dir/ä.txt")));
+        assert_eq!(parsed, Some(deleted_at));
+        assert_eq!(parse_trash_info("[Trash Info]\n"), (None, None));
+        assert_eq!(parse_local_time("2024-13-01T00:00:00"), None);
+    }
+
+    #[test]
+    fn test_entries_include_files_without_info() {
+        let tmp = TempDir::new().unwrap();
+        let trash = Trash::new(tmp.path().join("Trash"));
+        let file = tmp.path().join("notes.txt");
+        fs::write(&file, "x").unwrap();
+        trash.put(&file, SystemTime::now()).unwrap();
+        fs::write(tmp.path().join("Trash/files/orphan"), "x").unwrap();
+
+        let mut entries = trash.entries();
+        entries.sort_by(|a, b| a.stored.cmp(&b.stored));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].original, Some(file));
+        assert!(entries[0].deleted_at.is_some());
+        assert!(entries[1].stored.ends_with("orphan"));
+        assert!(entries[1].deleted_at.is_none());
+    }
+
+    #[test]
+    fn test_put_numbers_colliding_names() {
+        let tmp = TempDir::new().unwrap();
+        let trash = Trash::new(tmp.path().join("Trash"));
+        for _ in 0..2 {
+            let file = tmp.path().join("notes.txt");
+            fs::write(&file, "x").unwrap();
+            trash.put(&file, SystemTime::now()).unwrap();
+        }
+
+        assert!(tmp.path().join("Trash/files/notes.txt").exists());
+        assert!(tmp.path().join("Trash/files/notes.txt.2").exists());
+        assert!(tmp.path().join("Trash/info/notes.txt.2.trashinfo").exists());
+    }
+
+    #[test]
+    fn test_put_failure_releases_name() {
+        let tmp = TempDir::new().unwrap();
+        let trash = Trash::new(tmp.path().join("Trash"));
+
+        assert!(trash
+            .put(&tmp.path().join("missing"), SystemTime::now())
+            .is_err());
+        assert!(!tmp.path().join("Trash/info/missing.trashinfo").exists());
+    }
+}
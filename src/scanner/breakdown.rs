@@ -0,0 +1,372 @@
+//! Aggregation of a scanned tree by file extension and broad file category.
+//!
+//! Only files present in the tree are counted, so a tree cut off by
+//! `max_depth` yields a breakdown of the scanned part only.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::entry::DirEntry;
+
+/// Broad file category, derived from the extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCategory {
+    Video,
+    Image,
+    Archive,
+    /// Compiled objects, libraries and executables
+    Object,
+    Source,
+    Log,
+    /// Virtual machine and disk images
+    VmImage,
+    Other,
+}
+
+impl FileCategory {
+    /// Categorize a lowercase extension (without the dot).
+    pub fn from_extension(ext: &str) -> Self {
+        match ext {
+            "mkv" | "mp4" | "m4v" | "avi" | "mov" | "webm" | "wmv" | "flv" | "mpg" | "mpeg"
+            | "3gp" | "ogv" => FileCategory::Video,
+            "jpg" | "jpeg" | "png" | "gif" | "bmp" | "tif" | "tiff" | "webp" | "heic" | "heif"
+            | "svg" | "ico" | "raw" | "cr2" | "nef" | "arw" | "dng" | "psd" | "xcf" => {
+                FileCategory::Image
+            }
+            "zip" | "tar" | "gz" | "tgz" | "bz2" | "tbz2" | "xz" | "txz" | "zst" | "7z" | "rar"
+            | "lz" | "lz4" | "lzma" | "cab" | "deb" | "rpm" | "apk" | "jar" | "war" | "whl"
+            | "crate" | "dmg" => FileCategory::Archive,
+            "o" | "obj" | "a" | "lib" | "so" | "dll" | "dylib" | "exe" | "bin" | "rlib"
+            | "rmeta" | "pdb" | "class" | "pyc" | "pyo" | "wasm" | "elf" | "ko" | "pch" | "gch" => {
+                FileCategory::Object
+            }
+            "rs" | "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "go" | "py" | "js" | "mjs"
+            | "cjs" | "jsx" | "ts" | "tsx" | "java" | "kt" | "kts" | "scala" | "swift" | "m"
+            | "mm" | "rb" | "php" | "cs" | "fs" | "hs" | "ml" | "ex" | "exs" | "erl" | "clj"
+            | "lua" | "pl" | "sh" | "bash" | "zsh" | "zig" | "dart" | "vue" | "svelte" => {
+                FileCategory::Source
+            }
+            "log" | "err" | "trace" => FileCategory::Log,
+            "iso" | "img" | "qcow" | "qcow2" | "vdi" | "vmdk" | "vhd" | "vhdx" | "ova" | "ovf"
+            | "hdd" | "vmem" | "vmsn" => FileCategory::VmImage,
+            _ => FileCategory::Other,
+        }
+    }
+
+    /// Human-readable label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileCategory::Video => "Video",
+            FileCategory::Image => "Image",
+            FileCategory::Archive => "Archive",
+            FileCategory::Object => "Object/Binary",
+            FileCategory::Source => "Source",
+            FileCategory::Log => "Logs",
+            FileCategory::VmImage => "VM image",
+            FileCategory::Other => "Other",
+        }
+    }
+}
+
+/// Extension key for a file name: lowercase, without the dot.
+///
+/// Rotated logs such as `app.log.1` are keyed by the extension before the
+/// number. Files without an extension (and dotfiles like `.bashrc`) get an
+/// empty key.
+pub fn extension_key(name: &str) -> String {
+    let Some((stem, ext)) = name.rsplit_once('.') else {
+        return String::new();
+    };
+    if stem.is_empty() {
+        return String::new();
+    }
+    if !ext.is_empty() && ext.chars().all(|c| c.is_ascii_digit()) {
+        if let Some((inner_stem, inner)) = stem.rsplit_once('.') {
+            if !inner_stem.is_empty() && !inner.is_empty() {
+                return inner.to_lowercase();
+            }
+        }
+    }
+    ext.to_lowercase()
+}
+
+/// Totals for one group of files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TypeStats {
+    /// Apparent size in bytes
+    pub size: u64,
+    /// Actual disk usage in bytes
+    pub disk_usage: u64,
+    /// Number of files
+    pub files: u64,
+}
+
+impl TypeStats {
+    fn add(&mut self, file: &DirEntry) {
+        self.size += file.size;
+        self.disk_usage += file.disk_usage;
+        self.files += 1;
+    }
+}
+
+/// Totals for one file category.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryStats {
+    pub category: FileCategory,
+    #[serde(flatten)]
+    pub stats: TypeStats,
+}
+
+/// Totals for one extension.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtensionStats {
+    /// Lowercase extension without the dot, empty for files without one
+    pub extension: String,
+    pub category: FileCategory,
+    #[serde(flatten)]
+    pub stats: TypeStats,
+}
+
+/// File totals of a subtree grouped by category and by extension.
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeBreakdown {
+    /// Totals over all files
+    pub total: TypeStats,
+    /// Non-empty categories, largest first
+    pub categories: Vec<CategoryStats>,
+    /// Extensions, largest first
+    pub extensions: Vec<ExtensionStats>,
+}
+
+impl TypeBreakdown {
+    /// Totals for a category (zero if no files matched).
+    pub fn category(&self, category: FileCategory) -> TypeStats {
+        self.categories
+            .iter()
+            .find(|c| c.category == category)
+            .map(|c| c.stats)
+            .unwrap_or_default()
+    }
+
+    /// Totals for an extension (zero if no files matched).
+    pub fn extension(&self, extension: &str) -> TypeStats {
+        self.extensions
+            .iter()
+            .find(|e| e.extension == extension)
+            .map(|e| e.stats)
+            .unwrap_or_default()
+    }
+
+    /// Share of the total apparent size, in percent.
+    pub fn percent_of_total(&self, stats: &TypeStats) -> f64 {
+        if self.total.size == 0 {
+            0.0
+        } else {
+            stats.size as f64 / self.total.size as f64 * 100.0
+        }
+    }
+}
+
+impl DirEntry {
+    /// Group all files below this entry by extension and category.
+    ///
+    /// A file entry yields a breakdown of just that file.
+    pub fn type_breakdown(&self) -> TypeBreakdown {
+        let mut total = TypeStats::default();
+        let mut by_extension: HashMap<String, TypeStats> = HashMap::new();
+        collect_files(self, &mut total, &mut by_extension);
+
+        let mut by_category: HashMap<FileCategory, TypeStats> = HashMap::new();
+        let mut extensions: Vec<ExtensionStats> = by_extension
+            .into_iter()
+            .map(|(extension, stats)| {
+                let category = FileCategory::from_extension(&extension);
+                let group = by_category.entry(category).or_default();
+                group.size += stats.size;
+                group.disk_usage += stats.disk_usage;
+                group.files += stats.files;
+                ExtensionStats {
+                    extension,
+                    category,
+                    stats,
+                }
+            })
+            .collect();
+        extensions.sort_by(|a, b| {
+            b.stats
+                .size
+                .cmp(&a.stats.size)
+                .then_with(|| a.extension.cmp(&b.extension))
+        });
+
+        let mut categories: Vec<CategoryStats> = by_category
+            .into_iter()
+            .map(|(category, stats)| CategoryStats { category, stats })
+            .collect();
+        categories.sort_by(|a, b| {
+            b.stats
+                .size
+                .cmp(&a.stats.size)
+                .then_with(|| a.category.cmp(&b.category))
+        });
+
+        TypeBreakdown {
+            total,
+            categories,
+            extensions,
+        }
+    }
+}
+
+fn collect_files(
+    entry: &DirEntry,
+    total: &mut TypeStats,
+    by_extension: &mut HashMap<String, TypeStats>,
+) {
+    if !entry.is_dir {
+        total.add(entry);
+        by_extension
+            .entry(extension_key(&entry.name))
+            .or_default()
+            .add(entry);
+        return;
+    }
+    for child in &entry.children {
+        collect_files(child, total, by_extension);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn file(path: &str, size: u64) -> DirEntry {
+        DirEntry::new_file(PathBuf::from(path), size, size.div_ceil(4096) * 4096, None)
+    }
+
+    fn tree() -> DirEntry {
+        let mut videos = DirEntry::new_dir(PathBuf::from("/home/videos"), None);
+        videos.children = vec![
+            file("/home/videos/a.mkv", 6000),
+            file("/home/videos/b.MKV", 4000),
+            file("/home/videos/c.mp4", 2000),
+        ];
+        videos.recalculate_totals();
+
+        let mut build = DirEntry::new_dir(PathBuf::from("/home/build"), None);
+        build.children = vec![
+            file("/home/build/main.o", 500),
+            file("/home/build/main.c", 100),
+            file("/home/build/build.log.1", 50),
+            file("/home/build/Makefile", 20),
+        ];
+        build.recalculate_totals();
+
+        let mut root = DirEntry::new_dir(PathBuf::from("/home"), None);
+        root.children = vec![videos, build, file("/home/disk.qcow2", 3000)];
+        root.recalculate_totals();
+        root
+    }
+
+    #[test]
+    fn test_extension_key() {
+        assert_eq!(extension_key("movie.MKV"), "mkv");
+        assert_eq!(extension_key("archive.tar.gz"), "gz");
+        assert_eq!(extension_key("Makefile"), "");
+        assert_eq!(extension_key(".bashrc"), "");
+        assert_eq!(extension_key("app.log.1"), "log");
+        assert_eq!(extension_key("release.2024"), "2024");
+        assert_eq!(extension_key("trailing."), "");
+    }
+
+    #[test]
+    fn test_categories_from_extension() {
+        assert_eq!(FileCategory::from_extension("mkv"), FileCategory::Video);
+        assert_eq!(FileCategory::from_extension("png"), FileCategory::Image);
+        assert_eq!(FileCategory::from_extension("zst"), FileCategory::Archive);
+        assert_eq!(FileCategory::from_extension("rlib"), FileCategory::Object);
+        assert_eq!(FileCategory::from_extension("rs"), FileCategory::Source);
+        assert_eq!(FileCategory::from_extension("log"), FileCategory::Log);
+        assert_eq!(FileCategory::from_extension("vmdk"), FileCategory::VmImage);
+        assert_eq!(FileCategory::from_extension(""), FileCategory::Other);
+        assert_eq!(FileCategory::from_extension("xyz"), FileCategory::Other);
+    }
+
+    #[test]
+    fn test_breakdown_totals() {
+        let breakdown = tree().type_breakdown();
+
+        assert_eq!(breakdown.total.files, 8);
+        assert_eq!(breakdown.total.size, 15670);
+        assert_eq!(breakdown.total.size, tree().size);
+        assert_eq!(breakdown.total.disk_usage, tree().disk_usage);
+    }
+
+    #[test]
+    fn test_breakdown_by_extension_is_case_insensitive() {
+        let breakdown = tree().type_breakdown();
+
+        let mkv = breakdown.extension("mkv");
+        assert_eq!(mkv.files, 2);
+        assert_eq!(mkv.size, 10000);
+        assert_eq!(mkv.disk_usage, 2 * 4096 + 4096);
+        assert_eq!(breakdown.extensions[0].extension, "mkv");
+        assert_eq!(breakdown.extension("log").files, 1);
+        assert_eq!(breakdown.extension("").size, 20);
+    }
+
+    #[test]
+    fn test_breakdown_by_category() {
+        let breakdown = tree().type_breakdown();
+
+        assert_eq!(breakdown.category(FileCategory::Video).size, 12000);
+        assert_eq!(breakdown.category(FileCategory::Video).files, 3);
+        assert_eq!(breakdown.category(FileCategory::VmImage).size, 3000);
+        assert_eq!(breakdown.category(FileCategory::Object).size, 500);
+        assert_eq!(breakdown.category(FileCategory::Source).size, 100);
+        assert_eq!(breakdown.category(FileCategory::Log).size, 50);
+        assert_eq!(breakdown.category(FileCategory::Other).size, 20);
+        assert_eq!(
+            breakdown.category(FileCategory::Image),
+            TypeStats::default()
+        );
+
+        let order: Vec<FileCategory> = breakdown.categories.iter().map(|c| c.category).collect();
+        assert_eq!(order[0], FileCategory::Video);
+        assert_eq!(order[1], FileCategory::VmImage);
+        assert_eq!(order.len(), 6);
+    }
+
+    #[test]
+    fn test_breakdown_percent() {
+        let breakdown = tree().type_breakdown();
+        let video = breakdown.category(FileCategory::Video);
+        let percent = breakdown.percent_of_total(&video);
+        assert!((percent - 12000.0 / 15670.0 * 100.0).abs() < 1e-9);
+
+        let empty = DirEntry::new_dir(PathBuf::from("/empty"), None).type_breakdown();
+        assert_eq!(empty.percent_of_total(&empty.total), 0.0);
+        assert!(empty.categories.is_empty());
+    }
+
+    #[test]
+    fn test_breakdown_of_single_file() {
+        let breakdown = file("/x/notes.txt", 10).type_breakdown();
+        assert_eq!(breakdown.total.files, 1);
+        assert_eq!(breakdown.extensions[0].extension, "txt");
+        assert_eq!(breakdown.categories[0].category, FileCategory::Other);
+    }
+
+    #[test]
+    fn test_breakdown_json_shape() {
+        let json = serde_json::to_value(tree().type_breakdown()).unwrap();
+        assert_eq!(json["categories"][0]["category"], "video");
+        assert_eq!(json["categories"][0]["size"], 12000);
+        assert_eq!(json["categories"][1]["category"], "vm_image");
+        assert_eq!(json["extensions"][0]["extension"], "mkv");
+        assert_eq!(json["total"]["files"], 8);
+    }
+}
@@ -0,0 +1,439 @@
+//! Persistent on-disk cache of scan results.
+//!
+//! Each cached scan is a single file under `$XDG_CACHE_HOME/rusty-sweeper/`,
+//! named after a hash of the scanned root and the options that affect the
+//! resulting tree. The tree is stored as a varint-encoded bincode payload
+//! where each node only keeps its own name; full paths are rebuilt on load.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ScannerConfig;
+use crate::error::{Result, SweeperError};
+
+use super::entry::DirEntry;
+use super::options::ScanOptions;
+
+/// Magic bytes at the start of every cache file.
+const MAGIC: &[u8; 4] = b"RSSC";
+
+/// Bumped whenever the on-disk layout changes; older files are ignored.
+const FORMAT_VERSION: u32 = 3;
+
+/// Header stored in front of the tree, used to reject mismatched files.
+#[derive(Serialize, Deserialize)]
+struct CacheHeader {
+    root: Vec<u8>,
+    options: String,
+    scanned_at: u64,
+}
+
+/// Compact form of a `DirEntry` without the redundant full path.
+#[derive(Serialize, Deserialize)]
+struct CachedNode {
+    name: Vec<u8>,
+    is_dir: bool,
+    size: u64,
+    disk_usage: u64,
+    naive_size: u64,
+    naive_disk_usage: u64,
+    file_count: u64,
+    dir_count: u64,
+    mtime: Option<(u64, u32)>,
+    ctime: Option<(u64, u32)>,
+    inode: Option<(u64, u64)>,
+    error: Option<String>,
+    children: Vec<CachedNode>,
+}
+
+impl CachedNode {
+    fn from_entry(entry: &DirEntry) -> Self {
+        Self {
+            name: entry
+                .path
+                .file_name()
+                .map(|n| n.as_bytes().to_vec())
+                .unwrap_or_default(),
+            is_dir: entry.is_dir,
+            size: entry.size,
+            disk_usage: entry.disk_usage,
+            naive_size: entry.naive_size,
+            naive_disk_usage: entry.naive_disk_usage,
+            file_count: entry.file_count,
+            dir_count: entry.dir_count,
+            mtime: entry.mtime.and_then(encode_time),
+            ctime: entry.ctime.and_then(encode_time),
+            inode: entry.inode,
+            error: entry.error.clone(),
+            children: entry.children.iter().map(Self::from_entry).collect(),
+        }
+    }
+
+    fn into_entry(self, path: PathBuf) -> DirEntry {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+        let children = self
+            .children
+            .into_iter()
+            .map(|child| {
+                let child_path = path.join(OsStr::from_bytes(&child.name));
+                child.into_entry(child_path)
+            })
+            .collect();
+
+        DirEntry {
+            path,
+            name,
+            is_dir: self.is_dir,
+            size: self.size,
+            disk_usage: self.disk_usage,
+            naive_size: self.naive_size,
+            naive_disk_usage: self.naive_disk_usage,
+            file_count: self.file_count,
+            dir_count: self.dir_count,
+            mtime: self.mtime.map(decode_time),
+            ctime: self.ctime.map(decode_time),
+            inode: self.inode,
+            children,
+            error: self.error,
+        }
+    }
+}
+
+fn encode_time(time: SystemTime) -> Option<(u64, u32)> {
+    let since_epoch = time.duration_since(UNIX_EPOCH).ok()?;
+    Some((since_epoch.as_secs(), since_epoch.subsec_nanos()))
+}
+
+fn decode_time((secs, nanos): (u64, u32)) -> SystemTime {
+    UNIX_EPOCH + Duration::new(secs, nanos)
+}
+
+/// A scan result loaded from the cache.
+#[derive(Debug, Clone)]
+pub struct CachedScan {
+    /// The cached tree.
+    pub tree: DirEntry,
+    /// When the cached scan finished.
+    pub scanned_at: SystemTime,
+}
+
+impl CachedScan {
+    /// Time elapsed since the cached scan finished.
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.scanned_at)
+            .unwrap_or_default()
+    }
+}
+
+/// On-disk cache of scan trees.
+#[derive(Debug, Clone)]
+pub struct ScanCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ScanCache {
+    /// Create a cache stored in `dir`, treating entries older than `ttl` as stale.
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// Default cache directory (`$XDG_CACHE_HOME/rusty-sweeper`).
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|d| d.join("rusty-sweeper"))
+    }
+
+    /// Build a cache from the scanner configuration.
+    ///
+    /// Returns `None` if caching is disabled or no cache directory is available.
+    pub fn from_config(config: &ScannerConfig) -> Option<Self> {
+        if !config.use_cache || config.cache_ttl == 0 {
+            return None;
+        }
+        Self::default_dir().map(|dir| Self::new(dir, Duration::from_secs(config.cache_ttl)))
+    }
+
+    /// Path of the cache file for a given root and options.
+    pub fn cache_file(&self, root: &Path, options: &ScanOptions) -> PathBuf {
+        let mut hash = Fnv64::new();
+        hash.write(root.as_os_str().as_bytes());
+        hash.write(&[0]);
+        hash.write(options_key(options).as_bytes());
+        self.dir.join(format!("scan-{:016x}.bin", hash.finish()))
+    }
+
+    /// Load a cached scan of `root`, if one exists and is younger than the TTL.
+    pub fn load(&self, root: &Path, options: &ScanOptions) -> Option<CachedScan> {
+        let root = root.canonicalize().ok()?;
+        let data = fs::read(self.cache_file(&root, options)).ok()?;
+
+        let (header, node) = decode(&data)?;
+        if header.root != root.as_os_str().as_bytes() || header.options != options_key(options) {
+            return None;
+        }
+
+        let scanned_at = UNIX_EPOCH + Duration::from_secs(header.scanned_at);
+        let age = SystemTime::now()
+            .duration_since(scanned_at)
+            .unwrap_or_default();
+        if age > self.ttl {
+            return None;
+        }
+
+        Some(CachedScan {
+            tree: node.into_entry(root),
+            scanned_at,
+        })
+    }
+
+    /// Store a scan tree. The tree's root path is used as the cache key.
+    ///
+    /// The file is written atomically so concurrent readers never see a
+    /// partially written cache.
+    pub fn store(&self, tree: &DirEntry, options: &ScanOptions) -> Result<()> {
+        self.store_at(tree, options, SystemTime::now())
+    }
+
+    fn store_at(
+        &self,
+        tree: &DirEntry,
+        options: &ScanOptions,
+        scanned_at: SystemTime,
+    ) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|e| SweeperError::Io {
+            path: self.dir.clone(),
+            source: e,
+        })?;
+
+        let header = CacheHeader {
+            root: tree.path.as_os_str().as_bytes().to_vec(),
+            options: options_key(options),
+            scanned_at: scanned_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        let encoding = bincode::DefaultOptions::new();
+        let mut data = Vec::new();
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        encoding.serialize_into(&mut data, &header)?;
+        encoding.serialize_into(&mut data, &CachedNode::from_entry(tree))?;
+
+        let path = self.cache_file(&tree.path, options);
+        let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+        let write = |p: &Path| -> std::io::Result<()> {
+            let mut file = fs::File::create(p)?;
+            file.write_all(&data)?;
+            fs::rename(p, &path)
+        };
+        write(&tmp_path).map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            SweeperError::Io {
+                path: path.clone(),
+                source: e,
+            }
+        })
+    }
+
+    /// Remove the cached scan for `root`, if any.
+    pub fn invalidate(&self, root: &Path, options: &ScanOptions) {
+        if let Ok(root) = root.canonicalize() {
+            let _ = fs::remove_file(self.cache_file(&root, options));
+        }
+    }
+}
+
+fn decode(data: &[u8]) -> Option<(CacheHeader, CachedNode)> {
+    let rest = data.strip_prefix(MAGIC.as_slice())?;
+    let (version, mut rest) = rest.split_first_chunk::<4>()?;
+    if u32::from_le_bytes(*version) != FORMAT_VERSION {
+        return None;
+    }
+
+    let encoding = bincode::DefaultOptions::new();
+    let header: CacheHeader = encoding.deserialize_from(&mut rest).ok()?;
+    let node: CachedNode = encoding.deserialize_from(&mut rest).ok()?;
+    Some((header, node))
+}
+
+/// Canonical description of the options that change the scanned tree.
+///
+/// Thread count is deliberately left out since it doesn't affect results.
+fn options_key(options: &ScanOptions) -> String {
+    format!(
+        "depth={:?};hidden={};one_fs={};follow={};exclude={:?}",
+        options.max_depth,
+        options.include_hidden,
+        options.one_file_system,
+        options.follow_symlinks,
+        options.exclude_patterns
+    )
+}
+
+/// FNV-1a, used instead of `DefaultHasher` because file names must be
+/// stable across Rust releases.
+struct Fnv64(u64);
+
+impl Fnv64 {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::scan_directory;
+    use tempfile::TempDir;
+
+    fn create_scanned_tree() -> (TempDir, DirEntry) {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        fs::write(dir.path().join("sub/b.bin"), [0u8; 300]).unwrap();
+
+        let tree = scan_directory(dir.path(), &ScanOptions::default()).unwrap();
+        (dir, tree)
+    }
+
+    #[test]
+    fn test_store_and_load_roundtrip() {
+        let (dir, tree) = create_scanned_tree();
+        let cache_dir = TempDir::new().unwrap();
+        let cache = ScanCache::new(cache_dir.path().to_path_buf(), Duration::from_secs(60));
+        let options = ScanOptions::default();
+
+        cache.store(&tree, &options).unwrap();
+        let cached = cache.load(dir.path(), &options).unwrap();
+
+        assert_eq!(cached.tree.path, tree.path);
+        assert_eq!(cached.tree.size, tree.size);
+        assert_eq!(cached.tree.disk_usage, tree.disk_usage);
+        assert_eq!(cached.tree.file_count, tree.file_count);
+        assert_eq!(cached.tree.dir_count, tree.dir_count);
+        assert_eq!(cached.tree.mtime, tree.mtime);
+        assert_eq!(cached.tree.ctime, tree.ctime);
+
+        let sub = cached
+            .tree
+            .children
+            .iter()
+            .find(|c| c.name == "sub")
+            .unwrap();
+        assert_eq!(sub.path, tree.path.join("sub"));
+        assert_eq!(sub.children[0].path, tree.path.join("sub/b.bin"));
+        assert_eq!(sub.children[0].size, 300);
+        assert!(cached.age() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_load_missing_returns_none() {
+        let dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let cache = ScanCache::new(cache_dir.path().to_path_buf(), Duration::from_secs(60));
+
+        assert!(cache.load(dir.path(), &ScanOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_load_is_keyed_by_options() {
+        let (dir, tree) = create_scanned_tree();
+        let cache_dir = TempDir::new().unwrap();
+        let cache = ScanCache::new(cache_dir.path().to_path_buf(), Duration::from_secs(60));
+
+        cache.store(&tree, &ScanOptions::default()).unwrap();
+
+        let hidden = ScanOptions::default().with_hidden(true);
+        assert!(cache.load(dir.path(), &hidden).is_none());
+        // Thread count doesn't change the result, so it shares the entry
+        let threads = ScanOptions::default().with_threads(4);
+        assert!(cache.load(dir.path(), &threads).is_some());
+    }
+
+    #[test]
+    fn test_load_rejects_expired_entry() {
+        let (dir, tree) = create_scanned_tree();
+        let cache_dir = TempDir::new().unwrap();
+        let cache = ScanCache::new(cache_dir.path().to_path_buf(), Duration::from_secs(3600));
+        let options = ScanOptions::default();
+        let two_hours_ago = SystemTime::now() - Duration::from_secs(7200);
+
+        cache.store_at(&tree, &options, two_hours_ago).unwrap();
+
+        assert!(cache.load(dir.path(), &options).is_none());
+    }
+
+    #[test]
+    fn test_load_rejects_corrupt_file() {
+        let (dir, tree) = create_scanned_tree();
+        let cache_dir = TempDir::new().unwrap();
+        let cache = ScanCache::new(cache_dir.path().to_path_buf(), Duration::from_secs(60));
+        let options = ScanOptions::default();
+
+        cache.store(&tree, &options).unwrap();
+        let file = cache.cache_file(&tree.path, &options);
+        let mut data = fs::read(&file).unwrap();
+        data.truncate(data.len() / 2);
+        fs::write(&file, data).unwrap();
+
+        assert!(cache.load(dir.path(), &options).is_none());
+    }
+
+    #[test]
+    fn test_invalidate_removes_entry() {
+        let (dir, tree) = create_scanned_tree();
+        let cache_dir = TempDir::new().unwrap();
+        let cache = ScanCache::new(cache_dir.path().to_path_buf(), Duration::from_secs(60));
+        let options = ScanOptions::default();
+
+        cache.store(&tree, &options).unwrap();
+        cache.invalidate(dir.path(), &options);
+
+        assert!(cache.load(dir.path(), &options).is_none());
+    }
+
+    #[test]
+    fn test_from_config_respects_use_cache() {
+        let config = ScannerConfig {
+            use_cache: false,
+            ..Default::default()
+        };
+        assert!(ScanCache::from_config(&config).is_none());
+    }
+
+    #[test]
+    fn test_cache_file_is_stable() {
+        let cache = ScanCache::new(PathBuf::from("/cache"), Duration::from_secs(1));
+        let options = ScanOptions::default();
+        let a = cache.cache_file(Path::new("/home"), &options);
+        let b = cache.cache_file(Path::new("/home"), &options);
+        let c = cache.cache_file(Path::new("/tmp"), &options);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.starts_with("/cache"));
+    }
+}
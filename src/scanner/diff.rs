@@ -0,0 +1,346 @@
+//! Comparison of two scans of the same directory tree.
+//!
+//! Directories are matched by name relative to the scanned root, so a
+//! snapshot can be compared against a scan of a moved or remounted tree.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::{Result, SweeperError};
+
+use super::entry::DirEntry;
+
+/// How a directory changed between two scans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Only present in the newer scan
+    Added,
+    /// Only present in the older scan
+    Removed,
+    /// Larger in the newer scan
+    Grown,
+    /// Smaller in the newer scan
+    Shrunk,
+    /// Same size, though subdirectories may have changed
+    Unchanged,
+}
+
+/// Change of a single directory between two scans.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirChange {
+    /// Path in the newer scan (in the older scan for removed directories)
+    pub path: PathBuf,
+
+    /// Directory name
+    pub name: String,
+
+    /// Kind of change
+    pub kind: ChangeKind,
+
+    /// Size in the older scan (0 if added)
+    pub old_size: u64,
+
+    /// Size in the newer scan (0 if removed)
+    pub new_size: u64,
+
+    /// Size difference in bytes (positive = growth)
+    pub delta: i64,
+
+    /// Size difference relative to the old size, in percent.
+    /// `None` if the directory was empty or absent before.
+    pub percent: Option<f64>,
+
+    /// Changed subdirectories, largest absolute change first.
+    /// Not descended into for added or removed directories.
+    #[serde(skip)]
+    pub children: Vec<DirChange>,
+}
+
+impl DirChange {
+    fn new(path: PathBuf, name: String, old_size: u64, new_size: u64, kind: ChangeKind) -> Self {
+        let delta = new_size as i64 - old_size as i64;
+        let percent = (old_size > 0).then(|| delta as f64 / old_size as f64 * 100.0);
+        Self {
+            path,
+            name,
+            kind,
+            old_size,
+            new_size,
+            delta,
+            percent,
+            children: vec![],
+        }
+    }
+
+    /// Absolute size difference in bytes
+    pub fn abs_delta(&self) -> u64 {
+        self.delta.unsigned_abs()
+    }
+
+    fn collect<'a>(&'a self, out: &mut Vec<&'a DirChange>) {
+        for child in &self.children {
+            if child.kind != ChangeKind::Unchanged {
+                out.push(child);
+            }
+            child.collect(out);
+        }
+    }
+}
+
+/// Result of comparing two scans.
+#[derive(Debug, Clone)]
+pub struct ScanDiff {
+    /// Change of the scanned root, with changed subdirectories as children
+    pub root: DirChange,
+}
+
+impl ScanDiff {
+    /// All changed directories below the root, largest absolute change first.
+    pub fn changes(&self) -> Vec<&DirChange> {
+        let mut changes = Vec::new();
+        self.root.collect(&mut changes);
+        changes.sort_by_key(|c| std::cmp::Reverse(c.abs_delta()));
+        changes
+    }
+
+    /// Number of changed directories of the given kind.
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes().iter().filter(|c| c.kind == kind).count()
+    }
+}
+
+impl DirEntry {
+    /// Compare this (older) scan against a newer scan of the same tree.
+    pub fn diff(&self, newer: &DirEntry) -> ScanDiff {
+        ScanDiff {
+            root: diff_dirs(self, newer),
+        }
+    }
+}
+
+fn diff_dirs(old: &DirEntry, new: &DirEntry) -> DirChange {
+    let kind = match new.size.cmp(&old.size) {
+        std::cmp::Ordering::Greater => ChangeKind::Grown,
+        std::cmp::Ordering::Less => ChangeKind::Shrunk,
+        std::cmp::Ordering::Equal => ChangeKind::Unchanged,
+    };
+    let mut change = DirChange::new(new.path.clone(), new.name.clone(), old.size, new.size, kind);
+
+    let mut old_dirs: HashMap<&str, &DirEntry> = old
+        .children
+        .iter()
+        .filter(|c| c.is_dir)
+        .map(|c| (c.name.as_str(), c))
+        .collect();
+
+    for new_child in new.children.iter().filter(|c| c.is_dir) {
+        let child = match old_dirs.remove(new_child.name.as_str()) {
+            Some(old_child) => diff_dirs(old_child, new_child),
+            None => DirChange::new(
+                new_child.path.clone(),
+                new_child.name.clone(),
+                0,
+                new_child.size,
+                ChangeKind::Added,
+            ),
+        };
+        if child.kind != ChangeKind::Unchanged || !child.children.is_empty() {
+            change.children.push(child);
+        }
+    }
+
+    for old_child in old_dirs.into_values() {
+        change.children.push(DirChange::new(
+            old_child.path.clone(),
+            old_child.name.clone(),
+            old_child.size,
+            0,
+            ChangeKind::Removed,
+        ));
+    }
+
+    change
+        .children
+        .sort_by_key(|c| std::cmp::Reverse(c.abs_delta()));
+    change
+}
+
+/// Load a scan snapshot saved as JSON (e.g. by `scan --json`).
+pub fn read_snapshot(path: &Path) -> Result<DirEntry> {
+    let file = File::open(path).map_err(|e| SweeperError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::format_json;
+
+    fn dir(path: &str, children: Vec<DirEntry>) -> DirEntry {
+        let mut entry = DirEntry::new_dir(PathBuf::from(path), None);
+        entry.children = children;
+        entry.recalculate_totals();
+        entry
+    }
+
+    fn file(path: &str, size: u64) -> DirEntry {
+        DirEntry::new_file(PathBuf::from(path), size, size, None)
+    }
+
+    fn old_tree() -> DirEntry {
+        dir(
+            "/data",
+            vec![
+                dir("/data/logs", vec![file("/data/logs/a.log", 100)]),
+                dir("/data/cache", vec![file("/data/cache/blob", 1000)]),
+                dir("/data/stable", vec![file("/data/stable/x", 50)]),
+                dir("/data/old", vec![file("/data/old/y", 300)]),
+            ],
+        )
+    }
+
+    fn new_tree() -> DirEntry {
+        dir(
+            "/data",
+            vec![
+                dir("/data/logs", vec![file("/data/logs/a.log", 600)]),
+                dir("/data/cache", vec![file("/data/cache/blob", 400)]),
+                dir("/data/stable", vec![file("/data/stable/x", 50)]),
+                dir("/data/new", vec![file("/data/new/z", 2000)]),
+            ],
+        )
+    }
+
+    fn find<'a>(diff: &'a ScanDiff, name: &str) -> &'a DirChange {
+        diff.changes().into_iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_diff_root_totals() {
+        let diff = old_tree().diff(&new_tree());
+
+        assert_eq!(diff.root.old_size, 1450);
+        assert_eq!(diff.root.new_size, 3050);
+        assert_eq!(diff.root.delta, 1600);
+        assert_eq!(diff.root.kind, ChangeKind::Grown);
+    }
+
+    #[test]
+    fn test_diff_change_kinds() {
+        let diff = old_tree().diff(&new_tree());
+
+        assert_eq!(find(&diff, "logs").kind, ChangeKind::Grown);
+        assert_eq!(find(&diff, "cache").kind, ChangeKind::Shrunk);
+        assert_eq!(find(&diff, "new").kind, ChangeKind::Added);
+        assert_eq!(find(&diff, "old").kind, ChangeKind::Removed);
+        assert!(!diff.changes().iter().any(|c| c.name == "stable"));
+    }
+
+    #[test]
+    fn test_diff_deltas_and_percent() {
+        let diff = old_tree().diff(&new_tree());
+
+        let logs = find(&diff, "logs");
+        assert_eq!(logs.delta, 500);
+        assert_eq!(logs.percent, Some(500.0));
+
+        let cache = find(&diff, "cache");
+        assert_eq!(cache.delta, -600);
+        assert_eq!(cache.percent, Some(-60.0));
+
+        let added = find(&diff, "new");
+        assert_eq!(added.old_size, 0);
+        assert_eq!(added.percent, None);
+
+        let removed = find(&diff, "old");
+        assert_eq!(removed.delta, -300);
+        assert_eq!(removed.path, PathBuf::from("/data/old"));
+    }
+
+    #[test]
+    fn test_diff_sorted_by_absolute_change() {
+        let diff = old_tree().diff(&new_tree());
+        let names: Vec<&str> = diff.changes().iter().map(|c| c.name.as_str()).collect();
+
+        assert_eq!(names, vec!["new", "cache", "logs", "old"]);
+    }
+
+    #[test]
+    fn test_diff_nested_changes() {
+        let old = dir(
+            "/r",
+            vec![dir(
+                "/r/a",
+                vec![
+                    dir("/r/a/grow", vec![file("/r/a/grow/f", 10)]),
+                    dir("/r/a/shrink", vec![file("/r/a/shrink/f", 10)]),
+                ],
+            )],
+        );
+        let new = dir(
+            "/r",
+            vec![dir(
+                "/r/a",
+                vec![
+                    dir("/r/a/grow", vec![file("/r/a/grow/f", 20)]),
+                    dir("/r/a/shrink", vec![file("/r/a/shrink/f", 0)]),
+                ],
+            )],
+        );
+
+        let diff = old.diff(&new);
+
+        // "a" kept its size but is still reported as a container
+        assert_eq!(diff.root.children.len(), 1);
+        assert_eq!(diff.root.children[0].kind, ChangeKind::Unchanged);
+        assert_eq!(diff.root.children[0].children.len(), 2);
+        assert_eq!(diff.changes().len(), 2);
+        assert_eq!(diff.count(ChangeKind::Grown), 1);
+        assert_eq!(diff.count(ChangeKind::Shrunk), 1);
+    }
+
+    #[test]
+    fn test_diff_identical_trees() {
+        let diff = old_tree().diff(&old_tree());
+
+        assert_eq!(diff.root.kind, ChangeKind::Unchanged);
+        assert!(diff.changes().is_empty());
+    }
+
+    #[test]
+    fn test_read_snapshot_roundtrip() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let snapshot = tmp.path().join("snap.json");
+        std::fs::write(&snapshot, format_json(&old_tree(), false).unwrap()).unwrap();
+
+        let loaded = read_snapshot(&snapshot).unwrap();
+
+        assert_eq!(loaded.size, 1450);
+        assert_eq!(loaded.children.len(), 4);
+        assert!(old_tree().diff(&loaded).changes().is_empty());
+    }
+
+    #[test]
+    fn test_read_snapshot_missing_file() {
+        let err = read_snapshot(Path::new("/nonexistent/snap.json")).unwrap_err();
+        assert!(matches!(err, SweeperError::Io { .. }));
+    }
+
+    #[test]
+    fn test_read_snapshot_invalid_json() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let snapshot = tmp.path().join("snap.json");
+        std::fs::write(&snapshot, "not json").unwrap();
+
+        let err = read_snapshot(&snapshot).unwrap_err();
+        assert!(matches!(err, SweeperError::Json(_)));
+    }
+}
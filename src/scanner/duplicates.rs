@@ -0,0 +1,654 @@
+//! Duplicate file detection over a scanned tree.
+//!
+//! Candidates are narrowed in three passes so that most files are never read
+//! in full:
+//! 1. bucket by size (from the tree, no I/O)
+//! 2. hash the first `partial_bytes` of each file in a bucket
+//! 3. hash the whole file for buckets that still collide
+//!
+//! Paths that are hard links to an inode already seen are skipped: they
+//! share storage already and replacing them would reclaim nothing.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use super::entry::DirEntry;
+
+/// Options for duplicate detection.
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    /// Ignore files smaller than this many bytes
+    pub min_size: u64,
+
+    /// Number of leading bytes hashed in the partial pass
+    pub partial_bytes: u64,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            min_size: 1,
+            partial_bytes: 16 * 1024,
+        }
+    }
+}
+
+impl DuplicateOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_min_size(mut self, bytes: u64) -> Self {
+        // Empty files are trivially identical; never report them
+        self.min_size = bytes.max(1);
+        self
+    }
+
+    pub fn with_partial_bytes(mut self, bytes: u64) -> Self {
+        self.partial_bytes = bytes.max(1);
+        self
+    }
+}
+
+/// A set of files with identical content.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    /// Size of each copy in bytes
+    pub size: u64,
+
+    /// BLAKE3 hash of the content (hex)
+    pub hash: String,
+
+    /// Bytes freed by keeping one copy: `size * (files - 1)`
+    pub reclaimable: u64,
+
+    /// Paths of all copies, sorted; the first one is kept when linking
+    pub files: Vec<PathBuf>,
+}
+
+impl DuplicateGroup {
+    fn new(size: u64, hash: blake3::Hash, mut files: Vec<PathBuf>) -> Self {
+        files.sort();
+        Self {
+            size,
+            hash: hash.to_hex().to_string(),
+            reclaimable: size * (files.len() as u64 - 1),
+            files,
+        }
+    }
+}
+
+/// Result of a duplicate search.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DuplicateReport {
+    /// Duplicate groups, most reclaimable first
+    pub groups: Vec<DuplicateGroup>,
+
+    /// Files read at least partially
+    pub files_hashed: usize,
+
+    /// Paths skipped because they are hard links to an inode already seen
+    pub hardlinks_skipped: usize,
+
+    /// Files that could not be read
+    pub errors: usize,
+}
+
+impl DuplicateReport {
+    /// Total bytes freed by keeping one copy per group
+    pub fn reclaimable(&self) -> u64 {
+        self.groups.iter().map(|g| g.reclaimable).sum()
+    }
+
+    /// Number of redundant copies over all groups
+    pub fn duplicate_files(&self) -> usize {
+        self.groups.iter().map(|g| g.files.len() - 1).sum()
+    }
+}
+
+/// A file that survived the size bucketing pass.
+#[derive(Debug, Clone)]
+struct Candidate {
+    path: PathBuf,
+    size: u64,
+}
+
+/// Find files with identical content in a scanned tree.
+///
+/// Sizes come from the tree, so it should be reasonably fresh; content is
+/// always read from disk.
+pub fn find_duplicates(tree: &DirEntry, options: &DuplicateOptions) -> DuplicateReport {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    collect_files(tree, options.min_size, &mut by_size);
+
+    let mut report = DuplicateReport::default();
+    let mut seen_inodes: HashSet<(u64, u64)> = HashSet::new();
+    let mut buckets: Vec<Vec<Candidate>> = Vec::new();
+
+    let mut sizes: Vec<u64> = by_size.keys().copied().collect();
+    sizes.sort_unstable();
+    for size in sizes {
+        let mut paths = by_size.remove(&size).unwrap_or_default();
+        if paths.len() < 2 {
+            continue;
+        }
+        paths.sort();
+
+        let mut bucket = Vec::with_capacity(paths.len());
+        for path in paths {
+            let metadata = match fs::symlink_metadata(&path) {
+                Ok(m) if m.is_file() => m,
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::debug!(path = %path.display(), error = %e, "Skipping duplicate candidate");
+                    report.errors += 1;
+                    continue;
+                }
+            };
+            if !seen_inodes.insert((metadata.dev(), metadata.ino())) {
+                report.hardlinks_skipped += 1;
+                continue;
+            }
+            bucket.push(Candidate { path, size });
+        }
+        if bucket.len() > 1 {
+            buckets.push(bucket);
+        }
+    }
+
+    let partial_bytes = options.partial_bytes;
+    let partial = split_by_hash(buckets, &mut report, |c| {
+        hash_file(&c.path, Some(partial_bytes))
+    });
+
+    // The partial hash already covers small files completely
+    let (complete, needs_full): (Vec<_>, Vec<_>) = partial
+        .into_iter()
+        .partition(|(_, group)| group[0].size <= partial_bytes);
+    let full = split_by_hash(
+        needs_full.into_iter().map(|(_, group)| group).collect(),
+        &mut report,
+        |c| hash_file(&c.path, None),
+    );
+
+    report.groups = complete
+        .into_iter()
+        .chain(full)
+        .map(|(hash, group)| {
+            let size = group[0].size;
+            DuplicateGroup::new(size, hash, group.into_iter().map(|c| c.path).collect())
+        })
+        .collect();
+    report.groups.sort_by(|a, b| {
+        b.reclaimable
+            .cmp(&a.reclaimable)
+            .then_with(|| a.files[0].cmp(&b.files[0]))
+    });
+
+    report
+}
+
+fn collect_files(entry: &DirEntry, min_size: u64, by_size: &mut HashMap<u64, Vec<PathBuf>>) {
+    if !entry.is_dir {
+        // `size` is zero for extra hard links; `naive_size` is the real file size
+        if entry.naive_size >= min_size && entry.error.is_none() {
+            by_size
+                .entry(entry.naive_size)
+                .or_default()
+                .push(entry.path.clone());
+        }
+        return;
+    }
+    for child in &entry.children {
+        collect_files(child, min_size, by_size);
+    }
+}
+
+/// Hash every candidate and split each group by hash, dropping singletons.
+fn split_by_hash<F>(
+    groups: Vec<Vec<Candidate>>,
+    report: &mut DuplicateReport,
+    hash: F,
+) -> Vec<(blake3::Hash, Vec<Candidate>)>
+where
+    F: Fn(&Candidate) -> io::Result<blake3::Hash> + Sync,
+{
+    let hashed: Vec<(usize, Candidate, io::Result<blake3::Hash>)> = groups
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, group)| group.into_iter().map(move |c| (index, c)))
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(index, candidate)| {
+            let result = hash(&candidate);
+            (index, candidate, result)
+        })
+        .collect();
+
+    let mut split: HashMap<(usize, blake3::Hash), Vec<Candidate>> = HashMap::new();
+    for (index, candidate, result) in hashed {
+        report.files_hashed += 1;
+        match result {
+            Ok(hash) => split.entry((index, hash)).or_default().push(candidate),
+            Err(e) => {
+                tracing::debug!(path = %candidate.path.display(), error = %e, "Failed to hash file");
+                report.errors += 1;
+            }
+        }
+    }
+
+    split
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|((_, hash), group)| (hash, group))
+        .collect()
+}
+
+/// Hash a file's content, or only its first `limit` bytes.
+fn hash_file(path: &Path, limit: Option<u64>) -> io::Result<blake3::Hash> {
+    let file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    match limit {
+        Some(limit) => io::copy(&mut file.take(limit), &mut hasher)?,
+        None => io::copy(&mut io::BufReader::new(file), &mut hasher)?,
+    };
+    Ok(hasher.finalize())
+}
+
+/// How duplicates are replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMode {
+    /// Hard link to the kept copy (same filesystem only; copies share metadata)
+    Hardlink,
+    /// Copy-on-write clone of the kept copy (btrfs, XFS, ...)
+    Reflink,
+}
+
+impl LinkMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "hardlink" => Some(LinkMode::Hardlink),
+            "reflink" => Some(LinkMode::Reflink),
+            _ => None,
+        }
+    }
+}
+
+/// A duplicate that could not be replaced.
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Outcome of replacing duplicates with links.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkSummary {
+    /// Number of duplicates replaced
+    pub replaced: usize,
+    /// Bytes no longer stored separately
+    pub bytes: u64,
+    /// Duplicates left untouched
+    pub failed: Vec<LinkFailure>,
+}
+
+impl LinkSummary {
+    fn fail(&mut self, path: &Path, error: impl ToString) {
+        self.failed.push(LinkFailure {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        });
+    }
+}
+
+impl DuplicateGroup {
+    /// Replace every copy except the first with a link to the first.
+    ///
+    /// Content is re-hashed before anything is touched, so files changed
+    /// since the search are left alone. Each duplicate is swapped in with an
+    /// atomic rename; a failure leaves it as it was.
+    pub fn replace_with_links(&self, mode: LinkMode) -> LinkSummary {
+        let mut summary = LinkSummary::default();
+        let Some((original, duplicates)) = self.files.split_first() else {
+            return summary;
+        };
+
+        match hash_file(original, None) {
+            Ok(hash) if hash.to_hex().as_str() == self.hash => {}
+            Ok(_) => {
+                for path in duplicates {
+                    summary.fail(path, format!("{} changed since scan", original.display()));
+                }
+                return summary;
+            }
+            Err(e) => {
+                for path in duplicates {
+                    summary.fail(path, format!("{}: {}", original.display(), e));
+                }
+                return summary;
+            }
+        }
+
+        for duplicate in duplicates {
+            match link_duplicate(original, duplicate, &self.hash, mode) {
+                Ok(()) => {
+                    summary.replaced += 1;
+                    summary.bytes += self.size;
+                }
+                Err(e) => summary.fail(duplicate, e),
+            }
+        }
+        summary
+    }
+}
+
+impl DuplicateReport {
+    /// Replace duplicates in every group with links to the kept copy.
+    pub fn replace_with_links(&self, mode: LinkMode) -> LinkSummary {
+        let mut summary = LinkSummary::default();
+        for group in &self.groups {
+            let result = group.replace_with_links(mode);
+            summary.replaced += result.replaced;
+            summary.bytes += result.bytes;
+            summary.failed.extend(result.failed);
+        }
+        summary
+    }
+}
+
+fn link_duplicate(original: &Path, duplicate: &Path, hash: &str, mode: LinkMode) -> io::Result<()> {
+    if hash_file(duplicate, None)?.to_hex().as_str() != hash {
+        return Err(io::Error::other("changed since scan"));
+    }
+
+    let tmp = temp_sibling(duplicate);
+    match mode {
+        LinkMode::Hardlink => {
+            // A link shares the kept copy's owner and mode, which must not
+            // widen access to the duplicate's content
+            let (kept, replaced) = (fs::metadata(original)?, fs::metadata(duplicate)?);
+            if (kept.uid(), kept.gid(), kept.mode())
+                != (replaced.uid(), replaced.gid(), replaced.mode())
+            {
+                return Err(io::Error::other(format!(
+                    "owner or mode differs from {}",
+                    original.display()
+                )));
+            }
+            fs::hard_link(original, &tmp)?
+        }
+        LinkMode::Reflink => {
+            reflink(original, &tmp)?;
+            if let Err(e) = fs::set_permissions(&tmp, fs::metadata(duplicate)?.permissions()) {
+                let _ = fs::remove_file(&tmp);
+                return Err(e);
+            }
+        }
+    }
+
+    if let Err(e) = fs::rename(&tmp, duplicate) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Temporary path next to `path`, on the same filesystem so rename is atomic.
+fn temp_sibling(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.rusty-sweeper-link", name))
+}
+
+/// Clone `src` into a new file at `dst` with the FICLONE ioctl.
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    let source = File::open(src)?;
+    let target = OpenOptions::new().write(true).create_new(true).open(dst)?;
+
+    // SAFETY: both descriptors are open for the duration of the call, and
+    // FICLONE takes the source descriptor as its integer argument.
+    let ret = unsafe { libc::ioctl(target.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) };
+    if ret != 0 {
+        let err = io::Error::last_os_error();
+        drop(target);
+        let _ = fs::remove_file(dst);
+        return Err(err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::{scan_directory, ScanOptions};
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn scan(dir: &Path) -> DirEntry {
+        scan_directory(dir, &ScanOptions::new().with_hidden(true)).unwrap()
+    }
+
+    #[test]
+    fn test_finds_identical_files() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "a/data.bin", b"same content here");
+        write(tmp.path(), "b/data.bin", b"same content here");
+        write(tmp.path(), "c/copy.bin", b"same content here");
+        write(tmp.path(), "other.bin", b"different content");
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+
+        assert_eq!(report.groups.len(), 1);
+        let group = &report.groups[0];
+        assert_eq!(group.files.len(), 3);
+        assert_eq!(group.size, 17);
+        assert_eq!(group.reclaimable, 34);
+        assert!(group.files[0].ends_with("a/data.bin"));
+        assert_eq!(report.reclaimable(), 34);
+        assert_eq!(report.duplicate_files(), 2);
+    }
+
+    #[test]
+    fn test_same_size_different_content_not_grouped() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "x", b"aaaa");
+        write(tmp.path(), "y", b"bbbb");
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+
+        assert!(report.groups.is_empty());
+        assert_eq!(report.files_hashed, 2);
+    }
+
+    #[test]
+    fn test_full_hash_separates_equal_prefixes() {
+        let tmp = TempDir::new().unwrap();
+        let mut one = vec![7u8; 4096];
+        let mut two = one.clone();
+        one.push(1);
+        two.push(2);
+        write(tmp.path(), "one", &one);
+        write(tmp.path(), "two", &two);
+        write(tmp.path(), "three", &one);
+
+        let options = DuplicateOptions::new().with_partial_bytes(1024);
+        let report = find_duplicates(&scan(tmp.path()), &options);
+
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].files.len(), 2);
+        assert!(report.groups[0].files.iter().all(|p| !p.ends_with("two")));
+        // 3 partial reads, then 3 full reads of the colliding bucket
+        assert_eq!(report.files_hashed, 6);
+    }
+
+    #[test]
+    fn test_min_size_and_empty_files() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "empty1", b"");
+        write(tmp.path(), "empty2", b"");
+        write(tmp.path(), "small1", b"abc");
+        write(tmp.path(), "small2", b"abc");
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].size, 3);
+
+        let options = DuplicateOptions::new().with_min_size(4);
+        let report = find_duplicates(&scan(tmp.path()), &options);
+        assert!(report.groups.is_empty());
+    }
+
+    #[test]
+    fn test_hardlinks_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let original = write(tmp.path(), "original", b"linked content");
+        fs::hard_link(&original, tmp.path().join("link")).unwrap();
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        assert!(report.groups.is_empty());
+        assert_eq!(report.hardlinks_skipped, 1);
+
+        // A real copy is still found, reported once alongside the linked inode
+        write(tmp.path(), "copy", b"linked content");
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].files.len(), 2);
+        assert_eq!(report.groups[0].reclaimable, 14);
+    }
+
+    #[test]
+    fn test_groups_sorted_by_reclaimable() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "s1", b"small");
+        write(tmp.path(), "s2", b"small");
+        write(tmp.path(), "b1", &[1u8; 1000]);
+        write(tmp.path(), "b2", &[1u8; 1000]);
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.groups[0].size, 1000);
+        assert_eq!(report.groups[1].size, 5);
+    }
+
+    #[test]
+    fn test_unreadable_file_counted_as_error() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "a", b"content");
+        write(tmp.path(), "b", b"content");
+        let tree = scan(tmp.path());
+        fs::remove_file(tmp.path().join("b")).unwrap();
+
+        let report = find_duplicates(&tree, &DuplicateOptions::default());
+
+        assert!(report.groups.is_empty());
+        assert_eq!(report.errors, 1);
+    }
+
+    #[test]
+    fn test_replace_with_hardlinks() {
+        let tmp = TempDir::new().unwrap();
+        let a = write(tmp.path(), "a", b"dedupe me");
+        let b = write(tmp.path(), "b", b"dedupe me");
+        let c = write(tmp.path(), "c", b"dedupe me");
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        let summary = report.replace_with_links(LinkMode::Hardlink);
+
+        assert_eq!(summary.replaced, 2);
+        assert_eq!(summary.bytes, 18);
+        assert!(summary.failed.is_empty());
+        let ino = fs::metadata(&a).unwrap().ino();
+        assert_eq!(fs::metadata(&b).unwrap().ino(), ino);
+        assert_eq!(fs::metadata(&c).unwrap().ino(), ino);
+        assert_eq!(fs::read(&c).unwrap(), b"dedupe me");
+
+        // Nothing left to reclaim
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        assert!(report.groups.is_empty());
+        assert_eq!(report.hardlinks_skipped, 2);
+    }
+
+    #[test]
+    fn test_hardlinks_keep_files_with_other_modes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let a = write(tmp.path(), "a", b"secret data");
+        let b = write(tmp.path(), "b", b"secret data");
+        fs::set_permissions(&a, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::set_permissions(&b, fs::Permissions::from_mode(0o600)).unwrap();
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        let summary = report.replace_with_links(LinkMode::Hardlink);
+
+        assert_eq!(summary.replaced, 0);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].path, b);
+        assert!(summary.failed[0].error.contains("owner or mode differs"));
+        assert_ne!(
+            fs::metadata(&a).unwrap().ino(),
+            fs::metadata(&b).unwrap().ino()
+        );
+        assert_eq!(fs::metadata(&b).unwrap().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_replace_skips_changed_files() {
+        let tmp = TempDir::new().unwrap();
+        let a = write(tmp.path(), "a", b"original");
+        let b = write(tmp.path(), "b", b"original");
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        fs::write(&b, b"modified").unwrap();
+        let summary = report.replace_with_links(LinkMode::Hardlink);
+
+        assert_eq!(summary.replaced, 0);
+        assert_eq!(summary.failed.len(), 1);
+        assert!(summary.failed[0].error.contains("changed since scan"));
+        assert_ne!(
+            fs::metadata(&a).unwrap().ino(),
+            fs::metadata(&b).unwrap().ino()
+        );
+        assert_eq!(fs::read(&b).unwrap(), b"modified");
+    }
+
+    #[test]
+    fn test_replace_with_reflinks_is_safe_without_support() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "a", b"clone me");
+        let b = write(tmp.path(), "b", b"clone me");
+
+        let report = find_duplicates(&scan(tmp.path()), &DuplicateOptions::default());
+        let summary = report.replace_with_links(LinkMode::Reflink);
+
+        // Succeeds on CoW filesystems, fails cleanly elsewhere
+        assert_eq!(summary.replaced + summary.failed.len(), 1);
+        assert_eq!(fs::read(&b).unwrap(), b"clone me");
+        assert!(!temp_sibling(&b).exists());
+    }
+
+    #[test]
+    fn test_link_mode_parse() {
+        assert_eq!(LinkMode::parse("hardlink"), Some(LinkMode::Hardlink));
+        assert_eq!(LinkMode::parse("REFLINK"), Some(LinkMode::Reflink));
+        assert_eq!(LinkMode::parse("symlink"), None);
+    }
+}
@@ -1,10 +1,10 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::SystemTime;
 
 /// Represents a file or directory with its metadata and size information.
 /// This is the core data structure for the disk scanner.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirEntry {
     /// Full path to the file or directory
     pub path: PathBuf,
@@ -21,6 +21,14 @@ pub struct DirEntry {
     /// Actual disk usage in bytes (accounting for block size)
     pub disk_usage: u64,
 
+    /// Apparent size counting every hard link separately
+    #[serde(default)]
+    pub naive_size: u64,
+
+    /// Disk usage counting every hard link separately
+    #[serde(default)]
+    pub naive_disk_usage: u64,
+
     /// Number of files (1 for files, recursive count for dirs)
     pub file_count: u64,
 
@@ -30,7 +38,16 @@ pub struct DirEntry {
     /// Last modification time
     pub mtime: Option<SystemTime>,
 
+    /// Last status change time, used to detect changes on incremental rescans
+    pub ctime: Option<SystemTime>,
+
+    /// Device and inode number of a file with several hard links, so that
+    /// incremental rescans can deduplicate the entries they reuse
+    #[serde(default)]
+    pub inode: Option<(u64, u64)>,
+
     /// Child entries (empty for files)
+    #[serde(default)]
     pub children: Vec<DirEntry>,
 
     /// Error message if we couldn't read this entry (permission denied, etc.)
@@ -51,9 +68,13 @@ impl DirEntry {
             is_dir: false,
             size,
             disk_usage,
+            naive_size: size,
+            naive_disk_usage: disk_usage,
             file_count: 1,
             dir_count: 0,
             mtime,
+            ctime: None,
+            inode: None,
             children: vec![],
             error: None,
         }
@@ -72,9 +93,13 @@ impl DirEntry {
             is_dir: true,
             size: 0,
             disk_usage: 0,
+            naive_size: 0,
+            naive_disk_usage: 0,
             file_count: 0,
             dir_count: 0,
             mtime,
+            ctime: None,
+            inode: None,
             children: vec![],
             error: None,
         }
@@ -93,9 +118,13 @@ impl DirEntry {
             is_dir: false,
             size: 0,
             disk_usage: 0,
+            naive_size: 0,
+            naive_disk_usage: 0,
             file_count: 0,
             dir_count: 0,
             mtime: None,
+            ctime: None,
+            inode: None,
             children: vec![],
             error: Some(error),
         }
@@ -109,12 +138,16 @@ impl DirEntry {
 
         self.size = 0;
         self.disk_usage = 0;
+        self.naive_size = 0;
+        self.naive_disk_usage = 0;
         self.file_count = 0;
         self.dir_count = 0;
 
         for child in &self.children {
             self.size += child.size;
             self.disk_usage += child.disk_usage;
+            self.naive_size += child.naive_size;
+            self.naive_disk_usage += child.naive_disk_usage;
             self.file_count += child.file_count;
             if child.is_dir {
                 self.dir_count += 1 + child.dir_count;
@@ -122,9 +155,48 @@ impl DirEntry {
         }
     }
 
+    /// Clone this entry without its children.
+    pub fn clone_shallow(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            name: self.name.clone(),
+            is_dir: self.is_dir,
+            size: self.size,
+            disk_usage: self.disk_usage,
+            naive_size: self.naive_size,
+            naive_disk_usage: self.naive_disk_usage,
+            file_count: self.file_count,
+            dir_count: self.dir_count,
+            mtime: self.mtime,
+            ctime: self.ctime,
+            inode: self.inode,
+            children: vec![],
+            error: self.error.clone(),
+        }
+    }
+
+    /// Mark this file as an additional hard link to an inode that was
+    /// already counted elsewhere in the tree.
+    ///
+    /// Keeps the naive totals, but contributes nothing to the deduplicated ones.
+    pub fn mark_shared_link(&mut self) {
+        self.size = 0;
+        self.disk_usage = 0;
+    }
+
+    /// Apparent bytes not counted because they are hard links to data counted elsewhere
+    pub fn shared_size(&self) -> u64 {
+        self.naive_size.saturating_sub(self.size)
+    }
+
+    /// Disk bytes not counted because they are hard links to data counted elsewhere
+    pub fn shared_disk_usage(&self) -> u64 {
+        self.naive_disk_usage.saturating_sub(self.disk_usage)
+    }
+
     /// Sort children by size (largest first)
     pub fn sort_by_size(&mut self) {
-        self.children.sort_by(|a, b| b.size.cmp(&a.size));
+        self.children.sort_by_key(|c| std::cmp::Reverse(c.size));
         for child in &mut self.children {
             child.sort_by_size();
         }
@@ -156,9 +228,13 @@ mod tests {
             is_dir: true,
             size: 1024,
             disk_usage: 4096,
+            naive_size: 1024,
+            naive_disk_usage: 4096,
             file_count: 5,
             dir_count: 2,
             mtime: None,
+            ctime: None,
+            inode: None,
             children: vec![],
             error: None,
         };
@@ -177,9 +253,13 @@ mod tests {
             is_dir: true,
             size: 0,
             disk_usage: 0,
+            naive_size: 0,
+            naive_disk_usage: 0,
             file_count: 0,
             dir_count: 0,
             mtime: None,
+            ctime: None,
+            inode: None,
             children: vec![],
             error: Some("Permission denied".to_string()),
         };
@@ -245,6 +325,32 @@ mod tests {
 
         assert_eq!(parent.size, 300);
         assert_eq!(parent.disk_usage, 8192);
+        assert_eq!(parent.naive_size, 300);
+        assert_eq!(parent.naive_disk_usage, 8192);
+        assert_eq!(parent.file_count, 2);
+    }
+
+    #[test]
+    fn test_recalculate_totals_with_shared_link() {
+        let mut parent = DirEntry::new_dir(PathBuf::from("/parent"), None);
+        parent.children.push(DirEntry::new_file(
+            PathBuf::from("/parent/original.bin"),
+            1000,
+            4096,
+            None,
+        ));
+        let mut link = DirEntry::new_file(PathBuf::from("/parent/link.bin"), 1000, 4096, None);
+        link.mark_shared_link();
+        parent.children.push(link);
+
+        parent.recalculate_totals();
+
+        assert_eq!(parent.size, 1000);
+        assert_eq!(parent.disk_usage, 4096);
+        assert_eq!(parent.naive_size, 2000);
+        assert_eq!(parent.naive_disk_usage, 8192);
+        assert_eq!(parent.shared_size(), 1000);
+        assert_eq!(parent.shared_disk_usage(), 4096);
         assert_eq!(parent.file_count, 2);
     }
 
@@ -0,0 +1,301 @@
+//! Glob-based path exclusion shared by the disk scanner and the project scanner.
+//!
+//! Patterns follow gitignore-like rules:
+//! - `*` matches any run of characters within a path component, `?` exactly one
+//! - `**` as a whole component matches zero or more components
+//! - a pattern without `/` (e.g. `node_modules`, `*.log`) matches at any depth
+//! - a pattern containing `/` (e.g. `build/cache`, `/tmp`) is anchored to the scan root
+//! - a trailing `/` restricts the pattern to directories
+//! - a leading `!` re-includes paths excluded by an earlier pattern
+//!
+//! The last matching pattern wins. Since excluded directories are pruned
+//! before they are walked, a negated pattern cannot re-include anything
+//! below an excluded directory.
+
+use std::path::{Component, Path};
+
+/// One path component of a compiled pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// `**`: zero or more components
+    AnyDepth,
+    /// A component pattern, possibly containing `*` and `?`
+    Name(Vec<char>),
+}
+
+/// A single compiled exclude pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GlobPattern {
+    segments: Vec<Segment>,
+    negated: bool,
+    dir_only: bool,
+}
+
+impl GlobPattern {
+    fn parse(pattern: &str) -> Option<Self> {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+
+        let anchored = pattern.contains('/');
+        let mut segments: Vec<Segment> = pattern
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != ".")
+            .map(|s| match s {
+                "**" => Segment::AnyDepth,
+                _ => Segment::Name(s.chars().collect()),
+            })
+            .collect();
+
+        if segments.is_empty() {
+            return None;
+        }
+        if !anchored {
+            segments.insert(0, Segment::AnyDepth);
+        }
+
+        Some(Self {
+            segments,
+            negated,
+            dir_only,
+        })
+    }
+
+    fn matches(&self, components: &[&str], is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        match_segments(&self.segments, components)
+    }
+}
+
+fn match_segments(segments: &[Segment], components: &[&str]) -> bool {
+    match segments.split_first() {
+        None => components.is_empty(),
+        Some((Segment::AnyDepth, rest)) => {
+            (0..=components.len()).any(|skip| match_segments(rest, &components[skip..]))
+        }
+        Some((Segment::Name(pattern), rest)) => match components.split_first() {
+            Some((name, remaining)) => {
+                let name: Vec<char> = name.chars().collect();
+                match_component(pattern, &name) && match_segments(rest, remaining)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a single component against a pattern with `*` and `?` wildcards.
+fn match_component(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+            }
+            Some(c) if *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, tried)) => {
+                    p = star + 1;
+                    n = tried + 1;
+                    backtrack = Some((star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Compiled set of exclude patterns.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludeMatcher {
+    patterns: Vec<GlobPattern>,
+}
+
+impl ExcludeMatcher {
+    /// Compile a list of patterns. Empty patterns are ignored.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .filter_map(|p| GlobPattern::parse(p.as_ref().trim()))
+                .collect(),
+        }
+    }
+
+    /// Returns true if there are no patterns to apply.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Check whether a path, relative to the scan root, is excluded.
+    ///
+    /// Only the path itself is tested, not its ancestors: callers are
+    /// expected to prune excluded directories as they walk.
+    pub fn is_excluded(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+
+        let names: Vec<String> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        if names.is_empty() {
+            return false;
+        }
+        let components: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        let mut excluded = false;
+        for pattern in &self.patterns {
+            if pattern.matches(&components, is_dir) {
+                excluded = !pattern.negated;
+            }
+        }
+        excluded
+    }
+
+    /// Check whether `path` is excluded, given the root it was reached from.
+    ///
+    /// Paths outside `root` are never excluded.
+    pub fn is_excluded_under(&self, root: &Path, path: &Path, is_dir: bool) -> bool {
+        match path.strip_prefix(root) {
+            Ok(relative) => self.is_excluded(relative, is_dir),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn excluded(patterns: &[&str], path: &str) -> bool {
+        ExcludeMatcher::new(patterns).is_excluded(Path::new(path), true)
+    }
+
+    #[test]
+    fn test_match_component_wildcards() {
+        let m = |p: &str, n: &str| {
+            match_component(
+                &p.chars().collect::<Vec<_>>(),
+                &n.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert!(m("*.log", "build.log"));
+        assert!(m("*.log", ".log"));
+        assert!(!m("*.log", "build.log.gz"));
+        assert!(m("file?.txt", "file1.txt"));
+        assert!(!m("file?.txt", "file10.txt"));
+        assert!(m("*cache*", "my-cache-dir"));
+        assert!(m("a*b*c", "aXbYbZc"));
+        assert!(!m("a*b*c", "aXbYbZ"));
+        assert!(m("*", ""));
+        assert!(!m("?", ""));
+    }
+
+    #[test]
+    fn test_unanchored_name_matches_any_depth() {
+        assert!(excluded(&["node_modules"], "node_modules"));
+        assert!(excluded(&["node_modules"], "web/app/node_modules"));
+        assert!(!excluded(&["node_modules"], "web/node_modules_backup"));
+    }
+
+    #[test]
+    fn test_double_star_prefix() {
+        assert!(excluded(&["**/.git"], ".git"));
+        assert!(excluded(&["**/.git"], "a/b/.git"));
+        assert!(!excluded(&["**/.git"], "a/b/.github"));
+    }
+
+    #[test]
+    fn test_anchored_pattern() {
+        assert!(excluded(&["build/cache"], "build/cache"));
+        assert!(!excluded(&["build/cache"], "app/build/cache"));
+        assert!(excluded(&["/tmp"], "tmp"));
+        assert!(!excluded(&["/tmp"], "var/tmp"));
+    }
+
+    #[test]
+    fn test_double_star_in_middle() {
+        assert!(excluded(&["src/**/gen"], "src/gen"));
+        assert!(excluded(&["src/**/gen"], "src/a/b/gen"));
+        assert!(!excluded(&["src/**/gen"], "lib/a/gen"));
+    }
+
+    #[test]
+    fn test_trailing_double_star() {
+        assert!(excluded(&["logs/**"], "logs/2024/app.log"));
+    }
+
+    #[test]
+    fn test_wildcards_in_components() {
+        assert!(excluded(&["*.tmp"], "a/b/scratch.tmp"));
+        assert!(excluded(&["cache-?"], "x/cache-1"));
+        assert!(excluded(
+            &["target/*/incremental"],
+            "target/debug/incremental"
+        ));
+    }
+
+    #[test]
+    fn test_negation_last_match_wins() {
+        let patterns = ["*.log", "!keep.log"];
+        assert!(excluded(&patterns, "a/debug.log"));
+        assert!(!excluded(&patterns, "a/keep.log"));
+
+        // A later positive pattern overrides the negation again
+        let patterns = ["*.log", "!keep.log", "old/keep.log"];
+        assert!(excluded(&patterns, "old/keep.log"));
+    }
+
+    #[test]
+    fn test_dir_only_pattern() {
+        let matcher = ExcludeMatcher::new(&["build/"]);
+        assert!(matcher.is_excluded(Path::new("app/build"), true));
+        assert!(!matcher.is_excluded(Path::new("app/build"), false));
+    }
+
+    #[test]
+    fn test_root_never_excluded() {
+        assert!(!excluded(&["**"], ""));
+        assert!(!excluded(&["*"], "."));
+    }
+
+    #[test]
+    fn test_empty_patterns_ignored() {
+        let matcher = ExcludeMatcher::new(&["", "  ", "!", "/"]);
+        assert!(matcher.is_empty());
+        assert!(!matcher.is_excluded(Path::new("anything"), true));
+    }
+
+    #[test]
+    fn test_is_excluded_under_root() {
+        let matcher = ExcludeMatcher::new(&["/data"]);
+        let root = Path::new("/home/user");
+        assert!(matcher.is_excluded_under(root, Path::new("/home/user/data"), true));
+        assert!(!matcher.is_excluded_under(root, Path::new("/home/user/x/data"), true));
+        assert!(!matcher.is_excluded_under(root, Path::new("/other/data"), true));
+    }
+}
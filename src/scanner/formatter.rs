@@ -1,3 +1,6 @@
+use super::breakdown::TypeBreakdown;
+use super::diff::{ChangeKind, DirChange, ScanDiff};
+use super::duplicates::DuplicateReport;
 use super::entry::DirEntry;
 use super::size::format_size;
 
@@ -219,6 +222,8 @@ pub struct SummarizedEntry {
     pub path: String,
     pub size: u64,
     pub size_human: String,
+    /// Bytes not counted in `size` because they are hard links to data counted elsewhere
+    pub shared_size: u64,
     pub file_count: u64,
     pub dir_count: u64,
     pub children: Vec<SummarizedEntry>,
@@ -230,6 +235,7 @@ impl From<&DirEntry> for SummarizedEntry {
             path: entry.path.to_string_lossy().to_string(),
             size: entry.size,
             size_human: format_size(entry.size),
+            shared_size: entry.shared_size(),
             file_count: entry.file_count,
             dir_count: entry.dir_count,
             children: entry.children.iter().map(SummarizedEntry::from).collect(),
@@ -247,6 +253,321 @@ pub fn format_json_summary(entry: &DirEntry, pretty: bool) -> Result<String, ser
     }
 }
 
+/// Format a signed byte delta (e.g. "+1.00 MB", "-512 B")
+pub fn format_delta(delta: i64) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_size(delta.unsigned_abs()))
+}
+
+/// Format the relative change of a directory
+fn format_percent(change: &DirChange) -> String {
+    match (change.kind, change.percent) {
+        (ChangeKind::Added, _) => "new".to_string(),
+        (ChangeKind::Removed, _) => "gone".to_string(),
+        (_, Some(percent)) => format!("{:+.1}%", percent),
+        (_, None) => "-".to_string(),
+    }
+}
+
+fn change_marker(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => " [added]",
+        ChangeKind::Removed => " [removed]",
+        _ => "",
+    }
+}
+
+/// Format a scan diff as a tree of changed directories
+pub fn format_diff_tree(diff: &ScanDiff, options: &FormatOptions) -> String {
+    let mut output = String::new();
+    format_diff_recursive(&diff.root, &mut output, "", true, 0, options);
+    output
+}
+
+fn format_diff_recursive(
+    change: &DirChange,
+    output: &mut String,
+    prefix: &str,
+    is_last: bool,
+    depth: usize,
+    options: &FormatOptions,
+) {
+    if let Some(max_depth) = options.max_depth {
+        if depth > max_depth {
+            return;
+        }
+    }
+
+    let connector = if depth == 0 {
+        ""
+    } else if is_last {
+        "└── "
+    } else {
+        "├── "
+    };
+
+    output.push_str(&format!(
+        "{}{}{:>11} {:>8}  {}/{}\n",
+        prefix,
+        connector,
+        format_delta(change.delta),
+        format_percent(change),
+        change.name,
+        change_marker(change.kind)
+    ));
+
+    let new_prefix = if depth == 0 {
+        String::new()
+    } else if is_last {
+        format!("{}    ", prefix)
+    } else {
+        format!("{}│   ", prefix)
+    };
+
+    let shown = options
+        .top_n
+        .map_or(change.children.len(), |n| n.min(change.children.len()));
+    let remaining = change.children.len() - shown;
+
+    for (i, child) in change.children.iter().take(shown).enumerate() {
+        let is_last_child = i == shown - 1 && remaining == 0;
+        format_diff_recursive(
+            child,
+            output,
+            &new_prefix,
+            is_last_child,
+            depth + 1,
+            options,
+        );
+    }
+
+    if remaining > 0 {
+        output.push_str(&format!(
+            "{}└── ... and {} more changes\n",
+            new_prefix, remaining
+        ));
+    }
+}
+
+/// Format a scan diff as a flat table, largest absolute change first
+pub fn format_diff_table(diff: &ScanDiff, options: &FormatOptions) -> String {
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "{:>12}  {:>8}  {:>10}  {:>10}  {}\n",
+        "DELTA", "CHANGE", "OLD", "NEW", "PATH"
+    ));
+    output.push_str(&format!(
+        "{:->12}  {:->8}  {:->10}  {:->10}  {:-<40}\n",
+        "", "", "", "", ""
+    ));
+
+    let changes = diff.changes();
+    let shown = options
+        .top_n
+        .map_or(changes.len(), |n| n.min(changes.len()));
+
+    for change in changes.iter().take(shown) {
+        let path = change
+            .path
+            .strip_prefix(&diff.root.path)
+            .unwrap_or(&change.path);
+        output.push_str(&format!(
+            "{:>12}  {:>8}  {:>10}  {:>10}  {}{}\n",
+            format_delta(change.delta),
+            format_percent(change),
+            format_size(change.old_size),
+            format_size(change.new_size),
+            path.display(),
+            change_marker(change.kind)
+        ));
+    }
+
+    if changes.len() > shown {
+        output.push_str(&format!("... {} more\n", changes.len() - shown));
+    }
+
+    output
+}
+
+/// JSON structure for diff output
+#[derive(serde::Serialize)]
+pub struct DiffReport<'a> {
+    pub root: &'a std::path::Path,
+    pub old_size: u64,
+    pub new_size: u64,
+    pub delta: i64,
+    pub percent: Option<f64>,
+    pub added: usize,
+    pub removed: usize,
+    pub grown: usize,
+    pub shrunk: usize,
+    /// Changed directories, largest absolute change first
+    pub changes: Vec<&'a DirChange>,
+}
+
+impl<'a> From<&'a ScanDiff> for DiffReport<'a> {
+    fn from(diff: &'a ScanDiff) -> Self {
+        let changes = diff.changes();
+        let count = |kind| changes.iter().filter(|c| c.kind == kind).count();
+        Self {
+            root: &diff.root.path,
+            old_size: diff.root.old_size,
+            new_size: diff.root.new_size,
+            delta: diff.root.delta,
+            percent: diff.root.percent,
+            added: count(ChangeKind::Added),
+            removed: count(ChangeKind::Removed),
+            grown: count(ChangeKind::Grown),
+            shrunk: count(ChangeKind::Shrunk),
+            changes,
+        }
+    }
+}
+
+/// Format a scan diff as JSON
+pub fn format_diff_json(diff: &ScanDiff, pretty: bool) -> Result<String, serde_json::Error> {
+    let report = DiffReport::from(diff);
+    if pretty {
+        serde_json::to_string_pretty(&report)
+    } else {
+        serde_json::to_string(&report)
+    }
+}
+
+/// Format a type breakdown as two tables: categories, then the top extensions
+pub fn format_breakdown_table(breakdown: &TypeBreakdown, options: &FormatOptions) -> String {
+    let mut output = String::new();
+    let header = |output: &mut String, first: &str| {
+        output.push_str(&format!(
+            "{:<14}  {:>10}  {:>10}  {:>10}  {:>6}\n",
+            first, "SIZE", "DISK", "FILES", "SHARE"
+        ));
+        output.push_str(&format!(
+            "{:-<14}  {:->10}  {:->10}  {:->10}  {:->6}\n",
+            "", "", "", "", ""
+        ));
+    };
+
+    header(&mut output, "CATEGORY");
+    for group in &breakdown.categories {
+        output.push_str(&format!(
+            "{:<14}  {:>10}  {:>10}  {:>10}  {:>5.1}%\n",
+            group.category.label(),
+            format_size(group.stats.size),
+            format_size(group.stats.disk_usage),
+            group.stats.files,
+            breakdown.percent_of_total(&group.stats)
+        ));
+    }
+
+    output.push('\n');
+    header(&mut output, "EXTENSION");
+    let shown = options.top_n.map_or(breakdown.extensions.len(), |n| {
+        n.min(breakdown.extensions.len())
+    });
+    for group in breakdown.extensions.iter().take(shown) {
+        let name = if group.extension.is_empty() {
+            "(none)".to_string()
+        } else {
+            format!(".{}", group.extension)
+        };
+        output.push_str(&format!(
+            "{:<14}  {:>10}  {:>10}  {:>10}  {:>5.1}%\n",
+            name,
+            format_size(group.stats.size),
+            format_size(group.stats.disk_usage),
+            group.stats.files,
+            breakdown.percent_of_total(&group.stats)
+        ));
+    }
+    if breakdown.extensions.len() > shown {
+        output.push_str(&format!(
+            "... {} more\n",
+            breakdown.extensions.len() - shown
+        ));
+    }
+
+    output.push('\n');
+    output.push_str(&format!(
+        "{:<14}  {:>10}  {:>10}  {:>10}\n",
+        "TOTAL",
+        format_size(breakdown.total.size),
+        format_size(breakdown.total.disk_usage),
+        breakdown.total.files
+    ));
+
+    output
+}
+
+/// Format a type breakdown as JSON
+pub fn format_breakdown_json(
+    breakdown: &TypeBreakdown,
+    pretty: bool,
+) -> Result<String, serde_json::Error> {
+    if pretty {
+        serde_json::to_string_pretty(breakdown)
+    } else {
+        serde_json::to_string(breakdown)
+    }
+}
+
+/// Format duplicate groups as a table, most reclaimable first.
+/// The first path of each group is the copy that linking would keep.
+pub fn format_duplicates_table(report: &DuplicateReport, options: &FormatOptions) -> String {
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "{:>12}  {:>10}  {:>6}  {}\n",
+        "RECLAIMABLE", "SIZE", "COPIES", "FILES"
+    ));
+    output.push_str(&format!(
+        "{:->12}  {:->10}  {:->6}  {:-<40}\n",
+        "", "", "", ""
+    ));
+
+    let shown = options
+        .top_n
+        .map_or(report.groups.len(), |n| n.min(report.groups.len()));
+
+    for group in report.groups.iter().take(shown) {
+        for (i, path) in group.files.iter().enumerate() {
+            if i == 0 {
+                output.push_str(&format!(
+                    "{:>12}  {:>10}  {:>6}  {}\n",
+                    format_size(group.reclaimable),
+                    format_size(group.size),
+                    group.files.len(),
+                    path.display()
+                ));
+            } else {
+                output.push_str(&format!("{:>34}  {}\n", "", path.display()));
+            }
+        }
+    }
+
+    if report.groups.len() > shown {
+        output.push_str(&format!(
+            "... {} more groups\n",
+            report.groups.len() - shown
+        ));
+    }
+
+    output
+}
+
+/// Format a duplicate report as JSON
+pub fn format_duplicates_json(
+    report: &DuplicateReport,
+    pretty: bool,
+) -> Result<String, serde_json::Error> {
+    if pretty {
+        serde_json::to_string_pretty(report)
+    } else {
+        serde_json::to_string(report)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -429,6 +750,7 @@ mod tests {
         assert!(json.contains("\"path\""));
         assert!(json.contains("\"size\""));
         assert!(json.contains("\"size_human\""));
+        assert!(json.contains("\"shared_size\""));
         assert!(json.contains("\"file_count\""));
         assert!(json.contains("\"dir_count\""));
         assert!(json.contains("\"children\""));
@@ -444,5 +766,190 @@ mod tests {
         assert!(parsed.is_object());
         assert!(parsed["path"].is_string());
         assert!(parsed["size"].is_number());
+        assert!(parsed["naive_size"].is_number());
+        assert!(parsed["naive_disk_usage"].is_number());
+    }
+
+    fn create_diff() -> ScanDiff {
+        let old = create_test_entry();
+
+        let mut new = DirEntry::new_dir(PathBuf::from("/test"), None);
+        let mut subdir = DirEntry::new_dir(PathBuf::from("/test/subdir"), None);
+        subdir.children.push(DirEntry::new_file(
+            PathBuf::from("/test/subdir/file.txt"),
+            3072,
+            4096,
+            None,
+        ));
+        subdir.recalculate_totals();
+        let mut added = DirEntry::new_dir(PathBuf::from("/test/downloads"), None);
+        added.children.push(DirEntry::new_file(
+            PathBuf::from("/test/downloads/big.iso"),
+            1048576,
+            1048576,
+            None,
+        ));
+        added.recalculate_totals();
+        new.children.push(subdir);
+        new.children.push(added);
+        new.recalculate_totals();
+
+        old.diff(&new)
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(1048576), "+1.00 MB");
+        assert_eq!(format_delta(-512), "-512 B");
+        assert_eq!(format_delta(0), "+0 B");
+    }
+
+    #[test]
+    fn test_format_diff_tree() {
+        let diff = create_diff();
+        let output = format_diff_tree(&diff, &FormatOptions::default());
+
+        assert!(output.contains("downloads/ [added]"));
+        assert!(output.contains("+1.00 MB"));
+        assert!(output.contains("subdir/"));
+        assert!(output.contains("+200.0%"));
+        assert!(output.contains("└──"));
+    }
+
+    #[test]
+    fn test_format_diff_tree_top_n() {
+        let diff = create_diff();
+        let output = format_diff_tree(&diff, &FormatOptions::new().with_top_n(1));
+
+        assert!(output.contains("downloads/"));
+        assert!(!output.contains("subdir/"));
+        assert!(output.contains("1 more changes"));
+    }
+
+    #[test]
+    fn test_format_diff_table() {
+        let diff = create_diff();
+        let output = format_diff_table(&diff, &FormatOptions::default());
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].contains("DELTA"));
+        assert!(lines[0].contains("PATH"));
+        // Largest change first, paths relative to the root
+        assert!(lines[2].contains("downloads [added]"));
+        assert!(lines[2].contains("new"));
+        assert!(lines[3].contains("subdir"));
+    }
+
+    #[test]
+    fn test_format_diff_json() {
+        let diff = create_diff();
+        let json = format_diff_json(&diff, false).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["added"], 1);
+        assert_eq!(parsed["grown"], 1);
+        assert_eq!(parsed["changes"][0]["kind"], "added");
+        assert_eq!(parsed["changes"][0]["delta"], 1048576);
+        assert!(parsed["changes"][0]["percent"].is_null());
+        assert_eq!(parsed["changes"][1]["kind"], "grown");
+    }
+
+    #[test]
+    fn test_format_breakdown_table() {
+        let breakdown = create_test_entry().type_breakdown();
+        let output = format_breakdown_table(&breakdown, &FormatOptions::default());
+
+        assert!(output.contains("CATEGORY"));
+        assert!(output.contains("EXTENSION"));
+        assert!(output.contains("Object/Binary"));
+        assert!(output.contains(".bin"));
+        assert!(output.contains(".txt"));
+        assert!(output.contains("TOTAL"));
+        assert!(output.contains("99.9%"));
+    }
+
+    #[test]
+    fn test_format_breakdown_table_top_n() {
+        let breakdown = create_test_entry().type_breakdown();
+        let output = format_breakdown_table(&breakdown, &FormatOptions::new().with_top_n(1));
+
+        assert!(output.contains(".bin"));
+        assert!(!output.contains(".txt"));
+        assert!(output.contains("... 1 more"));
+    }
+
+    #[test]
+    fn test_format_breakdown_json() {
+        let breakdown = create_test_entry().type_breakdown();
+        let json = format_breakdown_json(&breakdown, false).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["total"]["files"], 2);
+        assert_eq!(parsed["categories"][0]["category"], "object");
+        assert_eq!(parsed["extensions"][0]["extension"], "bin");
+        assert_eq!(parsed["extensions"][1]["disk_usage"], 4096);
+    }
+
+    fn create_duplicates() -> DuplicateReport {
+        use crate::scanner::DuplicateGroup;
+
+        DuplicateReport {
+            groups: vec![
+                DuplicateGroup {
+                    size: 1048576,
+                    hash: "ab".repeat(32),
+                    reclaimable: 2097152,
+                    files: vec![
+                        PathBuf::from("/a/disk.iso"),
+                        PathBuf::from("/b/disk.iso"),
+                        PathBuf::from("/c/disk.iso"),
+                    ],
+                },
+                DuplicateGroup {
+                    size: 100,
+                    hash: "cd".repeat(32),
+                    reclaimable: 100,
+                    files: vec![PathBuf::from("/a/x.txt"), PathBuf::from("/b/x.txt")],
+                },
+            ],
+            files_hashed: 5,
+            hardlinks_skipped: 0,
+            errors: 0,
+        }
+    }
+
+    #[test]
+    fn test_format_duplicates_table() {
+        let output = format_duplicates_table(&create_duplicates(), &FormatOptions::default());
+
+        assert!(output.contains("RECLAIMABLE"));
+        assert!(output.contains("2.00 MB"));
+        assert!(output.contains("/c/disk.iso"));
+        assert!(output.contains("/b/x.txt"));
+
+        let first = output.lines().nth(2).unwrap();
+        assert!(first.contains("3"));
+        assert!(first.ends_with("/a/disk.iso"));
+    }
+
+    #[test]
+    fn test_format_duplicates_table_top_n() {
+        let output =
+            format_duplicates_table(&create_duplicates(), &FormatOptions::new().with_top_n(1));
+
+        assert!(output.contains("/a/disk.iso"));
+        assert!(!output.contains("x.txt"));
+        assert!(output.contains("... 1 more groups"));
+    }
+
+    #[test]
+    fn test_format_duplicates_json() {
+        let json = format_duplicates_json(&create_duplicates(), false).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["groups"][0]["reclaimable"], 2097152);
+        assert_eq!(parsed["groups"][0]["files"][2], "/c/disk.iso");
+        assert_eq!(parsed["groups"][1]["size"], 100);
+        assert_eq!(parsed["files_hashed"], 5);
     }
 }
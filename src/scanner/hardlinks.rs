@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::os::unix::fs::MetadataExt;
+use std::sync::Mutex;
+
+/// Tracks inodes that have more than one hard link so that each one is
+/// only counted once, like `du` does.
+///
+/// Safe to share between the rayon workers of the parallel scanner.
+#[derive(Debug, Default)]
+pub struct HardlinkTracker {
+    seen: Mutex<HashSet<(u64, u64)>>,
+}
+
+impl HardlinkTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a file and report whether its data should be counted.
+    ///
+    /// Returns `true` for files with a single link and for the first link
+    /// of a multiply-linked inode, `false` for every later link.
+    pub fn first_sighting(&self, metadata: &Metadata) -> bool {
+        if metadata.is_dir() || metadata.nlink() <= 1 {
+            return true;
+        }
+        self.first_sighting_of((metadata.dev(), metadata.ino()))
+    }
+
+    /// Record a multiply-linked inode by its device and inode number, e.g.
+    /// one remembered from an earlier scan.
+    pub fn first_sighting_of(&self, inode: (u64, u64)) -> bool {
+        self.seen.lock().unwrap().insert(inode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_single_link_always_counted() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "data").unwrap();
+
+        let tracker = HardlinkTracker::new();
+        let meta = fs::metadata(&file).unwrap();
+
+        assert!(tracker.first_sighting(&meta));
+        assert!(tracker.first_sighting(&meta));
+        assert!(tracker.seen.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_hardlink_counted_once() {
+        let dir = TempDir::new().unwrap();
+        let original = dir.path().join("original.txt");
+        let link = dir.path().join("link.txt");
+        fs::write(&original, "data").unwrap();
+        fs::hard_link(&original, &link).unwrap();
+
+        let tracker = HardlinkTracker::new();
+
+        assert!(tracker.first_sighting(&fs::metadata(&original).unwrap()));
+        assert!(!tracker.first_sighting(&fs::metadata(&link).unwrap()));
+        assert_eq!(tracker.seen.lock().unwrap().len(), 1);
+    }
+}
@@ -1,13 +1,35 @@
+mod breakdown;
+mod cache;
+mod diff;
+mod duplicates;
 mod entry;
+mod exclude;
 mod formatter;
+mod hardlinks;
 mod options;
 mod size;
 mod walker;
 
+pub use breakdown::{
+    extension_key, CategoryStats, ExtensionStats, FileCategory, TypeBreakdown, TypeStats,
+};
+pub use cache::{CachedScan, ScanCache};
+pub use diff::{read_snapshot, ChangeKind, DirChange, ScanDiff};
+pub use duplicates::{
+    find_duplicates, DuplicateGroup, DuplicateOptions, DuplicateReport, LinkFailure, LinkMode,
+    LinkSummary,
+};
 pub use entry::DirEntry;
+pub use exclude::ExcludeMatcher;
 pub use formatter::{
-    format_json, format_json_summary, format_table, format_tree, FormatOptions, SummarizedEntry,
+    format_breakdown_json, format_breakdown_table, format_delta, format_diff_json,
+    format_diff_table, format_diff_tree, format_duplicates_json, format_duplicates_table,
+    format_json, format_json_summary, format_table, format_tree, DiffReport, FormatOptions,
+    SummarizedEntry,
 };
 pub use options::ScanOptions;
-pub use size::format_size;
-pub use walker::{scan_directory, scan_directory_parallel, scan_directory_progressive, ScanUpdate};
+pub use size::{format_size, parse_size};
+pub use walker::{
+    rescan_incremental, scan_directory, scan_directory_parallel, scan_directory_progressive,
+    ScanUpdate,
+};
@@ -3,11 +3,14 @@ use std::fs;
 use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use walkdir::WalkDir;
 
 use crate::error::{Result, SweeperError};
 
 use super::entry::DirEntry;
+use super::exclude::ExcludeMatcher;
+use super::hardlinks::HardlinkTracker;
 use super::options::ScanOptions;
 use super::size::{apparent_size, disk_usage};
 
@@ -57,6 +60,13 @@ pub fn scan_directory(root: &Path, options: &ScanOptions) -> Result<DirEntry> {
 
     // Collect all entries, building a path -> entry map
     let mut entries: HashMap<PathBuf, DirEntry> = HashMap::new();
+    let links = HardlinkTracker::new();
+    let excludes = ExcludeMatcher::new(&options.exclude_patterns);
+
+    // Prune excluded subtrees before walkdir descends into them
+    let walker = walker
+        .into_iter()
+        .filter_entry(|e| !excludes.is_excluded_under(&root, e.path(), e.file_type().is_dir()));
 
     for result in walker {
         let entry = match result {
@@ -105,14 +115,9 @@ pub fn scan_directory(root: &Path, options: &ScanOptions) -> Result<DirEntry> {
 
         // Create entry
         let dir_entry = if metadata.is_dir() {
-            DirEntry::new_dir(path.clone(), metadata.modified().ok())
+            dir_entry(path.clone(), &metadata)
         } else {
-            DirEntry::new_file(
-                path.clone(),
-                apparent_size(&metadata),
-                disk_usage(&metadata),
-                metadata.modified().ok(),
-            )
+            file_entry(path.clone(), &metadata, &links)
         };
 
         entries.insert(path, dir_entry);
@@ -122,6 +127,38 @@ pub fn scan_directory(root: &Path, options: &ScanOptions) -> Result<DirEntry> {
     build_tree(&root, entries)
 }
 
+/// Create a file entry, counting its data only for the first link to its inode
+fn file_entry(path: PathBuf, metadata: &fs::Metadata, links: &HardlinkTracker) -> DirEntry {
+    let mut entry = DirEntry::new_file(
+        path,
+        apparent_size(metadata),
+        disk_usage(metadata),
+        metadata.modified().ok(),
+    );
+    entry.ctime = change_time(metadata);
+    if metadata.nlink() > 1 {
+        entry.inode = Some((metadata.dev(), metadata.ino()));
+    }
+    if !links.first_sighting(metadata) {
+        entry.mark_shared_link();
+    }
+    entry
+}
+
+/// Create an empty directory entry carrying the directory's timestamps
+fn dir_entry(path: PathBuf, metadata: &fs::Metadata) -> DirEntry {
+    let mut entry = DirEntry::new_dir(path, metadata.modified().ok());
+    entry.ctime = change_time(metadata);
+    entry
+}
+
+/// Status change time (ctime) of a file
+fn change_time(metadata: &fs::Metadata) -> Option<SystemTime> {
+    let secs = u64::try_from(metadata.ctime()).ok()?;
+    let nanos = u32::try_from(metadata.ctime_nsec()).ok()?;
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
 /// Build a tree structure from a flat HashMap of entries
 fn build_tree(root: &Path, mut entries: HashMap<PathBuf, DirEntry>) -> Result<DirEntry> {
     // Sort paths by depth (deepest first) so we process children before parents
@@ -173,14 +210,48 @@ pub fn scan_directory_parallel(root: &Path, options: &ScanOptions) -> Result<Dir
         source: e,
     })?;
 
-    scan_dir_recursive_parallel(&root, options, 0)
+    let ctx = WalkContext::new(&root, options);
+    scan_dir_recursive_parallel(&root, &ctx, 0)
+}
+
+/// State shared by every worker of a parallel scan.
+struct WalkContext<'a> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    excludes: ExcludeMatcher,
+    links: HardlinkTracker,
+}
+
+impl<'a> WalkContext<'a> {
+    fn new(root: &'a Path, options: &'a ScanOptions) -> Self {
+        Self {
+            root,
+            options,
+            excludes: ExcludeMatcher::new(&options.exclude_patterns),
+            links: HardlinkTracker::new(),
+        }
+    }
+
+    /// Whether a directory entry should be scanned at all.
+    fn should_scan(&self, entry: &fs::DirEntry) -> bool {
+        let path = entry.path();
+        if ScanOptions::is_linux_virtual_fs(&path) {
+            return false;
+        }
+        if !self.options.include_hidden {
+            if let Some(name) = path.file_name() {
+                if name.to_string_lossy().starts_with('.') {
+                    return false;
+                }
+            }
+        }
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        !self.excludes.is_excluded_under(self.root, &path, is_dir)
+    }
 }
 
-fn scan_dir_recursive_parallel(
-    path: &Path,
-    options: &ScanOptions,
-    depth: usize,
-) -> Result<DirEntry> {
+fn scan_dir_recursive_parallel(path: &Path, ctx: &WalkContext, depth: usize) -> Result<DirEntry> {
+    let options = ctx.options;
     use rayon::prelude::*;
 
     // Skip Linux virtual filesystem paths
@@ -218,21 +289,13 @@ fn scan_dir_recursive_parallel(
 
     // If it's a file, return immediately
     if !metadata.is_dir() {
-        return Ok(DirEntry::new_file(
-            path.to_path_buf(),
-            apparent_size(&metadata),
-            disk_usage(&metadata),
-            metadata.modified().ok(),
-        ));
+        return Ok(file_entry(path.to_path_buf(), &metadata, &ctx.links));
     }
 
     // Check if we've reached the depth limit - if so, return empty directory
     if let Some(max_depth) = options.max_depth {
         if depth >= max_depth {
-            return Ok(DirEntry::new_dir(
-                path.to_path_buf(),
-                metadata.modified().ok(),
-            ));
+            return Ok(dir_entry(path.to_path_buf(), &metadata));
         }
     }
 
@@ -244,22 +307,7 @@ fn scan_dir_recursive_parallel(
 
     let entries: Vec<_> = read_dir
         .filter_map(|e| e.ok())
-        .filter(|e| {
-            let child_path = e.path();
-            // Filter out Linux virtual filesystem paths
-            if ScanOptions::is_linux_virtual_fs(&child_path) {
-                return false;
-            }
-            // Filter hidden files if needed
-            if !options.include_hidden {
-                if let Some(name) = child_path.file_name() {
-                    if name.to_string_lossy().starts_with('.') {
-                        return false;
-                    }
-                }
-            }
-            true
-        })
+        .filter(|e| ctx.should_scan(e))
         .collect();
 
     // Process entries in parallel
@@ -267,17 +315,17 @@ fn scan_dir_recursive_parallel(
         .par_iter()
         .filter_map(|entry| {
             let child_path = entry.path();
-            scan_dir_recursive_parallel(&child_path, options, depth + 1).ok()
+            scan_dir_recursive_parallel(&child_path, ctx, depth + 1).ok()
         })
         .collect();
 
     // Build directory entry
-    let mut dir_entry = DirEntry::new_dir(path.to_path_buf(), metadata.modified().ok());
-    dir_entry.children = children;
-    dir_entry.recalculate_totals();
-    dir_entry.sort_by_size();
+    let mut entry = dir_entry(path.to_path_buf(), &metadata);
+    entry.children = children;
+    entry.recalculate_totals();
+    entry.sort_by_size();
 
-    Ok(dir_entry)
+    Ok(entry)
 }
 
 /// Progressive directory scanner that sends updates as it scans.
@@ -316,23 +364,13 @@ pub fn scan_directory_progressive(root: &Path, options: &ScanOptions, tx: Sender
         }
     };
 
+    // Shared across top-level entries so links between them are counted once
+    let ctx = WalkContext::new(&root, options);
+
     // Collect top-level entries to process
     let entries: Vec<_> = read_dir
         .filter_map(|e| e.ok())
-        .filter(|e| {
-            let path = e.path();
-            if ScanOptions::is_linux_virtual_fs(&path) {
-                return false;
-            }
-            if !options.include_hidden {
-                if let Some(name) = path.file_name() {
-                    if name.to_string_lossy().starts_with('.') {
-                        return false;
-                    }
-                }
-            }
-            true
-        })
+        .filter(|e| ctx.should_scan(e))
         .collect();
 
     let total = entries.len();
@@ -353,7 +391,7 @@ pub fn scan_directory_progressive(root: &Path, options: &ScanOptions, tx: Sender
         });
 
         // Scan this entry (parallel internally via scan_dir_recursive_parallel)
-        let child_entry = match scan_dir_recursive_parallel(&child_path, options, 1) {
+        let child_entry = match scan_dir_recursive_parallel(&child_path, &ctx, 1) {
             Ok(e) => e,
             Err(_) => DirEntry::new_error(child_path.clone(), "Scan failed".to_string()),
         };
@@ -365,13 +403,145 @@ pub fn scan_directory_progressive(root: &Path, options: &ScanOptions, tx: Sender
     let _ = tx.send(ScanUpdate::Complete { tree });
 }
 
+/// Rescan a previously scanned tree, re-reading only directories that changed.
+///
+/// Every directory in `previous` is re-stat'ed. If its mtime and ctime are
+/// unchanged its listing is reused and only its subdirectories are checked;
+/// otherwise it is re-read, new entries are scanned from scratch and removed
+/// ones dropped. Totals are only recomputed along the chains of ancestors
+/// of changed directories.
+///
+/// Reused and re-read files share one hard link tracker, so every inode is
+/// still counted once. Files modified in place don't touch their
+/// directory's timestamps, so their sizes are only refreshed when something
+/// else in the same directory changed. Run a full scan when exact numbers
+/// are needed.
+pub fn rescan_incremental(previous: &DirEntry, options: &ScanOptions) -> Result<DirEntry> {
+    let root = &previous.path;
+    let metadata = fs::metadata(root).map_err(|e| SweeperError::Io {
+        path: root.clone(),
+        source: e,
+    })?;
+    if !metadata.is_dir() {
+        return Err(SweeperError::NotADirectory(root.clone()));
+    }
+
+    let ctx = WalkContext::new(root, options);
+    let (tree, _) = rescan_dir(previous, &ctx, 0);
+    Ok(tree)
+}
+
+/// Incrementally rescan one directory. Returns the new entry and whether
+/// anything in its subtree changed.
+fn rescan_dir(previous: &DirEntry, ctx: &WalkContext, depth: usize) -> (DirEntry, bool) {
+    use rayon::prelude::*;
+
+    let path = &previous.path;
+    let metadata = if ctx.options.follow_symlinks {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    };
+    let metadata = match metadata {
+        Ok(m) if m.is_dir() => m,
+        // Gone, unreadable or no longer a directory: scan whatever is there now
+        _ => return (rescan_path(path, ctx, depth), true),
+    };
+
+    let unchanged = previous.error.is_none()
+        && previous.mtime == metadata.modified().ok()
+        && previous.ctime == change_time(&metadata);
+
+    if ctx.options.max_depth.is_some_and(|max| depth >= max) {
+        return (dir_entry(path.clone(), &metadata), !unchanged);
+    }
+
+    if unchanged {
+        let results: Vec<(DirEntry, bool)> = previous
+            .children
+            .par_iter()
+            .map(|child| {
+                if child.is_dir {
+                    rescan_dir(child, ctx, depth + 1)
+                } else {
+                    reuse_file(child, ctx)
+                }
+            })
+            .collect();
+
+        let changed = results.iter().any(|(_, changed)| *changed);
+        let mut entry = previous.clone_shallow();
+        entry.children = results.into_iter().map(|(child, _)| child).collect();
+        if changed {
+            entry.recalculate_totals();
+            entry.sort_by_size();
+        }
+        return (entry, changed);
+    }
+
+    // The listing changed: re-read it, reusing what we know about subdirectories
+    let read_dir = match fs::read_dir(path) {
+        Ok(rd) => rd,
+        Err(e) => return (DirEntry::new_error(path.clone(), e.to_string()), true),
+    };
+    let known: HashMap<&Path, &DirEntry> = previous
+        .children
+        .iter()
+        .filter(|c| c.is_dir)
+        .map(|c| (c.path.as_path(), c))
+        .collect();
+    let listing: Vec<_> = read_dir
+        .filter_map(|e| e.ok())
+        .filter(|e| ctx.should_scan(e))
+        .map(|e| e.path())
+        .collect();
+
+    let children: Vec<DirEntry> = listing
+        .par_iter()
+        .map(|child_path| match known.get(child_path.as_path()) {
+            Some(old) => rescan_dir(old, ctx, depth + 1).0,
+            None => rescan_path(child_path, ctx, depth + 1),
+        })
+        .collect();
+
+    let mut entry = dir_entry(path.clone(), &metadata);
+    entry.children = children;
+    entry.recalculate_totals();
+    entry.sort_by_size();
+    (entry, true)
+}
+
+/// Reuse a file entry from the previous tree, recording a multiply-linked
+/// file with the tracker like a re-read one. Returns the entry and whether
+/// its counted size changed.
+fn reuse_file(previous: &DirEntry, ctx: &WalkContext) -> (DirEntry, bool) {
+    let Some(inode) = previous.inode else {
+        return (previous.clone(), false);
+    };
+
+    let mut entry = previous.clone();
+    entry.size = entry.naive_size;
+    entry.disk_usage = entry.naive_disk_usage;
+    if !ctx.links.first_sighting_of(inode) {
+        entry.mark_shared_link();
+    }
+    let changed = entry.size != previous.size;
+    (entry, changed)
+}
+
+/// Scan a path from scratch during an incremental rescan.
+fn rescan_path(path: &Path, ctx: &WalkContext, depth: usize) -> DirEntry {
+    scan_dir_recursive_parallel(path, ctx, depth)
+        .unwrap_or_else(|e| DirEntry::new_error(path.to_path_buf(), e.to_string()))
+}
+
 /// Helper to build a partial tree from accumulated children.
 fn build_partial_tree(
     root: &Path,
     metadata: &std::fs::Metadata,
     children: &[DirEntry],
 ) -> DirEntry {
-    let mut tree = DirEntry::new_dir(root.to_path_buf(), metadata.modified().ok());
+    let mut tree = dir_entry(root.to_path_buf(), metadata);
     tree.children = children.to_vec();
     tree.recalculate_totals();
     tree.sort_by_size();
@@ -504,11 +674,11 @@ mod tests {
             .unwrap();
         File::create(root.join("large.txt"))
             .unwrap()
-            .write_all(&vec![b'x'; 1000])
+            .write_all(&[b'x'; 1000])
             .unwrap();
         File::create(root.join("medium.txt"))
             .unwrap()
-            .write_all(&vec![b'y'; 100])
+            .write_all(&[b'y'; 100])
             .unwrap();
 
         let options = ScanOptions::default();
@@ -538,11 +708,11 @@ mod tests {
         fs::create_dir(root.join("subdir")).unwrap();
         File::create(root.join("subdir/file1.txt"))
             .unwrap()
-            .write_all(&vec![b'a'; 100])
+            .write_all(&[b'a'; 100])
             .unwrap();
         File::create(root.join("subdir/file2.txt"))
             .unwrap()
-            .write_all(&vec![b'b'; 200])
+            .write_all(&[b'b'; 200])
             .unwrap();
 
         let options = ScanOptions::default();
@@ -556,6 +726,62 @@ mod tests {
         assert_eq!(subdir.size, 300);
     }
 
+    fn create_hardlink_structure() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        fs::create_dir(root.join("a")).unwrap();
+        fs::create_dir(root.join("b")).unwrap();
+        File::create(root.join("a/data.bin"))
+            .unwrap()
+            .write_all(&[b'x'; 1000])
+            .unwrap();
+        fs::hard_link(root.join("a/data.bin"), root.join("b/data.bin")).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_scan_counts_hardlinks_once() {
+        let dir = create_hardlink_structure();
+        let options = ScanOptions::default();
+
+        let result = scan_directory(dir.path(), &options).unwrap();
+
+        assert_eq!(result.size, 1000);
+        assert_eq!(result.naive_size, 2000);
+        assert_eq!(result.shared_size(), 1000);
+        assert_eq!(result.naive_disk_usage, 2 * result.disk_usage);
+        assert_eq!(result.file_count, 2);
+    }
+
+    #[test]
+    fn test_scan_exclude_patterns() {
+        let dir = create_test_structure();
+        let options = ScanOptions::new().with_exclude(vec!["subdir".to_string()]);
+
+        let result = scan_directory(dir.path(), &options).unwrap();
+
+        assert!(!result.children.iter().any(|c| c.name == "subdir"));
+        assert!(result.children.iter().any(|c| c.name == "file1.txt"));
+    }
+
+    #[test]
+    fn test_scan_exclude_glob_and_negation() {
+        let dir = create_test_structure();
+        let options =
+            ScanOptions::new().with_exclude(vec!["*.txt".to_string(), "!file2.txt".to_string()]);
+
+        let result = scan_directory(dir.path(), &options).unwrap();
+        let names: Vec<&str> = result.children.iter().map(|c| c.name.as_str()).collect();
+
+        assert!(!names.contains(&"file1.txt"));
+        assert!(names.contains(&"file2.txt"));
+        // Directories still walked, but their .txt files are excluded
+        let subdir = result.children.iter().find(|c| c.name == "subdir").unwrap();
+        assert_eq!(subdir.file_count, 0);
+    }
+
     // Parallel scanner tests
 
     #[test]
@@ -629,4 +855,205 @@ mod tests {
         assert_eq!(result.file_count, 100);
         assert_eq!(result.dir_count, 10);
     }
+
+    #[test]
+    fn test_parallel_scan_exclude_patterns() {
+        let dir = create_test_structure();
+        let options = ScanOptions::new().with_exclude(vec!["**/subdir".to_string()]);
+
+        let sequential = scan_directory(dir.path(), &options).unwrap();
+        let parallel = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        assert!(!parallel.children.iter().any(|c| c.name == "subdir"));
+        assert_eq!(sequential.size, parallel.size);
+        assert_eq!(sequential.file_count, parallel.file_count);
+    }
+
+    #[test]
+    fn test_parallel_scan_exclude_anchored() {
+        let dir = create_test_structure();
+        fs::create_dir_all(dir.path().join("other/subdir")).unwrap();
+        File::create(dir.path().join("other/subdir/kept.txt"))
+            .unwrap()
+            .write_all(b"kept")
+            .unwrap();
+        let options = ScanOptions::new().with_exclude(vec!["/subdir".to_string()]);
+
+        let result = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        assert!(!result.children.iter().any(|c| c.name == "subdir"));
+        let other = result.children.iter().find(|c| c.name == "other").unwrap();
+        assert_eq!(other.file_count, 1);
+    }
+
+    #[test]
+    fn test_parallel_scan_counts_hardlinks_once() {
+        let dir = create_hardlink_structure();
+        let options = ScanOptions::default();
+
+        let result = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        assert_eq!(result.size, 1000);
+        assert_eq!(result.naive_size, 2000);
+        assert_eq!(result.file_count, 2);
+
+        // Exactly one of the two links carries the data
+        let counted: u64 = result.children.iter().map(|c| c.size).sum();
+        assert_eq!(counted, 1000);
+    }
+
+    #[test]
+    fn test_progressive_scan_counts_hardlinks_once() {
+        let dir = create_hardlink_structure();
+        let options = ScanOptions::default();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        scan_directory_progressive(dir.path(), &options, tx);
+
+        let tree = rx
+            .iter()
+            .find_map(|update| match update {
+                ScanUpdate::Complete { tree } => Some(tree),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(tree.size, 1000);
+        assert_eq!(tree.naive_size, 2000);
+    }
+
+    // Incremental rescan tests
+
+    #[test]
+    fn test_incremental_rescan_unchanged_tree() {
+        let dir = create_test_structure();
+        let options = ScanOptions::default();
+        let previous = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        let result = rescan_incremental(&previous, &options).unwrap();
+
+        assert_eq!(result.size, previous.size);
+        assert_eq!(result.file_count, previous.file_count);
+        assert_eq!(result.dir_count, previous.dir_count);
+    }
+
+    #[test]
+    fn test_incremental_rescan_reuses_unchanged_directories() {
+        let dir = create_test_structure();
+        let options = ScanOptions::default();
+        let previous = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        // Rewriting a file in place doesn't touch its directory's timestamps
+        let nested = dir.path().join("subdir/nested.txt");
+        let subdir_mtime = fs::metadata(dir.path().join("subdir"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&nested)
+            .unwrap()
+            .write_all(b" more")
+            .unwrap();
+        assert_eq!(
+            fs::metadata(dir.path().join("subdir"))
+                .unwrap()
+                .modified()
+                .unwrap(),
+            subdir_mtime
+        );
+
+        let result = rescan_incremental(&previous, &options).unwrap();
+
+        assert_eq!(result.size, previous.size);
+    }
+
+    #[test]
+    fn test_incremental_rescan_counts_hardlinks_once() {
+        let dir = create_hardlink_structure();
+        let options = ScanOptions::default();
+        let previous = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        // One directory is re-read while the other is reused; the link
+        // shared by both counts once whichever was counted before
+        fs::write(dir.path().join("a/new.txt"), "new").unwrap();
+        let result = rescan_incremental(&previous, &options).unwrap();
+        assert_eq!(result.size, 1003);
+        assert_eq!(result.naive_size, 2003);
+
+        fs::write(dir.path().join("b/new.txt"), "new").unwrap();
+        let result = rescan_incremental(&result, &options).unwrap();
+        assert_eq!(result.size, 1006);
+        assert_eq!(result.naive_size, 2006);
+        assert_eq!(result.file_count, 4);
+    }
+
+    #[test]
+    fn test_incremental_rescan_detects_deleted_file() {
+        let dir = create_test_structure();
+        let options = ScanOptions::default();
+        let previous = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        fs::remove_file(dir.path().join("subdir/nested.txt")).unwrap();
+        let result = rescan_incremental(&previous, &options).unwrap();
+
+        let subdir = result.children.iter().find(|c| c.name == "subdir").unwrap();
+        assert!(subdir.children.is_empty());
+        assert_eq!(subdir.size, 0);
+        assert_eq!(result.size, previous.size - 14);
+        assert_eq!(result.file_count, previous.file_count - 1);
+    }
+
+    #[test]
+    fn test_incremental_rescan_detects_deleted_directory() {
+        let dir = create_test_structure();
+        let options = ScanOptions::default();
+        let previous = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        fs::remove_dir_all(dir.path().join("subdir")).unwrap();
+        let result = rescan_incremental(&previous, &options).unwrap();
+
+        assert!(!result.children.iter().any(|c| c.name == "subdir"));
+        assert_eq!(result.dir_count, 0);
+        assert_eq!(result.size, 11);
+    }
+
+    #[test]
+    fn test_incremental_rescan_scans_new_entries() {
+        let dir = create_test_structure();
+        let options = ScanOptions::default();
+        let previous = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        // New file deep in an existing directory, and a whole new subtree
+        fs::create_dir(dir.path().join("subdir/deeper")).unwrap();
+        File::create(dir.path().join("subdir/deeper/new.bin"))
+            .unwrap()
+            .write_all(&[b'x'; 100])
+            .unwrap();
+        fs::create_dir_all(dir.path().join("fresh/inner")).unwrap();
+        File::create(dir.path().join("fresh/inner/data.bin"))
+            .unwrap()
+            .write_all(&[b'x'; 50])
+            .unwrap();
+
+        let result = rescan_incremental(&previous, &options).unwrap();
+        let full = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        assert_eq!(result.size, previous.size + 150);
+        assert_eq!(result.size, full.size);
+        assert_eq!(result.file_count, full.file_count);
+        assert_eq!(result.dir_count, full.dir_count);
+        let subdir = result.children.iter().find(|c| c.name == "subdir").unwrap();
+        assert_eq!(subdir.size, 14 + 100);
+    }
+
+    #[test]
+    fn test_incremental_rescan_missing_root() {
+        let dir = create_test_structure();
+        let options = ScanOptions::default();
+        let previous = scan_directory_parallel(dir.path(), &options).unwrap();
+
+        drop(dir);
+
+        assert!(rescan_incremental(&previous, &options).is_err());
+    }
 }
@@ -4,16 +4,23 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
 use nix::sys::statvfs::statvfs;
 
 use crate::cleaner::{
-    CleanExecutor, CleanOptions, CleanResult, DetectedProject, DetectedSystemResource,
-    DetectorRegistry, SystemCleanerRegistry,
+    reclaimable_usage, CleanExecutor, CleanOptions, CleanPolicy, CleanResult, DetectedProject,
+    DetectedSystemResource, DetectorRegistry, GitState, ProjectScanner,
+    ScanOptions as ProjectScanOptions, SystemCleanResult, SystemCleanerRegistry,
+    SystemResourceItem,
 };
+use crate::journal::{CleanMethod, HistoryFilter, Journal, JournalRecord};
+use crate::quarantine::{Quarantine, QuarantineEntry};
 use crate::scanner::{
-    scan_directory, scan_directory_progressive, DirEntry, ScanOptions, ScanUpdate,
+    rescan_incremental, scan_directory, scan_directory_progressive, DirEntry, ScanCache,
+    ScanOptions, ScanUpdate, TypeBreakdown,
 };
 use walkdir::WalkDir;
 
@@ -28,6 +35,14 @@ pub enum Mode {
     Confirm(ConfirmAction),
     /// Help overlay mode.
     Help,
+    /// File type breakdown panel for the selected entry.
+    Breakdown,
+    /// Quarantined entries panel.
+    Quarantine,
+    /// Cleanup history panel.
+    History,
+    /// Items of the selected system resource.
+    ResourceItems,
 }
 
 /// Action requiring confirmation.
@@ -88,18 +103,63 @@ fn dir_size(path: &Path) -> u64 {
         .sum()
 }
 
-/// Detect project type at the given path.
-/// Returns the short display name (e.g., "Rust", "Node") if detected.
-fn detect_project_type(path: &Path) -> Option<String> {
-    let registry = DetectorRegistry::new();
+/// Format measured freed space, with the estimate when it differs.
+fn format_freed(freed: u64, estimated: u64) -> String {
+    let freed_str = humansize::format_size(freed, humansize::BINARY);
+    if freed == estimated {
+        freed_str
+    } else {
+        format!(
+            "{} (est. {})",
+            freed_str,
+            humansize::format_size(estimated, humansize::BINARY)
+        )
+    }
+}
+
+/// Options used for every TUI scan.
+///
+/// Hidden files are always included so visibility can be toggled in the UI.
+fn scan_options() -> ScanOptions {
+    ScanOptions::default().with_hidden(true)
+}
+
+/// Write a finished scan to the cache, if caching is enabled.
+fn store_in_cache(cache: Option<&ScanCache>, tree: &DirEntry) {
+    if let Some(cache) = cache {
+        if let Err(e) = cache.store(tree, &scan_options()) {
+            tracing::warn!(error = %e, "Failed to write scan cache");
+        }
+    }
+}
+
+/// Format a duration as a short "time ago" string (e.g. "5m").
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
+}
+
+/// Detect project types at the given path.
+/// Returns the short display names (e.g., "Rust", "Rust+Node") if detected.
+fn detect_project_type(registry: &DetectorRegistry, path: &Path) -> Option<String> {
+    let mut short_names: Vec<&str> = Vec::new();
     for detector in registry.detectors() {
         if detector.detect(path) {
             // Extract short name from display_name (e.g., "Rust/Cargo" -> "Rust")
             let display_name = detector.display_name();
             let short_name = display_name.split('/').next().unwrap_or(display_name);
-            return Some(short_name.to_string());
+            if !short_names.contains(&short_name) {
+                short_names.push(short_name);
+            }
         }
     }
+    if !short_names.is_empty() {
+        return Some(short_names.join("+"));
+    }
 
     // Fallback: detect Docker projects by Dockerfile/compose files
     if path.join("Dockerfile").exists()
@@ -114,6 +174,9 @@ fn detect_project_type(path: &Path) -> Option<String> {
     None
 }
 
+/// Date ranges the history panel cycles through, in days (`None` = all time).
+const HISTORY_RANGES: [Option<u64>; 4] = [None, Some(1), Some(7), Some(30)];
+
 /// Preview info for clean confirmation dialog.
 #[derive(Debug, Clone, Default)]
 pub struct CleanPreview {
@@ -123,6 +186,10 @@ pub struct CleanPreview {
     pub artifacts: Vec<(String, u64)>, // (name, size)
     /// Total size that would be freed.
     pub total_size: u64,
+    /// Cleanup policy rule that allowed the clean (`None` without a policy).
+    pub policy_rule: Option<String>,
+    /// Git state of the project's repository (`None` outside a repository).
+    pub git_state: Option<String>,
 }
 
 /// Main application state for the TUI.
@@ -166,12 +233,77 @@ pub struct App {
     /// Preview info for clean confirmation.
     pub clean_preview: Option<CleanPreview>,
 
+    /// File type breakdown shown in the breakdown panel, with the path it describes.
+    pub breakdown: Option<(PathBuf, TypeBreakdown)>,
+
     /// Detected system-level resources (e.g., Docker build cache).
     pub system_resources: Vec<DetectedSystemResource>,
 
     /// Whether to detect system-level resources (Docker, etc.).
     pub detect_system: bool,
 
+    /// System resource whose items are listed in the items panel.
+    pub items_resource: Option<DetectedSystemResource>,
+
+    /// Items listed in the items panel, largest first.
+    pub resource_items: Vec<SystemResourceItem>,
+
+    /// Selected index in resource_items.
+    pub resource_items_selected: usize,
+
+    /// Persistent scan cache (`None` disables caching).
+    pub cache: Option<ScanCache>,
+
+    /// Project detectors, including custom types from the config.
+    pub detectors: DetectorRegistry,
+
+    /// Cleanup policy checked before a project is offered for cleaning.
+    pub policy: CleanPolicy,
+
+    /// Quarantine shown in the quarantine panel (`None` if unavailable).
+    pub quarantine: Option<Arc<Quarantine>>,
+
+    /// Whether deletions and cleans move paths into the quarantine.
+    pub use_quarantine: bool,
+
+    /// Days after which the quarantine panel purges entries.
+    pub quarantine_retention_days: u32,
+
+    /// Entries listed in the quarantine panel.
+    pub quarantine_entries: Vec<QuarantineEntry>,
+
+    /// Selected index in quarantine_entries.
+    pub quarantine_selected: usize,
+
+    /// Journal that deletions and cleans are recorded in (`None` disables it).
+    pub journal: Option<Arc<Journal>>,
+
+    /// Records listed in the history panel, newest first.
+    pub history_records: Vec<JournalRecord>,
+
+    /// Selected index in history_records.
+    pub history_selected: usize,
+
+    /// Path the history panel is limited to (`None` for all paths).
+    pub history_path: Option<PathBuf>,
+
+    /// Project type the history panel is limited to (`None` for all types).
+    pub history_type: Option<String>,
+
+    /// Index into the history panel's date ranges.
+    history_range: usize,
+
+    /// Whether the current tree came from the cache and is being revalidated.
+    revalidating: bool,
+
+    /// Whether the tree changed on disk while a scan was running, so it
+    /// needs another refresh once that scan finishes.
+    refresh_pending: bool,
+
+    /// Whether the running scan is a refresh after a change, whose status
+    /// message stays visible.
+    quiet_scan: bool,
+
     /// Receiver for progressive scan updates.
     scan_receiver: Option<Receiver<ScanUpdate>>,
 
@@ -197,8 +329,29 @@ impl App {
             status_message: None,
             scanning: false,
             clean_preview: None,
+            breakdown: None,
             system_resources: Vec::new(),
             detect_system: false,
+            items_resource: None,
+            resource_items: Vec::new(),
+            resource_items_selected: 0,
+            cache: None,
+            detectors: DetectorRegistry::new(),
+            policy: CleanPolicy::default(),
+            quarantine: None,
+            use_quarantine: false,
+            quarantine_retention_days: 30,
+            quarantine_entries: Vec::new(),
+            quarantine_selected: 0,
+            journal: None,
+            history_records: Vec::new(),
+            history_selected: 0,
+            history_path: None,
+            history_type: None,
+            history_range: 0,
+            revalidating: false,
+            refresh_pending: false,
+            quiet_scan: false,
             scan_receiver: None,
             scan_thread: None,
         }
@@ -248,12 +401,12 @@ impl App {
         }
     }
 
-    /// Detect system-level resources (e.g., Docker build cache, images).
+    /// Detect system-level resources (e.g., Docker build cache, package caches).
     pub fn detect_system_resources(&mut self) {
         if !self.detect_system {
             return;
         }
-        let registry = SystemCleanerRegistry::new();
+        let registry = SystemCleanerRegistry::with_policy(&self.policy);
         self.system_resources = registry.detect_all();
     }
 
@@ -277,7 +430,7 @@ impl App {
 
         // Detect project type for directories
         let project_type = if entry.is_dir {
-            detect_project_type(&entry.path)
+            detect_project_type(&self.detectors, &entry.path)
         } else {
             None
         };
@@ -321,9 +474,9 @@ impl App {
     /// Sort entries according to current sort order.
     fn sort_entries(&self, entries: &mut [&DirEntry]) {
         match self.sort_order {
-            SortOrder::Size => entries.sort_by(|a, b| b.size.cmp(&a.size)),
+            SortOrder::Size => entries.sort_by_key(|e| std::cmp::Reverse(e.size)),
             SortOrder::Name => entries.sort_by(|a, b| a.name.cmp(&b.name)),
-            SortOrder::Mtime => entries.sort_by(|a, b| b.mtime.cmp(&a.mtime)),
+            SortOrder::Mtime => entries.sort_by_key(|e| std::cmp::Reverse(e.mtime)),
         }
     }
 
@@ -449,17 +602,35 @@ impl App {
         self.rebuild_visible_entries();
 
         self.scanning = true;
-        self.status_message = Some("Scanning...".to_string());
+        if !self.revalidating {
+            self.status_message = Some("Scanning...".to_string());
+        }
 
         let (tx, rx) = mpsc::channel();
         self.scan_receiver = Some(rx);
 
         let root = self.root.clone();
+        let cache = self.cache.clone();
 
         // Spawn background thread for progressive scanning
         let handle = thread::spawn(move || {
-            let options = ScanOptions::default().with_hidden(true);
-            scan_directory_progressive(&root, &options, tx);
+            let options = scan_options();
+            if cache.is_none() {
+                scan_directory_progressive(&root, &options, tx);
+                return;
+            }
+
+            // Relay updates so the finished tree can be written to the cache
+            let (scan_tx, scan_rx) = mpsc::channel();
+            thread::spawn(move || scan_directory_progressive(&root, &options, scan_tx));
+            for update in scan_rx {
+                if let ScanUpdate::Complete { tree } = &update {
+                    store_in_cache(cache.as_ref(), tree);
+                }
+                if tx.send(update).is_err() {
+                    break;
+                }
+            }
         });
 
         self.scan_thread = Some(handle);
@@ -477,37 +648,36 @@ impl App {
         if let Some(scan_update) = update {
             match scan_update {
                 ScanUpdate::Progress { tree, scanning } => {
-                    self.tree = Some(tree);
-                    self.rebuild_visible_entries();
+                    // Keep showing the complete cached tree rather than a partial one
+                    if !self.revalidating {
+                        self.tree = Some(tree);
+                        self.rebuild_visible_entries();
+                    }
                     if let Some(name) = scanning {
-                        self.status_message = Some(format!("Scanning: {}", name));
+                        let verb = if self.revalidating {
+                            "Revalidating"
+                        } else {
+                            "Scanning"
+                        };
+                        self.status_message = Some(format!("{}: {}", verb, name));
                     }
                 }
                 ScanUpdate::Complete { tree } => {
-                    self.scanning = false;
-                    self.scan_receiver = None;
-                    self.scan_thread = None;
-
-                    // Preserve expanded paths that still exist
-                    let old_expanded: Vec<_> = self.expanded.iter().cloned().collect();
-                    self.expanded.clear();
-                    for path in old_expanded {
-                        if path.exists() {
-                            self.expanded.insert(path);
-                        }
+                    self.finish_scan();
+                    self.apply_scanned_tree(tree);
+                    if !std::mem::take(&mut self.quiet_scan) {
+                        self.status_message = None; // Clear to show keybinding hints
                     }
-
-                    self.tree = Some(tree);
-                    self.rebuild_visible_entries();
-                    self.status_message = None; // Clear to show keybinding hints
                 }
                 ScanUpdate::Error { message } => {
-                    self.scanning = false;
-                    self.scan_receiver = None;
-                    self.scan_thread = None;
+                    self.finish_scan();
+                    self.quiet_scan = false;
                     self.status_message = Some(format!("Scan error: {}", message));
                 }
             }
+            if std::mem::take(&mut self.refresh_pending) {
+                self.refresh_tree();
+            }
             return true;
         }
 
@@ -521,22 +691,10 @@ impl App {
         // Detect system resources first — fast query that gives immediate results
         self.detect_system_resources();
 
-        // Always scan with hidden files included so we can toggle visibility in the UI
-        let options = ScanOptions::default().with_hidden(true);
-        match scan_directory(&self.root, &options) {
+        match scan_directory(&self.root, &scan_options()) {
             Ok(tree) => {
-                // Preserve expanded paths that still exist
-                let old_expanded: Vec<_> = self.expanded.iter().cloned().collect();
-                self.expanded.clear();
-
-                for path in old_expanded {
-                    if path.exists() {
-                        self.expanded.insert(path);
-                    }
-                }
-
-                self.tree = Some(tree);
-                self.rebuild_visible_entries();
+                store_in_cache(self.cache.as_ref(), &tree);
+                self.apply_scanned_tree(tree);
             }
             Err(e) => {
                 self.status_message = Some(format!("Scan error: {}", e));
@@ -546,6 +704,97 @@ impl App {
         self.scanning = false;
     }
 
+    /// Reset the scan state once a background scan has finished.
+    fn finish_scan(&mut self) {
+        self.scanning = false;
+        self.revalidating = false;
+        self.scan_receiver = None;
+        self.scan_thread = None;
+    }
+
+    /// Refresh the tree in the background after a change on disk.
+    ///
+    /// Uses an incremental rescan when a tree is already loaded, so only
+    /// directories whose timestamps changed are re-read, and keeps the
+    /// status message describing the change. If a scan is already running
+    /// the refresh follows it.
+    pub fn refresh_tree(&mut self) {
+        if self.scanning {
+            self.refresh_pending = true;
+            return;
+        }
+        let Some(previous) = self.tree.clone() else {
+            self.start_background_scan();
+            return;
+        };
+
+        self.quiet_scan = true;
+        self.spawn_incremental_rescan(previous);
+    }
+
+    /// Start an incremental rescan in the background.
+    ///
+    /// Falls back to a full background scan if no tree is loaded yet.
+    pub fn start_background_rescan(&mut self) {
+        if self.scanning {
+            return;
+        }
+        let Some(previous) = self.tree.clone() else {
+            self.start_background_scan();
+            return;
+        };
+
+        self.detect_system_resources();
+        self.rebuild_visible_entries();
+
+        self.status_message = Some("Rescanning changed directories...".to_string());
+        self.spawn_incremental_rescan(previous);
+    }
+
+    /// Start a full rescan in the background, re-reading every directory.
+    ///
+    /// Picks up files that changed in place, which incremental rescans miss.
+    /// The current tree stays visible until the scan completes.
+    pub fn start_full_rescan(&mut self) {
+        if self.scanning {
+            return;
+        }
+        self.revalidating = self.tree.is_some();
+        self.start_background_scan();
+    }
+
+    /// Run an incremental rescan of `previous` on a background thread.
+    fn spawn_incremental_rescan(&mut self, previous: DirEntry) {
+        self.scanning = true;
+
+        let (tx, rx) = mpsc::channel();
+        self.scan_receiver = Some(rx);
+        let cache = self.cache.clone();
+
+        let handle = thread::spawn(move || {
+            let update = match rescan_incremental(&previous, &scan_options()) {
+                Ok(tree) => {
+                    store_in_cache(cache.as_ref(), &tree);
+                    ScanUpdate::Complete { tree }
+                }
+                Err(e) => ScanUpdate::Error {
+                    message: e.to_string(),
+                },
+            };
+            let _ = tx.send(update);
+        });
+
+        self.scan_thread = Some(handle);
+    }
+
+    /// Replace the tree with a freshly scanned one, keeping expanded
+    /// directories that still exist.
+    fn apply_scanned_tree(&mut self, tree: DirEntry) {
+        self.expanded.retain(|path| path.exists());
+        self.tree = Some(tree);
+        self.rebuild_visible_entries();
+    }
+
     /// Perform initial scan, expanding root by default.
     pub fn initial_scan(&mut self) {
         self.expanded.insert(self.root.clone());
@@ -553,11 +802,35 @@ impl App {
     }
 
     /// Start initial scan in background, expanding root by default.
+    ///
+    /// A fresh cached tree is shown immediately and revalidated by the scan.
     pub fn start_initial_scan(&mut self) {
         self.expanded.insert(self.root.clone());
+        self.load_cached_tree();
         self.start_background_scan();
     }
 
+    /// Show a fresh cached tree for the root, if there is one.
+    /// Returns true if the cache was used.
+    pub fn load_cached_tree(&mut self) -> bool {
+        let Some(cached) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.load(&self.root, &scan_options()))
+        else {
+            return false;
+        };
+
+        self.status_message = Some(format!(
+            "Cached scan from {} ago, revalidating...",
+            format_age(cached.age())
+        ));
+        self.tree = Some(cached.tree);
+        self.revalidating = true;
+        self.rebuild_visible_entries();
+        true
+    }
+
     /// Delete the selected entry.
     pub fn delete_selected(&mut self) {
         let entry = match self.selected_entry() {
@@ -585,17 +858,326 @@ impl App {
             return;
         }
 
+        // Measured up front, since the path is gone afterwards
+        let usage = if self.journal.is_some() {
+            reclaimable_usage(std::slice::from_ref(&path))
+        } else {
+            0
+        };
+
+        if let Some(quarantine) = self.active_quarantine() {
+            let record = JournalRecord::new(&path, None, CleanMethod::Quarantine);
+            match quarantine.quarantine(&path) {
+                Ok(entry) => {
+                    self.record(record.freed(usage));
+                    self.status_message = Some(format!(
+                        "Quarantined: {} (restore with [u])",
+                        entry.original_path.display()
+                    ));
+                    self.refresh_tree();
+                }
+                Err(e) => {
+                    self.record(record.failed(&e));
+                    self.status_message = Some(format!("Error: {}", e));
+                }
+            }
+            return;
+        }
+
+        let record = JournalRecord::new(&path, None, CleanMethod::Delete);
         match delete_path(&path) {
             Ok(()) => {
+                self.record(record.freed(usage));
                 self.status_message = Some(format!("Deleted: {}", path.display()));
-                self.trigger_rescan();
+                self.refresh_tree();
+            }
+            Err(e) => {
+                self.record(record.failed(&e));
+                self.status_message = Some(format!("Error: {}", e));
+            }
+        }
+    }
+
+    /// Append a record to the journal, if there is one.
+    fn record(&self, record: JournalRecord) {
+        if let Some(journal) = &self.journal {
+            journal.record(record);
+        }
+    }
+
+    /// The quarantine deletions go to, if quarantine mode is on.
+    fn active_quarantine(&self) -> Option<Arc<Quarantine>> {
+        self.quarantine.clone().filter(|_| self.use_quarantine)
+    }
+
+    /// Load the quarantined entries for the quarantine panel, newest first.
+    /// Returns false if the quarantine is unavailable.
+    pub fn prepare_quarantine_panel(&mut self) -> bool {
+        let Some(quarantine) = &self.quarantine else {
+            self.status_message = Some("Quarantine is unavailable".to_string());
+            return false;
+        };
+        match quarantine.list() {
+            Ok(mut entries) => {
+                entries.reverse();
+                self.quarantine_entries = entries;
+                self.quarantine_selected = self
+                    .quarantine_selected
+                    .min(self.quarantine_entries.len().saturating_sub(1));
+                true
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Error: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Move the selection in the quarantine panel.
+    pub fn move_quarantine_selection(&mut self, delta: i32) {
+        let max_idx = self.quarantine_entries.len().saturating_sub(1);
+        self.quarantine_selected = if delta < 0 {
+            self.quarantine_selected
+                .saturating_sub(delta.unsigned_abs() as usize)
+        } else {
+            self.quarantine_selected.saturating_add(delta as usize)
+        }
+        .min(max_idx);
+    }
+
+    /// Restore the entry selected in the quarantine panel.
+    pub fn restore_selected_quarantine(&mut self) {
+        let (Some(quarantine), Some(entry)) = (
+            self.quarantine.clone(),
+            self.quarantine_entries.get(self.quarantine_selected),
+        ) else {
+            return;
+        };
+
+        match quarantine.restore(&entry.id) {
+            Ok(entry) => {
+                self.status_message = Some(format!("Restored: {}", entry.original_path.display()));
+                if entry.original_path.starts_with(&self.root) {
+                    self.refresh_tree();
+                }
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Restore failed: {}", e));
+            }
+        }
+        self.prepare_quarantine_panel();
+    }
+
+    /// Purge entries older than the configured retention period.
+    pub fn purge_expired_quarantine(&mut self) {
+        let Some(quarantine) = self.quarantine.clone() else {
+            return;
+        };
+
+        let retention = Duration::from_secs(u64::from(self.quarantine_retention_days) * 86400);
+        match quarantine.purge_older_than(retention) {
+            Ok(summary) => {
+                self.status_message = Some(format!(
+                    "Purged {} entries older than {}d, freed {}",
+                    summary.purged.len(),
+                    self.quarantine_retention_days,
+                    humansize::format_size(summary.freed, humansize::BINARY)
+                ));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Purge failed: {}", e));
+            }
+        }
+        self.prepare_quarantine_panel();
+    }
+
+    /// Load the items of the selected system resource for the items panel,
+    /// largest first. Returns false if there is nothing to list.
+    pub fn prepare_resource_items_panel(&mut self) -> bool {
+        let Some(resource) = self
+            .selected_entry()
+            .and_then(|e| e.system_resource.clone())
+        else {
+            return false;
+        };
+        let registry = SystemCleanerRegistry::with_policy(&self.policy);
+        let items = registry
+            .get_cleaner(&resource.category)
+            .map(|cleaner| cleaner.list_items(&resource))
+            .unwrap_or_default();
+        if items.is_empty() {
+            self.status_message = Some(format!("No items to list in {}", resource.display_name));
+            return false;
+        }
+
+        self.resource_items = items;
+        self.resource_items_selected = 0;
+        self.items_resource = Some(resource);
+        true
+    }
+
+    /// Close the items panel.
+    pub fn close_resource_items_panel(&mut self) {
+        self.items_resource = None;
+        self.resource_items.clear();
+        self.resource_items_selected = 0;
+    }
+
+    /// Move the selection in the items panel.
+    pub fn move_resource_item_selection(&mut self, delta: i32) {
+        let max_idx = self.resource_items.len().saturating_sub(1);
+        self.resource_items_selected = if delta < 0 {
+            self.resource_items_selected
+                .saturating_sub(delta.unsigned_abs() as usize)
+        } else {
+            self.resource_items_selected.saturating_add(delta as usize)
+        }
+        .min(max_idx);
+    }
+
+    /// Remove the item selected in the items panel, then reload the items
+    /// and the resource sizes.
+    pub fn remove_selected_resource_item(&mut self) {
+        let (Some(resource), Some(item)) = (
+            self.items_resource.clone(),
+            self.resource_items
+                .get(self.resource_items_selected)
+                .cloned(),
+        ) else {
+            return;
+        };
+        let registry = SystemCleanerRegistry::with_policy(&self.policy);
+        let Some(cleaner) = registry.get_cleaner(&resource.category) else {
+            self.status_message = Some("No cleaner found for this resource".to_string());
+            return;
+        };
+
+        match cleaner.remove_item_recorded(&resource, &item, self.journal.as_deref()) {
+            SystemCleanResult::Success {
+                freed_bytes,
+                estimated_bytes,
+                ..
+            } => {
+                self.status_message = Some(format!(
+                    "Removed {}, freed {}",
+                    item.name,
+                    format_freed(freed_bytes, estimated_bytes)
+                ));
+            }
+            SystemCleanResult::Failed { error, .. } => {
+                self.status_message = Some(format!("Remove failed: {}", error));
+            }
+            SystemCleanResult::Skipped { reason, .. } => {
+                self.status_message = Some(format!("Remove skipped: {}", reason));
+            }
+        }
+
+        self.resource_items = cleaner.list_items(&resource);
+        self.move_resource_item_selection(0);
+        self.detect_system_resources();
+        self.rebuild_visible_entries();
+    }
+
+    /// Open the history panel, limited to the selected directory if there
+    /// is one. Returns false if the journal is unavailable.
+    pub fn prepare_history_panel(&mut self) -> bool {
+        if self.journal.is_none() {
+            self.status_message = Some("Cleanup history is unavailable".to_string());
+            return false;
+        }
+        self.history_path = self
+            .selected_entry()
+            .filter(|e| !e.is_separator && e.system_resource.is_none())
+            .map(|e| e.entry.path.clone());
+        self.history_type = None;
+        self.history_range = 0;
+        self.history_selected = 0;
+        self.load_history()
+    }
+
+    /// Reload the history panel's records with its current filters.
+    fn load_history(&mut self) -> bool {
+        let Some(journal) = &self.journal else {
+            return false;
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let filter = HistoryFilter {
+            since: self
+                .history_days()
+                .map(|days| now.saturating_sub(days * 86400)),
+            until: None,
+            path: self.history_path.clone(),
+            project_type: self.history_type.clone(),
+        };
+        match journal.query(&filter) {
+            Ok(mut records) => {
+                records.reverse();
+                self.history_records = records;
+                self.history_selected = self
+                    .history_selected
+                    .min(self.history_records.len().saturating_sub(1));
+                true
             }
             Err(e) => {
                 self.status_message = Some(format!("Error: {}", e));
+                false
             }
         }
     }
 
+    /// Number of days the history panel looks back (`None` for all time).
+    pub fn history_days(&self) -> Option<u64> {
+        HISTORY_RANGES[self.history_range]
+    }
+
+    /// Move the selection in the history panel.
+    pub fn move_history_selection(&mut self, delta: i32) {
+        let max_idx = self.history_records.len().saturating_sub(1);
+        self.history_selected = if delta < 0 {
+            self.history_selected
+                .saturating_sub(delta.unsigned_abs() as usize)
+        } else {
+            self.history_selected.saturating_add(delta as usize)
+        }
+        .min(max_idx);
+    }
+
+    /// Switch the history panel to the next date range.
+    pub fn cycle_history_range(&mut self) {
+        self.history_range = (self.history_range + 1) % HISTORY_RANGES.len();
+        self.load_history();
+    }
+
+    /// Switch the history panel to the next project type found in the journal.
+    pub fn cycle_history_type(&mut self) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        let mut types: Vec<String> = journal
+            .query(&HistoryFilter::default())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|r| r.project_type)
+            .collect();
+        types.sort();
+        types.dedup();
+
+        self.history_type = match &self.history_type {
+            None => types.into_iter().next(),
+            Some(current) => types.into_iter().find(|t| t > current),
+        };
+        self.load_history();
+    }
+
+    /// Show history for all paths instead of just the selected directory.
+    pub fn clear_history_path(&mut self) {
+        self.history_path = None;
+        self.load_history();
+    }
+
     /// Clean the selected project or system resource.
     pub fn clean_selected(&mut self) {
         let entry = match self.selected_entry() {
@@ -613,12 +1195,15 @@ impl App {
 
         // Handle system resource cleaning
         if let Some(resource) = &entry.system_resource {
-            let registry = SystemCleanerRegistry::new();
+            let registry = SystemCleanerRegistry::with_policy(&self.policy);
             if let Some(cleaner) = registry.get_cleaner(&resource.category) {
-                use crate::cleaner::SystemCleanResult;
-                match cleaner.clean(resource, false) {
-                    SystemCleanResult::Success { freed_bytes, .. } => {
-                        let freed_str = humansize::format_size(freed_bytes, humansize::BINARY);
+                match cleaner.clean_recorded(resource, false, self.journal.as_deref()) {
+                    SystemCleanResult::Success {
+                        freed_bytes,
+                        estimated_bytes,
+                        ..
+                    } => {
+                        let freed_str = format_freed(freed_bytes, estimated_bytes);
                         self.status_message = Some(format!(
                             "Cleaned {}, freed {}",
                             resource.display_name, freed_str
@@ -641,56 +1226,66 @@ impl App {
 
         // Handle regular project cleaning
         let path = entry.entry.path;
+        let Some(project) = self.detect_project_at(&path) else {
+            return;
+        };
 
-        // Try to detect a project at this path
-        let registry = DetectorRegistry::new();
-
-        // Find a matching detector
-        let matching_detector = registry.detectors().iter().find(|d| d.detect(&path));
-
-        match matching_detector {
-            Some(detector) => {
-                // Find artifact paths and calculate size
-                let artifact_paths = detector.find_artifacts(&path);
-                if artifact_paths.is_empty() {
-                    self.status_message = Some("No artifacts to clean".to_string());
-                    return;
-                }
-
-                let artifact_size: u64 = artifact_paths.iter().map(|p| dir_size(p)).sum();
+        let registry = self.detectors.clone();
+        let executor = CleanExecutor::new(CleanOptions {
+            quarantine: self.active_quarantine(),
+            journal: self.journal.clone(),
+            ..Default::default()
+        });
 
-                let project = DetectedProject {
-                    path: path.clone(),
-                    project_type: detector.id().to_string(),
-                    display_name: detector.display_name().to_string(),
-                    artifact_size,
-                    artifact_paths,
+        // A composite project runs each of its types' clean commands
+        let result = executor.clean_groups(&project, |project_type| {
+            registry.get(project_type).and_then(|d| d.clean_command())
+        });
+        match result {
+            CleanResult::Success {
+                freed_bytes,
+                estimated_bytes,
+                ..
+            } => {
+                let freed_str = format_freed(freed_bytes, estimated_bytes);
+                let verb = if self.use_quarantine {
+                    "quarantined"
+                } else {
+                    "freed"
                 };
-
-                let clean_cmd = detector.clean_command();
-                let executor = CleanExecutor::new(CleanOptions::default());
-
-                match executor.clean(&project, clean_cmd) {
-                    CleanResult::Success { freed_bytes, .. } => {
-                        let freed_str = humansize::format_size(freed_bytes, humansize::BINARY);
-                        self.status_message = Some(format!(
-                            "Cleaned {} project, freed {}",
-                            project.display_name, freed_str
-                        ));
-                        self.trigger_rescan();
-                    }
-                    CleanResult::Failed { error, .. } => {
-                        self.status_message = Some(format!("Clean failed: {}", error));
-                    }
-                    CleanResult::Skipped { reason, .. } => {
-                        self.status_message = Some(format!("Clean skipped: {}", reason));
-                    }
-                }
+                self.status_message = Some(format!(
+                    "Cleaned {} project, {} {}",
+                    project.display_name, verb, freed_str
+                ));
+                self.refresh_tree();
             }
-            None => {
-                self.status_message = Some("Not a recognized project".to_string());
+            CleanResult::Failed { error, .. } => {
+                self.status_message = Some(format!("Clean failed: {}", error));
             }
+            CleanResult::Skipped { reason, .. } => {
+                self.status_message = Some(format!("Clean skipped: {}", reason));
+            }
+        }
+    }
+
+    /// Detect the project at `path` with all of its types.
+    ///
+    /// Sets the status message and returns `None` if there is nothing to clean.
+    fn detect_project_at(&mut self, path: &Path) -> Option<DetectedProject> {
+        let options = ProjectScanOptions {
+            check_git: false,
+            ..Default::default()
+        };
+        let project = ProjectScanner::new(self.detectors.clone(), options).detect_project(path);
+        if project.is_none() {
+            let recognized = self.detectors.detectors().iter().any(|d| d.detect(path));
+            self.status_message = Some(if recognized {
+                "No artifacts to clean".to_string()
+            } else {
+                "Not a recognized project".to_string()
+            });
         }
+        project
     }
 
     /// Check if the selected entry is a cleanable project or system resource.
@@ -704,8 +1299,7 @@ impl App {
             if entry.is_separator {
                 return false;
             }
-            let registry = DetectorRegistry::new();
-            registry
+            self.detectors
                 .detectors()
                 .iter()
                 .any(|d| d.detect(&entry.entry.path))
@@ -738,63 +1332,136 @@ impl App {
                 project_name: resource.display_name.clone(),
                 artifacts: vec![(item_info, resource.size)],
                 total_size: resource.size,
+                policy_rule: None,
+                git_state: None,
             });
             return true;
         }
 
         // Regular project preview
         let path = entry.entry.path;
+        let Some(mut project) = self.detect_project_at(&path) else {
+            return false;
+        };
 
-        let registry = DetectorRegistry::new();
-        let matching_detector = registry.detectors().iter().find(|d| d.detect(&path));
-
-        match matching_detector {
-            Some(detector) => {
-                let artifact_paths = detector.find_artifacts(&path);
-                if artifact_paths.is_empty() {
-                    self.status_message = Some("No artifacts to clean".to_string());
-                    return false;
-                }
-
-                let mut artifacts = Vec::new();
-                let mut total_size = 0u64;
-
-                for artifact_path in &artifact_paths {
-                    let name = artifact_path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_else(|| artifact_path.display().to_string());
-                    let size = dir_size(artifact_path);
-                    artifacts.push((name, size));
-                    total_size += size;
+        // Name each artifact after its type when several types share the directory
+        let composite = project.is_composite();
+        let mut artifacts = Vec::new();
+        for group in project.groups() {
+            for artifact_path in &group.artifact_paths {
+                let mut name = artifact_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| artifact_path.display().to_string());
+                if composite {
+                    name = format!("{} ({})", name, group.project_type);
                 }
-
-                self.clean_preview = Some(CleanPreview {
-                    project_name: detector.display_name().to_string(),
-                    artifacts,
-                    total_size,
-                });
-                true
-            }
-            None => {
-                self.status_message = Some("Not a recognized project".to_string());
-                false
+                artifacts.push((name, dir_size(artifact_path)));
             }
         }
+        let total_size = artifacts.iter().map(|(_, size)| size).sum();
+
+        project.git = GitState::inspect(&path, &project.artifact_paths);
+        let git_state = project.git.as_ref().map(|g| g.to_string());
+        let project_name = project.display_name.clone();
+        let policy_rule = match self.check_policy(project) {
+            Ok(rule) => rule,
+            Err(reason) => {
+                self.status_message = Some(format!("Skipped by policy: {}", reason));
+                return false;
+            }
+        };
+
+        self.clean_preview = Some(CleanPreview {
+            project_name,
+            artifacts,
+            total_size,
+            policy_rule,
+            git_state,
+        });
+        true
     }
 
-    /// Get filesystem disk usage information.
-    /// Returns (total_bytes, used_bytes, available_bytes) or None if unavailable.
-    pub fn get_disk_usage(&self) -> Option<(u64, u64, u64)> {
-        // Only get disk usage if the root path actually exists
-        if !self.root.exists() {
-            return None;
+    /// Evaluate the cleanup policy for a single project.
+    ///
+    /// Returns the rule that allows cleaning it (`None` without a policy),
+    /// or the reason it must be kept.
+    fn check_policy(&self, project: DetectedProject) -> Result<Option<String>, String> {
+        if self.policy.is_empty() {
+            return Ok(None);
         }
 
-        match statvfs(&self.root) {
-            Ok(stat) => {
-                let block_size = stat.fragment_size();
-                let total = stat.blocks() * block_size;
+        // keep_newest ranks the project against the projects of its types
+        // next to it; scanning the whole root would block the UI
+        let mut projects = Vec::new();
+        let groups = project.groups();
+        let types: Vec<&str> = groups.iter().map(|g| g.project_type.as_str()).collect();
+        let ranked = types
+            .iter()
+            .any(|t| self.policy.rules_for(t).keep_newest.is_some());
+        if let Some(parent) = project.path.parent().filter(|_| ranked) {
+            let registry = self.detectors.clone().retain_types(&types);
+            let options = ProjectScanOptions {
+                check_git: false,
+                ..Default::default()
+            };
+            let scanner = ProjectScanner::new(registry, options);
+            projects = fs::read_dir(parent)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|e| e.path())
+                .filter(|path| *path != project.path)
+                .filter_map(|path| scanner.detect_project(&path))
+                .collect();
+        }
+        let path = project.path.clone();
+        projects.push(project);
+
+        let verdict = self
+            .policy
+            .evaluate(projects)
+            .into_iter()
+            .find(|v| v.project.path == path)
+            .expect("evaluated project is in the verdicts");
+        if verdict.is_included() {
+            Ok(Some(verdict.rule.to_string()))
+        } else {
+            Err(verdict.rule.to_string())
+        }
+    }
+
+    /// Compute the file type breakdown of the selected entry.
+    /// Returns false if nothing with files is selected.
+    pub fn prepare_breakdown(&mut self) -> bool {
+        let entry = match self.selected_entry() {
+            Some(entry) if !entry.is_separator && entry.system_resource.is_none() => entry,
+            _ => return false,
+        };
+
+        let breakdown = entry.entry.type_breakdown();
+        if breakdown.total.files == 0 {
+            self.status_message = Some("No files to break down".to_string());
+            return false;
+        }
+
+        self.breakdown = Some((entry.entry.path.clone(), breakdown));
+        true
+    }
+
+    /// Get filesystem disk usage information.
+    /// Returns (total_bytes, used_bytes, available_bytes) or None if unavailable.
+    pub fn get_disk_usage(&self) -> Option<(u64, u64, u64)> {
+        // Only get disk usage if the root path actually exists
+        if !self.root.exists() {
+            return None;
+        }
+
+        match statvfs(&self.root) {
+            Ok(stat) => {
+                let block_size = stat.fragment_size();
+                let total = stat.blocks() * block_size;
                 let available = stat.blocks_available() * block_size;
                 let used = total.saturating_sub(available);
                 Some((total, used, available))
@@ -819,6 +1486,14 @@ impl App {
 mod tests {
     use super::*;
 
+    /// Poll a background scan until it finishes.
+    fn wait_for_scan(app: &mut App) {
+        while app.scanning {
+            app.poll_scan_result();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
     #[test]
     fn test_app_new() {
         let app = App::new(PathBuf::from("/home/user"));
@@ -1248,6 +1923,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_clean_composite_project_preview_and_clean() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+        fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+        let target_dir = temp_dir.path().join("target");
+        let node_modules = temp_dir.path().join("node_modules");
+        fs::create_dir(&target_dir).unwrap();
+        fs::create_dir(&node_modules).unwrap();
+        fs::write(target_dir.join("artifact.bin"), "x".repeat(1000)).unwrap();
+        fs::write(node_modules.join("dep.js"), "x".repeat(500)).unwrap();
+
+        let root = DirEntry::new_dir(temp_dir.path().to_path_buf(), None);
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.tree = Some(root);
+        app.rebuild_visible_entries();
+        assert_eq!(
+            app.visible_entries[0].project_type.as_deref(),
+            Some("Rust+npm")
+        );
+
+        assert!(app.prepare_clean_preview());
+        let preview = app.clean_preview.as_ref().unwrap();
+        assert_eq!(preview.project_name, "Rust/Cargo + npm/Node.js");
+        assert_eq!(
+            preview.artifacts,
+            vec![
+                ("target (cargo)".to_string(), 1000),
+                ("node_modules (npm)".to_string(), 500)
+            ]
+        );
+        assert_eq!(preview.total_size, 1500);
+
+        app.clean_selected();
+        assert!(!target_dir.exists());
+        assert!(!node_modules.exists());
+    }
+
     #[test]
     fn test_clean_selected_no_artifacts() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1302,7 +2015,7 @@ mod tests {
         let temp_dir = tempfile::tempdir().unwrap();
         fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
 
-        let project_type = detect_project_type(temp_dir.path());
+        let project_type = detect_project_type(&DetectorRegistry::new(), temp_dir.path());
         assert_eq!(project_type, Some("Rust".to_string()));
     }
 
@@ -1311,7 +2024,7 @@ mod tests {
         let temp_dir = tempfile::tempdir().unwrap();
         fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
 
-        let project_type = detect_project_type(temp_dir.path());
+        let project_type = detect_project_type(&DetectorRegistry::new(), temp_dir.path());
         assert_eq!(project_type, Some("npm".to_string()));
     }
 
@@ -1319,7 +2032,7 @@ mod tests {
     fn test_detect_project_type_none() {
         let temp_dir = tempfile::tempdir().unwrap();
 
-        let project_type = detect_project_type(temp_dir.path());
+        let project_type = detect_project_type(&DetectorRegistry::new(), temp_dir.path());
         assert!(project_type.is_none());
     }
 
@@ -1388,6 +2101,36 @@ mod tests {
 
     // Rescan tests
 
+    #[test]
+    fn test_prepare_breakdown_of_selected_dir() {
+        let mut app = App::new(PathBuf::from("/root"));
+        app.tree = Some(create_test_tree());
+        app.rebuild_visible_entries();
+
+        assert!(app.prepare_breakdown());
+        let (path, breakdown) = app.breakdown.as_ref().unwrap();
+        assert_eq!(path, &PathBuf::from("/root"));
+        assert_eq!(breakdown.total.files, 3);
+        assert_eq!(breakdown.extension("txt").size, 800);
+    }
+
+    #[test]
+    fn test_prepare_breakdown_empty_dir() {
+        let mut app = App::new(PathBuf::from("/empty"));
+        app.tree = Some(DirEntry::new_dir(PathBuf::from("/empty"), None));
+        app.rebuild_visible_entries();
+
+        assert!(!app.prepare_breakdown());
+        assert!(app.breakdown.is_none());
+        assert!(app.status_message.is_some());
+    }
+
+    #[test]
+    fn test_prepare_breakdown_nothing_selected() {
+        let mut app = App::new(PathBuf::from("/root"));
+        assert!(!app.prepare_breakdown());
+    }
+
     #[test]
     fn test_trigger_rescan() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1435,6 +2178,123 @@ mod tests {
         assert!(!app.expanded.contains(&sub_dir));
     }
 
+    #[test]
+    fn test_refresh_tree_without_tree_does_full_scan() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "test").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.refresh_tree();
+        wait_for_scan(&mut app);
+
+        assert_eq!(app.tree.as_ref().unwrap().size, 4);
+    }
+
+    #[test]
+    fn test_refresh_tree_picks_up_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let sub_dir = temp_dir.path().join("subdir");
+        fs::create_dir(&sub_dir).unwrap();
+        fs::write(sub_dir.join("old.txt"), "test").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.initial_scan();
+
+        fs::remove_file(sub_dir.join("old.txt")).unwrap();
+        fs::write(sub_dir.join("new.txt"), "longer content").unwrap();
+        app.refresh_tree();
+        wait_for_scan(&mut app);
+
+        let tree = app.tree.as_ref().unwrap();
+        assert_eq!(tree.size, 14);
+        assert_eq!(tree.children[0].children[0].name, "new.txt");
+    }
+
+    #[test]
+    fn test_refresh_tree_during_scan_follows_it() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "test").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.initial_scan();
+        app.start_background_rescan();
+        fs::write(temp_dir.path().join("new.txt"), "new").unwrap();
+        app.refresh_tree();
+        assert!(app.refresh_pending);
+        wait_for_scan(&mut app);
+
+        assert!(!app.refresh_pending);
+        assert_eq!(app.tree.as_ref().unwrap().size, 7);
+    }
+
+    #[test]
+    fn test_full_rescan_picks_up_files_changed_in_place() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let sub_dir = temp_dir.path().join("subdir");
+        fs::create_dir(&sub_dir).unwrap();
+        fs::write(sub_dir.join("log.txt"), "test").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.initial_scan();
+
+        // Appending doesn't change the directory's timestamps
+        std::io::Write::write_all(
+            &mut fs::OpenOptions::new()
+                .append(true)
+                .open(sub_dir.join("log.txt"))
+                .unwrap(),
+            b" more",
+        )
+        .unwrap();
+        app.start_background_rescan();
+        wait_for_scan(&mut app);
+        assert_eq!(app.tree.as_ref().unwrap().size, 4);
+
+        app.start_full_rescan();
+        wait_for_scan(&mut app);
+        assert_eq!(app.tree.as_ref().unwrap().size, 9);
+    }
+
+    #[test]
+    fn test_delete_selected_refreshes_tree() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("keep.txt"), "keep").unwrap();
+        fs::write(temp_dir.path().join("remove.txt"), "remove me").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.initial_scan();
+        app.selected = app
+            .visible_entries
+            .iter()
+            .position(|e| e.entry.name == "remove.txt")
+            .unwrap();
+
+        app.delete_selected();
+        wait_for_scan(&mut app);
+
+        assert_eq!(app.tree.as_ref().unwrap().size, 4);
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.starts_with("Deleted:")));
+        assert!(!app
+            .visible_entries
+            .iter()
+            .any(|e| e.entry.name == "remove.txt"));
+    }
+
+    #[test]
+    fn test_background_rescan_without_tree_starts_full_scan() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "test").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.start_background_rescan();
+        wait_for_scan(&mut app);
+
+        assert_eq!(app.tree.as_ref().unwrap().size, 4);
+    }
+
     #[test]
     fn test_initial_scan() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1453,6 +2313,77 @@ mod tests {
         assert!(app.visible_entries.len() > 1);
     }
 
+    fn temp_cache(dir: &Path) -> ScanCache {
+        ScanCache::new(dir.to_path_buf(), Duration::from_secs(3600))
+    }
+
+    #[test]
+    fn test_load_cached_tree_without_cache() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut app = App::new(temp_dir.path().to_path_buf());
+
+        assert!(!app.load_cached_tree());
+        assert!(app.tree.is_none());
+    }
+
+    #[test]
+    fn test_rescan_populates_cache() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "test").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.cache = Some(temp_cache(cache_dir.path()));
+        app.trigger_rescan();
+
+        // A new session is served from the cache before any scan
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.cache = Some(temp_cache(cache_dir.path()));
+
+        assert!(app.load_cached_tree());
+        assert!(app.revalidating);
+        assert_eq!(app.tree.as_ref().unwrap().size, 4);
+        assert!(app
+            .status_message
+            .as_deref()
+            .unwrap()
+            .contains("revalidating"));
+    }
+
+    #[test]
+    fn test_background_scan_writes_cache() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("file.txt"), "test").unwrap();
+
+        let mut app = App::new(temp_dir.path().to_path_buf());
+        app.cache = Some(temp_cache(cache_dir.path()));
+        app.start_initial_scan();
+        assert!(!app.revalidating);
+
+        while app.scanning {
+            app.poll_scan_result();
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let cache = temp_cache(cache_dir.path());
+        let cached = cache.load(temp_dir.path(), &scan_options()).unwrap();
+        assert_eq!(cached.tree.size, 4);
+    }
+
+    #[test]
+    fn test_format_freed() {
+        assert_eq!(format_freed(4096, 4096), "4 KiB");
+        assert_eq!(format_freed(8192, 1000), "8 KiB (est. 1000 B)");
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(5)), "5s");
+        assert_eq!(format_age(Duration::from_secs(125)), "2m");
+        assert_eq!(format_age(Duration::from_secs(7200)), "2h");
+    }
+
     #[test]
     fn test_rescan_nonexistent_directory() {
         let mut app = App::new(PathBuf::from("/nonexistent/path/that/does/not/exist"));
@@ -1623,6 +2554,157 @@ mod tests {
         assert_eq!(preview.total_size, 5000);
     }
 
+    #[test]
+    fn test_prepare_clean_preview_skipped_by_policy() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project = tmp.path().join("app");
+        fs::create_dir_all(project.join("target")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(project.join("target/bin"), "x").unwrap();
+
+        let mut app = App::new(tmp.path().to_path_buf());
+        app.tree = Some(scan_directory(tmp.path(), &ScanOptions::default()).unwrap());
+        app.expanded.insert(tmp.path().to_path_buf());
+        app.rebuild_visible_entries();
+        app.selected = app
+            .visible_entries
+            .iter()
+            .position(|e| e.entry.path == project)
+            .unwrap();
+
+        // Without a policy the project is offered for cleaning
+        assert!(app.prepare_clean_preview());
+        assert_eq!(app.clean_preview.as_ref().unwrap().policy_rule, None);
+
+        let mut config = crate::config::PolicyConfig {
+            min_artifact_size: Some(1024),
+            ..Default::default()
+        };
+        app.policy = CleanPolicy::from_config(&config);
+        app.clean_preview = None;
+
+        assert!(!app.prepare_clean_preview());
+        assert!(app.clean_preview.is_none());
+        assert!(app
+            .status_message
+            .as_ref()
+            .unwrap()
+            .starts_with("Skipped by policy: artifacts"));
+
+        // The only Cargo project under the root is also its newest
+        config.min_artifact_size = None;
+        config.keep_newest = Some(1);
+        app.policy = CleanPolicy::from_config(&config);
+        assert!(!app.prepare_clean_preview());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Skipped by policy: #1 newest of its type (keep 1)")
+        );
+
+        config.keep_newest = None;
+        config.min_age_days = Some(0);
+        app.policy = CleanPolicy::from_config(&config);
+        assert!(app.prepare_clean_preview());
+        assert_eq!(
+            app.clean_preview.as_ref().unwrap().policy_rule.as_deref(),
+            Some("idle 0d >= 0d")
+        );
+    }
+
+    #[test]
+    fn test_keep_newest_ranks_sibling_projects() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        for name in ["old", "new"] {
+            let project = tmp.path().join(name);
+            fs::create_dir_all(project.join("target")).unwrap();
+            fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+            fs::write(project.join("target/bin"), "x".repeat(100)).unwrap();
+        }
+        let last_month = std::time::SystemTime::now() - Duration::from_secs(30 * 86400);
+        for path in ["old", "old/Cargo.toml"] {
+            fs::File::open(tmp.path().join(path))
+                .unwrap()
+                .set_modified(last_month)
+                .unwrap();
+        }
+
+        let mut app = App::new(tmp.path().to_path_buf());
+        app.tree = Some(scan_directory(tmp.path(), &ScanOptions::default()).unwrap());
+        app.expanded.insert(tmp.path().to_path_buf());
+        app.rebuild_visible_entries();
+        app.policy = CleanPolicy::from_config(&crate::config::PolicyConfig {
+            keep_newest: Some(1),
+            ..Default::default()
+        });
+        let select = |app: &mut App, name: &str| {
+            app.selected = app
+                .visible_entries
+                .iter()
+                .position(|e| e.entry.path == tmp.path().join(name))
+                .unwrap();
+        };
+
+        select(&mut app, "new");
+        assert!(!app.prepare_clean_preview());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Skipped by policy: #1 newest of its type (keep 1)")
+        );
+
+        select(&mut app, "old");
+        assert!(app.prepare_clean_preview());
+    }
+
+    #[test]
+    fn test_prepare_clean_preview_git_state() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project = tmp.path().join("app");
+        fs::create_dir_all(project.join("target")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(project.join("target/bin"), "x").unwrap();
+        let git_init = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .arg(&project)
+            .status()
+            .unwrap();
+        assert!(git_init.success());
+
+        let mut app = App::new(tmp.path().to_path_buf());
+        app.tree = Some(scan_directory(tmp.path(), &ScanOptions::default()).unwrap());
+        app.expanded.insert(tmp.path().to_path_buf());
+        app.rebuild_visible_entries();
+        app.selected = app
+            .visible_entries
+            .iter()
+            .position(|e| e.entry.path == project)
+            .unwrap();
+
+        assert!(app.prepare_clean_preview());
+        assert_eq!(
+            app.clean_preview.as_ref().unwrap().git_state.as_deref(),
+            Some("dirty, no remote")
+        );
+
+        let mut config = crate::config::PolicyConfig {
+            dirty_repos: Some(crate::config::DirtyRepoAction::Confirm),
+            ..Default::default()
+        };
+        app.policy = CleanPolicy::from_config(&config);
+        assert!(app.prepare_clean_preview());
+        assert_eq!(
+            app.clean_preview.as_ref().unwrap().policy_rule.as_deref(),
+            Some("git: dirty, no remote (confirm)")
+        );
+
+        config.dirty_repos = Some(crate::config::DirtyRepoAction::Skip);
+        app.policy = CleanPolicy::from_config(&config);
+        assert!(!app.prepare_clean_preview());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Skipped by policy: git: dirty, no remote")
+        );
+    }
+
     #[test]
     fn test_prepare_clean_preview_separator() {
         let mut app = App::new(PathBuf::from("/root"));
@@ -1634,4 +2716,189 @@ mod tests {
         let result = app.prepare_clean_preview();
         assert!(!result);
     }
+
+    fn quarantine_app() -> (tempfile::TempDir, App, PathBuf) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root_path = temp_dir.path().join("work");
+        let victim = root_path.join("old-build");
+        fs::create_dir_all(&victim).unwrap();
+        fs::write(victim.join("out.bin"), "x".repeat(100)).unwrap();
+
+        let mut app = App::new(root_path.clone());
+        app.quarantine = Some(Arc::new(Quarantine::with_dirs(
+            temp_dir.path().join("data"),
+            temp_dir.path().join("Trash"),
+        )));
+        app.use_quarantine = true;
+        app.tree = Some(scan_directory(&root_path, &scan_options()).unwrap());
+        app.expanded.insert(root_path);
+        app.rebuild_visible_entries();
+        app.selected = 1;
+
+        (temp_dir, app, victim)
+    }
+
+    #[test]
+    fn test_delete_selected_quarantines_and_restores() {
+        let (_temp_dir, mut app, victim) = quarantine_app();
+
+        app.delete_selected();
+        assert!(!victim.exists());
+        assert!(app
+            .status_message
+            .as_deref()
+            .unwrap()
+            .starts_with("Quarantined:"));
+
+        assert!(app.prepare_quarantine_panel());
+        assert_eq!(app.quarantine_entries.len(), 1);
+        assert_eq!(app.quarantine_entries[0].original_path, victim);
+
+        app.restore_selected_quarantine();
+        assert!(victim.join("out.bin").exists());
+        assert!(app.quarantine_entries.is_empty());
+        assert!(app
+            .status_message
+            .as_deref()
+            .unwrap()
+            .starts_with("Restored:"));
+    }
+
+    #[test]
+    fn test_delete_selected_without_quarantine_mode() {
+        let (_temp_dir, mut app, victim) = quarantine_app();
+        app.use_quarantine = false;
+
+        app.delete_selected();
+
+        assert!(!victim.exists());
+        assert!(app.prepare_quarantine_panel());
+        assert!(app.quarantine_entries.is_empty());
+    }
+
+    #[test]
+    fn test_purge_expired_quarantine_keeps_recent_entries() {
+        let (_temp_dir, mut app, _victim) = quarantine_app();
+        app.delete_selected();
+
+        app.purge_expired_quarantine();
+        assert_eq!(app.quarantine_entries.len(), 1);
+
+        app.quarantine_retention_days = 0;
+        app.purge_expired_quarantine();
+        assert!(app.quarantine_entries.is_empty());
+        assert!(app
+            .status_message
+            .as_deref()
+            .unwrap()
+            .starts_with("Purged 1 entries"));
+    }
+
+    fn journal_app() -> (tempfile::TempDir, App, PathBuf) {
+        let (temp_dir, mut app, victim) = quarantine_app();
+        app.use_quarantine = false;
+        app.journal = Some(Arc::new(Journal::with_path(
+            temp_dir.path().join("state/journal.jsonl"),
+        )));
+        (temp_dir, app, victim)
+    }
+
+    #[test]
+    fn test_delete_selected_records_journal() {
+        let (_temp_dir, mut app, victim) = journal_app();
+
+        app.delete_selected();
+
+        app.selected = 0;
+        assert!(app.prepare_history_panel());
+        assert_eq!(app.history_path.as_deref(), Some(app.root.as_path()));
+        assert_eq!(app.history_records.len(), 1);
+        let record = &app.history_records[0];
+        assert_eq!(record.path, victim);
+        assert_eq!(record.method, CleanMethod::Delete);
+        assert!(record.freed_bytes > 0);
+        assert!(record.project_type.is_none());
+    }
+
+    #[test]
+    fn test_clean_selected_records_project_type() {
+        let (_temp_dir, mut app, _victim) = journal_app();
+        let project = app.root.join("crate");
+        fs::create_dir_all(project.join("target")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(project.join("target/out"), "x".repeat(10)).unwrap();
+        app.refresh_tree();
+        wait_for_scan(&mut app);
+        app.selected = app
+            .visible_entries
+            .iter()
+            .position(|e| e.entry.path == project)
+            .unwrap();
+
+        app.clean_selected();
+
+        assert!(app.prepare_history_panel());
+        assert_eq!(app.history_records.len(), 1);
+        assert_eq!(
+            app.history_records[0].project_type.as_deref(),
+            Some("cargo")
+        );
+    }
+
+    #[test]
+    fn test_history_panel_filters() {
+        let (_temp_dir, mut app, victim) = journal_app();
+        let journal = app.journal.clone().unwrap();
+        let mut old = JournalRecord::new(
+            Path::new("/elsewhere/target"),
+            Some("cargo"),
+            CleanMethod::Delete,
+        );
+        old.timestamp -= 10 * 86400;
+        journal.append(&old).unwrap();
+        app.delete_selected();
+
+        // Nothing selected: all paths
+        app.visible_entries.clear();
+        assert!(app.prepare_history_panel());
+        assert!(app.history_path.is_none());
+        assert_eq!(app.history_records.len(), 2);
+        assert_eq!(app.history_records[0].path, victim);
+
+        app.cycle_history_range();
+        assert_eq!(app.history_days(), Some(1));
+        assert_eq!(app.history_records.len(), 1);
+
+        app.cycle_history_range();
+        app.cycle_history_range();
+        assert_eq!(app.history_days(), Some(30));
+        app.cycle_history_type();
+        assert_eq!(app.history_type.as_deref(), Some("cargo"));
+        assert_eq!(app.history_records.len(), 1);
+        assert_eq!(app.history_records[0].path, old.path);
+
+        app.cycle_history_type();
+        assert!(app.history_type.is_none());
+        assert_eq!(app.history_records.len(), 2);
+    }
+
+    #[test]
+    fn test_history_panel_unavailable() {
+        let mut app = App::new(PathBuf::from("/"));
+        assert!(!app.prepare_history_panel());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Cleanup history is unavailable")
+        );
+    }
+
+    #[test]
+    fn test_quarantine_panel_unavailable() {
+        let mut app = App::new(PathBuf::from("/"));
+        assert!(!app.prepare_quarantine_panel());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Quarantine is unavailable")
+        );
+    }
 }
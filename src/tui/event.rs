@@ -33,6 +33,10 @@ pub fn handle_key_event(app: &mut App, key: KeyEvent) {
         Mode::Search => handle_search_mode(app, key),
         Mode::Confirm(action) => handle_confirm_mode(app, key, action),
         Mode::Help => handle_help_mode(app, key),
+        Mode::Breakdown => handle_breakdown_mode(app, key),
+        Mode::Quarantine => handle_quarantine_mode(app, key),
+        Mode::History => handle_history_mode(app, key),
+        Mode::ResourceItems => handle_resource_items_mode(app, key),
     }
 }
 
@@ -53,10 +57,8 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Home | KeyCode::Char('g') => {
             app.selected = 0;
         }
-        KeyCode::End | KeyCode::Char('G') => {
-            if !app.visible_entries.is_empty() {
-                app.selected = app.visible_entries.len() - 1;
-            }
+        KeyCode::End | KeyCode::Char('G') if !app.visible_entries.is_empty() => {
+            app.selected = app.visible_entries.len() - 1;
         }
         KeyCode::PageUp => {
             app.move_selection(-20);
@@ -65,9 +67,13 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.move_selection(20);
         }
 
-        // Expand/Collapse
+        // Expand/Collapse, or list the items of a system resource
         KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => {
-            app.expand_selected();
+            if app.prepare_resource_items_panel() {
+                app.mode = Mode::ResourceItems;
+            } else {
+                app.expand_selected();
+            }
         }
         KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => {
             app.collapse_selected();
@@ -86,12 +92,34 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             }
         }
         KeyCode::Char('c') => {
-            if app.selected_entry().is_some() && app.prepare_clean_preview() {
+            let ready = app.selected_entry().is_some() && app.prepare_clean_preview();
+            if ready {
                 app.mode = Mode::Confirm(ConfirmAction::Clean);
             }
         }
         KeyCode::Char('r') => {
-            app.start_background_scan();
+            app.start_background_rescan();
+        }
+        KeyCode::Char('R') => {
+            app.start_full_rescan();
+        }
+        KeyCode::Char('t') => {
+            let ready = app.prepare_breakdown();
+            if ready {
+                app.mode = Mode::Breakdown;
+            }
+        }
+        KeyCode::Char('u') => {
+            let ready = app.prepare_quarantine_panel();
+            if ready {
+                app.mode = Mode::Quarantine;
+            }
+        }
+        KeyCode::Char('H') => {
+            let ready = app.prepare_history_panel();
+            if ready {
+                app.mode = Mode::History;
+            }
         }
 
         // Search
@@ -173,6 +201,82 @@ fn handle_help_mode(app: &mut App, key: KeyEvent) {
     }
 }
 
+fn handle_breakdown_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('t') | KeyCode::Enter => {
+            app.mode = Mode::Normal;
+            app.breakdown = None;
+        }
+        _ => {}
+    }
+}
+
+fn handle_quarantine_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('u') => {
+            app.mode = Mode::Normal;
+            app.quarantine_entries.clear();
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.move_quarantine_selection(-1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.move_quarantine_selection(1);
+        }
+        KeyCode::Char('r') | KeyCode::Enter => {
+            app.restore_selected_quarantine();
+        }
+        KeyCode::Char('p') => {
+            app.purge_expired_quarantine();
+        }
+        _ => {}
+    }
+}
+
+fn handle_resource_items_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Left | KeyCode::Char('h') => {
+            app.mode = Mode::Normal;
+            app.close_resource_items_panel();
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.move_resource_item_selection(-1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.move_resource_item_selection(1);
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            app.remove_selected_resource_item();
+        }
+        _ => {}
+    }
+}
+
+fn handle_history_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('H') => {
+            app.mode = Mode::Normal;
+            app.history_records.clear();
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.move_history_selection(-1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.move_history_selection(1);
+        }
+        KeyCode::Char('d') => {
+            app.cycle_history_range();
+        }
+        KeyCode::Char('t') => {
+            app.cycle_history_type();
+        }
+        KeyCode::Char('a') => {
+            app.clear_history_path();
+        }
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,6 +394,133 @@ mod tests {
         assert_eq!(app.mode, Mode::Normal);
     }
 
+    #[test]
+    fn test_breakdown_panel_open_and_close() {
+        use crate::scanner::DirEntry;
+
+        let mut app = App::new(PathBuf::from("/test"));
+        let mut root = DirEntry::new_dir(PathBuf::from("/test"), None);
+        root.children.push(DirEntry::new_file(
+            PathBuf::from("/test/movie.mkv"),
+            1000,
+            4096,
+            None,
+        ));
+        root.recalculate_totals();
+        app.tree = Some(root);
+        app.rebuild_visible_entries();
+
+        let t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE);
+        handle_key_event(&mut app, t);
+        assert_eq!(app.mode, Mode::Breakdown);
+        assert!(app.breakdown.is_some());
+
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.breakdown.is_none());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_quarantine_panel_open_navigate_close() {
+        use crate::quarantine::Quarantine;
+        use std::sync::Arc;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let quarantine = Quarantine::with_dirs(tmp.path().join("data"), tmp.path().join("Trash"));
+        for name in ["a", "b"] {
+            let path = tmp.path().join(name);
+            std::fs::write(&path, name).unwrap();
+            quarantine.quarantine(&path).unwrap();
+        }
+        let mut app = App::new(tmp.path().to_path_buf());
+        app.quarantine = Some(Arc::new(quarantine));
+
+        let u = KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE);
+        handle_key_event(&mut app, u);
+        assert_eq!(app.mode, Mode::Quarantine);
+        assert_eq!(app.quarantine_entries.len(), 2);
+
+        let down = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        handle_key_event(&mut app, down);
+        handle_key_event(&mut app, down);
+        assert_eq!(app.quarantine_selected, 1);
+
+        // Entries are listed newest first, so this restores "a"
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(tmp.path().join("a").exists());
+        assert_eq!(app.quarantine_entries.len(), 1);
+        assert_eq!(app.quarantine_selected, 0);
+
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_resource_items_panel_navigate_close() {
+        use crate::cleaner::SystemResourceItem;
+
+        let mut app = App::new(PathBuf::from("/"));
+        app.resource_items = ["a", "b"]
+            .into_iter()
+            .map(|id| SystemResourceItem {
+                id: id.to_string(),
+                name: id.to_string(),
+                size: 100,
+                last_used: None,
+            })
+            .collect();
+        app.mode = Mode::ResourceItems;
+
+        let down = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        handle_key_event(&mut app, down);
+        handle_key_event(&mut app, down);
+        assert_eq!(app.resource_items_selected, 1);
+
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.resource_items.is_empty());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_enter_on_directory_does_not_open_items_panel() {
+        use crate::scanner::DirEntry;
+
+        let mut app = App::new(PathBuf::from("/test"));
+        let mut root = DirEntry::new_dir(PathBuf::from("/test"), None);
+        root.children
+            .push(DirEntry::new_dir(PathBuf::from("/test/sub"), None));
+        root.recalculate_totals();
+        app.tree = Some(root);
+        app.rebuild_visible_entries();
+
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.items_resource.is_none());
+    }
+
+    #[test]
+    fn test_quarantine_panel_unavailable_stays_normal() {
+        let mut app = App::new(PathBuf::from("/"));
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE),
+        );
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_breakdown_without_selection_stays_normal() {
+        let mut app = App::new(PathBuf::from("/"));
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE),
+        );
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
     #[test]
     fn test_toggle_hidden() {
         let mut app = App::new(PathBuf::from("/"));
@@ -9,8 +9,14 @@ pub use app::App;
 
 use std::io::{self, stdout, Stdout};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::cleaner::{CleanPolicy, DetectorRegistry};
+use crate::config::Config;
+use crate::journal::Journal;
+use crate::quarantine::Quarantine;
+use crate::scanner::ScanCache;
 use event::handle_events;
 use ui::render;
 
@@ -24,7 +30,7 @@ use ratatui::prelude::*;
 ///
 /// This is the main entry point for the TUI. It initializes the terminal,
 /// performs an initial scan, and enters the main event loop.
-pub fn run(root: PathBuf) -> anyhow::Result<()> {
+pub fn run(root: PathBuf, config: &Config) -> anyhow::Result<()> {
     // Setup
     install_panic_hook();
     let mut terminal = init_terminal()?;
@@ -32,6 +38,22 @@ pub fn run(root: PathBuf) -> anyhow::Result<()> {
     // Initialize app and start background scan
     let mut app = App::new(root);
     app.detect_system = true;
+    app.cache = ScanCache::from_config(&config.scanner);
+    app.detectors = DetectorRegistry::from_config(&config.project_types);
+    app.policy = CleanPolicy::from_config(&config.cleaner.policy);
+    app.quarantine = Quarantine::open().ok().map(Arc::new);
+    app.use_quarantine = config.quarantine.enabled && app.quarantine.is_some();
+    app.quarantine_retention_days = config.quarantine.retention_days;
+    if let Some(quarantine) = app.quarantine.clone() {
+        // Expire old entries without holding up the first frame
+        let retention_days = app.quarantine_retention_days;
+        std::thread::spawn(move || {
+            if let Err(e) = quarantine.purge_expired(retention_days) {
+                tracing::warn!(error = %e, "Failed to purge expired quarantine entries");
+            }
+        });
+    }
+    app.journal = Journal::open().ok().map(Arc::new);
     app.start_initial_scan();
 
     // Main loop
@@ -28,6 +28,10 @@ pub fn render(app: &App, frame: &mut Frame) {
         Mode::Search => render_search_overlay(app, frame),
         Mode::Confirm(action) => render_confirm_dialog(app, frame, action),
         Mode::Help => render_help_overlay(frame),
+        Mode::Breakdown => render_breakdown_panel(app, frame),
+        Mode::Quarantine => render_quarantine_panel(app, frame),
+        Mode::History => render_history_panel(app, frame),
+        Mode::ResourceItems => render_resource_items_panel(app, frame),
         Mode::Normal => {}
     }
 }
@@ -37,7 +41,18 @@ fn render_header(app: &App, frame: &mut Frame, area: Rect) {
     let size_display = app
         .tree
         .as_ref()
-        .map(|t| humansize::format_size(t.size, humansize::BINARY))
+        .map(|t| {
+            let size = humansize::format_size(t.size, humansize::BINARY);
+            // Hard links are counted once; mention what that saved, like `du` vs `ls`
+            match t.shared_size() {
+                0 => size,
+                shared => format!(
+                    "{} (+{} hardlinked)",
+                    size,
+                    humansize::format_size(shared, humansize::BINARY)
+                ),
+            }
+        })
         .unwrap_or_else(|| "...".to_string());
 
     // Get disk usage info
@@ -351,7 +366,10 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
         }
         Mode::Search => "[Enter] Confirm  [Esc] Cancel",
         Mode::Confirm(_) => "[y] Yes  [n] No",
-        Mode::Help => "[Esc] Close",
+        Mode::Help | Mode::Breakdown => "[Esc] Close",
+        Mode::Quarantine => "[↑↓] Navigate  [r] Restore  [p] Purge expired  [Esc] Close",
+        Mode::History => "[↑↓] Navigate  [d] Date range  [t] Type  [a] All paths  [Esc] Close",
+        Mode::ResourceItems => "[↑↓] Navigate  [d] Remove  [Esc] Close",
     };
 
     // Show status message if present, otherwise hints
@@ -418,9 +436,14 @@ fn render_confirm_dialog(app: &App, frame: &mut Frame, action: ConfirmAction) {
                 .selected_entry()
                 .map(|e| humansize::format_size(e.entry.size, humansize::BINARY))
                 .unwrap_or_default();
+            let verb = if app.use_quarantine {
+                "Move to quarantine"
+            } else {
+                "Delete"
+            };
             (
                 " Delete ",
-                format!("Delete '{}'?\n\nSize: {}\n\n[y]es  [n]o", path, size),
+                format!("{} '{}'?\n\nSize: {}\n\n[y]es  [n]o", verb, path, size),
                 7u16,
             )
         }
@@ -439,6 +462,17 @@ fn render_confirm_dialog(app: &App, frame: &mut Frame, action: ConfirmAction) {
                     artifact_lines.push_str(&format!("  {} ({})\n", name, size_str));
                 }
 
+                let policy_line = preview
+                    .policy_rule
+                    .as_ref()
+                    .map(|rule| format!("Policy: {}\n", rule))
+                    .unwrap_or_default();
+                let git_line = preview
+                    .git_state
+                    .as_ref()
+                    .map(|state| format!("Git: {}\n", state))
+                    .unwrap_or_default();
+
                 let (title, message) = if is_system_resource {
                     (
                         " Clean Resource ",
@@ -449,16 +483,23 @@ fn render_confirm_dialog(app: &App, frame: &mut Frame, action: ConfirmAction) {
                     )
                 } else {
                     (
-                        " Clean Project ",
-                        format!(
-                            "Clean {} project?\n\nArtifacts to remove:\n{}\nTotal: {}\n\n[y]es  [n]o",
-                            preview.project_name, artifact_lines, total_str
+                            " Clean Project ",
+                            format!(
+                            "Clean {} project?\n\nArtifacts to {}:\n{}\n{}{}Total: {}\n\n[y]es  [n]o",
+                            preview.project_name,
+                            if app.use_quarantine { "quarantine" } else { "remove" },
+                            artifact_lines,
+                            git_line,
+                            policy_line,
+                            total_str
                         ),
-                    )
+                        )
                 };
 
                 // Height: title + blank + "Artifacts:" + artifacts + blank + total + blank + buttons + borders
-                let height = (7 + preview.artifacts.len()).min(15) as u16;
+                let policy_rows = usize::from(preview.policy_rule.is_some())
+                    + usize::from(preview.git_state.is_some());
+                let height = (7 + preview.artifacts.len() + policy_rows).min(15) as u16;
 
                 (title, message, height)
             } else {
@@ -495,54 +536,455 @@ fn render_confirm_dialog(app: &App, frame: &mut Frame, action: ConfirmAction) {
 fn render_help_overlay(frame: &mut Frame) {
     let area = frame.area();
 
-    // Near full-screen overlay
-    let help_width = 60u16.min(area.width.saturating_sub(8));
-    let help_height = 22u16.min(area.height.saturating_sub(4));
-    let help_area = Rect {
-        x: (area.width.saturating_sub(help_width)) / 2,
-        y: (area.height.saturating_sub(help_height)) / 2,
-        width: help_width,
-        height: help_height,
-    };
-
-    frame.render_widget(Clear, help_area);
-
-    let help_text = r#"
+    // Two columns, so every key fits on an 80x24 terminal
+    let left = r#"
  NAVIGATION
- ─────────────────────────────────
+ ──────────────────────────────
  ↑/k        Move up
  ↓/j        Move down
- →/l/Enter  Expand directory
+ →/l/Enter  Expand / List items
  ←/h/Bksp   Collapse / Go to parent
  Space      Toggle expand/collapse
  g          Go to top
  G          Go to bottom
 
- ACTIONS
- ─────────────────────────────────
- d          Delete selected
- c          Clean project artifacts
- r          Refresh / Rescan
-
  VIEW
- ─────────────────────────────────
+ ──────────────────────────────
  /          Search / Filter
  s          Cycle sort order
  .          Toggle hidden files
  ?          Toggle this help
- q/Esc      Quit
-"#;
+ q/Esc      Quit"#;
+    let right = r#"
+ ACTIONS
+ ──────────────────────────────
+ d          Delete selected
+ c          Clean project artifacts
+ r          Rescan changed dirs
+ R          Full rescan
+ t          File type breakdown
+ u          Quarantine panel
+ H          Cleanup history"#;
+
+    let lines = left.lines().count().max(right.lines().count()) as u16;
+    let help_width = 76u16.min(area.width.saturating_sub(4));
+    let help_height = (lines + 2).min(area.height.saturating_sub(4));
+    let help_area = Rect {
+        x: (area.width.saturating_sub(help_width)) / 2,
+        y: (area.height.saturating_sub(help_height)) / 2,
+        width: help_width,
+        height: help_height,
+    };
+
+    frame.render_widget(Clear, help_area);
 
     let block = Block::default()
         .title(" Help ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(help_area);
+    frame.render_widget(block, help_area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    for (text, column) in [(left, columns[0]), (right, columns[1])] {
+        let paragraph = Paragraph::new(text).style(Style::default().fg(Color::White));
+        frame.render_widget(paragraph, column);
+    }
+}
 
-    let paragraph = Paragraph::new(help_text)
-        .block(block)
-        .style(Style::default().fg(Color::White));
+fn render_breakdown_panel(app: &App, frame: &mut Frame) {
+    let Some((path, breakdown)) = &app.breakdown else {
+        return;
+    };
+    let area = frame.area();
+
+    let max_height = 24u16.min(area.height.saturating_sub(2));
+
+    let header_style = Style::default().fg(Color::Cyan).bold();
+    let row = |label: String, size: u64, disk: u64, files: u64, percent: f64| {
+        Line::from(vec![
+            Span::styled(format!(" {:<14}", label), Style::default().fg(Color::White)),
+            Span::styled(
+                format!(
+                    "{:>10} {:>10}",
+                    humansize::format_size(size, humansize::BINARY),
+                    humansize::format_size(disk, humansize::BINARY)
+                ),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                format!(" {:>7} {:>5.1}%", files, percent),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])
+    };
+    let columns = |first: &str| {
+        Line::from(Span::styled(
+            format!(
+                " {:<14}{:>10} {:>10} {:>7} {:>6}",
+                first, "Size", "Disk", "Files", "Share"
+            ),
+            header_style,
+        ))
+    };
+
+    let mut lines = vec![columns("Category")];
+    for group in &breakdown.categories {
+        lines.push(row(
+            group.category.label().to_string(),
+            group.stats.size,
+            group.stats.disk_usage,
+            group.stats.files,
+            breakdown.percent_of_total(&group.stats),
+        ));
+    }
+
+    lines.push(Line::default());
+    lines.push(columns("Extension"));
+    // Fill whatever height is left with the largest extensions
+    // (borders, this header and the blank + total lines below)
+    let room = (max_height as usize).saturating_sub(lines.len() + 5);
+    for group in breakdown.extensions.iter().take(room) {
+        let name = if group.extension.is_empty() {
+            "(none)".to_string()
+        } else {
+            format!(".{}", group.extension)
+        };
+        lines.push(row(
+            name,
+            group.stats.size,
+            group.stats.disk_usage,
+            group.stats.files,
+            breakdown.percent_of_total(&group.stats),
+        ));
+    }
+
+    lines.push(Line::default());
+    lines.push(row(
+        "Total".to_string(),
+        breakdown.total.size,
+        breakdown.total.disk_usage,
+        breakdown.total.files,
+        100.0,
+    ));
+
+    let panel_width = 64u16.min(area.width.saturating_sub(4));
+    let panel_height = (lines.len() as u16 + 2).min(max_height);
+    let panel_area = Rect {
+        x: (area.width.saturating_sub(panel_width)) / 2,
+        y: (area.height.saturating_sub(panel_height)) / 2,
+        width: panel_width,
+        height: panel_height,
+    };
+
+    frame.render_widget(Clear, panel_area);
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    let block = Block::default()
+        .title(format!(" File Types: {} ", name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Paragraph::new(lines).block(block), panel_area);
+}
+
+fn render_quarantine_panel(app: &App, frame: &mut Frame) {
+    let area = frame.area();
+    let now = std::time::SystemTime::now();
+
+    let panel_width = 72u16.min(area.width.saturating_sub(4));
+    let panel_height = 20u16.min(area.height.saturating_sub(2));
+    // Borders, header and the summary line below the list
+    let rows = (panel_height as usize).saturating_sub(5);
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(" {:>5} {:>10}  {}", "Age", "Size", "Original path"),
+        Style::default().fg(Color::Cyan).bold(),
+    ))];
+
+    if app.quarantine_entries.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " Quarantine is empty",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let offset =
+        calculate_scroll_offset(app.quarantine_selected, rows, app.quarantine_entries.len());
+    let path_width = (panel_width as usize).saturating_sub(22);
+    for (i, entry) in app
+        .quarantine_entries
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(rows)
+    {
+        let days = entry.age(now).as_secs() / 86400;
+        let path = entry.original_path.display().to_string();
+        let path = if path.len() > path_width {
+            let start = path.len() - path_width.saturating_sub(3);
+            let start = (start..path.len())
+                .find(|&i| path.is_char_boundary(i))
+                .unwrap_or(path.len());
+            format!("...{}", &path[start..])
+        } else {
+            path
+        };
+        let style = if i == app.quarantine_selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                " {:>4}d {:>10}  {}",
+                days,
+                humansize::format_size(entry.size, humansize::BINARY),
+                path
+            ),
+            style,
+        )));
+    }
 
-    frame.render_widget(paragraph, help_area);
+    let total: u64 = app.quarantine_entries.iter().map(|e| e.size).sum();
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        format!(
+            " {} entries, {}  (purge keeps the last {}d)",
+            app.quarantine_entries.len(),
+            humansize::format_size(total, humansize::BINARY),
+            app.quarantine_retention_days
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let panel_height = (lines.len() as u16 + 2).min(panel_height);
+    let panel_area = Rect {
+        x: (area.width.saturating_sub(panel_width)) / 2,
+        y: (area.height.saturating_sub(panel_height)) / 2,
+        width: panel_width,
+        height: panel_height,
+    };
+
+    frame.render_widget(Clear, panel_area);
+
+    let block = Block::default()
+        .title(" Quarantine ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Paragraph::new(lines).block(block), panel_area);
+}
+
+fn render_resource_items_panel(app: &App, frame: &mut Frame) {
+    let area = frame.area();
+    let now = std::time::SystemTime::now();
+
+    let panel_width = 72u16.min(area.width.saturating_sub(4));
+    let panel_height = 20u16.min(area.height.saturating_sub(2));
+    // Borders, header and the summary line below the list
+    let rows = (panel_height as usize).saturating_sub(5);
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(" {:>9} {:>10}  {}", "Last used", "Size", "Name"),
+        Style::default().fg(Color::Cyan).bold(),
+    ))];
+
+    let offset =
+        calculate_scroll_offset(app.resource_items_selected, rows, app.resource_items.len());
+    let name_width = (panel_width as usize).saturating_sub(26);
+    for (i, item) in app
+        .resource_items
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(rows)
+    {
+        let last_used = item
+            .last_used
+            .and_then(|t| now.duration_since(t).ok())
+            .map(|age| format!("{}d", age.as_secs() / 86400))
+            .unwrap_or_else(|| "-".to_string());
+        let name: String = if item.name.chars().count() > name_width {
+            let kept: String = item
+                .name
+                .chars()
+                .take(name_width.saturating_sub(3))
+                .collect();
+            format!("{}...", kept)
+        } else {
+            item.name.clone()
+        };
+        let style = if i == app.resource_items_selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                " {:>9} {:>10}  {}",
+                last_used,
+                humansize::format_size(item.size, humansize::BINARY),
+                name
+            ),
+            style,
+        )));
+    }
+
+    let total: u64 = app.resource_items.iter().map(|i| i.size).sum();
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        format!(
+            " {} items, {}",
+            app.resource_items.len(),
+            humansize::format_size(total, humansize::BINARY)
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let panel_height = (lines.len() as u16 + 2).min(panel_height);
+    let panel_area = Rect {
+        x: (area.width.saturating_sub(panel_width)) / 2,
+        y: (area.height.saturating_sub(panel_height)) / 2,
+        width: panel_width,
+        height: panel_height,
+    };
+
+    frame.render_widget(Clear, panel_area);
+
+    let title = app
+        .items_resource
+        .as_ref()
+        .map(|r| format!(" {} ", r.display_name))
+        .unwrap_or_else(|| " Items ".to_string());
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Paragraph::new(lines).block(block), panel_area);
+}
+
+fn render_history_panel(app: &App, frame: &mut Frame) {
+    let area = frame.area();
+
+    let panel_width = 96u16.min(area.width.saturating_sub(4));
+    let panel_height = 20u16.min(area.height.saturating_sub(2));
+    // Borders, filter line, header and the summary line below the list
+    let rows = (panel_height as usize).saturating_sub(6);
+
+    let filters = format!(
+        " Path: {}  Type: {}  Since: {}",
+        app.history_path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "all".to_string()),
+        app.history_type.as_deref().unwrap_or("all"),
+        app.history_days()
+            .map(|d| format!("{}d ago", d))
+            .unwrap_or_else(|| "all time".to_string()),
+    );
+    let mut lines = vec![
+        Line::from(Span::styled(filters, Style::default().fg(Color::DarkGray))),
+        Line::from(Span::styled(
+            format!(
+                " {:<16} {:<10} {:<10} {:>10}  {}",
+                "When", "Type", "Method", "Freed", "Path"
+            ),
+            Style::default().fg(Color::Cyan).bold(),
+        )),
+    ];
+
+    if app.history_records.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No deletions recorded",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let offset = calculate_scroll_offset(app.history_selected, rows, app.history_records.len());
+    let path_width = (panel_width as usize).saturating_sub(54);
+    for (i, record) in app
+        .history_records
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(rows)
+    {
+        let path = record.path.display().to_string();
+        let path = if path.len() > path_width {
+            let start = path.len() - path_width.saturating_sub(3);
+            let start = (start..path.len())
+                .find(|&i| path.is_char_boundary(i))
+                .unwrap_or(path.len());
+            format!("...{}", &path[start..])
+        } else {
+            path
+        };
+        let freed = match &record.error {
+            Some(_) => "failed".to_string(),
+            None => humansize::format_size(record.freed_bytes, humansize::BINARY),
+        };
+        let style = if i == app.history_selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else if record.error.is_some() {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        // Seconds are dropped to keep the row narrow
+        let when = crate::journal::format_timestamp(record.timestamp);
+        lines.push(Line::from(Span::styled(
+            format!(
+                " {:<16} {:<10} {:<10} {:>10}  {}",
+                &when[..16],
+                record.project_type.as_deref().unwrap_or("-"),
+                record.method.to_string(),
+                freed,
+                path
+            ),
+            style,
+        )));
+    }
+
+    let total: u64 = app
+        .history_records
+        .iter()
+        .filter(|r| r.error.is_none())
+        .map(|r| r.freed_bytes)
+        .sum();
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        format!(
+            " {} records, {} freed",
+            app.history_records.len(),
+            humansize::format_size(total, humansize::BINARY)
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let panel_height = (lines.len() as u16 + 2).min(panel_height);
+    let panel_area = Rect {
+        x: (area.width.saturating_sub(panel_width)) / 2,
+        y: (area.height.saturating_sub(panel_height)) / 2,
+        width: panel_width,
+        height: panel_height,
+    };
+
+    frame.render_widget(Clear, panel_area);
+
+    let block = Block::default()
+        .title(" Cleanup History ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Paragraph::new(lines).block(block), panel_area);
 }
 
 #[cfg(test)]
@@ -628,6 +1070,48 @@ mod tests {
         terminal.draw(|frame| render(&app, frame)).unwrap();
     }
 
+    #[test]
+    fn test_render_breakdown_panel() {
+        use crate::scanner::DirEntry;
+
+        let mut app = App::new(PathBuf::from("/test"));
+        let mut root = DirEntry::new_dir(PathBuf::from("/test"), None);
+        for name in ["a.mkv", "b.png", "c.rs", "d.log", "Makefile"] {
+            root.children.push(DirEntry::new_file(
+                PathBuf::from("/test").join(name),
+                1000,
+                4096,
+                None,
+            ));
+        }
+        root.recalculate_totals();
+        app.tree = Some(root);
+        app.rebuild_visible_entries();
+        assert!(app.prepare_breakdown());
+        app.mode = Mode::Breakdown;
+
+        let backend = TestBackend::new(80, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|frame| render(&app, frame)).unwrap();
+
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(content.contains("File Types: test"));
+        assert!(content.contains("Video"));
+        assert!(content.contains(".mkv"));
+        assert!(content.contains("(none)"));
+
+        // A tiny terminal must not panic
+        let backend = TestBackend::new(20, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|frame| render(&app, frame)).unwrap();
+    }
+
     #[test]
     fn test_size_color_gradient() {
         let max_size = 1000;
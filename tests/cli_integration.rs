@@ -0,0 +1,1477 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn rusty_sweeper() -> Command {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("rusty-sweeper");
+    // A configured Cargo target dir would be claimed by the test projects
+    cmd.env_remove("CARGO_TARGET_DIR")
+        .env_remove("CARGO_BUILD_TARGET_DIR")
+        .env("CARGO_HOME", "/nonexistent/rusty-sweeper-tests");
+    cmd
+}
+
+fn create_project_tree() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+
+    let cargo_proj = tmp.path().join("rust-app");
+    fs::create_dir_all(cargo_proj.join("target")).unwrap();
+    fs::write(cargo_proj.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(cargo_proj.join("target/debug.bin"), "x".repeat(1000)).unwrap();
+
+    fs::create_dir_all(tmp.path().join("docs")).unwrap();
+    fs::write(tmp.path().join("docs/readme.txt"), "hello").unwrap();
+
+    tmp
+}
+
+#[test]
+fn test_help_lists_subcommands() {
+    rusty_sweeper()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("scan"))
+        .stdout(predicate::str::contains("clean"))
+        .stdout(predicate::str::contains("tui"))
+        .stdout(predicate::str::contains("duplicates"))
+        .stdout(predicate::str::contains("quarantine"))
+        .stdout(predicate::str::contains("history"));
+}
+
+#[test]
+fn test_scan_tree_output() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["scan", "--all"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rust-app/"))
+        .stdout(predicate::str::contains("Total:"));
+}
+
+#[test]
+fn test_scan_json_output() {
+    let tmp = create_project_tree();
+
+    let output = rusty_sweeper()
+        .args(["scan", "--json"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(parsed["children"].is_array());
+}
+
+#[test]
+fn test_scan_table_output() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["scan", "--table"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SIZE"))
+        .stdout(predicate::str::contains("PATH"));
+}
+
+#[test]
+fn test_scan_exclude_prunes_subtree() {
+    let tmp = create_project_tree();
+
+    let output = rusty_sweeper()
+        .args(["scan", "--json", "--exclude", "**/target"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("rust-app"));
+    assert!(!stdout.contains("debug.bin"));
+}
+
+fn save_snapshot(tmp: &TempDir, name: &str) -> std::path::PathBuf {
+    let output = rusty_sweeper()
+        .args(["scan", "--json"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let snapshot = tmp.path().join(name);
+    fs::write(&snapshot, &output.stdout).unwrap();
+    snapshot
+}
+
+#[test]
+fn test_scan_diff_against_live_tree() {
+    let tmp = create_project_tree();
+    let snapshots = TempDir::new().unwrap();
+    let output = rusty_sweeper()
+        .args(["scan", "--json"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+    let snapshot = snapshots.path().join("before.json");
+    fs::write(&snapshot, &output.stdout).unwrap();
+
+    fs::create_dir(tmp.path().join("downloads")).unwrap();
+    fs::write(tmp.path().join("downloads/big.iso"), "x".repeat(5000)).unwrap();
+    fs::remove_dir_all(tmp.path().join("docs")).unwrap();
+
+    rusty_sweeper()
+        .args(["scan", "--table", "--diff"])
+        .arg(&snapshot)
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("downloads [added]"))
+        .stdout(predicate::str::contains("docs [removed]"))
+        .stdout(predicate::str::contains("Total:"));
+}
+
+#[test]
+fn test_scan_diff_two_snapshots_json() {
+    let tmp = create_project_tree();
+    let before = save_snapshot(&tmp, "before.json");
+    fs::write(
+        tmp.path().join("rust-app/target/more.bin"),
+        "x".repeat(2000),
+    )
+    .unwrap();
+    let after = save_snapshot(&tmp, "after.json");
+
+    let output = rusty_sweeper()
+        .args(["scan", "--json", "--diff"])
+        .arg(&before)
+        .arg(&after)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["changes"][0]["kind"], "grown");
+    assert!(parsed["changes"][0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("rust-app"));
+}
+
+#[test]
+fn test_scan_diff_missing_snapshot_fails() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["scan", "--diff", "/nonexistent/snap.json"])
+        .arg(tmp.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error"));
+}
+
+#[test]
+fn test_scan_by_type_table() {
+    let tmp = create_project_tree();
+    fs::write(tmp.path().join("docs/movie.mkv"), "x".repeat(8000)).unwrap();
+
+    rusty_sweeper()
+        .args(["scan", "--by-type"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CATEGORY"))
+        .stdout(predicate::str::contains("Video"))
+        .stdout(predicate::str::contains(".mkv"))
+        .stdout(predicate::str::contains("TOTAL"));
+}
+
+#[test]
+fn test_scan_by_type_json_counts_deep_files() {
+    let tmp = create_project_tree();
+    let deep = tmp.path().join("a/b/c/d/e/f");
+    fs::create_dir_all(&deep).unwrap();
+    fs::write(deep.join("server.log"), "x".repeat(3000)).unwrap();
+
+    let output = rusty_sweeper()
+        .args(["scan", "--by-type", "--json", "--max-depth", "1"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let log = parsed["extensions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["extension"] == "log")
+        .expect("log extension present");
+    assert_eq!(log["category"], "log");
+    assert_eq!(log["size"], 3000);
+}
+
+#[test]
+fn test_scan_nonexistent_path_fails() {
+    rusty_sweeper()
+        .args(["scan", "/nonexistent/path/12345"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error"));
+}
+
+#[test]
+fn test_clean_size_only() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["clean", "--size-only", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo"))
+        .stdout(predicate::str::contains("rust-app"));
+
+    assert!(tmp.path().join("rust-app/target").exists());
+}
+
+#[test]
+fn test_clean_composite_project() {
+    let tmp = TempDir::new().unwrap();
+    let app = tmp.path().join("tauri-app");
+    fs::create_dir_all(app.join("target")).unwrap();
+    fs::create_dir_all(app.join("node_modules")).unwrap();
+    fs::write(app.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(app.join("package.json"), "{}").unwrap();
+    fs::write(app.join("target/debug.bin"), "x".repeat(1000)).unwrap();
+    fs::write(app.join("node_modules/dep.js"), "x".repeat(500)).unwrap();
+
+    rusty_sweeper()
+        .args(["clean", "--force", "--types", "cargo,npm"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo+npm"))
+        .stdout(predicate::str::contains("tauri-app"));
+
+    assert!(!app.join("target").exists());
+    assert!(!app.join("node_modules").exists());
+}
+
+#[test]
+fn test_clean_nested_projects() {
+    let tmp = TempDir::new().unwrap();
+    let web = tmp.path().join("web");
+    let tool = web.join("tools/codegen");
+    fs::create_dir_all(web.join("node_modules")).unwrap();
+    fs::create_dir_all(tool.join("target")).unwrap();
+    fs::write(web.join("package.json"), "{}").unwrap();
+    fs::write(web.join("node_modules/dep.js"), "x".repeat(500)).unwrap();
+    fs::write(tool.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(tool.join("target/codegen"), "x".repeat(1000)).unwrap();
+
+    // Without --nested the scan stops at the outer project
+    rusty_sweeper()
+        .args(["clean", "--size-only", "--types", "cargo,npm"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("codegen").not());
+
+    rusty_sweeper()
+        .args(["clean", "--force", "--nested", "--types", "cargo,npm"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("↳"))
+        .stdout(predicate::str::contains("codegen"));
+
+    assert!(!web.join("node_modules").exists());
+    assert!(!tool.join("target").exists());
+    assert!(tool.join("Cargo.toml").exists());
+}
+
+#[test]
+fn test_clean_reports_gradle_module_builds() {
+    let tmp = TempDir::new().unwrap();
+    let shop = tmp.path().join("shop");
+    fs::create_dir_all(shop.join("core/build")).unwrap();
+    fs::create_dir_all(shop.join("feature-x/build")).unwrap();
+    fs::write(shop.join("build.gradle.kts"), "").unwrap();
+    fs::write(
+        shop.join("settings.gradle.kts"),
+        "include(\":core\", \":feature-x\")",
+    )
+    .unwrap();
+    fs::write(shop.join("core/build/core.jar"), "x".repeat(3000)).unwrap();
+    fs::write(shop.join("feature-x/build/x.jar"), "x".repeat(2000)).unwrap();
+
+    rusty_sweeper()
+        .args(["clean", "--size-only", "--types", "gradle"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("shop"))
+        .stdout(predicate::str::contains("4.88 KiB"));
+}
+
+#[test]
+fn test_clean_shared_cargo_target_dir() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join(".cargo")).unwrap();
+    fs::write(
+        root.join(".cargo/config.toml"),
+        "[build]\ntarget-dir = \"shared-target\"\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("shared-target/debug")).unwrap();
+    fs::write(root.join("shared-target/debug/app"), "x".repeat(1000)).unwrap();
+    for project in ["alpha", "beta"] {
+        fs::create_dir_all(root.join(project)).unwrap();
+        fs::write(root.join(project).join("Cargo.toml"), "[package]").unwrap();
+    }
+
+    rusty_sweeper()
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(shared with"))
+        .stdout(predicate::str::contains("beta"))
+        .stdout(predicate::str::contains("Cleaned: 1 item"));
+
+    assert!(!root.join("shared-target").exists());
+}
+
+#[test]
+fn test_clean_skips_a_workspace_target_outside_the_scanned_member() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\n",
+    )
+    .unwrap();
+    let member = root.join("crates/core");
+    fs::create_dir_all(&member).unwrap();
+    fs::write(member.join("Cargo.toml"), "[package]\nname = \"core\"\n").unwrap();
+    fs::create_dir_all(root.join("target/debug")).unwrap();
+    fs::write(root.join("target/debug/core"), "x".repeat(1000)).unwrap();
+
+    rusty_sweeper()
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(&member)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(outside scan root:"))
+        .stdout(predicate::str::contains("needs confirmation"))
+        .stdout(predicate::str::contains("Cleaned: 0 items"));
+
+    assert!(root.join("target/debug/core").exists());
+}
+
+#[test]
+fn test_clean_never_removes_a_cargo_target_dir_holding_the_project() {
+    let tmp = create_project_tree();
+    let project = tmp.path().join("rust-app");
+    fs::create_dir_all(project.join(".cargo")).unwrap();
+    fs::write(
+        project.join(".cargo/config.toml"),
+        "[build]\ntarget-dir = \".\"\n",
+    )
+    .unwrap();
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", tmp.path().join("xdg-data"))
+        .args(["clean", "--force", "--quarantine", "--types", "cargo"])
+        .arg(&project)
+        .assert()
+        .success();
+    assert!(project.join("Cargo.toml").exists());
+    assert!(!project.join("target").exists());
+
+    fs::remove_dir_all(project.join(".cargo")).unwrap();
+    rusty_sweeper()
+        .env("CARGO_TARGET_DIR", "..")
+        .env("XDG_DATA_HOME", tmp.path().join("xdg-data"))
+        .args(["clean", "--force", "--quarantine", "--types", "cargo"])
+        .arg(&project)
+        .assert()
+        .success();
+    assert!(project.join("Cargo.toml").exists());
+    assert!(tmp.path().join("docs/readme.txt").exists());
+}
+
+#[test]
+fn test_clean_prune_keeps_current_cargo_units() {
+    let tmp = TempDir::new().unwrap();
+    let project = tmp.path().join("rust-app");
+    let target = project.join("target");
+    let write = |rel: &str, content: &str| {
+        let path = target.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+    // `old` was last built a week ago by another toolchain
+    write(
+        "debug/.fingerprint/old-0000000000000001/lib-old.json",
+        r#"{"rustc":1}"#,
+    );
+    write("debug/deps/libold-0000000000000001.rlib", &"x".repeat(600));
+    write(
+        "debug/.fingerprint/app-0000000000000002/bin-app.json",
+        r#"{"rustc":2}"#,
+    );
+    write("debug/deps/app-0000000000000002", &"x".repeat(400));
+    let last_week = std::time::SystemTime::now() - std::time::Duration::from_secs(7 * 86400);
+    fs::File::open(target.join("debug/.fingerprint/old-0000000000000001/lib-old.json"))
+        .unwrap()
+        .set_times(
+            fs::FileTimes::new()
+                .set_accessed(last_week)
+                .set_modified(last_week),
+        )
+        .unwrap();
+
+    rusty_sweeper()
+        .args(["clean", "--force", "--prune", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cargo target pruning"))
+        .stdout(predicate::str::contains("old toolchain"));
+
+    assert!(!target
+        .join("debug/deps/libold-0000000000000001.rlib")
+        .exists());
+    assert!(target.join("debug/deps/app-0000000000000002").exists());
+    assert!(target
+        .join("debug/.fingerprint/app-0000000000000002")
+        .exists());
+}
+
+#[test]
+fn test_clean_dry_run_keeps_artifacts() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["clean", "--dry-run", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DRY RUN"));
+
+    assert!(tmp.path().join("rust-app/target").exists());
+}
+
+#[test]
+fn test_clean_force_removes_artifacts() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cleaned: 1 item"));
+
+    assert!(!tmp.path().join("rust-app/target").exists());
+}
+
+/// Turn the Cargo project from `create_project_tree` into a git repository
+/// with an untracked Cargo.toml.
+fn init_dirty_repo(tmp: &TempDir) {
+    let status = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .arg(tmp.path().join("rust-app"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn test_clean_shows_git_column() {
+    let tmp = create_project_tree();
+    init_dirty_repo(&tmp);
+    let config = write_config(&tmp, "[cleaner.policy]\ndirty_repos = \"confirm\"\n");
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--size-only", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("GIT"))
+        .stdout(predicate::str::contains("dirty, no remote"));
+}
+
+#[test]
+fn test_clean_skips_git_check_without_dirty_repos_rule() {
+    let tmp = create_project_tree();
+    init_dirty_repo(&tmp);
+
+    rusty_sweeper()
+        .args(["clean", "--size-only", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SIZE  GIT").not());
+}
+
+#[test]
+fn test_clean_dirty_repos_skip() {
+    let tmp = create_project_tree();
+    init_dirty_repo(&tmp);
+    let config = write_config(&tmp, "[cleaner.policy]\ndirty_repos = \"skip\"\n");
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("git: dirty"))
+        .stdout(predicate::str::contains("all were skipped"));
+
+    assert!(tmp.path().join("rust-app/target").exists());
+}
+
+#[test]
+fn test_clean_dirty_repos_confirm() {
+    let tmp = create_project_tree();
+    init_dirty_repo(&tmp);
+    let config = write_config(&tmp, "[cleaner.policy]\ndirty_repos = \"confirm\"\n");
+
+    // --force does not answer the per-project question
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("needs confirmation"));
+    assert!(tmp.path().join("rust-app/target").exists());
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--types", "cargo"])
+        .arg(tmp.path())
+        .write_stdin("y\ny\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "has unsaved git work (dirty, no remote)",
+        ))
+        .stdout(predicate::str::contains("Cleaned: 1 item"));
+    assert!(!tmp.path().join("rust-app/target").exists());
+}
+
+#[test]
+fn test_clean_quarantine_and_restore() {
+    let tmp = create_project_tree();
+    let data_home = tmp.path().join("xdg-data");
+    let target = tmp.path().join("rust-app/target");
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .args(["clean", "--force", "--quarantine", "--types", "cargo"])
+        .arg(tmp.path().join("rust-app"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Quarantined:"))
+        // Measured in allocated blocks, so never exactly the apparent size
+        .stdout(predicate::str::contains("Estimated: 1000 B"));
+    assert!(!target.exists());
+
+    let output = rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .args(["quarantine", "list", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["original_path"], target.to_str().unwrap());
+    let id = entries[0]["id"].as_str().unwrap();
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .args(["quarantine", "restore", id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored"));
+    assert!(target.join("debug.bin").exists());
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .args(["quarantine", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Quarantine is empty."));
+}
+
+#[test]
+fn test_clean_quarantine_purges_expired_entries() {
+    let tmp = create_project_tree();
+    let data_home = tmp.path().join("xdg-data");
+    let quarantine_dir = data_home.join("rusty-sweeper/quarantine");
+    let stored = quarantine_dir.join("holding/1-000000000-0/target");
+    fs::create_dir_all(&stored).unwrap();
+    fs::write(stored.join("old.bin"), "x".repeat(500)).unwrap();
+    let index = serde_json::json!([{
+        "id": "1-000000000-0",
+        "original_path": tmp.path().join("old-app/target"),
+        "stored_path": stored,
+        "storage": "holding",
+        "quarantined_at": 1,
+        "size": 500,
+    }]);
+    fs::write(quarantine_dir.join("index.json"), index.to_string()).unwrap();
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .args(["clean", "--force", "--quarantine", "--types", "cargo"])
+        .arg(tmp.path().join("rust-app"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Expired: 500 B purged"));
+
+    assert!(!stored.exists());
+    let index = fs::read_to_string(quarantine_dir.join("index.json")).unwrap();
+    assert!(!index.contains("1-000000000-0"));
+    assert!(index.contains("rust-app"));
+}
+
+#[test]
+fn test_clean_records_history() {
+    let tmp = create_project_tree();
+    let state_home = tmp.path().join("xdg-state");
+    let target = tmp.path().join("rust-app/target");
+
+    // Dry runs are not recorded
+    rusty_sweeper()
+        .env("XDG_STATE_HOME", &state_home)
+        .args(["clean", "--dry-run", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+    rusty_sweeper()
+        .env("XDG_STATE_HOME", &state_home)
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No deletions recorded."));
+
+    rusty_sweeper()
+        .env("XDG_STATE_HOME", &state_home)
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+    assert!(state_home.join("rusty-sweeper/journal.jsonl").exists());
+
+    let output = rusty_sweeper()
+        .env("XDG_STATE_HOME", &state_home)
+        .args([
+            "history", "--json", "--since", "1d", "--type", "cargo", "--path",
+        ])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(records.as_array().unwrap().len(), 1);
+    let record = &records[0];
+    assert_eq!(record["project_type"], "cargo");
+    assert!(record["user"].is_string());
+    assert!(record["invocation"].is_string());
+    // `cargo clean` fails on the stub manifest, so the directory is deleted
+    assert_eq!(record["method"], "delete");
+    assert_eq!(record["path"], target.to_str().unwrap());
+    assert!(record["freed_bytes"].as_u64().unwrap() > 0);
+
+    rusty_sweeper()
+        .env("XDG_STATE_HOME", &state_home)
+        .args(["history", "--type", "npm"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No deletions recorded."));
+
+    rusty_sweeper()
+        .env("XDG_STATE_HOME", &state_home)
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rust-app/target"))
+        .stdout(predicate::str::contains("freed in 1 record"));
+}
+
+#[test]
+fn test_clean_package_cache_by_age() {
+    let tmp = TempDir::new().unwrap();
+    let repo = tmp.path().join("m2");
+    let old = repo.join("org/example/lib/1.0");
+    let new = repo.join("org/example/lib/2.0");
+    fs::create_dir_all(&old).unwrap();
+    fs::create_dir_all(&new).unwrap();
+    fs::write(old.join("lib-1.0.pom"), "x".repeat(100)).unwrap();
+    fs::write(new.join("lib-2.0.pom"), "x".repeat(100)).unwrap();
+    let stale = std::time::SystemTime::now() - std::time::Duration::from_secs(200 * 86400);
+    fs::File::options()
+        .write(true)
+        .open(old.join("lib-1.0.pom"))
+        .unwrap()
+        .set_times(fs::FileTimes::new().set_accessed(stale).set_modified(stale))
+        .unwrap();
+
+    let maven_opts = format!("-Dmaven.repo.local={}", repo.display());
+    let config = write_config(
+        &tmp,
+        "[cleaner.policy.types.maven-repo]\nmin_age_days = 90\n",
+    );
+    // --age is for projects: the 200-day-old entry still goes
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .env("MAVEN_OPTS", &maven_opts)
+        .env("XDG_STATE_HOME", tmp.path().join("xdg-state"))
+        .args(["clean", "--force", "--age", "1000", "--types", "maven-repo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Maven Repository"))
+        .stdout(predicate::str::contains("(1 items)"));
+    assert!(!old.exists());
+    assert!(new.exists());
+}
+
+/// Install a fake `name` command that logs its arguments and answers with
+/// the given `case` arms; other invocations fail. Returns the directory to
+/// put on PATH and the log.
+fn fake_command(
+    tmp: &std::path::Path,
+    name: &str,
+    cases: &str,
+) -> (std::path::PathBuf, std::path::PathBuf) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = tmp.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = tmp.join(format!("{}.log", name));
+    let script = format!(
+        "#!/bin/sh\necho \"$*\" >> '{}'\ncase \"$*\" in\n{}\n  *) exit 1 ;;\nesac\n",
+        log.display(),
+        cases
+    );
+    let path = bin.join(name);
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path_var = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+    (std::path::PathBuf::from(path_var), log)
+}
+
+/// Install a fake `docker` that answers with canned `{{json .}}` output.
+fn fake_docker(tmp: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    let cases = format!(
+        r#"  "info") exit 0 ;;
+  "info --format"*) echo '{root}' ;;
+  "system df --format"*)
+    echo '{{"Type":"Images","TotalCount":"3","Active":"1","Size":"667MB","Reclaimable":"480MB (71%)"}}'
+    echo '{{"Type":"Containers","TotalCount":"2","Active":"1","Size":"1.5kB","Reclaimable":"1.5kB (99%)"}}'
+    echo '{{"Type":"Local Volumes","TotalCount":"2","Active":"1","Size":"1.21GB","Reclaimable":"1.2GB (99%)"}}'
+    echo '{{"Type":"Build Cache","TotalCount":"0","Active":"0","Size":"0B","Reclaimable":"0B"}}' ;;
+  "system df -v --format"*)
+    echo '{{"BuildCache":[],"Containers":[{{"CreatedAt":"2024-03-01 12:00:00 +0000 UTC","ID":"c0ffee000001","Image":"alpine","Names":"old_job","Size":"1.5kB","State":"exited"}}],"Images":[{{"Containers":"1","CreatedAt":"2024-01-10 08:00:00 +0000 UTC","ID":"1111aaaa2222","Repository":"nginx","Size":"187MB","Tag":"latest","UniqueSize":"187MB"}},{{"Containers":"0","CreatedAt":"2024-01-05 08:00:00 +0000 UTC","ID":"3333bbbb4444","Repository":"<none>","Size":"80MB","Tag":"<none>","UniqueSize":"75MB"}},{{"Containers":"0","CreatedAt":"2023-12-01 08:00:00 +0000 UTC","ID":"5555cccc6666","Repository":"postgres","Size":"400MB","Tag":"15","UniqueSize":"400MB"}}],"Volumes":[{{"Links":"0","Name":"pgdata","Size":"1.2GB"}},{{"Links":"1","Name":"data","Size":"10MB"}}]}}' ;;
+  "image inspect"*)
+    echo '{{"Id":"sha256:3333bbbb4444ffff","Created":"2024-01-05T08:00:00Z","Metadata":{{"LastTagTime":"0001-01-01T00:00:00Z"}}}}'
+    echo '{{"Id":"sha256:5555cccc6666ffff","Created":"2023-12-01T08:00:00Z","Metadata":{{"LastTagTime":"2024-02-01T00:00:00Z"}}}}' ;;
+  "volume inspect"*) echo '{{"Name":"pgdata","CreatedAt":"2024-02-10T09:00:00Z"}}' ;;
+  *" prune "*) echo 'Total reclaimed space: 1.5kB' ;;
+  *" rm "*) echo "$3" ;;"#,
+        root = tmp.display()
+    );
+    fake_command(tmp, "docker", &cases)
+}
+
+#[test]
+fn test_docker_resources_and_items() {
+    let tmp = TempDir::new().unwrap();
+    let (path_var, log) = fake_docker(tmp.path());
+    let sweeper = || {
+        let mut cmd = rusty_sweeper();
+        cmd.env("PATH", &path_var)
+            .env("HOME", tmp.path())
+            .env("XDG_STATE_HOME", tmp.path().join("xdg-state"));
+        cmd
+    };
+
+    sweeper()
+        .args(["resources", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("docker-dangling-images"))
+        .stdout(predicate::str::contains("docker-containers"))
+        .stdout(predicate::str::contains("docker-volumes"));
+
+    sweeper()
+        .args(["resources", "list", "docker-images"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("postgres:15"))
+        .stdout(predicate::str::contains("3333bbbb4444").not())
+        .stdout(predicate::str::contains("nginx").not())
+        .stdout(predicate::str::contains("in 1 item\n"));
+    sweeper()
+        .args(["resources", "list", "docker-dangling-images"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3333bbbb4444"));
+
+    // The 75MB of dangling images are part of Docker's 480MB reclaimable
+    // image space, so they are counted once
+    sweeper()
+        .args(["clean", "--size-only", "--types", "docker"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total: 457.77 MiB"));
+
+    let output = sweeper()
+        .args(["resources", "list", "docker-volumes", "--json"])
+        .output()
+        .unwrap();
+    let volumes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(volumes.as_array().unwrap().len(), 1);
+    assert_eq!(volumes[0]["name"], "pgdata");
+    assert_eq!(volumes[0]["size"], 1_200_000_000);
+    assert!(volumes[0]["last_used"].is_u64());
+
+    sweeper()
+        .args(["resources", "rm", "docker-images", "postgres:15"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed postgres:15"));
+    sweeper()
+        .args(["resources", "rm", "docker-images", "nginx:latest"])
+        .assert()
+        .code(5);
+
+    sweeper()
+        .args(["clean", "--force", "--types", "docker"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+
+    let log = fs::read_to_string(log).unwrap();
+    assert!(log.contains("image rm 5555cccc6666"));
+    assert!(!log.contains("image rm 1111aaaa2222"));
+    assert!(log.contains("image prune -f\n"));
+    assert!(log.contains("container prune -f"));
+    // Volumes released by the pruned containers are kept for the next run
+    assert!(!log.contains("volume prune"));
+    assert!(!log.contains("volume ls"));
+}
+
+#[test]
+fn test_clean_without_types_keeps_docker_containers_and_volumes() {
+    let tmp = TempDir::new().unwrap();
+    let (path_var, log) = fake_docker(tmp.path());
+
+    // Only the fake docker runs by default among the system cleaners
+    rusty_sweeper()
+        .env("PATH", &path_var)
+        .env("HOME", tmp.path())
+        .env("XDG_STATE_HOME", tmp.path().join("xdg-state"))
+        .args(["clean", "--force"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Docker Unused Images"))
+        .stdout(predicate::str::contains("Stopped Containers").not())
+        .stdout(predicate::str::contains("Unused Volumes").not());
+
+    let log = fs::read_to_string(log).unwrap();
+    assert!(log.contains("image prune -f -a"));
+    assert!(!log.contains("container prune"));
+    assert!(!log.contains("volume"));
+}
+
+#[test]
+fn test_clean_docker_removes_only_anonymous_volumes() {
+    let tmp = TempDir::new().unwrap();
+    let anonymous = "3f2a9c0b7e1d4a6f8b2c5e9d0a1b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d";
+    let cases = format!(
+        r#"  "info") exit 0 ;;
+  "system df --format"*)
+    echo '{{"Type":"Containers","TotalCount":"1","Active":"1","Size":"1.5kB","Reclaimable":"0B (0%)"}}'
+    echo '{{"Type":"Local Volumes","TotalCount":"2","Active":"0","Size":"1.3GB","Reclaimable":"1.3GB (100%)"}}' ;;
+  "system df -v --format"*)
+    echo '{{"BuildCache":[],"Containers":[],"Images":[],"Volumes":[{{"Links":"0","Name":"pgdata","Size":"1.2GB"}},{{"Links":"0","Name":"{anonymous}","Size":"100MB"}}]}}' ;;
+  "volume ls --quiet --filter dangling=true") printf 'pgdata\n{anonymous}\n' ;;
+  "volume rm"*) exit 0 ;;
+  *" prune "*) echo 'Total reclaimed space: 0B' ;;"#
+    );
+    let (path_var, log) = fake_command(tmp.path(), "docker", &cases);
+
+    rusty_sweeper()
+        .env("PATH", &path_var)
+        .env("HOME", tmp.path())
+        .env("XDG_STATE_HOME", tmp.path().join("xdg-state"))
+        .args(["clean", "--force", "--types", "docker"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Anonymous volumes not used by any container (1 items)",
+        ));
+
+    let log = fs::read_to_string(log).unwrap();
+    assert!(log.contains(&format!("volume rm {}\n", anonymous)));
+    assert!(!log.contains("pgdata"));
+    assert!(!log.contains("volume prune"));
+}
+
+#[test]
+fn test_clean_go_caches() {
+    let tmp = TempDir::new().unwrap();
+    let gocache = tmp.path().join("go-build");
+    let gopath = tmp.path().join("go");
+    fs::create_dir_all(gocache.join("0a")).unwrap();
+    fs::write(gocache.join("0a/obj-d"), "x".repeat(1000)).unwrap();
+    fs::create_dir_all(gopath.join("pkg/mod/example.com/lib@v1.0.0")).unwrap();
+    fs::write(
+        gopath.join("pkg/mod/example.com/lib@v1.0.0/lib.go"),
+        "x".repeat(500),
+    )
+    .unwrap();
+
+    // Without a `go` binary the caches come from the environment and are
+    // deleted directly
+    let no_go = tmp.path().join("empty-path");
+    fs::create_dir(&no_go).unwrap();
+    let go_env = |cmd: &mut Command| {
+        cmd.env("PATH", &no_go)
+            .env("GOCACHE", &gocache)
+            .env("GOPATH", &gopath)
+            .env_remove("GOMODCACHE")
+            .env("XDG_STATE_HOME", tmp.path().join("xdg-state"));
+    };
+
+    let mut cmd = rusty_sweeper();
+    go_env(&mut cmd);
+    cmd.args(["clean", "--size-only", "--types", "go"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Go Build Cache"))
+        .stdout(predicate::str::contains("Go Module Cache"));
+
+    let mut cmd = rusty_sweeper();
+    go_env(&mut cmd);
+    cmd.args(["clean", "--force", "--types", "go"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+    assert!(gocache.exists());
+    assert!(!gocache.join("0a").exists());
+    assert!(!gopath.join("pkg/mod/example.com").exists());
+}
+
+#[test]
+fn test_clean_podman_resources() {
+    let tmp = TempDir::new().unwrap();
+    let anonymous = "3f2a9c0b7e1d4a6f8b2c5e9d0a1b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d";
+    let cases = format!(
+        r#"  "info") exit 0 ;;
+  "info --format"*) echo /nonexistent ;;
+  "system df --format json")
+    echo '[{{"Type":"Images","Total":2,"Active":0,"RawReclaimable":480000000,"TotalCount":2,"Reclaimable":"480MB (100%)"}},'
+    echo ' {{"Type":"Local Volumes","Total":2,"Active":0,"RawReclaimable":1200000000,"TotalCount":2,"Reclaimable":"1.2GB (100%)"}}]' ;;
+  "images --format json")
+    echo '[{{"Id":"5555cccc6666dddd","Names":["docker.io/library/postgres:15"],"Size":400000000,"Containers":0,"Created":1701417600}},'
+    echo ' {{"Id":"3333bbbb4444eeee","Names":null,"Size":80000000,"Containers":0,"Created":1704441600}}]' ;;
+  "volume ls --quiet --filter dangling=true") printf 'pgdata\n{anonymous}\n' ;;
+  "volume inspect --format"*) echo /nonexistent ;;
+  "volume rm"*) exit 0 ;;
+  *" prune "*) echo 3333bbbb4444eeee ;;"#
+    );
+    let (path_var, log) = fake_command(tmp.path(), "podman", &cases);
+    let sweeper = || {
+        let mut cmd = rusty_sweeper();
+        cmd.env("PATH", &path_var)
+            .env("XDG_STATE_HOME", tmp.path().join("xdg-state"));
+        cmd
+    };
+
+    sweeper()
+        .args(["clean", "--size-only", "--types", "podman"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Podman Dangling Images"))
+        .stdout(predicate::str::contains("Podman Unused Images"));
+
+    sweeper()
+        .args(["clean", "--force", "--types", "podman"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+    let calls = fs::read_to_string(&log).unwrap();
+    assert!(calls.contains("image prune -f -a"));
+    // Named volumes such as pgdata are never pruned in bulk
+    assert!(calls.contains(&format!("volume rm {}\n", anonymous)));
+    assert!(!calls.contains("volume prune"));
+    assert!(!calls.contains("rm pgdata"));
+}
+
+#[test]
+fn test_clean_accepts_linux_system_types() {
+    let tmp = TempDir::new().unwrap();
+    rusty_sweeper()
+        .args([
+            "clean",
+            "--size-only",
+            "--types",
+            "journald,apt-cache,dnf-cache,pacman-cache,coredump",
+        ])
+        .arg(tmp.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_clean_runs_only_default_system_cleaners_without_types() {
+    let tmp = create_project_tree();
+    let data_home = tmp.path().join("xdg-data");
+    fs::create_dir_all(data_home.join("Trash/files")).unwrap();
+    fs::write(data_home.join("Trash/files/old.iso"), "x".repeat(100)).unwrap();
+    let sweeper = || {
+        let mut cmd = rusty_sweeper();
+        cmd.env("HOME", tmp.path())
+            .env("XDG_DATA_HOME", &data_home)
+            .env("XDG_CACHE_HOME", tmp.path().join("xdg-cache"))
+            .env("XDG_STATE_HOME", tmp.path().join("xdg-state"));
+        cmd
+    };
+
+    sweeper()
+        .args(["clean", "--size-only"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rust-app"))
+        .stdout(predicate::str::contains("Trash").not());
+
+    sweeper()
+        .args(["clean", "--size-only", "--types", "trash"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Trash"));
+}
+
+#[test]
+fn test_clean_trash_by_deletion_date() {
+    let tmp = TempDir::new().unwrap();
+    let data_home = tmp.path().join("xdg-data");
+    let trash = data_home.join("Trash");
+    fs::create_dir_all(trash.join("files")).unwrap();
+    fs::create_dir_all(trash.join("info")).unwrap();
+    fs::write(trash.join("files/old.iso"), "x".repeat(100)).unwrap();
+    fs::write(
+        trash.join("info/old.iso.trashinfo"),
+        "[Trash Info]\nPath=/home/u/old.iso\nDeletionDate=2020-01-01T00:00:00\n",
+    )
+    .unwrap();
+    // No .trashinfo: judged by its modification time
+    fs::write(trash.join("files/recent.txt"), "x").unwrap();
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .env("XDG_STATE_HOME", tmp.path().join("xdg-state"))
+        .args(["resources", "list", "trash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home/u/old.iso"))
+        .stdout(predicate::str::contains("recent.txt"));
+
+    let config = write_config(&tmp, "[cleaner.policy.types.trash]\nmin_age_days = 30\n");
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .env("XDG_DATA_HOME", &data_home)
+        .env("XDG_STATE_HOME", tmp.path().join("xdg-state"))
+        .args(["clean", "--force", "--types", "trash"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Trash"))
+        .stdout(predicate::str::contains("(1 items)"));
+    assert!(!trash.join("files/old.iso").exists());
+    assert!(!trash.join("info/old.iso.trashinfo").exists());
+    assert!(trash.join("files/recent.txt").exists());
+}
+
+#[test]
+fn test_quarantine_enabled_in_config_and_purge() {
+    let tmp = create_project_tree();
+    let data_home = tmp.path().join("xdg-data");
+    let config = write_config(&tmp, "[quarantine]\nenabled = true\n");
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(tmp.path().join("rust-app"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Quarantined:"));
+
+    // Younger than the default retention period
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .args(["quarantine", "purge"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Purged 0 entries"));
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", &data_home)
+        .args(["quarantine", "purge", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Purged 1 entry, freed 1000 B"));
+}
+
+#[test]
+fn test_quarantine_restore_unknown_id() {
+    let tmp = TempDir::new().unwrap();
+
+    rusty_sweeper()
+        .env("XDG_DATA_HOME", tmp.path())
+        .args(["quarantine", "restore", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nope"));
+}
+
+#[test]
+fn test_clean_invalid_types_exit_code() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["clean", "--size-only", "--types", "nonexistent"])
+        .arg(tmp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("No valid types"));
+}
+
+fn write_config(tmp: &TempDir, policy: &str) -> std::path::PathBuf {
+    let config = tmp.path().join("config.toml");
+    fs::write(&config, policy).unwrap();
+    config
+}
+
+#[test]
+fn test_clean_combines_configured_and_cli_excludes() {
+    let tmp = create_project_tree();
+    for project in ["vendor/lib", "generated/app", "extra/app"] {
+        let dir = tmp.path().join(project);
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(dir.join("target/out.bin"), "x".repeat(100)).unwrap();
+    }
+    let sweeper = |config: &str| {
+        let mut cmd = rusty_sweeper();
+        cmd.arg("--config").arg(write_config(&tmp, config)).args([
+            "clean",
+            "--size-only",
+            "--types",
+            "cargo",
+        ]);
+        cmd
+    };
+
+    // The default patterns skip `vendor`
+    sweeper("")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rust-app"))
+        .stdout(predicate::str::contains("generated"))
+        .stdout(predicate::str::contains("vendor").not());
+
+    sweeper("[cleaner]\nexclude_patterns = [\"**/generated\"]\n")
+        .args(["--exclude", "extra"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rust-app"))
+        .stdout(predicate::str::contains("vendor"))
+        .stdout(predicate::str::contains("generated").not())
+        .stdout(predicate::str::contains("extra").not());
+}
+
+#[test]
+fn test_clean_policy_reports_skipped_projects() {
+    let tmp = create_project_tree();
+    let config = write_config(
+        &tmp,
+        "[cleaner.policy.types.cargo]\nmin_artifact_size = 1048576\n",
+    );
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped by policy"))
+        .stdout(predicate::str::contains("artifacts 1000 B < 1 MiB"))
+        .stdout(predicate::str::contains("all were skipped"));
+
+    assert!(tmp.path().join("rust-app/target").exists());
+}
+
+#[test]
+fn test_clean_policy_protect_pattern() {
+    let tmp = create_project_tree();
+    let config = write_config(&tmp, "[cleaner.policy]\nprotect = [\"**/rust-*\"]\n");
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--force", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("protected by '**/rust-*'"));
+
+    assert!(tmp.path().join("rust-app/target").exists());
+}
+
+#[test]
+fn test_clean_policy_shows_rule_for_included_projects() {
+    let tmp = create_project_tree();
+    let config = write_config(&tmp, "[cleaner.policy]\nmin_age_days = 0\n");
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--size-only", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("RULE"))
+        .stdout(predicate::str::contains("idle 0d >= 0d"));
+}
+
+#[test]
+fn test_clean_age_flag_skips_recent_projects() {
+    let tmp = create_project_tree();
+
+    rusty_sweeper()
+        .args(["clean", "--force", "--age", "30", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("modified 0d ago (< 30d)"));
+
+    assert!(tmp.path().join("rust-app/target").exists());
+}
+
+const BLAZE_TYPE: &str = r#"
+[project_types.blaze]
+display_name = "Blaze"
+detection_files = ["BLAZE"]
+artifact_dirs = ["blaze-out"]
+"#;
+
+#[test]
+fn test_clean_custom_project_type() {
+    let tmp = create_project_tree();
+    let blaze = tmp.path().join("tool");
+    fs::create_dir_all(blaze.join("blaze-out")).unwrap();
+    fs::write(blaze.join("BLAZE"), "").unwrap();
+    fs::write(blaze.join("blaze-out/lib.a"), "x".repeat(100)).unwrap();
+    let config = write_config(&tmp, BLAZE_TYPE);
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--force", "--types", "blaze"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("blaze"))
+        .stdout(predicate::str::contains("Cleaned: 1 item"));
+
+    assert!(!blaze.join("blaze-out").exists());
+    assert!(tmp.path().join("rust-app/target").exists());
+}
+
+#[test]
+fn test_clean_disabled_builtin_type_is_invalid() {
+    let tmp = create_project_tree();
+    let config = write_config(
+        &tmp,
+        &format!("{}\n[project_types.cargo]\nenabled = false\n", BLAZE_TYPE),
+    );
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--size-only", "--types", "cargo"])
+        .arg(tmp.path())
+        .assert()
+        .code(2)
+        // Custom types come first; the disabled built-in is gone
+        .stderr(predicate::str::contains("Valid types: blaze, gradle,"));
+}
+
+#[test]
+fn test_invalid_custom_project_type_exit_code() {
+    let tmp = create_project_tree();
+    let config = write_config(&tmp, "[project_types.blaze]\nartifact_dirs = [\"../x\"]\n");
+
+    rusty_sweeper()
+        .arg("--config")
+        .arg(&config)
+        .args(["clean", "--size-only"])
+        .arg(tmp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("project_types.blaze"));
+}
+
+#[test]
+fn test_tui_nonexistent_path_fails() {
+    rusty_sweeper()
+        .args(["tui", "/nonexistent/path/12345"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Path not found"));
+}
+
+#[test]
+fn test_invalid_config_exit_code() {
+    rusty_sweeper()
+        .args(["--config", "/nonexistent/config.toml", "scan", "."])
+        .assert()
+        .code(2);
+}
+
+fn create_duplicate_tree() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    for dir in ["a", "b", "c"] {
+        fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        fs::write(tmp.path().join(dir).join("model.bin"), "w".repeat(4000)).unwrap();
+    }
+    fs::write(tmp.path().join("a/unique.txt"), "only once").unwrap();
+    tmp
+}
+
+#[test]
+fn test_duplicates_table() {
+    let tmp = create_duplicate_tree();
+
+    rusty_sweeper()
+        .arg("duplicates")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("RECLAIMABLE"))
+        .stdout(predicate::str::contains("c/model.bin"))
+        .stdout(predicate::str::contains("2 duplicate files (1 groups)"))
+        .stdout(predicate::str::contains("unique.txt").not());
+}
+
+#[test]
+fn test_duplicates_json() {
+    let tmp = create_duplicate_tree();
+
+    let output = rusty_sweeper()
+        .args(["dupes", "--json"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["groups"].as_array().unwrap().len(), 1);
+    assert_eq!(parsed["groups"][0]["reclaimable"], 8000);
+    assert_eq!(parsed["groups"][0]["files"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn test_duplicates_min_size_filters() {
+    let tmp = create_duplicate_tree();
+
+    rusty_sweeper()
+        .args(["duplicates", "--min-size", "1MB"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No duplicate files found."));
+}
+
+#[test]
+fn test_duplicates_link_dry_run_keeps_files() {
+    use std::os::unix::fs::MetadataExt;
+
+    let tmp = create_duplicate_tree();
+
+    rusty_sweeper()
+        .args(["duplicates", "--link", "hardlink", "--dry-run"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dry run"));
+
+    let a = fs::metadata(tmp.path().join("a/model.bin")).unwrap();
+    let b = fs::metadata(tmp.path().join("b/model.bin")).unwrap();
+    assert_ne!(a.ino(), b.ino());
+}
+
+#[test]
+fn test_duplicates_hardlink_replaces_copies() {
+    use std::os::unix::fs::MetadataExt;
+
+    let tmp = create_duplicate_tree();
+
+    rusty_sweeper()
+        .args(["duplicates", "--link", "hardlink"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Replaced 2 duplicates"));
+
+    let a = fs::metadata(tmp.path().join("a/model.bin")).unwrap();
+    let c = fs::metadata(tmp.path().join("c/model.bin")).unwrap();
+    assert_eq!(a.ino(), c.ino());
+    assert_eq!(a.nlink(), 3);
+
+    rusty_sweeper()
+        .arg("duplicates")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No duplicate files found."));
+}
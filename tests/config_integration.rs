@@ -39,7 +39,8 @@ large_dir_threshold = 536870912
 
     assert_eq!(config.monitor.interval, 600);
     assert_eq!(config.monitor.warn_threshold, 75);
-    assert_eq!(config.cleaner.min_age_days, 14);
+    assert_eq!(config.cleaner.min_age_days, Some(14));
+    assert_eq!(config.cleaner.policy.min_age_days, Some(14));
     assert_eq!(config.scanner.parallel_threads, 4);
     assert!(config.tui.show_hidden);
 }
@@ -2,7 +2,7 @@ use assert_cmd::Command;
 use predicates::prelude::*;
 
 fn rusty_sweeper_monitor() -> Command {
-    Command::cargo_bin("rusty-sweeper-monitor").unwrap()
+    assert_cmd::cargo::cargo_bin_cmd!("rusty-sweeper-monitor")
 }
 
 #[test]
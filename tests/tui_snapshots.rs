@@ -5,12 +5,14 @@
 
 use insta::assert_snapshot;
 use ratatui::{backend::TestBackend, Terminal};
-use rusty_sweeper::cleaner::DetectedSystemResource;
+use rusty_sweeper::cleaner::{DetectedSystemResource, SystemResourceItem};
+use rusty_sweeper::quarantine::{QuarantineEntry, StorageKind};
 use rusty_sweeper::scanner::DirEntry;
-use rusty_sweeper::tui::app::{ConfirmAction, Mode};
+use rusty_sweeper::tui::app::{CleanPreview, ConfirmAction, Mode};
 use rusty_sweeper::tui::ui::render;
 use rusty_sweeper::tui::App;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Render the app to a string for snapshot comparison.
 fn render_to_string(app: &App, width: u16, height: u16) -> String {
@@ -80,10 +82,122 @@ fn test_with_tree_snapshot() {
 fn test_help_overlay_snapshot() {
     let mut app = App::new(PathBuf::from("/home/user"));
     app.mode = Mode::Help;
+    // Every key, down to quitting, fits on a standard terminal
+    let output = render_to_string(&app, 80, 24);
+    for key in [
+        "Search / Filter",
+        "Toggle this help",
+        "q/Esc      Quit",
+        "H          Cleanup history",
+    ] {
+        assert!(output.contains(key), "{} is cut off", key);
+    }
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_breakdown_panel_snapshot() {
+    let mut app = App::new(PathBuf::from("/test"));
+
+    let mut root = DirEntry::new_dir(PathBuf::from("/test"), None);
+    for (name, size) in [
+        ("movie.mkv", 700 * 1024 * 1024),
+        ("clip.mp4", 120 * 1024 * 1024),
+        ("photo.jpg", 4 * 1024 * 1024),
+        ("main.o", 2 * 1024 * 1024),
+        ("main.rs", 12 * 1024),
+        ("README", 2048),
+    ] {
+        root.children.push(DirEntry::new_file(
+            PathBuf::from("/test").join(name),
+            size,
+            size,
+            None,
+        ));
+    }
+    root.recalculate_totals();
+
+    app.tree = Some(root);
+    app.rebuild_visible_entries();
+    assert!(app.prepare_breakdown());
+    app.mode = Mode::Breakdown;
+
     let output = render_to_string(&app, 80, 30);
     assert_snapshot!(output);
 }
 
+#[test]
+fn test_quarantine_panel_snapshot() {
+    let mut app = App::new(PathBuf::from("/home/user"));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let day = 24 * 60 * 60;
+    app.quarantine_entries = [
+        (
+            "/home/user/dev/api/target",
+            2 * day + 60,
+            3 * 1024 * 1024 * 1024,
+        ),
+        (
+            "/home/user/dev/web/node_modules",
+            40 * day,
+            450 * 1024 * 1024,
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (path, age, size))| QuarantineEntry {
+        id: i.to_string(),
+        original_path: PathBuf::from(path),
+        stored_path: PathBuf::from("/unused"),
+        storage: StorageKind::Holding,
+        quarantined_at: now - age,
+        size,
+    })
+    .collect();
+    app.quarantine_selected = 1;
+    app.mode = Mode::Quarantine;
+
+    let output = render_to_string(&app, 80, 24);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_resource_items_panel_snapshot() {
+    let mut app = App::new(PathBuf::from("/home/user"));
+    let now = SystemTime::now();
+    let day = Duration::from_secs(24 * 60 * 60);
+    app.items_resource = Some(DetectedSystemResource {
+        resource_id: "docker-images".to_string(),
+        display_name: "Docker Unused Images".to_string(),
+        category: "docker".to_string(),
+        size: 1_500 * 1024 * 1024,
+        description: "Images not used by any container".to_string(),
+        item_count: Some(2),
+    });
+    app.resource_items = vec![
+        SystemResourceItem {
+            id: "3f2a9c1d8e7b".to_string(),
+            name: "postgres:15".to_string(),
+            size: 1_200 * 1024 * 1024,
+            last_used: Some(now - day * 30 - Duration::from_secs(60)),
+        },
+        SystemResourceItem {
+            id: "9b8c7d6e5f4a".to_string(),
+            name: "<none>:<none>".to_string(),
+            size: 300 * 1024 * 1024,
+            last_used: None,
+        },
+    ];
+    app.resource_items_selected = 1;
+    app.mode = Mode::ResourceItems;
+
+    let output = render_to_string(&app, 80, 24);
+    assert_snapshot!(output);
+}
+
 #[test]
 fn test_search_mode_snapshot() {
     let mut app = App::new(PathBuf::from("/home/user"));
@@ -134,6 +248,31 @@ fn test_confirm_clean_snapshot() {
     assert_snapshot!(output);
 }
 
+#[test]
+fn test_confirm_clean_git_state_snapshot() {
+    let mut app = App::new(PathBuf::from("/project"));
+
+    let mut root = DirEntry::new_dir(PathBuf::from("/project"), None);
+    root.children
+        .push(DirEntry::new_dir(PathBuf::from("/project/target"), None));
+    root.recalculate_totals();
+
+    app.tree = Some(root);
+    app.expanded.insert(PathBuf::from("/project"));
+    app.rebuild_visible_entries();
+    app.clean_preview = Some(CleanPreview {
+        project_name: "Rust/Cargo".to_string(),
+        artifacts: vec![("target".to_string(), 512 * 1024 * 1024)],
+        total_size: 512 * 1024 * 1024,
+        policy_rule: Some("git: dirty, 1 stash (confirm)".to_string()),
+        git_state: Some("dirty, 1 stash".to_string()),
+    });
+    app.mode = Mode::Confirm(ConfirmAction::Clean);
+
+    let output = render_to_string(&app, 80, 24);
+    assert_snapshot!(output);
+}
+
 #[test]
 fn test_status_message_snapshot() {
     let mut app = App::new(PathBuf::from("/test"));